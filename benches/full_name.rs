@@ -0,0 +1,57 @@
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use evil::SuperVillain;
+
+const VILLAINS: usize = 10_000;
+
+fn villains() -> Vec<SuperVillain<'static>> {
+    (0..VILLAINS)
+        .map(|i| SuperVillain {
+            first_name: format!("Villain{i}"),
+            last_name: String::from("Luthor"),
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn full_name(c: &mut Criterion) {
+    let villains = villains();
+    let mut group = c.benchmark_group("full_name");
+
+    group.bench_function("full_name", |b| {
+        b.iter(|| {
+            for villain in &villains {
+                black_box(villain.full_name());
+            }
+        })
+    });
+
+    group.bench_function("write_full_name", |b| {
+        let mut buffer = String::new();
+        b.iter(|| {
+            for villain in &villains {
+                buffer.clear();
+                villain
+                    .write_full_name(&mut buffer)
+                    .expect("Writing to a String can't fail");
+                black_box(&buffer);
+            }
+        })
+    });
+
+    group.bench_function("full_name_cached", |b| {
+        b.iter(|| {
+            for villain in &villains {
+                black_box(villain.full_name_cached());
+            }
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, full_name);
+criterion_main!(benches);
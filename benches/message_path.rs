@@ -0,0 +1,133 @@
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::hint::black_box;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use criterion::BatchSize;
+use criterion::Criterion;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use evil::Cipher;
+use evil::Gadget;
+use evil::SuperVillain;
+use evil::envelope::MessageEnvelope;
+use evil::sidekick::Sidekick;
+
+const DOSSIER_SIZE: usize = 4 * 1024 * 1024;
+const KEY: &str = "Kryptonite";
+
+/// Counts the bytes allocated, to show how much each way of telling a dossier copies.
+struct CountingAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATED.fetch_add(new_size, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+fn allocated_by<T>(f: impl FnOnce() -> T) -> usize {
+    let before = ALLOCATED.load(Ordering::Relaxed);
+    black_box(f());
+    ALLOCATED.load(Ordering::Relaxed) - before
+}
+
+#[derive(Clone)]
+struct Radio;
+
+impl Gadget for Radio {
+    fn do_stuff(&self) {}
+}
+
+/// Cipher as cheap as it gets, so the benchmark measures the message path and not ciphering.
+struct PlusWrap;
+
+impl Cipher for PlusWrap {
+    fn transform(&self, secret: &str, _key: &str) -> String {
+        format!("+{secret}+")
+    }
+
+    fn id(&self) -> String {
+        String::from("plus-wrap")
+    }
+}
+
+fn villain() -> SuperVillain<'static> {
+    SuperVillain {
+        first_name: String::from("Lex"),
+        last_name: String::from("Luthor"),
+        sidekick: Some(Sidekick::new(Radio)),
+        shared_key: KEY.into(),
+        ..Default::default()
+    }
+}
+
+/// How plans were told before the envelope was shared: copied whole to be sealed, encoded as a
+/// `String`, and copied again by the sidekick.
+fn tell_plans_copying(villain: &SuperVillain, secret: &str) {
+    let ciphered_message = PlusWrap.transform(secret, KEY);
+    let envelope = MessageEnvelope::new(&villain.full_name(), &PlusWrap.id(), ciphered_message);
+    black_box(envelope.to_bytes());
+    let envelope = envelope.seal(KEY);
+    let json = envelope.try_to_json().expect("Envelope is serializable");
+    if let Some(ref sidekick) = villain.sidekick {
+        sidekick.tell(&json);
+    }
+}
+
+fn message_path(c: &mut Criterion) {
+    let dossier = "x".repeat(DOSSIER_SIZE);
+
+    let copying = villain();
+    let shared = villain();
+    eprintln!(
+        "Allocated telling a {DOSSIER_SIZE} B dossier: copying {} B, tell_plans {} B",
+        allocated_by(|| tell_plans_copying(&copying, &dossier)),
+        allocated_by(|| shared.tell_plans(&dossier, &PlusWrap)),
+    );
+
+    let mut group = c.benchmark_group("message_path");
+
+    group.bench_function("copying", |b| {
+        b.iter_batched(
+            villain,
+            |villain| {
+                tell_plans_copying(&villain, &dossier);
+                villain
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.bench_function("tell_plans", |b| {
+        b.iter_batched(
+            villain,
+            |villain| {
+                black_box(villain.tell_plans(&dossier, &PlusWrap)).expect("Sidekick is available");
+                villain
+            },
+            BatchSize::LargeInput,
+        )
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, message_path);
+criterion_main!(benches);
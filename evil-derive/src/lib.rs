@@ -0,0 +1,128 @@
+//! Derive macros for the traits of the `evil` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::DeriveInput;
+use syn::LitStr;
+use syn::Path;
+use syn::parse_macro_input;
+
+/// Derives `Henchman`, doing nothing for every method unless told otherwise.
+///
+/// Each method can be delegated to a function taking the henchman as its first argument,
+/// followed by the arguments of the method:
+///
+/// ```ignore
+/// #[derive(Henchman)]
+/// #[henchman(fight_enemies = "brawl")]
+/// struct Bruiser;
+///
+/// fn brawl(_bruiser: &Bruiser) {
+///     println!("POW!");
+/// }
+/// ```
+///
+/// Use `crate = "..."` when the `evil` crate is known by another name.
+#[proc_macro_derive(Henchman, attributes(henchman))]
+pub fn derive_henchman(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_henchman(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct Overrides {
+    krate: Option<Path>,
+    build_secret_hq: Option<Path>,
+    do_hard_things: Option<Path>,
+    fight_enemies: Option<Path>,
+    report_progress: Option<Path>,
+    is_on_strike: Option<Path>,
+}
+
+impl Overrides {
+    fn parse(input: &DeriveInput) -> syn::Result<Overrides> {
+        let mut overrides = Overrides::default();
+        for attr in input
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("henchman"))
+        {
+            attr.parse_nested_meta(|meta| {
+                let slot = if meta.path.is_ident("crate") {
+                    &mut overrides.krate
+                } else if meta.path.is_ident("build_secret_hq") {
+                    &mut overrides.build_secret_hq
+                } else if meta.path.is_ident("do_hard_things") {
+                    &mut overrides.do_hard_things
+                } else if meta.path.is_ident("fight_enemies") {
+                    &mut overrides.fight_enemies
+                } else if meta.path.is_ident("report_progress") {
+                    &mut overrides.report_progress
+                } else if meta.path.is_ident("is_on_strike") {
+                    &mut overrides.is_on_strike
+                } else {
+                    return Err(meta.error("unknown henchman attribute"));
+                };
+                if slot.is_some() {
+                    return Err(meta.error("duplicate henchman attribute"));
+                }
+                *slot = Some(meta.value()?.parse::<LitStr>()?.parse()?);
+                Ok(())
+            })?;
+        }
+        Ok(overrides)
+    }
+}
+
+fn expand_henchman(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let overrides = Overrides::parse(input)?;
+    let krate = overrides
+        .krate
+        .map_or_else(|| quote!(::evil), |krate| quote!(#krate));
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+
+    let build_secret_hq = match overrides.build_secret_hq {
+        Some(path) => quote!(#path(self, location)),
+        None => quote!(let _ = location;),
+    };
+    let do_hard_things = overrides.do_hard_things.map(|path| quote!(#path(self)));
+    let fight_enemies = overrides.fight_enemies.map(|path| quote!(#path(self)));
+    let report_progress = overrides.report_progress.map(|path| {
+        quote! {
+            fn report_progress(&self) -> #krate::Progress {
+                #path(self)
+            }
+        }
+    });
+    let is_on_strike = overrides.is_on_strike.map(|path| {
+        quote! {
+            fn is_on_strike(&self) -> bool {
+                #path(self)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics #krate::Henchman for #name #type_generics #where_clause {
+            fn build_secret_hq(&mut self, location: ::std::string::String) {
+                #build_secret_hq
+            }
+
+            fn do_hard_things(&self) {
+                #do_hard_things
+            }
+
+            fn fight_enemies(&self) {
+                #fight_enemies
+            }
+
+            #report_progress
+
+            #is_on_strike
+        }
+    })
+}
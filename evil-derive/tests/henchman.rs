@@ -0,0 +1,74 @@
+use std::cell::Cell;
+
+use evil::Henchman;
+use evil::Progress;
+
+#[derive(Henchman, Default)]
+struct Lazy;
+
+#[derive(Henchman, Default)]
+#[henchman(
+    build_secret_hq = "Builder::build",
+    report_progress = "Builder::progress"
+)]
+struct Builder {
+    built: Option<String>,
+}
+
+impl Builder {
+    fn build(&mut self, location: String) {
+        self.built = Some(location);
+    }
+
+    fn progress(&self) -> Progress {
+        Progress {
+            percent_complete: if self.built.is_some() { 100 } else { 0 },
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Henchman)]
+#[henchman(fight_enemies = "count_fight", is_on_strike = "always_striking")]
+struct Striker<T> {
+    fights: Cell<u32>,
+    demands: T,
+}
+
+fn count_fight<T>(striker: &Striker<T>) {
+    striker.fights.set(striker.fights.get() + 1);
+}
+
+fn always_striking<T>(_striker: &Striker<T>) -> bool {
+    true
+}
+
+#[test]
+fn derived_methods_default_to_doing_nothing() {
+    let mut lazy = Lazy;
+    lazy.build_secret_hq(String::from("Tampa"));
+    lazy.do_hard_things();
+    lazy.fight_enemies();
+    assert_eq!(lazy.report_progress(), Progress::default());
+    assert!(!lazy.is_on_strike());
+}
+
+#[test]
+fn overridden_methods_are_delegated() {
+    let mut builder = Builder::default();
+    builder.build_secret_hq(String::from("Tampa"));
+    assert_eq!(builder.built.as_deref(), Some("Tampa"));
+    assert_eq!(builder.report_progress().percent_complete, 100);
+}
+
+#[test]
+fn generic_henchmen_can_be_derived() {
+    let striker = Striker {
+        fights: Cell::new(0),
+        demands: vec!["Dental plan"],
+    };
+    striker.fight_enemies();
+    assert_eq!(striker.fights.get(), 1);
+    assert!(striker.is_on_strike());
+    assert_eq!(striker.demands.len(), 1);
+}
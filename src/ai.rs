@@ -0,0 +1,298 @@
+//! Module for goal-oriented action planning (GOAP): villains work out a plan from a goal and the
+//! actions they can take, instead of following hand-written schemes.
+#![allow(dead_code)]
+
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::collections::BinaryHeap;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use crate::error::EvilError;
+use crate::plan::PlanGraph;
+use crate::plan::PlanStep;
+use crate::plan::StepAction;
+use crate::units::EvilDuration;
+
+/// World states explored before giving up on finding a plan.
+pub const MAX_EXPLORED_STATES: usize = 10_000;
+
+/// Facts about the world, each true or false. Facts not set are unknown, which only matches
+/// conditions on them being false.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct WorldState {
+    facts: BTreeMap<String, bool>,
+}
+
+impl WorldState {
+    pub fn new() -> WorldState {
+        Self::default()
+    }
+
+    pub fn with(mut self, fact: &str, value: bool) -> WorldState {
+        self.set(fact, value);
+        self
+    }
+
+    pub fn set(&mut self, fact: &str, value: bool) {
+        self.facts.insert(fact.into(), value);
+    }
+
+    pub fn get(&self, fact: &str) -> bool {
+        self.facts.get(fact).copied().unwrap_or(false)
+    }
+
+    /// Whether every fact in the conditions has the same value here.
+    pub fn satisfies(&self, conditions: &WorldState) -> bool {
+        conditions
+            .facts
+            .iter()
+            .all(|(fact, &value)| self.get(fact) == value)
+    }
+
+    /// State after the effects happen.
+    pub fn after(&self, effects: &WorldState) -> WorldState {
+        let mut state = self.clone();
+        for (fact, &value) in &effects.facts {
+            state.set(fact, value);
+        }
+        state
+    }
+}
+
+/// What a villain wants the world to look like, e.g. "control Metropolis".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Goal {
+    pub name: String,
+    pub conditions: WorldState,
+}
+
+impl Goal {
+    pub fn new(name: &str, conditions: WorldState) -> Goal {
+        Self {
+            name: name.into(),
+            conditions,
+        }
+    }
+}
+
+/// Something a villain can do: the step its henchmen carry out, what has to be true before,
+/// and what becomes true after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GoapAction {
+    pub name: String,
+    pub step: StepAction,
+    pub duration: EvilDuration,
+    /// How much the villain would rather not do it. Plans with the lowest total cost win.
+    pub cost: u32,
+    pub preconditions: WorldState,
+    pub effects: WorldState,
+}
+
+impl GoapAction {
+    /// Action costing 1 and taking no time, with no preconditions nor effects.
+    pub fn new(name: &str, step: StepAction) -> GoapAction {
+        Self {
+            name: name.into(),
+            step,
+            duration: EvilDuration::ZERO,
+            cost: 1,
+            preconditions: WorldState::new(),
+            effects: WorldState::new(),
+        }
+    }
+
+    pub fn requires(mut self, fact: &str, value: bool) -> GoapAction {
+        self.preconditions.set(fact, value);
+        self
+    }
+
+    pub fn causes(mut self, fact: &str, value: bool) -> GoapAction {
+        self.effects.set(fact, value);
+        self
+    }
+
+    pub fn with_cost(mut self, cost: u32) -> GoapAction {
+        self.cost = cost;
+        self
+    }
+
+    pub fn with_duration(mut self, duration: impl Into<EvilDuration>) -> GoapAction {
+        self.duration = duration.into();
+        self
+    }
+}
+
+/// World state reached while searching, and how.
+struct Node {
+    state: WorldState,
+    /// Node it was reached from, with the action taken.
+    parent: Option<(usize, usize)>,
+}
+
+/// Finds the cheapest sequence of actions reaching a goal.
+#[derive(Debug, Clone, Default)]
+pub struct GoapPlanner {
+    actions: Vec<GoapAction>,
+}
+
+impl GoapPlanner {
+    pub fn new() -> GoapPlanner {
+        Self::default()
+    }
+
+    pub fn with_action(mut self, action: GoapAction) -> GoapPlanner {
+        self.actions.push(action);
+        self
+    }
+
+    pub fn actions(&self) -> &[GoapAction] {
+        &self.actions
+    }
+
+    /// Cheapest actions to take, in order, from the state to the goal. Ties go to the plan
+    /// using the actions added first.
+    pub fn sequence(&self, state: &WorldState, goal: &Goal) -> Result<Vec<&GoapAction>, EvilError> {
+        let mut nodes = Vec::from([Node {
+            state: state.clone(),
+            parent: None,
+        }]);
+        let mut frontier = BinaryHeap::from([Reverse((0u64, 0usize))]);
+        let mut explored = BTreeSet::new();
+        while let Some(Reverse((cost, index))) = frontier.pop() {
+            if !explored.insert(nodes[index].state.clone()) {
+                continue;
+            }
+            if nodes[index].state.satisfies(&goal.conditions) {
+                return Ok(self.actions_to(&nodes, index));
+            }
+            if explored.len() >= MAX_EXPLORED_STATES {
+                break;
+            }
+            for (action_index, action) in self.actions.iter().enumerate() {
+                if !nodes[index].state.satisfies(&action.preconditions) {
+                    continue;
+                }
+                let next = nodes[index].state.after(&action.effects);
+                if explored.contains(&next) {
+                    continue;
+                }
+                frontier.push(Reverse((cost + u64::from(action.cost), nodes.len())));
+                nodes.push(Node {
+                    state: next,
+                    parent: Some((index, action_index)),
+                });
+            }
+        }
+        Err(EvilError::NoPlanFound {
+            goal: goal.name.clone(),
+        })
+    }
+
+    /// Plan reaching the goal from the state, with every step depending on the one before.
+    pub fn plan(&self, state: &WorldState, goal: &Goal) -> Result<PlanGraph, EvilError> {
+        let mut plan = PlanGraph::new();
+        let mut previous = None;
+        for action in self.sequence(state, goal)? {
+            let step = plan.add_step(PlanStep::new(
+                &action.name,
+                action.duration,
+                action.step.clone(),
+            ));
+            if let Some(previous) = previous {
+                plan.add_dependency(step, previous)?;
+            }
+            previous = Some(step);
+        }
+        Ok(plan)
+    }
+
+    fn actions_to(&self, nodes: &[Node], mut index: usize) -> Vec<&GoapAction> {
+        let mut actions = Vec::new();
+        while let Some((parent, action)) = nodes[index].parent {
+            actions.push(&self.actions[action]);
+            index = parent;
+        }
+        actions.reverse();
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+
+    fn planner() -> GoapPlanner {
+        GoapPlanner::new()
+            .with_action(
+                GoapAction::new("Recruit goons", StepAction::DoHardThings)
+                    .causes("has_henchmen", true),
+            )
+            .with_action(
+                GoapAction::new(
+                    "Build lair",
+                    StepAction::BuildSecretHq(String::from("Metropolis")),
+                )
+                .requires("has_henchmen", true)
+                .causes("has_lair", true),
+            )
+            .with_action(
+                GoapAction::new("Storm city hall", StepAction::FightEnemies)
+                    .requires("has_henchmen", true)
+                    .requires("has_lair", true)
+                    .causes("controls_metropolis", true)
+                    .with_cost(5),
+            )
+            .with_action(
+                GoapAction::new("Bribe the mayor", StepAction::DoHardThings)
+                    .requires("has_lair", true)
+                    .causes("controls_metropolis", true)
+                    .with_cost(2),
+            )
+    }
+
+    fn control_metropolis() -> Goal {
+        Goal::new(
+            "control Metropolis",
+            WorldState::new().with("controls_metropolis", true),
+        )
+    }
+
+    #[test]
+    fn cheapest_actions_reach_the_goal_in_order() -> Result<(), EvilError> {
+        let planner = planner();
+        let names = planner
+            .sequence(&WorldState::new(), &control_metropolis())?
+            .into_iter()
+            .map(|action| action.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["Recruit goons", "Build lair", "Bribe the mayor"]);
+
+        let plan = planner.plan(&WorldState::new(), &control_metropolis())?;
+        let order = plan.topological_order()?;
+        assert_eq!(order.len(), 3);
+        assert_eq!(
+            plan.step(order[1]).map(|step| step.name.as_str()),
+            Some("Build lair")
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn goal_already_met_needs_no_plan() -> Result<(), EvilError> {
+        let state = WorldState::new().with("controls_metropolis", true);
+        assert!(planner().plan(&state, &control_metropolis())?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn unreachable_goal_fails() {
+        let goal = Goal::new("rule Gotham", WorldState::new().with("rules_gotham", true));
+        assert_matches!(
+            planner().sequence(&WorldState::new(), &goal),
+            Err(EvilError::NoPlanFound { goal }) if goal == "rule Gotham"
+        );
+    }
+}
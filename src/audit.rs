@@ -0,0 +1,255 @@
+//! Module for the append-only log of everything significant that happened, where every entry
+//! seals the previous one so the history can't be quietly rewritten.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use crate::event::EventListener;
+use crate::event::EvilEvent;
+use crate::supervillain::EvilError;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Something that happened, sealed with the hash of the entry before it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditEntry {
+    /// Position in the log, starting at 0.
+    pub sequence: u64,
+    pub actor: String,
+    pub operation: String,
+    pub details: String,
+    /// Hash of the previous entry, or 0 for the first one.
+    pub previous: u64,
+    pub hash: u64,
+}
+
+impl AuditEntry {
+    fn seal(sequence: u64, actor: &str, operation: &str, details: &str, previous: u64) -> Self {
+        let mut entry = Self {
+            sequence,
+            actor: actor.into(),
+            operation: operation.into(),
+            details: details.into(),
+            previous,
+            hash: 0,
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+
+    /// FNV-1a hash of every field but the hash itself. It detects tampering by henchmen, not
+    /// by anyone willing to recompute the whole chain.
+    fn compute_hash(&self) -> u64 {
+        let sequence = self.sequence.to_be_bytes();
+        let previous = self.previous.to_be_bytes();
+        let parts: [&[u8]; 5] = [
+            &sequence,
+            self.actor.as_bytes(),
+            self.operation.as_bytes(),
+            self.details.as_bytes(),
+            &previous,
+        ];
+        let mut hash = FNV_OFFSET_BASIS;
+        for part in parts {
+            for &byte in part.iter().chain(&[0xff]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        hash
+    }
+}
+
+/// Log of operations that can only grow, each entry chained to the one before.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AuditLog {
+    entries: Vec<AuditEntry>,
+    /// Hash of the last entry, or 0 while empty.
+    head: u64,
+}
+
+impl AuditLog {
+    pub fn new() -> AuditLog {
+        Self::default()
+    }
+
+    /// Log made of entries kept elsewhere, e.g. loaded from storage, along with the
+    /// [`AuditLog::head`] saved with them. They aren't checked until [`AuditLog::verify`] is
+    /// called.
+    pub fn from_entries(entries: Vec<AuditEntry>, head: u64) -> AuditLog {
+        Self { entries, head }
+    }
+
+    /// Hash of the last entry, or 0 while empty. Kept apart from the entries, it's what tells
+    /// that entries were dropped from the end.
+    pub fn head(&self) -> u64 {
+        self.head
+    }
+
+    pub fn entries(&self) -> &[AuditEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends an entry sealed with the hash of the last one.
+    pub fn record(&mut self, actor: &str, operation: &str, details: &str) -> &AuditEntry {
+        let sequence = self.entries.len() as u64;
+        let entry = AuditEntry::seal(sequence, actor, operation, details, self.head);
+        self.head = entry.hash;
+        self.entries.push(entry);
+        &self.entries[self.entries.len() - 1]
+    }
+
+    /// Checks that no entry was changed, removed or reordered, failing at the first one that
+    /// was. Entries dropped from the end fail at the sequence of the first one missing.
+    pub fn verify(&self) -> Result<(), EvilError> {
+        let mut previous = 0;
+        for (sequence, entry) in (0u64..).zip(&self.entries) {
+            if entry.sequence != sequence
+                || entry.previous != previous
+                || entry.hash != entry.compute_hash()
+            {
+                return Err(EvilError::AuditTampered { sequence });
+            }
+            previous = entry.hash;
+        }
+        if previous != self.head {
+            return Err(EvilError::AuditTampered {
+                sequence: self.entries.len() as u64,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Records every event published on an [`EventBus`](crate::event::EventBus) in a shared
+/// audit log.
+#[derive(Debug, Clone)]
+pub struct AuditRecorder {
+    log: Arc<Mutex<AuditLog>>,
+}
+
+impl AuditRecorder {
+    pub fn new(log: Arc<Mutex<AuditLog>>) -> AuditRecorder {
+        Self { log }
+    }
+}
+
+impl EventListener for AuditRecorder {
+    fn on_event(&self, event: &EvilEvent) {
+        let (actor, operation, details) = match event {
+            EvilEvent::MonologueDelivered { villain, hero, .. } => {
+                (villain.clone(), "monologue-delivered", hero.clone())
+            }
+            EvilEvent::HqBuilt { villain, target } => (villain.clone(), "hq-built", target.clone()),
+            EvilEvent::SidekickFired { villain, sidekick } => {
+                (villain.clone(), "sidekick-fired", sidekick.to_string())
+            }
+            EvilEvent::GadgetSwapped { sidekick, slot } => (
+                format!("sidekick {sidekick}"),
+                "gadget-swapped",
+                format!("{slot:?}"),
+            ),
+            EvilEvent::StepFired { schedule, step } => (
+                String::from("scheduler"),
+                "step-fired",
+                format!("{schedule:?} {step:?}"),
+            ),
+        };
+        let mut log = self
+            .log
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        log.record(&actor, operation, &details);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::EventBus;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use assertables::assert_ok;
+
+    fn sample_log() -> AuditLog {
+        let mut log = AuditLog::new();
+        log.record("Gru", "gadget-lent", "Shrink ray to Kevin");
+        log.record("Kevin", "gadget-returned", "Shrink ray");
+        log.record("Gru", "hq-built", test_common::FIRST_TARGET);
+        log
+    }
+
+    #[test]
+    fn untouched_log_verifies() {
+        let log = sample_log();
+        assert_ok!(log.verify());
+        assert_eq!(log.entries()[1].previous, log.entries()[0].hash);
+    }
+
+    #[test]
+    fn rewritten_entry_is_detected() {
+        let log = sample_log();
+        let mut entries = log.entries().to_vec();
+        entries[1].details = String::from("Never had the shrink ray");
+        assert_matches!(
+            AuditLog::from_entries(entries, log.head()).verify(),
+            Err(EvilError::AuditTampered { sequence: 1 })
+        );
+    }
+
+    #[test]
+    fn removed_entry_is_detected() {
+        let log = sample_log();
+        let mut entries = log.entries().to_vec();
+        entries.remove(1);
+        assert_matches!(
+            AuditLog::from_entries(entries, log.head()).verify(),
+            Err(EvilError::AuditTampered { sequence: 1 })
+        );
+    }
+
+    #[test]
+    fn truncated_log_is_detected() {
+        let log = sample_log();
+        let mut entries = log.entries().to_vec();
+        entries.pop();
+        assert_matches!(
+            AuditLog::from_entries(entries, log.head()).verify(),
+            Err(EvilError::AuditTampered { sequence: 2 })
+        );
+        assert_matches!(
+            AuditLog::from_entries(Vec::new(), log.head()).verify(),
+            Err(EvilError::AuditTampered { sequence: 0 })
+        );
+    }
+
+    #[test]
+    fn reloaded_log_verifies() {
+        let log = sample_log();
+        assert_ok!(AuditLog::from_entries(log.entries().to_vec(), log.head()).verify());
+    }
+
+    #[test]
+    fn recorder_logs_published_events() {
+        let log = Arc::new(Mutex::new(AuditLog::new()));
+        let mut bus = EventBus::new();
+        bus.subscribe(AuditRecorder::new(Arc::clone(&log)));
+        bus.publish(&EvilEvent::HqBuilt {
+            villain: test_common::PRIMARY_FULL_NAME.into(),
+            target: test_common::FIRST_TARGET.into(),
+        });
+        let log = log.lock().expect("Audit log poisoned");
+        assert_eq!(log.len(), 1);
+        assert_eq!(log.entries()[0].operation, "hq-built");
+    }
+}
@@ -0,0 +1,351 @@
+//! Module to load lairs and doomsday devices from blueprint files kept in version control.
+//!
+//! Blueprints are TOML, with a `[lair]` table and any number of `[[trap]]` and `[[device]]`
+//! tables.
+//!
+//! ```toml
+//! [lair]
+//! location = "Volcano Island"
+//! vault_capacity = 500
+//!
+//! [[trap]]
+//! kind = "Sharks"
+//! strength = 80
+//!
+//! [[device]]
+//! name = "Moon laser"
+//! power_watts = 5000000
+//! range_meters = 384400000
+//! ```
+#![allow(dead_code)]
+
+use std::fmt;
+use std::ops::Range;
+use std::str::FromStr;
+
+use serde::Deserialize;
+use toml::Spanned;
+
+use crate::device::DoomsdayDevice;
+use crate::device::DoomsdayDeviceBuilder;
+use crate::lair::Lair;
+use crate::lair::LairBuilder;
+use crate::lair::Trap;
+use crate::lair::TrapKind;
+use crate::supervillain::EvilError;
+use crate::units::Distance;
+use crate::units::Power;
+
+const MAX_TRAP_STRENGTH: u8 = 100;
+
+/// Problem found in a blueprint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlueprintIssue {
+    pub line: usize,
+    /// Field that couldn't be used, if the problem is in a single field.
+    pub field: Option<String>,
+    pub reason: String,
+}
+
+impl fmt::Display for BlueprintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "line {}, field '{field}': {}", self.line, self.reason),
+            None => write!(f, "line {}: {}", self.line, self.reason),
+        }
+    }
+}
+
+/// Layout of a lair and the devices in it, ready to be built.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blueprint {
+    pub lair: LairBuilder,
+    pub devices: Vec<DoomsdayDeviceBuilder>,
+}
+
+impl Blueprint {
+    /// Every problem in the blueprint; empty if it's valid. A blueprint that isn't valid TOML,
+    /// or has unknown tables or fields, or values of the wrong type, reports only the first of
+    /// those.
+    pub fn validate(contents: &str) -> Vec<BlueprintIssue> {
+        parse(contents).err().unwrap_or_default()
+    }
+
+    pub fn build_lair(&self) -> Result<Lair, EvilError> {
+        self.lair.clone().build()
+    }
+
+    pub fn build_devices(&self) -> Result<Vec<DoomsdayDevice>, EvilError> {
+        self.devices
+            .iter()
+            .cloned()
+            .map(DoomsdayDeviceBuilder::build)
+            .collect()
+    }
+}
+
+/// Parses and validates the blueprint. The error lists every issue found, see
+/// [`Blueprint::validate`].
+impl FromStr for Blueprint {
+    type Err = EvilError;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        parse(contents).map_err(|issues| EvilError::ParseError {
+            purpose: "blueprint".into(),
+            reason: issues
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("; "),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BlueprintFile {
+    lair: Option<LairTable>,
+    #[serde(default)]
+    trap: Vec<TrapTable>,
+    #[serde(default)]
+    device: Vec<DeviceTable>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct LairTable {
+    location: Spanned<String>,
+    vault_capacity: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TrapTable {
+    kind: Spanned<String>,
+    strength: Spanned<u8>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DeviceTable {
+    name: Spanned<String>,
+    power_watts: Option<u64>,
+    range_meters: Option<u64>,
+}
+
+fn parse(contents: &str) -> Result<Blueprint, Vec<BlueprintIssue>> {
+    let file: BlueprintFile = toml::from_str(contents).map_err(|error| {
+        vec![BlueprintIssue {
+            line: error.span().map_or(0, |span| line_of(contents, &span)),
+            field: None,
+            reason: error.message().trim().into(),
+        }]
+    })?;
+    let mut issues = Issues {
+        contents,
+        found: Vec::new(),
+    };
+
+    let lair = match file.lair {
+        Some(table) => issues.text("location", &table.location).map(|location| {
+            let lair = LairBuilder::new(location);
+            match table.vault_capacity {
+                Some(capacity) => lair.vault_capacity(capacity),
+                None => lair,
+            }
+        }),
+        None => {
+            issues.found.push(BlueprintIssue {
+                line: 0,
+                field: None,
+                reason: "Missing [lair] table".into(),
+            });
+            None
+        }
+    };
+    let traps = file
+        .trap
+        .iter()
+        .filter_map(|table| issues.trap(table))
+        .collect::<Vec<_>>();
+    let devices = file
+        .device
+        .iter()
+        .filter_map(|table| {
+            let mut device = DoomsdayDeviceBuilder::new(issues.text("name", &table.name)?);
+            if let Some(watts) = table.power_watts {
+                device = device.power(Power::from_watts(watts));
+            }
+            if let Some(meters) = table.range_meters {
+                device = device.range(Distance::from_meters(meters));
+            }
+            Some(device)
+        })
+        .collect();
+    match lair {
+        Some(lair) if issues.found.is_empty() => Ok(Blueprint {
+            lair: traps.into_iter().fold(lair, LairBuilder::trap),
+            devices,
+        }),
+        _ => Err(issues.found),
+    }
+}
+
+/// Line, starting at 1, where the span starts.
+fn line_of(contents: &str, span: &Range<usize>) -> usize {
+    contents
+        .get(..span.start)
+        .map_or(0, |before| before.matches('\n').count())
+        + 1
+}
+
+/// Problems found in the values of a blueprint that is otherwise well formed.
+struct Issues<'c> {
+    contents: &'c str,
+    found: Vec<BlueprintIssue>,
+}
+
+impl Issues<'_> {
+    fn trap(&mut self, table: &TrapTable) -> Option<Trap> {
+        let kind = self.text("kind", &table.kind);
+        let strength = *table.strength.get_ref();
+        if strength > MAX_TRAP_STRENGTH {
+            self.push(
+                &table.strength.span(),
+                "strength",
+                &format!("Greater than {MAX_TRAP_STRENGTH}"),
+            );
+        }
+        let kind = match kind?.parse::<TrapKind>() {
+            Ok(kind) => kind,
+            Err(_) => {
+                self.push(&table.kind.span(), "kind", "Unknown trap kind");
+                return None;
+            }
+        };
+        (strength <= MAX_TRAP_STRENGTH).then(|| Trap::new(kind, strength))
+    }
+
+    fn text<'t>(&mut self, field: &str, value: &'t Spanned<String>) -> Option<&'t str> {
+        let text = value.get_ref();
+        if text.trim().is_empty() {
+            self.push(&value.span(), field, "Empty");
+            return None;
+        }
+        Some(text)
+    }
+
+    fn push(&mut self, span: &Range<usize>, field: &str, reason: &str) {
+        self.found.push(BlueprintIssue {
+            line: line_of(self.contents, span),
+            field: Some(field.into()),
+            reason: reason.into(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::supervillain::MegaWeapon;
+    use assertables::assert_matches;
+
+    const BLUEPRINT: &str = r#"
+# Main base
+[lair]
+location = "Volcano Island"
+vault_capacity = 500
+
+[[trap]]
+kind = "Sharks"
+strength = 80
+
+[[trap]]
+kind = "Lasers"
+strength = 40
+
+[[device]]
+name = "Moon laser"
+power_watts = 5_000_000
+range_meters = 384400000
+"#;
+
+    #[test]
+    fn blueprint_builds_lair_and_devices() -> Result<(), EvilError> {
+        let blueprint = BLUEPRINT.parse::<Blueprint>()?;
+        let lair = blueprint.build_lair()?;
+        assert_eq!(lair.location, "Volcano Island");
+        assert_eq!(lair.vault.capacity(), Some(500));
+        assert_eq!(
+            lair.traps(),
+            [
+                Trap::new(TrapKind::Sharks, 80),
+                Trap::new(TrapKind::Lasers, 40)
+            ]
+        );
+
+        let devices = blueprint.build_devices()?;
+        assert_eq!(devices.len(), 1);
+        assert_eq!(devices[0].power(), Power::from_megawatts(5));
+        assert_eq!(devices[0].range(), Distance::from_meters(384_400_000));
+        Ok(())
+    }
+
+    #[test]
+    fn validation_reports_every_issue_with_its_line() {
+        let issues = Blueprint::validate(
+            "[lair]\n\
+             location = \"Moon\"\n\
+             [[trap]]\n\
+             kind = \"Piranhas\"\n\
+             strength = 120\n\
+             [[device]]\n\
+             name = \" \"\n",
+        );
+        assert_eq!(
+            issues,
+            vec![
+                BlueprintIssue {
+                    line: 5,
+                    field: Some("strength".into()),
+                    reason: "Greater than 100".into(),
+                },
+                BlueprintIssue {
+                    line: 4,
+                    field: Some("kind".into()),
+                    reason: "Unknown trap kind".into(),
+                },
+                BlueprintIssue {
+                    line: 7,
+                    field: Some("name".into()),
+                    reason: "Empty".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn malformed_blueprint_reports_where_it_went_wrong() {
+        let issues = Blueprint::validate("[lair]\nlocation = \"Moon\"\nmoat = 3\n");
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0].reason.contains("unknown field `moat`"));
+
+        let issues = Blueprint::validate("[lair]\nlocation = \"Moon\"\n[garage]\n");
+        assert_eq!(issues[0].line, 3);
+        assert!(issues[0].reason.contains("unknown field `garage`"));
+
+        let issues = Blueprint::validate("[lair]\nlocation = Moon\n");
+        assert_eq!(issues[0].line, 2);
+    }
+
+    #[test]
+    fn blueprint_without_lair_is_rejected() {
+        let result = "[[device]]\nname = \"Moon laser\"\n".parse::<Blueprint>();
+        assert_matches!(
+            result,
+            Err(EvilError::ParseError { purpose, reason })
+                if purpose == "blueprint" && reason == "line 0: Missing [lair] table"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Module to tell plans to many recipients at once, each with its own key.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::sidekick::Sidekick;
+use crate::supervillain::EvilError;
+
+/// Someone plans can be told to, like a sidekick or a syndicate member.
+#[cfg_attr(test, automock)]
+pub trait Recipient {
+    fn name(&self) -> String;
+
+    /// Tells the encoded envelope, shared with every other holder rather than copied.
+    fn tell(&self, message: &Arc<[u8]>) -> Result<(), EvilError>;
+}
+
+impl Recipient for Sidekick<'_> {
+    fn name(&self) -> String {
+        Sidekick::name(self).into()
+    }
+
+    fn tell(&self, message: &Arc<[u8]>) -> Result<(), EvilError> {
+        Sidekick::tell(self, message);
+        Ok(())
+    }
+}
+
+/// How telling the plans to one recipient went.
+#[derive(Debug)]
+pub struct Delivery {
+    pub recipient: String,
+    pub result: Result<(), EvilError>,
+}
+
+impl Delivery {
+    pub fn is_delivered(&self) -> bool {
+        self.result.is_ok()
+    }
+}
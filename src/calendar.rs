@@ -0,0 +1,139 @@
+//! Module for when sidekicks are busy or on holiday, so plans can be scheduled around it.
+#![allow(dead_code)]
+
+use std::time::SystemTime;
+
+use crate::supervillain::EvilError;
+
+/// Why someone is away.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Absence {
+    Busy,
+    Holiday,
+}
+
+/// Time someone is away, from its start up to, but not including, its end.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Unavailability {
+    pub absence: Absence,
+    pub start: SystemTime,
+    pub end: SystemTime,
+}
+
+impl Unavailability {
+    pub fn covers(&self, at: SystemTime) -> bool {
+        self.start <= at && at < self.end
+    }
+}
+
+/// Windows of time someone is unavailable. They may overlap.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AvailabilityCalendar {
+    windows: Vec<Unavailability>,
+}
+
+impl AvailabilityCalendar {
+    pub fn new() -> AvailabilityCalendar {
+        Self::default()
+    }
+
+    pub fn windows(&self) -> &[Unavailability] {
+        &self.windows
+    }
+
+    /// Marks the time from start to end as unavailable. The end has to come after the start.
+    pub fn block(
+        &mut self,
+        absence: Absence,
+        start: SystemTime,
+        end: SystemTime,
+    ) -> Result<(), EvilError> {
+        if end <= start {
+            return Err(EvilError::InvalidParameter {
+                parameter: "end".into(),
+                reason: "Must come after the start".into(),
+            });
+        }
+        self.windows.push(Unavailability {
+            absence,
+            start,
+            end,
+        });
+        Ok(())
+    }
+
+    pub fn busy(&mut self, start: SystemTime, end: SystemTime) -> Result<(), EvilError> {
+        self.block(Absence::Busy, start, end)
+    }
+
+    pub fn holiday(&mut self, start: SystemTime, end: SystemTime) -> Result<(), EvilError> {
+        self.block(Absence::Holiday, start, end)
+    }
+
+    /// The window covering the time, if any. When several do, the one ending last.
+    pub fn unavailability_at(&self, at: SystemTime) -> Option<&Unavailability> {
+        self.windows
+            .iter()
+            .filter(|window| window.covers(at))
+            .max_by_key(|window| window.end)
+    }
+
+    pub fn is_available_at(&self, at: SystemTime) -> bool {
+        self.unavailability_at(at).is_none()
+    }
+
+    /// First time from the given one with no window covering it, skipping back-to-back and
+    /// overlapping windows.
+    pub fn next_available_at(&self, from: SystemTime) -> SystemTime {
+        let mut at = from;
+        while let Some(window) = self.unavailability_at(at) {
+            at = window.end;
+        }
+        at
+    }
+
+    /// Forgets the windows already over by the given time.
+    pub fn clear_before(&mut self, at: SystemTime) {
+        self.windows.retain(|window| window.end > at);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use std::time::Duration;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    #[test]
+    fn next_availability_skips_back_to_back_windows() -> Result<(), EvilError> {
+        let now = SystemTime::now();
+        let mut calendar = AvailabilityCalendar::new();
+        calendar.busy(now, now + HOUR)?;
+        calendar.holiday(now + HOUR / 2, now + 3 * HOUR)?;
+        calendar.busy(now + 3 * HOUR, now + 4 * HOUR)?;
+        calendar.busy(now + 5 * HOUR, now + 6 * HOUR)?;
+
+        assert!(!calendar.is_available_at(now));
+        assert_eq!(calendar.next_available_at(now), now + 4 * HOUR);
+        assert_eq!(
+            calendar
+                .unavailability_at(now + HOUR)
+                .map(|window| window.absence),
+            Some(Absence::Holiday)
+        );
+        calendar.clear_before(now + 4 * HOUR);
+        assert_eq!(calendar.windows().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn window_has_to_end_after_it_starts() {
+        let now = SystemTime::now();
+        assert_matches!(
+            AvailabilityCalendar::new().holiday(now, now),
+            Err(EvilError::InvalidParameter { .. })
+        );
+    }
+}
@@ -0,0 +1,145 @@
+//! Module to inject faults into evil operations, to test how game logic copes with them.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::cell::RefCell;
+
+use rand::Rng;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+/// What can go wrong.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// The weapon doesn't fire; the attack fails with [`EvilError::WeaponJammed`].
+    ///
+    /// [`EvilError::WeaponJammed`]: crate::supervillain::EvilError::WeaponJammed
+    WeaponJam,
+    /// The henchman sent to deploy doesn't fight nor do the hard work.
+    IgnoredOrders,
+    /// The ciphered plans get scrambled on their way to the sidekick.
+    CorruptedCipher,
+}
+
+impl Fault {
+    fn index(self) -> usize {
+        match self {
+            Fault::WeaponJam => 0,
+            Fault::IgnoredOrders => 1,
+            Fault::CorruptedCipher => 2,
+        }
+    }
+}
+
+/// Injects each fault a percentage of the times it could happen. Register it on the villain,
+/// as [`SuperVillain::fault_injector`], to have its operations fail now and then.
+///
+/// Seeded, so that the same faults happen in the same order on every run.
+///
+/// [`SuperVillain::fault_injector`]: crate::supervillain::SuperVillain::fault_injector
+#[derive(Debug)]
+pub struct FaultInjector {
+    rates: [u8; 3],
+    injected: [Cell<u32>; 3],
+    rng: RefCell<StdRng>,
+}
+
+impl FaultInjector {
+    /// Injector with no faults yet.
+    pub fn new(seed: u64) -> FaultInjector {
+        Self {
+            rates: [0; 3],
+            injected: Default::default(),
+            rng: RefCell::new(StdRng::seed_from_u64(seed)),
+        }
+    }
+
+    /// Has the fault happen that percentage of the times, from 0 to 100.
+    pub fn with(mut self, fault: Fault, percent: u8) -> FaultInjector {
+        self.rates[fault.index()] = percent.min(100);
+        self
+    }
+
+    pub fn rate(&self, fault: Fault) -> u8 {
+        self.rates[fault.index()]
+    }
+
+    /// Times the fault has been injected so far.
+    pub fn injected(&self, fault: Fault) -> u32 {
+        self.injected[fault.index()].get()
+    }
+
+    /// Whether the fault happens this time.
+    pub fn strikes(&self, fault: Fault) -> bool {
+        let rate = self.rate(fault);
+        let strikes = rate > 0 && self.rng.borrow_mut().random_ratio(u32::from(rate), 100);
+        if strikes {
+            let injected = &self.injected[fault.index()];
+            injected.set(injected.get() + 1);
+        }
+        strikes
+    }
+
+    /// The text, reversed when [`Fault::CorruptedCipher`] strikes.
+    pub fn corrupt(&self, text: String) -> String {
+        if self.strikes(Fault::CorruptedCipher) {
+            text.chars().rev().collect()
+        } else {
+            text
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::henchman::Progress;
+    use crate::supervillain::EvilError;
+    use crate::supervillain::MockMegaWeapon;
+    use crate::supervillain::SuperVillain;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    #[test]
+    fn faults_follow_their_rates() {
+        let injector = FaultInjector::new(7)
+            .with(Fault::WeaponJam, 100)
+            .with(Fault::CorruptedCipher, 0);
+        assert!(injector.strikes(Fault::WeaponJam));
+        assert_eq!(injector.corrupt(String::from("plan")), "plan");
+        assert_eq!(injector.injected(Fault::WeaponJam), 1);
+        assert_eq!(injector.injected(Fault::CorruptedCipher), 0);
+    }
+
+    #[test]
+    fn jammed_weapon_never_fires() {
+        let villain = SuperVillain {
+            fault_injector: Some(FaultInjector::new(7).with(Fault::WeaponJam, 100)),
+            ..Default::default()
+        };
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().never();
+        assert_matches!(villain.attack(&weapon, false), Err(EvilError::WeaponJammed));
+    }
+
+    #[test]
+    fn henchmen_ignoring_orders_do_no_work() {
+        let villain = SuperVillain {
+            fault_injector: Some(FaultInjector::new(7).with(Fault::IgnoredOrders, 100)),
+            ..Default::default()
+        };
+        let mut henchman = MockHenchman::new();
+        henchman.expect_is_on_strike().return_const(false);
+        henchman.expect_fight_enemies().never();
+        henchman.expect_do_hard_things().never();
+        henchman
+            .expect_report_progress()
+            .return_const(Progress::default());
+        assert!(
+            villain
+                .start_world_domination_stage2(test_common::FIRST_TARGET, henchman)
+                .is_ok()
+        );
+    }
+}
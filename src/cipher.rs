@@ -1,9 +1,66 @@
 #![allow(dead_code)]
 
-#[cfg(test)]
+use alloc::string::String;
+
+use crate::error::EvilError;
+
+#[cfg(feature = "std")]
+pub mod bench;
+pub mod handshake;
+pub mod morse;
+pub mod secret_sharing;
+pub mod steganography;
+
+#[cfg(any(test, feature = "test-utils"))]
 use mockall::automock;
 
-#[cfg_attr(test, automock)]
+#[cfg_attr(any(test, feature = "test-utils"), automock)]
 pub trait Cipher {
     fn transform(&self, secret: &str, key: &str) -> String;
+
+    /// Name identifying the cipher, so receivers know how to decipher its output.
+    fn id(&self) -> String {
+        String::from("unknown")
+    }
+}
+
+/// Cipher whose output can be turned back into the secret by whoever has the key.
+pub trait Decipher: Cipher {
+    fn decipher(&self, ciphered: &str, key: &str) -> Result<String, EvilError>;
+}
+
+/// Cipher whose transformation has to be awaited, e.g. one backed by a remote HSM or KMS.
+#[allow(async_fn_in_trait)]
+pub trait AsyncCipher {
+    async fn transform(&self, secret: &str, key: &str) -> String;
+
+    /// Name identifying the cipher, so receivers know how to decipher its output.
+    fn id(&self) -> String {
+        String::from("unknown")
+    }
+}
+
+/// Adapter that exposes any synchronous [`Cipher`] as an [`AsyncCipher`].
+pub struct SyncCipherAdapter<C: Cipher> {
+    cipher: C,
+}
+
+impl<C: Cipher> SyncCipherAdapter<C> {
+    pub fn new(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    pub fn into_inner(self) -> C {
+        self.cipher
+    }
+}
+
+impl<C: Cipher> AsyncCipher for SyncCipherAdapter<C> {
+    async fn transform(&self, secret: &str, key: &str) -> String {
+        self.cipher.transform(secret, key)
+    }
+
+    fn id(&self) -> String {
+        self.cipher.id()
+    }
 }
@@ -0,0 +1,220 @@
+//! Measures how fast ciphers are at runtime, to pick the fastest one that is secure enough.
+
+use std::time::Duration;
+
+use super::Cipher;
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+
+const BENCH_KEY: &str = "benchmark-key";
+const PLAN_LINE: &str = "Step 1: steal the moon. Step 2: ransom it. Step 3: rule the world.\n";
+
+/// How hard a cipher is to break, from toy ciphers to serious cryptography.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SecurityLevel {
+    Toy,
+    Low,
+    Medium,
+    High,
+}
+
+/// Secrets of the sizes villains usually tell: a short order, a message and a full plan.
+pub fn representative_payloads() -> Vec<String> {
+    vec![
+        String::from("Attack at dawn"),
+        PLAN_LINE.repeat(8),
+        PLAN_LINE.repeat(64),
+    ]
+}
+
+/// How long a cipher took to transform the payloads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BenchResult {
+    pub id: String,
+    pub security: SecurityLevel,
+    pub bytes: usize,
+    pub elapsed: Duration,
+}
+
+impl BenchResult {
+    /// Bytes transformed per second.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return f64::INFINITY;
+        }
+        self.bytes as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Times `rounds` transformations of every payload with the cipher.
+pub fn bench(
+    cipher: &dyn Cipher,
+    payloads: &[String],
+    rounds: u32,
+    clock: &dyn Clock,
+) -> (usize, Duration) {
+    let started = clock.now();
+    let mut bytes = 0;
+    for _ in 0..rounds {
+        for payload in payloads {
+            std::hint::black_box(cipher.transform(payload, BENCH_KEY));
+            bytes += payload.len();
+        }
+    }
+    (bytes, clock.now().saturating_duration_since(started))
+}
+
+/// Registered ciphers, with their security level, to choose one from for
+/// [`SuperVillain::tell_plans`](crate::supervillain::SuperVillain::tell_plans).
+pub struct CipherSelector<'c> {
+    ciphers: Vec<(Box<dyn Cipher + 'c>, SecurityLevel)>,
+    payloads: Vec<String>,
+    rounds: u32,
+    clock: &'c dyn Clock,
+}
+
+impl Default for CipherSelector<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'c> CipherSelector<'c> {
+    /// Selector benchmarking with [`representative_payloads`] and the system clock.
+    pub fn new() -> CipherSelector<'c> {
+        Self {
+            ciphers: Vec::new(),
+            payloads: representative_payloads(),
+            rounds: 10,
+            clock: &SystemClock,
+        }
+    }
+
+    pub fn with_clock(mut self, clock: &'c dyn Clock) -> CipherSelector<'c> {
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_payloads(mut self, payloads: Vec<String>, rounds: u32) -> CipherSelector<'c> {
+        self.payloads = payloads;
+        self.rounds = rounds.max(1);
+        self
+    }
+
+    pub fn register<C: Cipher + 'c>(&mut self, cipher: C, security: SecurityLevel) {
+        self.ciphers.push((Box::new(cipher), security));
+    }
+
+    /// Benchmarks every registered cipher, fastest first.
+    pub fn bench(&self) -> Vec<BenchResult> {
+        let mut results = self
+            .ciphers
+            .iter()
+            .map(|(cipher, security)| self.result(cipher.as_ref(), *security))
+            .collect::<Vec<_>>();
+        results.sort_by_key(|result| result.elapsed);
+        results
+    }
+
+    /// Benchmarks the ciphers that are at least as secure as `minimum` and returns the fastest.
+    pub fn fastest_secure(&self, minimum: SecurityLevel) -> Option<&dyn Cipher> {
+        self.ciphers
+            .iter()
+            .filter(|(_, security)| *security >= minimum)
+            .map(|(cipher, security)| (cipher.as_ref(), self.result(cipher.as_ref(), *security)))
+            .min_by_key(|(_, result)| result.elapsed)
+            .map(|(cipher, _)| cipher)
+    }
+
+    fn result(&self, cipher: &dyn Cipher, security: SecurityLevel) -> BenchResult {
+        let (bytes, elapsed) = bench(cipher, &self.payloads, self.rounds, self.clock);
+        BenchResult {
+            id: cipher.id(),
+            security,
+            bytes,
+            elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::Instant;
+
+    use super::*;
+
+    /// Clock that only moves when ciphers spend time on it.
+    struct FakeClock(Cell<Instant>);
+
+    impl Clock for FakeClock {
+        fn now(&self) -> Instant {
+            self.0.get()
+        }
+    }
+
+    struct TimedCipher<'a> {
+        id: &'static str,
+        cost: Duration,
+        clock: &'a FakeClock,
+    }
+
+    impl Cipher for TimedCipher<'_> {
+        fn transform(&self, secret: &str, _key: &str) -> String {
+            self.clock.0.set(self.clock.0.get() + self.cost);
+            secret.into()
+        }
+
+        fn id(&self) -> String {
+            self.id.into()
+        }
+    }
+
+    fn selector(clock: &FakeClock) -> CipherSelector<'_> {
+        let mut selector = CipherSelector::new().with_clock(clock);
+        for (id, millis, security) in [
+            ("caesar", 1, SecurityLevel::Toy),
+            ("vault", 9, SecurityLevel::High),
+            ("scrambler", 4, SecurityLevel::Medium),
+        ] {
+            selector.register(
+                TimedCipher {
+                    id,
+                    cost: Duration::from_millis(millis),
+                    clock,
+                },
+                security,
+            );
+        }
+        selector
+    }
+
+    #[test]
+    fn bench_ranks_ciphers_fastest_first() {
+        let clock = FakeClock(Cell::new(Instant::now()));
+        let results = selector(&clock).bench();
+        assert_eq!(
+            results.iter().map(|r| r.id.as_str()).collect::<Vec<_>>(),
+            vec!["caesar", "scrambler", "vault"]
+        );
+        assert_eq!(results[0].elapsed, Duration::from_millis(30));
+        assert!(results[0].throughput() > results[1].throughput());
+    }
+
+    #[test]
+    fn fastest_secure_skips_ciphers_below_minimum() {
+        let clock = FakeClock(Cell::new(Instant::now()));
+        let selector = selector(&clock);
+        assert_eq!(
+            selector
+                .fastest_secure(SecurityLevel::Medium)
+                .map(|cipher| cipher.id()),
+            Some(String::from("scrambler"))
+        );
+        assert!(
+            CipherSelector::new()
+                .fastest_secure(SecurityLevel::Toy)
+                .is_none()
+        );
+    }
+}
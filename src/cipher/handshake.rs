@@ -0,0 +1,110 @@
+//! Handshake in which a villain and a sidekick agree on a cipher and a session key.
+
+use alloc::string::String;
+use core::fmt::Write;
+
+use super::Cipher;
+use crate::redacted::Redacted;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Cipher that can be offered or accepted during a handshake, named by its [`Cipher::id`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CipherSuite(String);
+
+impl CipherSuite {
+    pub fn new(id: &str) -> CipherSuite {
+        Self(id.into())
+    }
+
+    /// Suite of the given cipher.
+    pub fn of(cipher: &impl Cipher) -> CipherSuite {
+        Self(cipher.id())
+    }
+
+    pub fn id(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Outcome of a successful handshake: the agreed cipher and the key to use with it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub suite: CipherSuite,
+    pub key: Redacted<String>,
+    pub nonce: u64,
+}
+
+impl Session {
+    /// Derives the session key from the long-term shared key, the agreed suite and a nonce, so
+    /// every session ciphers with a different key.
+    pub fn establish(shared_key: &str, suite: CipherSuite, nonce: u64) -> Session {
+        let mut hash = FNV_OFFSET_BASIS;
+        let nonce = nonce.to_be_bytes();
+        let parts: [&[u8]; 3] = [shared_key.as_bytes(), suite.id().as_bytes(), &nonce];
+        for part in parts {
+            for &byte in part.iter().chain(&[0xff]) {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(FNV_PRIME);
+            }
+        }
+        let mut key = String::with_capacity(16);
+        let _ = write!(key, "{hash:016x}");
+        Self {
+            suite,
+            key: key.into(),
+            nonce: u64::from_be_bytes(nonce),
+        }
+    }
+
+    /// Whether the cipher is the one agreed on.
+    pub fn allows(&self, cipher: &impl Cipher) -> bool {
+        cipher.id() == self.suite.id()
+    }
+}
+
+/// Picks the first offered suite that is also supported, so the offer order is a preference.
+pub fn pick(offered: &[CipherSuite], supported: &[CipherSuite]) -> Option<CipherSuite> {
+    offered
+        .iter()
+        .find(|suite| supported.contains(suite))
+        .cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_none;
+
+    fn suites(ids: &[&str]) -> Vec<CipherSuite> {
+        ids.iter().map(|id| CipherSuite::new(id)).collect()
+    }
+
+    #[test]
+    fn pick_follows_offer_order() {
+        let offered = suites(&["one-time-pad", "caesar", "plus-wrap"]);
+        let supported = suites(&["plus-wrap", "caesar"]);
+        assert_eq!(pick(&offered, &supported), Some(CipherSuite::new("caesar")));
+    }
+
+    #[test]
+    fn pick_without_common_suite_is_none() {
+        assert_none!(pick(&suites(&["caesar"]), &suites(&["plus-wrap"])));
+    }
+
+    #[test]
+    fn session_key_depends_on_every_input() {
+        let key = |shared_key, suite, nonce| {
+            Session::establish(shared_key, CipherSuite::new(suite), nonce)
+                .key
+                .into_secret()
+        };
+        let base = key("Kryptonite", "caesar", 1);
+        assert_eq!(base, key("Kryptonite", "caesar", 1));
+        assert_eq!(base.len(), 16);
+        assert_ne!(base, key("Kryptonite", "caesar", 2));
+        assert_ne!(base, key("Kryptonite", "plus-wrap", 1));
+        assert_ne!(base, key("Lead", "caesar", 1));
+    }
+}
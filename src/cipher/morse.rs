@@ -0,0 +1,268 @@
+//! Morse code, for villains who still trust the telegraph.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::Cipher;
+use super::Decipher;
+use crate::error::EvilError;
+
+/// Code of every character Morse has, letters in uppercase.
+const CODES: [(char, &str); 54] = [
+    ('A', ".-"),
+    ('B', "-..."),
+    ('C', "-.-."),
+    ('D', "-.."),
+    ('E', "."),
+    ('F', "..-."),
+    ('G', "--."),
+    ('H', "...."),
+    ('I', ".."),
+    ('J', ".---"),
+    ('K', "-.-"),
+    ('L', ".-.."),
+    ('M', "--"),
+    ('N', "-."),
+    ('O', "---"),
+    ('P', ".--."),
+    ('Q', "--.-"),
+    ('R', ".-."),
+    ('S', "..."),
+    ('T', "-"),
+    ('U', "..-"),
+    ('V', "...-"),
+    ('W', ".--"),
+    ('X', "-..-"),
+    ('Y', "-.--"),
+    ('Z', "--.."),
+    ('0', "-----"),
+    ('1', ".----"),
+    ('2', "..---"),
+    ('3', "...--"),
+    ('4', "....-"),
+    ('5', "....."),
+    ('6', "-...."),
+    ('7', "--..."),
+    ('8', "---.."),
+    ('9', "----."),
+    ('.', ".-.-.-"),
+    (',', "--..--"),
+    ('?', "..--.."),
+    ('\'', ".----."),
+    ('!', "-.-.--"),
+    ('/', "-..-."),
+    ('(', "-.--."),
+    (')', "-.--.-"),
+    ('&', ".-..."),
+    (':', "---..."),
+    (';', "-.-.-."),
+    ('=', "-...-"),
+    ('+', ".-.-."),
+    ('-', "-....-"),
+    ('_', "..--.-"),
+    ('"', ".-..-."),
+    ('$', "...-..-"),
+    ('@', ".--.-."),
+];
+/// Written before the code of a letter to make it lowercase.
+const LOWERCASE: char = '^';
+/// Written before the hexadecimal code point of a character Morse doesn't have.
+const ESCAPE: char = '#';
+const WORD_SEPARATOR: &str = " / ";
+
+/// Writes plans in Morse code: characters separated by spaces and words by slashes. The key isn't
+/// used. [`MorseCipher::encode`] sticks to standard Morse; ciphering with [`Cipher::transform`]
+/// escapes what Morse doesn't have instead, so both round-trip through [`MorseCipher::decode`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MorseCipher;
+
+impl MorseCipher {
+    /// Writes the text in standard Morse code, failing rather than losing what can't be read
+    /// back: lowercase letters, characters Morse doesn't have, and whitespace other than single
+    /// spaces between words.
+    pub fn encode(&self, text: &str) -> Result<String, EvilError> {
+        if text.is_empty() {
+            return Ok(String::new());
+        }
+        let mut words = Vec::new();
+        for word in text.split(' ') {
+            if word.is_empty() {
+                return Err(unencodable(
+                    "Words must be separated by single spaces".into(),
+                ));
+            }
+            let mut codes = Vec::new();
+            for character in word.chars() {
+                let Some(code) = code_of(character) else {
+                    return Err(unencodable(format!("No code for '{character}'")));
+                };
+                codes.push(code);
+            }
+            words.push(codes.join(" "));
+        }
+        Ok(words.join(WORD_SEPARATOR))
+    }
+
+    /// Reads Morse code back into text, escapes included.
+    pub fn decode(&self, morse: &str) -> Result<String, EvilError> {
+        let mut words = Vec::new();
+        for word in morse.split(WORD_SEPARATOR) {
+            let mut text = String::new();
+            for code in word.split_whitespace() {
+                let character = if let Some(code) = code.strip_prefix(LOWERCASE) {
+                    character_of(code).map(|character| character.to_ascii_lowercase())
+                } else if let Some(hex) = code.strip_prefix(ESCAPE) {
+                    u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+                } else {
+                    character_of(code)
+                };
+                let Some(character) = character else {
+                    return Err(EvilError::ParseError {
+                        purpose: "morse".into(),
+                        reason: format!("Unknown code '{code}'"),
+                    });
+                };
+                text.push(character);
+            }
+            words.push(text);
+        }
+        Ok(words.join(" "))
+    }
+
+    fn escaped_code(character: char) -> String {
+        match code_of(character) {
+            Some(code) => code.into(),
+            None => match code_of(character.to_ascii_uppercase()) {
+                Some(code) => format!("{LOWERCASE}{code}"),
+                None => format!("{ESCAPE}{:x}", u32::from(character)),
+            },
+        }
+    }
+}
+
+/// Never loses anything: lowercase letters are written as their code after `^`, and characters
+/// Morse doesn't have, whitespace other than spaces included, as their hexadecimal code point
+/// after `#`. Use [`MorseCipher::encode`] to fail instead.
+impl Cipher for MorseCipher {
+    fn transform(&self, secret: &str, _key: &str) -> String {
+        secret
+            .split(' ')
+            .map(|word| {
+                word.chars()
+                    .map(Self::escaped_code)
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            })
+            .collect::<Vec<_>>()
+            .join(WORD_SEPARATOR)
+    }
+
+    fn id(&self) -> String {
+        String::from("morse")
+    }
+}
+
+/// Deciphers by decoding, so the secret comes back exactly as it was ciphered.
+impl Decipher for MorseCipher {
+    fn decipher(&self, ciphered: &str, _key: &str) -> Result<String, EvilError> {
+        self.decode(ciphered)
+    }
+}
+
+fn code_of(character: char) -> Option<&'static str> {
+    CODES
+        .iter()
+        .find(|(known, _)| *known == character)
+        .map(|&(_, code)| code)
+}
+
+fn character_of(code: &str) -> Option<char> {
+    CODES
+        .iter()
+        .find(|(_, known)| *known == code)
+        .map(|&(character, _)| character)
+}
+
+fn unencodable(reason: String) -> EvilError {
+    EvilError::InvalidParameter {
+        parameter: "text".into(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use proptest::collection;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+    use proptest::sample;
+
+    #[test]
+    fn plans_round_trip_through_morse() -> Result<(), EvilError> {
+        let morse = MorseCipher.transform("ATTACK AT DAWN!", "");
+        assert_eq!(morse, ".- - - .- -.-. -.- / .- - / -.. .- .-- -. -.-.--");
+        assert_eq!(MorseCipher.decode(&morse)?, "ATTACK AT DAWN!");
+        Ok(())
+    }
+
+    #[test]
+    fn what_morse_doesnt_have_is_escaped() -> Result<(), EvilError> {
+        let morse = MorseCipher.transform("Lex™", "");
+        assert_eq!(morse, ".-.. ^. ^-..- #2122");
+        assert_eq!(MorseCipher.decode(&morse)?, "Lex™");
+        for secret in ["", " Attack\tat  dawn ", "Attack at dawn!"] {
+            assert_eq!(
+                MorseCipher.decode(&MorseCipher.transform(secret, ""))?,
+                secret
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_codes_fail_to_decode() {
+        assert!(MorseCipher.decode(".-.-.-.-.-").is_err());
+    }
+
+    #[test]
+    fn what_cant_be_read_back_fails_to_encode() {
+        for lossy in [
+            "Attack at dawn",
+            "LEX™",
+            "ATTACK\tAT DAWN",
+            "ATTACK  AT DAWN",
+            " ATTACK",
+        ] {
+            assert_matches!(
+                MorseCipher.encode(lossy),
+                Err(EvilError::InvalidParameter { .. })
+            );
+        }
+    }
+
+    fn alphabet() -> Vec<char> {
+        CODES.iter().map(|&(character, _)| character).collect()
+    }
+
+    proptest! {
+        #[test]
+        fn supported_text_round_trips(
+            words in collection::vec(collection::vec(sample::select(alphabet()), 1..8), 1..6)
+        ) {
+            let text = words
+                .iter()
+                .map(|word| word.iter().collect::<String>())
+                .collect::<Vec<_>>()
+                .join(" ");
+            prop_assert_eq!(MorseCipher.decode(&MorseCipher.encode(&text)?)?, text);
+        }
+
+        #[test]
+        fn any_secret_round_trips_through_transform(secret in ".*") {
+            prop_assert_eq!(MorseCipher.decode(&MorseCipher.transform(&secret, ""))?, secret);
+        }
+    }
+}
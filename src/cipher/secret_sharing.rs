@@ -0,0 +1,182 @@
+//! Shamir's Secret Sharing, so a key can only be rebuilt when enough share holders agree.
+//!
+//! Every byte of the secret is split independently using polynomials over GF(256).
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use rand::Rng;
+
+use crate::error::EvilError;
+
+/// One of the shares a secret was split into.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyShare {
+    pub index: u8,
+    pub bytes: Vec<u8>,
+}
+
+/// Splits the secret into `count` shares, any `threshold` of which can rebuild it.
+pub fn split<R: Rng + ?Sized>(
+    secret: &[u8],
+    threshold: u8,
+    count: u8,
+    rng: &mut R,
+) -> Result<Vec<KeyShare>, EvilError> {
+    if threshold == 0 || threshold > count {
+        return Err(sharing_error(
+            "Threshold must be between 1 and the number of shares",
+        ));
+    }
+    let mut shares = (1..=count)
+        .map(|index| KeyShare {
+            index,
+            bytes: Vec::with_capacity(secret.len()),
+        })
+        .collect::<Vec<_>>();
+    let mut coefficients = vec![0u8; threshold as usize];
+    for &byte in secret {
+        coefficients[0] = byte;
+        for coefficient in coefficients.iter_mut().skip(1) {
+            *coefficient = rng.random();
+        }
+        for share in &mut shares {
+            share.bytes.push(evaluate(&coefficients, share.index));
+        }
+    }
+    Ok(shares)
+}
+
+/// Rebuilds the secret from the shares. With fewer shares than the threshold used to split it,
+/// the result is garbage.
+pub fn combine(shares: &[KeyShare]) -> Result<Vec<u8>, EvilError> {
+    let Some(first) = shares.first() else {
+        return Err(sharing_error("No shares"));
+    };
+    for (i, share) in shares.iter().enumerate() {
+        if share.index == 0 {
+            return Err(sharing_error("Share index can't be 0"));
+        }
+        if share.bytes.len() != first.bytes.len() {
+            return Err(sharing_error("Shares have different lengths"));
+        }
+        if shares[..i].iter().any(|other| other.index == share.index) {
+            return Err(sharing_error("Duplicate share"));
+        }
+    }
+
+    let weights = shares
+        .iter()
+        .map(|share| {
+            shares
+                .iter()
+                .filter(|other| other.index != share.index)
+                .fold(1, |weight, other| {
+                    mul(weight, div(other.index, other.index ^ share.index))
+                })
+        })
+        .collect::<Vec<_>>();
+    Ok((0..first.bytes.len())
+        .map(|position| {
+            shares
+                .iter()
+                .zip(&weights)
+                .fold(0, |secret, (share, &weight)| {
+                    secret ^ mul(share.bytes[position], weight)
+                })
+        })
+        .collect())
+}
+
+/// Rebuilds a textual key from the shares.
+pub fn recover_key(shares: &[KeyShare]) -> Result<String, EvilError> {
+    String::from_utf8(combine(shares)?).map_err(|_| sharing_error("Recovered key isn't text"))
+}
+
+fn evaluate(coefficients: &[u8], x: u8) -> u8 {
+    coefficients
+        .iter()
+        .rev()
+        .fold(0, |result, &coefficient| mul(result, x) ^ coefficient)
+}
+
+fn mul(mut a: u8, mut b: u8) -> u8 {
+    let mut product = 0;
+    while b != 0 {
+        if b & 1 != 0 {
+            product ^= a;
+        }
+        let carry = a & 0x80 != 0;
+        a <<= 1;
+        if carry {
+            a ^= 0x1b;
+        }
+        b >>= 1;
+    }
+    product
+}
+
+fn div(a: u8, b: u8) -> u8 {
+    // b^254 is the inverse of b in GF(256).
+    let mut inverse = 1;
+    for _ in 0..254 {
+        inverse = mul(inverse, b);
+    }
+    mul(a, inverse)
+}
+
+fn sharing_error(reason: &str) -> EvilError {
+    EvilError::SecretSharing {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const SECRET: &[u8] = b"Nobody should know this";
+
+    #[test]
+    fn any_threshold_shares_rebuild_secret() -> Result<(), EvilError> {
+        let shares = split(SECRET, 3, 5, &mut StdRng::seed_from_u64(7))?;
+        assert_eq!(combine(&shares[..3])?, SECRET);
+        assert_eq!(combine(&shares[2..])?, SECRET);
+        assert_eq!(
+            combine(&[shares[4].clone(), shares[0].clone(), shares[2].clone()])?,
+            SECRET
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fewer_shares_than_threshold_dont_rebuild_secret() -> Result<(), EvilError> {
+        let shares = split(SECRET, 3, 5, &mut StdRng::seed_from_u64(7))?;
+        assert_ne!(combine(&shares[..2])?, SECRET);
+        Ok(())
+    }
+
+    #[test]
+    fn threshold_above_share_count_is_rejected() {
+        let result = split(SECRET, 4, 3, &mut StdRng::seed_from_u64(7));
+        assert_matches!(result, Err(EvilError::SecretSharing { .. }));
+    }
+
+    #[test]
+    fn duplicate_shares_are_rejected() -> Result<(), EvilError> {
+        let shares = split(SECRET, 2, 3, &mut StdRng::seed_from_u64(7))?;
+        let result = combine(&[shares[0].clone(), shares[0].clone()]);
+        assert_matches!(result, Err(EvilError::SecretSharing { reason }) if reason == "Duplicate share");
+        Ok(())
+    }
+
+    #[test]
+    fn division_undoes_multiplication() {
+        for a in 1..=255u8 {
+            assert_eq!(div(mul(a, 0x53), 0x53), a);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! Hiding ciphered plans inside innocent-looking text, so nobody knows there's a plan at all.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::Cipher;
+use super::Decipher;
+use crate::error::EvilError;
+
+/// Invisible characters standing for the bits of the hidden plan.
+const ZERO: char = '\u{200B}';
+const ONE: char = '\u{200C}';
+
+/// Wraps a cipher to hide what it outputs in a cover text, as invisible characters after its
+/// first word. The cover text reads the same; [`Steganography::reveal`] gets the ciphered plan
+/// back out.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Steganography<C> {
+    cipher: C,
+    cover: String,
+}
+
+impl<C: Cipher> Steganography<C> {
+    pub fn new(cipher: C, cover: &str) -> Steganography<C> {
+        Self {
+            cipher,
+            cover: cover.into(),
+        }
+    }
+
+    pub fn cover(&self) -> &str {
+        &self.cover
+    }
+
+    pub fn into_inner(self) -> C {
+        self.cipher
+    }
+
+    /// The ciphered plan hidden in the text, empty if there's none.
+    pub fn reveal(&self, text: &str) -> Result<String, EvilError> {
+        let bits = text
+            .chars()
+            .filter_map(|c| match c {
+                ZERO => Some(0u8),
+                ONE => Some(1),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        if bits.len() % 8 != 0 {
+            return Err(reveal_error("Hidden bits are not whole bytes"));
+        }
+        let bytes = bits
+            .chunks(8)
+            .map(|byte| byte.iter().fold(0, |value, bit| value << 1 | bit))
+            .collect::<Vec<_>>();
+        String::from_utf8(bytes).map_err(|_| reveal_error("Hidden bytes are not UTF-8"))
+    }
+}
+
+impl<C: Cipher> Cipher for Steganography<C> {
+    fn transform(&self, secret: &str, key: &str) -> String {
+        let ciphered = self.cipher.transform(secret, key);
+        let position = self
+            .cover
+            .find(char::is_whitespace)
+            .unwrap_or(self.cover.len());
+        let mut text =
+            String::with_capacity(self.cover.len() + ciphered.len() * 8 * ZERO.len_utf8());
+        text.push_str(&self.cover[..position]);
+        for byte in ciphered.bytes() {
+            for shift in (0..8).rev() {
+                text.push(if byte >> shift & 1 == 1 { ONE } else { ZERO });
+            }
+        }
+        text.push_str(&self.cover[position..]);
+        text
+    }
+
+    fn id(&self) -> String {
+        let mut id = String::from("steganography+");
+        id.push_str(&self.cipher.id());
+        id
+    }
+}
+
+impl<C: Decipher> Decipher for Steganography<C> {
+    fn decipher(&self, ciphered: &str, key: &str) -> Result<String, EvilError> {
+        self.cipher.decipher(&self.reveal(ciphered)?, key)
+    }
+}
+
+fn reveal_error(reason: &str) -> EvilError {
+    EvilError::ParseError {
+        purpose: "steganography".into(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cipher::morse::MorseCipher;
+    use crate::test_common;
+
+    const COVER: &str = "Lovely weather for a picnic today.";
+
+    #[test]
+    fn hidden_plans_round_trip() -> Result<(), EvilError> {
+        let hidden = Steganography::new(MorseCipher, COVER);
+        let text = hidden.transform(test_common::MAIN_SECRET_MESSAGE, test_common::SHARED_KEY);
+        assert_eq!(
+            text.chars()
+                .filter(|&c| c != ZERO && c != ONE)
+                .collect::<String>(),
+            COVER
+        );
+        let morse = hidden.reveal(&text)?;
+        assert_eq!(
+            MorseCipher.decode(&morse)?,
+            test_common::MAIN_SECRET_MESSAGE
+        );
+        assert_eq!(hidden.id(), "steganography+morse");
+        Ok(())
+    }
+
+    #[test]
+    fn plain_cover_hides_nothing() -> Result<(), EvilError> {
+        let hidden = Steganography::new(MorseCipher, COVER);
+        assert_eq!(hidden.reveal(COVER)?, "");
+        assert!(hidden.reveal("\u{200B}").is_err());
+        Ok(())
+    }
+}
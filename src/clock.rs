@@ -0,0 +1,42 @@
+//! Module to tell the time, so time-dependent behavior can be tested.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+use std::time::Instant;
+use std::time::SystemTime;
+
+#[cfg(any(test, feature = "test-utils"))]
+use mockall::automock;
+
+/// Source of the current time.
+#[cfg_attr(any(test, feature = "test-utils"), automock)]
+pub trait Clock {
+    fn now(&self) -> Instant;
+
+    /// Current calendar time, e.g. to check availability windows. The system's by default.
+    fn system_time(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// Clock that tells the real time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Clocks shared between owners, e.g. every villain wired from an
+/// [`EvilContext`](crate::container::EvilContext).
+impl<C: Clock + ?Sized> Clock for Arc<C> {
+    fn now(&self) -> Instant {
+        (**self).now()
+    }
+
+    fn system_time(&self) -> SystemTime {
+        (**self).system_time()
+    }
+}
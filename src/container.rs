@@ -0,0 +1,190 @@
+//! Module for the container applications configure their evil stack in once, to build villains
+//! and the services they work with from it.
+#![allow(dead_code)]
+
+use std::any::Any;
+use std::any::TypeId;
+use std::any::type_name;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(test)]
+use mockall_double::double;
+
+use crate::clock::Clock;
+use crate::names::NameParser;
+use crate::personality::Personality;
+use crate::presets::Archetype;
+#[cfg_attr(test, double)]
+use crate::sidekick::Sidekick;
+use crate::supervillain::Cooldown;
+use crate::supervillain::EvilError;
+use crate::supervillain::SuperVillain;
+
+/// Clock shared by every villain of a context.
+pub type SharedClock = Arc<dyn Clock + Send + Sync>;
+
+/// Time between attacks of every villain of a context.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CooldownWindow(pub Duration);
+
+type Factory = Box<dyn Fn(&EvilContext) -> Result<Box<dyn Any>, EvilError> + Send + Sync>;
+
+/// Services registered by type: instances shared by everyone who resolves them, and factories
+/// making a new value for everyone who asks.
+///
+/// [`EvilContext::villain`] wires villains with the services it knows about: a [`NameParser`],
+/// a [`Personality`], an [`Archetype`], a [`SharedClock`], a [`CooldownWindow`],
+/// and a factory of sidekicks. Villains don't keep ciphers, henchmen or RNGs, so those are
+/// resolved or made from the context where they are used, e.g. when telling plans.
+#[derive(Default)]
+pub struct EvilContext {
+    services: HashMap<TypeId, Arc<dyn Any + Send + Sync>>,
+    factories: HashMap<TypeId, Factory>,
+}
+
+impl EvilContext {
+    pub fn new() -> EvilContext {
+        Self::default()
+    }
+
+    /// Registers the service, replacing any other one of the same type.
+    pub fn register<T: Any + Send + Sync>(mut self, service: T) -> EvilContext {
+        self.services.insert(TypeId::of::<T>(), Arc::new(service));
+        self
+    }
+
+    /// Registers how to make values of a type, replacing any other factory of it. The factory
+    /// can resolve other services from the context.
+    pub fn register_factory<T, F>(mut self, factory: F) -> EvilContext
+    where
+        T: Any,
+        F: Fn(&EvilContext) -> Result<T, EvilError> + Send + Sync + 'static,
+    {
+        self.factories.insert(
+            TypeId::of::<T>(),
+            Box::new(move |context| Ok(Box::new(factory(context)?))),
+        );
+        self
+    }
+
+    /// The registered service, if any.
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<&T> {
+        self.services.get(&TypeId::of::<T>())?.downcast_ref()
+    }
+
+    /// The registered service, shared.
+    pub fn resolve<T: Any + Send + Sync>(&self) -> Result<Arc<T>, EvilError> {
+        self.services
+            .get(&TypeId::of::<T>())
+            .cloned()
+            .and_then(|service| service.downcast().ok())
+            .ok_or_else(unresolved::<T>)
+    }
+
+    /// A new value from the registered factory.
+    pub fn make<T: Any>(&self) -> Result<T, EvilError> {
+        let factory = self
+            .factories
+            .get(&TypeId::of::<T>())
+            .ok_or_else(unresolved::<T>)?;
+        let value = factory(self)?;
+        Ok(*value
+            .downcast()
+            .expect("Factory registered under its own type"))
+    }
+
+    pub fn can_make<T: Any>(&self) -> bool {
+        self.factories.contains_key(&TypeId::of::<T>())
+    }
+
+    /// Villain with the full name, wired with the registered services listed on
+    /// [`EvilContext`]. Those not registered are left as a default villain has them; other
+    /// services are ignored.
+    pub fn villain(&self, full_name: &str) -> Result<SuperVillain<'static>, EvilError> {
+        let mut villain = SuperVillain::default();
+        if let Some(parser) = self.get::<NameParser>() {
+            villain.name_parser = *parser;
+        }
+        villain.try_set_full_name(full_name)?;
+        if let Some(personality) = self.get::<Personality>() {
+            villain.personality = *personality;
+        }
+        villain.archetype = self.get::<Archetype>().copied();
+        if let Some(clock) = self.get::<SharedClock>() {
+            villain.clock = Some(Box::new(Arc::clone(clock)));
+        }
+        if let Some(CooldownWindow(window)) = self.get::<CooldownWindow>() {
+            villain.cooldown = Some(match self.get::<SharedClock>() {
+                Some(clock) => Cooldown::with_clock(*window, Arc::clone(clock)),
+                None => Cooldown::new(*window),
+            });
+        }
+        if self.can_make::<Sidekick<'static>>() {
+            villain.sidekick = Some(self.make()?);
+        }
+        Ok(villain)
+    }
+}
+
+fn unresolved<T>() -> EvilError {
+    EvilError::UnresolvedService {
+        service: type_name::<T>().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::SystemClock;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn villains_are_wired_with_registered_services() -> Result<(), EvilError> {
+        let personality = Personality::new(90, 10, 10);
+        let clock: SharedClock = Arc::new(SystemClock);
+        let context = EvilContext::new()
+            .register(personality)
+            .register(Archetype::CrimeLord)
+            .register(CooldownWindow(Duration::from_secs(5)))
+            .register(clock)
+            .register_factory(|_| {
+                let mut sidekick = Sidekick::new();
+                sidekick.expect_agree().return_const(true);
+                Ok(sidekick)
+            });
+
+        let villain = context.villain(test_common::PRIMARY_FULL_NAME)?;
+        assert_eq!(villain.full_name(), test_common::PRIMARY_FULL_NAME);
+        assert_eq!(villain.personality, personality);
+        assert_eq!(villain.archetype, Some(Archetype::CrimeLord));
+        assert!(villain.cooldown.is_some());
+        assert!(villain.clock.is_some());
+        assert!(villain.sidekick.is_some_and(|sidekick| sidekick.agree()));
+        Ok(())
+    }
+
+    #[test]
+    fn factories_make_new_values_and_services_are_shared() -> Result<(), EvilError> {
+        let context = EvilContext::new()
+            .register(String::from(test_common::SHARED_KEY))
+            .register_factory(|context| {
+                let seed = context.resolve::<String>()?.len() as u64;
+                Ok(StdRng::seed_from_u64(seed))
+            });
+        assert!(Arc::ptr_eq(
+            &context.resolve::<String>()?,
+            &context.resolve::<String>()?
+        ));
+        assert!(context.make::<StdRng>().is_ok());
+        assert_matches!(
+            context.resolve::<Personality>(),
+            Err(EvilError::UnresolvedService { service }) if service.ends_with("Personality")
+        );
+        Ok(())
+    }
+}
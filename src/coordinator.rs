@@ -0,0 +1,322 @@
+//! Module coordinating the stages of the villains in a syndicate, which share a henchman pool and
+//! a gadget inventory.
+#![allow(dead_code)]
+
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::EvilError;
+
+/// Identifier of the resources reserved for a stage, used to release them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ReservationId(u64);
+
+/// Resources a villain needs to run a stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StageRequest {
+    pub villain: String,
+    pub stage: String,
+    pub henchmen: u32,
+    pub gadgets: Vec<String>,
+}
+
+impl StageRequest {
+    /// Request for no resources at all.
+    pub fn new(villain: &str, stage: &str) -> StageRequest {
+        Self {
+            villain: villain.into(),
+            stage: stage.into(),
+            henchmen: 0,
+            gadgets: Vec::new(),
+        }
+    }
+
+    pub fn with_henchmen(mut self, henchmen: u32) -> StageRequest {
+        self.henchmen = henchmen;
+        self
+    }
+
+    pub fn with_gadget(mut self, gadget: &str) -> StageRequest {
+        self.gadgets.push(gadget.into());
+        self
+    }
+}
+
+/// What happened to a request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Admission {
+    /// Resources reserved, the stage can run.
+    Granted(ReservationId),
+    /// Resources busy, the stage waits until they are released.
+    Queued,
+}
+
+/// Stage that got its resources after waiting for them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grant {
+    pub id: ReservationId,
+    pub request: StageRequest,
+}
+
+/// Reserves shared resources for stages of different villains.
+///
+/// Stages that fit are granted right away. The others wait and are granted as resources are
+/// released, villains served least recently first, so none of them hogs the pool. A villain
+/// keeps what it holds while one of its stages waits, so a wait closing a cycle between villains
+/// is a deadlock and gets rejected.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceCoordinator {
+    henchmen: u32,
+    free_henchmen: u32,
+    /// Every gadget, with the reservation holding it.
+    gadgets: BTreeMap<String, Option<ReservationId>>,
+    reservations: BTreeMap<ReservationId, StageRequest>,
+    waiting: Vec<StageRequest>,
+    /// When each villain got resources last, counting grants.
+    served: BTreeMap<String, u64>,
+    grants: u64,
+    next_id: u64,
+}
+
+impl ResourceCoordinator {
+    pub fn new(henchmen: u32) -> ResourceCoordinator {
+        Self {
+            henchmen,
+            free_henchmen: henchmen,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_gadget(mut self, gadget: &str) -> ResourceCoordinator {
+        self.gadgets.entry(gadget.into()).or_default();
+        self
+    }
+
+    pub fn free_henchmen(&self) -> u32 {
+        self.free_henchmen
+    }
+
+    /// Stages waiting for resources, in arrival order.
+    pub fn waiting(&self) -> &[StageRequest] {
+        &self.waiting
+    }
+
+    /// Reserves the resources for the stage, or queues it until they are released.
+    ///
+    /// Fails if the syndicate doesn't have the resources at all, or if waiting would deadlock.
+    pub fn reserve(&mut self, request: StageRequest) -> Result<Admission, EvilError> {
+        if request.henchmen > self.henchmen {
+            return Err(EvilError::NotEnoughItems {
+                item: String::from("henchmen"),
+                available: self.henchmen,
+                requested: request.henchmen,
+            });
+        }
+        if let Some(gadget) = request
+            .gadgets
+            .iter()
+            .find(|gadget| !self.gadgets.contains_key(*gadget))
+        {
+            return Err(EvilError::NotEnoughItems {
+                item: gadget.clone(),
+                available: 0,
+                requested: 1,
+            });
+        }
+        if self.fits(&request) {
+            return Ok(Admission::Granted(self.grant(request)));
+        }
+        let villain = request.villain.clone();
+        self.waiting.push(request);
+        if let Some(villains) = self.deadlock(&villain) {
+            self.waiting.pop();
+            return Err(EvilError::ResourceDeadlock { villains });
+        }
+        Ok(Admission::Queued)
+    }
+
+    /// Gives back the resources of the reservation and grants them to waiting stages. Releasing
+    /// an unknown reservation grants nothing.
+    pub fn release(&mut self, id: ReservationId) -> Vec<Grant> {
+        let Some(request) = self.reservations.remove(&id) else {
+            return Vec::new();
+        };
+        self.free_henchmen += request.henchmen;
+        for gadget in &request.gadgets {
+            self.gadgets.insert(gadget.clone(), None);
+        }
+        self.dispatch()
+    }
+
+    fn dispatch(&mut self) -> Vec<Grant> {
+        let mut granted = Vec::new();
+        loop {
+            let next = self
+                .waiting
+                .iter()
+                .enumerate()
+                .filter(|(_, request)| self.fits(request))
+                .min_by_key(|&(position, request)| {
+                    (self.served.get(&request.villain).copied(), position)
+                })
+                .map(|(position, _)| position);
+            let Some(position) = next else {
+                return granted;
+            };
+            let request = self.waiting.remove(position);
+            let id = self.grant(request.clone());
+            granted.push(Grant { id, request });
+        }
+    }
+
+    fn fits(&self, request: &StageRequest) -> bool {
+        request.henchmen <= self.free_henchmen
+            && request
+                .gadgets
+                .iter()
+                .all(|gadget| matches!(self.gadgets.get(gadget), Some(None)))
+    }
+
+    fn grant(&mut self, request: StageRequest) -> ReservationId {
+        let id = ReservationId(self.next_id);
+        self.next_id += 1;
+        self.grants += 1;
+        self.free_henchmen -= request.henchmen;
+        for gadget in &request.gadgets {
+            self.gadgets.insert(gadget.clone(), Some(id));
+        }
+        self.served.insert(request.villain.clone(), self.grants);
+        self.reservations.insert(id, request);
+        id
+    }
+
+    /// Other villains holding what a waiting stage of the villain needs. A stage waiting on the
+    /// villain's own earlier stage is just queued behind it.
+    fn waits_on(&self, villain: &str) -> BTreeSet<&str> {
+        let mut holders = BTreeSet::new();
+        for request in self.waiting.iter().filter(|r| r.villain == villain) {
+            for gadget in &request.gadgets {
+                if let Some(Some(holder)) = self.gadgets.get(gadget) {
+                    holders.insert(self.reservations[holder].villain.as_str());
+                }
+            }
+            if request.henchmen > self.free_henchmen {
+                holders.extend(
+                    self.reservations
+                        .values()
+                        .filter(|held| held.henchmen > 0)
+                        .map(|held| held.villain.as_str()),
+                );
+            }
+        }
+        holders.remove(villain);
+        holders
+    }
+
+    /// Villains waiting on each other in a cycle through the villain, if any.
+    fn deadlock(&self, villain: &str) -> Option<Vec<String>> {
+        let mut path = Vec::from([villain]);
+        let mut visited = BTreeSet::new();
+        self.cycle_to(villain, &mut path, &mut visited)
+            .then(|| path.into_iter().map(String::from).collect())
+    }
+
+    fn cycle_to<'s>(
+        &'s self,
+        start: &str,
+        path: &mut Vec<&'s str>,
+        visited: &mut BTreeSet<&'s str>,
+    ) -> bool {
+        let Some(&current) = path.last() else {
+            return false;
+        };
+        for holder in self.waits_on(current) {
+            if holder == start {
+                return true;
+            }
+            if visited.insert(holder) {
+                path.push(holder);
+                if self.cycle_to(start, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+
+    fn coordinator() -> ResourceCoordinator {
+        ResourceCoordinator::new(4)
+            .with_gadget("Shrink ray")
+            .with_gadget("Freeze ray")
+    }
+
+    #[test]
+    fn stages_wait_for_busy_resources() -> Result<(), EvilError> {
+        let mut coordinator = coordinator();
+        let lex = coordinator.reserve(
+            StageRequest::new("Lex", "build-hq")
+                .with_henchmen(3)
+                .with_gadget("Shrink ray"),
+        )?;
+        let Admission::Granted(lex) = lex else {
+            panic!("Lex should get the resources");
+        };
+        let joker = StageRequest::new("Joker", "deploy").with_henchmen(2);
+        assert_eq!(coordinator.reserve(joker.clone())?, Admission::Queued);
+
+        let granted = coordinator.release(lex);
+        assert_eq!(granted.len(), 1);
+        assert_eq!(granted[0].request, joker);
+        assert_eq!(coordinator.free_henchmen(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn villains_served_least_recently_go_first() -> Result<(), EvilError> {
+        let mut coordinator = coordinator();
+        let ray = |villain, stage| StageRequest::new(villain, stage).with_gadget("Freeze ray");
+        let Admission::Granted(first) = coordinator.reserve(ray("Lex", "stage-1"))? else {
+            panic!("The ray should be free");
+        };
+        coordinator.reserve(ray("Lex", "stage-2"))?;
+        coordinator.reserve(ray("Joker", "stage-1"))?;
+
+        let granted = coordinator.release(first);
+        assert_eq!(granted[0].request.villain, "Joker");
+        let granted = coordinator.release(granted[0].id);
+        assert_eq!(granted[0].request.stage, "stage-2");
+        Ok(())
+    }
+
+    #[test]
+    fn waiting_on_each_other_is_a_deadlock() -> Result<(), EvilError> {
+        let mut coordinator = coordinator();
+        coordinator.reserve(StageRequest::new("Lex", "hq").with_gadget("Shrink ray"))?;
+        coordinator.reserve(StageRequest::new("Joker", "hq").with_gadget("Freeze ray"))?;
+        coordinator.reserve(StageRequest::new("Lex", "attack").with_gadget("Freeze ray"))?;
+
+        let result =
+            coordinator.reserve(StageRequest::new("Joker", "attack").with_gadget("Shrink ray"));
+        assert_matches!(result, Err(EvilError::ResourceDeadlock { villains }) if villains.len() == 2);
+        assert_eq!(coordinator.waiting().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn resources_the_syndicate_lacks_are_rejected() {
+        let mut coordinator = coordinator();
+        let result = coordinator.reserve(StageRequest::new("Lex", "hq").with_henchmen(5));
+        assert_matches!(result, Err(EvilError::NotEnoughItems { available: 4, .. }));
+        let result = coordinator.reserve(StageRequest::new("Lex", "hq").with_gadget("Death ray"));
+        assert_matches!(result, Err(EvilError::NotEnoughItems { available: 0, .. }));
+    }
+}
@@ -0,0 +1,103 @@
+//! Module for the kinds of damage weapons deal and how well targets resist them.
+#![allow(dead_code)]
+
+use crate::units::Power;
+
+/// Damage a shot deals for every megawatt of weapon power.
+const DAMAGE_PER_MEGAWATT: u64 = 1;
+
+/// What a weapon or combatant hurts with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DamageKind {
+    #[default]
+    Kinetic,
+    Laser,
+    Cryo,
+    Psychic,
+}
+
+impl DamageKind {
+    pub const ALL: [DamageKind; 4] = [
+        DamageKind::Kinetic,
+        DamageKind::Laser,
+        DamageKind::Cryo,
+        DamageKind::Psychic,
+    ];
+
+    fn index(self) -> usize {
+        match self {
+            DamageKind::Kinetic => 0,
+            DamageKind::Laser => 1,
+            DamageKind::Cryo => 2,
+            DamageKind::Psychic => 3,
+        }
+    }
+}
+
+/// Resistance of a target to every kind of damage, in percent. 100 is immunity, and negative
+/// values are weaknesses that increase the damage taken, down to -100 for double damage.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Resistances([i8; 4]);
+
+impl Resistances {
+    /// Takes every kind of damage in full.
+    pub const fn none() -> Resistances {
+        Self([0; 4])
+    }
+
+    pub fn with(mut self, kind: DamageKind, percent: i8) -> Resistances {
+        self.0[kind.index()] = percent.clamp(-100, 100);
+        self
+    }
+
+    pub fn get(&self, kind: DamageKind) -> i8 {
+        self.0[kind.index()]
+    }
+
+    /// Kind of damage the target resists the least, the first in [`DamageKind::ALL`] on ties.
+    pub fn weakest_to(&self) -> DamageKind {
+        DamageKind::ALL
+            .into_iter()
+            .min_by_key(|&kind| self.get(kind))
+            .unwrap_or_default()
+    }
+}
+
+/// Damage left of `base` after the target's resistance to the kind.
+pub fn calculate(base: u32, kind: DamageKind, resistances: &Resistances) -> u32 {
+    let multiplier = u64::try_from(100 - i16::from(resistances.get(kind))).unwrap_or(0);
+    (u64::from(base) * multiplier / 100)
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
+
+/// Damage of a single shot from a weapon of that power, before resistances.
+pub fn per_shot(power: Power) -> u32 {
+    (power.watts() / 1_000_000 * DAMAGE_PER_MEGAWATT)
+        .try_into()
+        .unwrap_or(u32::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistances_scale_damage() {
+        let resistances = Resistances::none()
+            .with(DamageKind::Cryo, 100)
+            .with(DamageKind::Laser, 50)
+            .with(DamageKind::Psychic, -50);
+        assert_eq!(calculate(80, DamageKind::Kinetic, &resistances), 80);
+        assert_eq!(calculate(80, DamageKind::Laser, &resistances), 40);
+        assert_eq!(calculate(80, DamageKind::Cryo, &resistances), 0);
+        assert_eq!(calculate(80, DamageKind::Psychic, &resistances), 120);
+        assert_eq!(resistances.weakest_to(), DamageKind::Psychic);
+    }
+
+    #[test]
+    fn resistances_are_clamped() {
+        let resistances = Resistances::none().with(DamageKind::Kinetic, -128);
+        assert_eq!(calculate(10, DamageKind::Kinetic, &resistances), 20);
+    }
+}
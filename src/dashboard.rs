@@ -0,0 +1,153 @@
+//! Module with a snapshot of how world domination is going, for UIs to poll.
+#![allow(dead_code)]
+
+use std::collections::BTreeSet;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::pipeline::DominationContext;
+use crate::plan::StageOutcome;
+use crate::plan::portfolio::PlanPortfolio;
+use crate::supervillain::EvilError;
+
+/// A plan in the portfolio, as shown on the dashboard.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanStatus {
+    pub name: String,
+    pub steps: usize,
+    pub deadline: Option<u32>,
+    pub payoff: u32,
+    pub overdue: bool,
+}
+
+/// How world domination is going, at a given turn.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DominationStatus {
+    pub villain: String,
+    pub turn: u32,
+    /// Regions with the villain's lair or a secret HQ, sorted by name.
+    pub regions_controlled: Vec<String>,
+    pub infamy: u32,
+    pub treasury: u64,
+    /// Plans in the portfolio, most important first, then the overdue ones.
+    pub active_plans: Vec<PlanStatus>,
+    pub henchmen: usize,
+    pub henchmen_working: usize,
+    /// Percentage of the henchmen not on strike, from 0 to 100.
+    pub henchman_utilization: u8,
+}
+
+impl DominationStatus {
+    /// Takes a snapshot of the villain and henchmen in the context, the HQs built by the stages
+    /// completed so far and the plans in the portfolio.
+    pub fn capture(
+        context: &DominationContext,
+        outcomes: &[StageOutcome],
+        portfolio: &PlanPortfolio,
+    ) -> DominationStatus {
+        let villain = &context.villain;
+        let regions = villain
+            .lair
+            .iter()
+            .map(|lair| lair.location.clone())
+            .chain(outcomes.iter().filter_map(|outcome| match outcome {
+                StageOutcome::HqBuilt { target } => Some(target.clone()),
+                _ => None,
+            }))
+            .collect::<BTreeSet<_>>();
+        let turn = portfolio.turn();
+        let active_plans = portfolio
+            .ranked()
+            .into_iter()
+            .chain(portfolio.overdue())
+            .map(|active| PlanStatus {
+                name: active.name.clone(),
+                steps: active.plan.len(),
+                deadline: active.plan.deadline(),
+                payoff: active.payoff,
+                overdue: active.is_overdue(turn),
+            })
+            .collect();
+        let henchmen = context.henchmen.len();
+        let henchmen_working = context
+            .henchmen
+            .iter()
+            .filter(|henchman| !henchman.is_on_strike())
+            .count();
+        let henchman_utilization = match henchmen {
+            0 => 0,
+            henchmen => (henchmen_working * 100 / henchmen) as u8,
+        };
+        Self {
+            villain: villain.full_name(),
+            turn,
+            regions_controlled: regions.into_iter().collect(),
+            infamy: villain.total_infamy().points(),
+            treasury: villain.treasury.balance(),
+            active_plans,
+            henchmen,
+            henchmen_working,
+            henchman_utilization,
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, EvilError> {
+        serde_json::to_string(self).map_err(|error| EvilError::ParseError {
+            purpose: "dashboard".into(),
+            reason: error.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::lair::Lair;
+    use crate::plan::PlanGraph;
+    use crate::test_common;
+
+    fn henchman(on_strike: bool) -> MockHenchman {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_is_on_strike().return_const(on_strike);
+        henchman
+    }
+
+    #[test]
+    fn snapshot_aggregates_villain_state() -> Result<(), EvilError> {
+        let mut context = DominationContext::default();
+        context.villain.lair = Some(Lair::new(test_common::TARGETS[1]));
+        context.villain.treasury.deposit(250);
+        context.villain.infamy.gain(42);
+        context.hire(henchman(false));
+        context.hire(henchman(false));
+        context.hire(henchman(true));
+        let mut overdue = PlanGraph::new();
+        overdue.set_deadline(0);
+        let mut portfolio = PlanPortfolio::default();
+        portfolio.add("Rob the bank", overdue, 20);
+        portfolio.add("Steal the moon", PlanGraph::new(), 500);
+        portfolio.advance_turn();
+        let outcomes = [StageOutcome::HqBuilt {
+            target: test_common::FIRST_TARGET.into(),
+        }];
+
+        let status = DominationStatus::capture(&context, &outcomes, &portfolio);
+        assert_eq!(status.regions_controlled, ["Pamplona", "Tampa"]);
+        assert_eq!(status.infamy, 42);
+        assert_eq!(status.treasury, 250);
+        assert_eq!(status.henchman_utilization, 66);
+        let plans = status
+            .active_plans
+            .iter()
+            .map(|plan| (plan.name.as_str(), plan.overdue))
+            .collect::<Vec<_>>();
+        assert_eq!(plans, [("Steal the moon", false), ("Rob the bank", true)]);
+
+        let parsed: DominationStatus =
+            serde_json::from_str(&status.to_json()?).expect("Dashboard JSON should parse back");
+        assert_eq!(parsed, status);
+        Ok(())
+    }
+}
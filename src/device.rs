@@ -0,0 +1,202 @@
+//! Module for doomsday devices: the mega weapons villains build in their lairs.
+#![allow(dead_code)]
+
+use crate::damage::DamageKind;
+use crate::supervillain::EvilError;
+use crate::supervillain::MegaWeapon;
+use crate::units::Distance;
+use crate::units::Power;
+
+/// Mega weapon with a name, a power, a range and a kind of damage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoomsdayDevice {
+    pub name: String,
+    pub power: Power,
+    pub range: Distance,
+    pub damage_kind: DamageKind,
+}
+
+impl DoomsdayDevice {
+    pub fn builder(name: &str) -> DoomsdayDeviceBuilder {
+        DoomsdayDeviceBuilder::new(name)
+    }
+}
+
+impl MegaWeapon for DoomsdayDevice {
+    fn shoot(&self) {}
+
+    fn power(&self) -> Power {
+        self.power
+    }
+
+    fn range(&self) -> Distance {
+        self.range
+    }
+
+    fn damage_kind(&self) -> DamageKind {
+        self.damage_kind
+    }
+}
+
+/// Builder of [`DoomsdayDevice`]s. Power and range are zero and damage is kinetic unless set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoomsdayDeviceBuilder {
+    name: String,
+    power: Power,
+    range: Distance,
+    damage_kind: DamageKind,
+}
+
+impl DoomsdayDeviceBuilder {
+    pub fn new(name: &str) -> DoomsdayDeviceBuilder {
+        Self {
+            name: name.into(),
+            power: Power::ZERO,
+            range: Distance::ZERO,
+            damage_kind: DamageKind::default(),
+        }
+    }
+
+    pub fn power(mut self, power: Power) -> DoomsdayDeviceBuilder {
+        self.power = power;
+        self
+    }
+
+    pub fn range(mut self, range: Distance) -> DoomsdayDeviceBuilder {
+        self.range = range;
+        self
+    }
+
+    pub fn damage_kind(mut self, kind: DamageKind) -> DoomsdayDeviceBuilder {
+        self.damage_kind = kind;
+        self
+    }
+
+    /// Builds the device, failing if it has no name.
+    pub fn build(self) -> Result<DoomsdayDevice, EvilError> {
+        if self.name.trim().is_empty() {
+            return Err(EvilError::ParseError {
+                purpose: "doomsday_device".into(),
+                reason: "Missing name".into(),
+            });
+        }
+        Ok(DoomsdayDevice {
+            name: self.name,
+            power: self.power,
+            range: self.range,
+            damage_kind: self.damage_kind,
+        })
+    }
+}
+
+/// Marker for a required field of a [`WeaponBuilder`] that isn't set yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unset;
+
+/// Builder of [`DoomsdayDevice`]s that only builds once the power source and the kind of
+/// damage are set, so half-configured weapons don't compile. Range is zero unless set.
+///
+/// ```compile_fail
+/// # use evil::device::WeaponBuilder;
+/// # use evil::units::Power;
+/// let death_ray = WeaponBuilder::new("Death ray")
+///     .power_source(Power::from_watts(9_000))
+///     .build();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WeaponBuilder<P, K> {
+    name: String,
+    power: P,
+    damage_kind: K,
+    range: Distance,
+}
+
+impl WeaponBuilder<Unset, Unset> {
+    pub fn new(name: &str) -> WeaponBuilder<Unset, Unset> {
+        Self {
+            name: name.into(),
+            power: Unset,
+            damage_kind: Unset,
+            range: Distance::ZERO,
+        }
+    }
+}
+
+impl<K> WeaponBuilder<Unset, K> {
+    pub fn power_source(self, power: Power) -> WeaponBuilder<Power, K> {
+        WeaponBuilder {
+            name: self.name,
+            power,
+            damage_kind: self.damage_kind,
+            range: self.range,
+        }
+    }
+}
+
+impl<P> WeaponBuilder<P, Unset> {
+    pub fn damage_kind(self, kind: DamageKind) -> WeaponBuilder<P, DamageKind> {
+        WeaponBuilder {
+            name: self.name,
+            power: self.power,
+            damage_kind: kind,
+            range: self.range,
+        }
+    }
+}
+
+impl<P, K> WeaponBuilder<P, K> {
+    pub fn range(mut self, range: Distance) -> WeaponBuilder<P, K> {
+        self.range = range;
+        self
+    }
+}
+
+impl WeaponBuilder<Power, DamageKind> {
+    pub fn build(self) -> DoomsdayDevice {
+        DoomsdayDevice {
+            name: self.name,
+            power: self.power,
+            range: self.range,
+            damage_kind: self.damage_kind,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+
+    #[test]
+    fn builder_sets_power_and_range() {
+        let Ok(device) = DoomsdayDevice::builder("Moon laser")
+            .power(Power::from_watts(5_000))
+            .range(Distance::from_meters(300))
+            .build()
+        else {
+            panic!("Device should be built");
+        };
+        assert_eq!(device.power(), Power::from_watts(5_000));
+        assert_eq!(device.range(), Distance::from_meters(300));
+    }
+
+    #[test]
+    fn weapon_builder_builds_once_required_fields_are_set() {
+        let death_ray = WeaponBuilder::new("Death ray")
+            .range(Distance::from_meters(300))
+            .damage_kind(DamageKind::Laser)
+            .power_source(Power::from_watts(9_000))
+            .build();
+        assert_eq!(death_ray.power(), Power::from_watts(9_000));
+        assert_eq!(death_ray.damage_kind(), DamageKind::Laser);
+        assert_eq!(death_ray.range(), Distance::from_meters(300));
+    }
+
+    #[test]
+    fn device_without_name_is_rejected() {
+        assert_matches!(
+            DoomsdayDeviceBuilder::new(" ").build(),
+            Err(EvilError::ParseError { .. })
+        );
+    }
+}
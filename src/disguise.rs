@@ -0,0 +1,84 @@
+//! Module for the disguises villains hide behind, and heroes try to see through.
+#![allow(dead_code)]
+
+use rand::Rng;
+
+/// Quality a disguise loses every time it passes scrutiny.
+const WEAR_PER_SCRUTINY: u8 = 5;
+
+/// How a disguise did under scrutiny.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Scrutiny {
+    Held,
+    Blown,
+}
+
+/// A cover identity, as convincing as its quality, from 0 to 100.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Disguise {
+    pub cover: String,
+    quality: u8,
+}
+
+impl Disguise {
+    pub fn new(cover: &str, quality: u8) -> Disguise {
+        Self {
+            cover: cover.into(),
+            quality: quality.min(100),
+        }
+    }
+
+    pub fn quality(&self) -> u8 {
+        self.quality
+    }
+
+    /// Chance, between 0 and 1, the disguise holds against an investigation of that level,
+    /// from 0 to 100.
+    pub fn hold_chance(&self, investigation_level: u8) -> f64 {
+        let total = u32::from(self.quality) + u32::from(investigation_level);
+        if total == 0 {
+            1.0
+        } else {
+            f64::from(self.quality) / f64::from(total)
+        }
+    }
+
+    /// Puts the disguise to the test. It wears a little every time it holds.
+    pub fn resist_scrutiny<R: Rng + ?Sized>(
+        &mut self,
+        investigation_level: u8,
+        rng: &mut R,
+    ) -> Scrutiny {
+        let quality = u32::from(self.quality);
+        let total = quality + u32::from(investigation_level);
+        if total == 0 || rng.random_ratio(quality, total) {
+            self.quality = self.quality.saturating_sub(WEAR_PER_SCRUTINY);
+            Scrutiny::Held
+        } else {
+            Scrutiny::Blown
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn perfect_disguise_fools_lazy_heroes_but_wears() {
+        let mut disguise = Disguise::new("Clark Kent", 100);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(disguise.resist_scrutiny(0, &mut rng), Scrutiny::Held);
+        assert_eq!(disguise.quality(), 95);
+    }
+
+    #[test]
+    fn worthless_disguise_never_holds() {
+        let mut disguise = Disguise::new("Fake moustache", 0);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(disguise.resist_scrutiny(10, &mut rng), Scrutiny::Blown);
+        assert_eq!(disguise.hold_chance(10), 0.0);
+    }
+}
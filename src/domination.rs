@@ -0,0 +1,177 @@
+//! Module to run the stages of world domination in order, checked at compile time: stage 2 can
+//! only be started once stage 1 built the HQ.
+#![allow(dead_code)]
+
+use crate::Gadget;
+use crate::henchman::Henchman;
+use crate::henchman::Progress;
+use crate::plan::StageOutcome;
+use crate::supervillain::EvilError;
+use crate::supervillain::SuperVillain;
+
+/// No stage run yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotStarted;
+
+/// Stage 1 built the HQ.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage1Done {
+    pub hq: String,
+}
+
+/// Stage 2 deployed the henchmen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stage2Done {
+    pub hq: String,
+    pub progress: Progress,
+}
+
+/// World domination by a villain, at the stage its type says it reached.
+#[derive(Debug)]
+pub struct Domination<'v, 'a, S> {
+    villain: &'v SuperVillain<'a>,
+    stage: S,
+}
+
+impl<'v, 'a, S> Domination<'v, 'a, S> {
+    pub fn villain(&self) -> &'v SuperVillain<'a> {
+        self.villain
+    }
+
+    /// What the last stage run achieved.
+    pub fn stage(&self) -> &S {
+        &self.stage
+    }
+}
+
+impl<'v, 'a> Domination<'v, 'a, NotStarted> {
+    pub fn new(villain: &'v SuperVillain<'a>) -> Domination<'v, 'a, NotStarted> {
+        Self {
+            villain,
+            stage: NotStarted,
+        }
+    }
+
+    /// Runs [`SuperVillain::start_world_domination_stage1`], failing unless it built the HQ.
+    pub fn stage1<H: Henchman, G: Gadget>(
+        self,
+        henchman: &mut H,
+        gadget: &G,
+    ) -> Result<Domination<'v, 'a, Stage1Done>, EvilError> {
+        match self
+            .villain
+            .start_world_domination_stage1(henchman, gadget)?
+        {
+            StageOutcome::HqBuilt { target } => Ok(Domination {
+                villain: self.villain,
+                stage: Stage1Done { hq: target },
+            }),
+            outcome => Err(EvilError::StageFailed {
+                stage: "stage1".into(),
+                outcome,
+            }),
+        }
+    }
+}
+
+impl<'v, 'a> Domination<'v, 'a, Stage1Done> {
+    /// Deploys the henchman out of the HQ built in stage 1, failing unless it did.
+    pub fn stage2<H: Henchman>(
+        self,
+        henchman: H,
+    ) -> Result<Domination<'v, 'a, Stage2Done>, EvilError> {
+        match self
+            .villain
+            .start_world_domination_stage2(&self.stage.hq, henchman)?
+        {
+            StageOutcome::HenchmenDeployed { hq, progress, .. } => Ok(Domination {
+                villain: self.villain,
+                stage: Stage2Done { hq, progress },
+            }),
+            outcome => Err(EvilError::StageFailed {
+                stage: "stage2".into(),
+                outcome,
+            }),
+        }
+    }
+}
+
+impl<'a> SuperVillain<'a> {
+    /// World domination by this villain, to run stage by stage.
+    pub fn domination(&self) -> Domination<'_, 'a, NotStarted> {
+        Domination::new(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::MockGadget;
+    use crate::henchman::MockHenchman;
+    use crate::sidekick::MockSidekick;
+    use crate::supervillain::VillainStatus;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    fn villain_with_targets<'a>(targets: &[&str]) -> SuperVillain<'a> {
+        let targets = targets
+            .iter()
+            .map(|&target| String::from(target))
+            .collect::<Vec<_>>();
+        let mut sidekick = MockSidekick::new();
+        sidekick
+            .expect_get_weak_targets()
+            .returning(move |_| targets.clone());
+        SuperVillain {
+            sidekick: Some(sidekick),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stages_run_in_order() -> Result<(), EvilError> {
+        let gadget = MockGadget::new();
+        let villain = villain_with_targets(&[test_common::FIRST_TARGET]);
+        let mut henchman = MockHenchman::new();
+        henchman.expect_build_secret_hq().return_const(());
+        henchman.expect_is_on_strike().return_const(false);
+        henchman.expect_fight_enemies().return_const(());
+        henchman.expect_do_hard_things().return_const(());
+        henchman
+            .expect_report_progress()
+            .return_const(Progress::default());
+
+        let built = villain.domination().stage1(&mut henchman, &gadget)?;
+        assert_eq!(built.stage().hq, test_common::FIRST_TARGET);
+        let deployed = built.stage2(&mut henchman)?;
+        assert_eq!(deployed.stage().hq, test_common::FIRST_TARGET);
+        Ok(())
+    }
+
+    #[test]
+    fn stage1_without_hq_fails() {
+        let gadget = MockGadget::new();
+        let villain = villain_with_targets(&[]);
+        let result = villain
+            .domination()
+            .stage1(&mut MockHenchman::new(), &gadget);
+        assert_matches!(
+            result,
+            Err(EvilError::StageFailed {
+                outcome: StageOutcome::NoTargets,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn captured_villains_cannot_start() {
+        let gadget = MockGadget::new();
+        let mut villain = villain_with_targets(&[test_common::FIRST_TARGET]);
+        villain.status = VillainStatus::Captured;
+        let result = villain
+            .domination()
+            .stage1(&mut MockHenchman::new(), &gadget);
+        assert_matches!(result, Err(EvilError::Incarcerated));
+    }
+}
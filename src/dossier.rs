@@ -0,0 +1,274 @@
+//! Module for what villains know about each hero.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::time::SystemTime;
+
+use rand::Rng;
+
+use crate::gadget::Capability;
+use crate::gadget::Gadget;
+use crate::gossip::Rumor;
+use crate::skillcheck::CheckResult;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+
+const SIGHTING_BONUS: u32 = 2;
+const MAX_SIGHTINGS_BONUS: u32 = 20;
+const WEAKNESS_BONUS: u32 = 5;
+const MAX_WEAKNESSES_BONUS: u32 = 25;
+const MAX_INTEL_BONUS: u32 = 25;
+/// Reliability of what a successful interrogation gets out of someone.
+const INTERROGATION_RELIABILITY: u8 = 60;
+const CRITICAL_INTERROGATION_RELIABILITY: u8 = 100;
+
+/// Where and when a hero was seen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sighting {
+    pub location: String,
+    pub at: SystemTime,
+}
+
+/// Information gathered about a hero with a gadget, as reliable as the gadget is at
+/// surveillance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intel {
+    pub note: String,
+    pub reliability: u8,
+}
+
+/// Everything known about one hero.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeroDossier {
+    pub sightings: Vec<Sighting>,
+    pub weaknesses: BTreeSet<String>,
+    pub intel: Vec<Intel>,
+    /// Heard from allies; unverified, so it doesn't help aiming.
+    pub rumors: Vec<Rumor>,
+}
+
+impl HeroDossier {
+    pub fn last_seen(&self) -> Option<&Sighting> {
+        self.sightings.iter().max_by_key(|sighting| sighting.at)
+    }
+
+    /// Extra accuracy, from 0 to 70, against a hero that's been well researched: every sighting,
+    /// known weakness and reliable piece of intel helps, up to a limit each.
+    pub fn accuracy_bonus(&self) -> u8 {
+        let sightings = (self.sightings.len() as u32)
+            .saturating_mul(SIGHTING_BONUS)
+            .min(MAX_SIGHTINGS_BONUS);
+        let weaknesses = (self.weaknesses.len() as u32)
+            .saturating_mul(WEAKNESS_BONUS)
+            .min(MAX_WEAKNESSES_BONUS);
+        let intel = self
+            .intel
+            .iter()
+            .map(|intel| u32::from(intel.reliability) / 10)
+            .sum::<u32>()
+            .min(MAX_INTEL_BONUS);
+        (sightings + weaknesses + intel) as u8
+    }
+}
+
+/// Dossiers of every hero the villain has come across, by hero name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Dossiers {
+    heroes: BTreeMap<String, HeroDossier>,
+}
+
+impl Dossiers {
+    pub fn new() -> Dossiers {
+        Self::default()
+    }
+
+    pub fn get(&self, hero: &str) -> Option<&HeroDossier> {
+        self.heroes.get(hero)
+    }
+
+    /// Names of the heroes with a dossier, sorted.
+    pub fn heroes(&self) -> impl Iterator<Item = &str> {
+        self.heroes.keys().map(String::as_str)
+    }
+
+    pub fn record_sighting(&mut self, hero: &str, location: &str) {
+        self.record_sighting_at(hero, location, SystemTime::now());
+    }
+
+    pub fn record_sighting_at(&mut self, hero: &str, location: &str, at: SystemTime) {
+        self.entry(hero).sightings.push(Sighting {
+            location: location.into(),
+            at,
+        });
+    }
+
+    pub fn add_weakness(&mut self, hero: &str, weakness: &str) {
+        self.entry(hero).weaknesses.insert(weakness.into());
+    }
+
+    /// Spies on the hero with the gadget. Gadgets useless for surveillance gather nothing.
+    pub fn gather_intel(&mut self, hero: &str, note: &str, gadget: &dyn Gadget) -> bool {
+        let reliability = gadget.rating(Capability::Surveillance);
+        if reliability == 0 {
+            return false;
+        }
+        gadget.do_stuff();
+        self.entry(hero).intel.push(Intel {
+            note: note.into(),
+            reliability,
+        });
+        true
+    }
+
+    /// Files intel gathered some other way than spying with a gadget.
+    pub fn add_intel(&mut self, hero: &str, intel: Intel) {
+        self.entry(hero).intel.push(intel);
+    }
+
+    /// Interrogates someone who knows the hero, taking a skill check. Intel is only filed when
+    /// it succeeds, fully reliable on a critical success.
+    pub fn interrogate<R: Rng + ?Sized>(
+        &mut self,
+        hero: &str,
+        note: &str,
+        skill: u8,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> CheckResult {
+        let result = SkillCheck::roll(skill, difficulty, rng);
+        let reliability = match result {
+            CheckResult::CriticalSuccess => CRITICAL_INTERROGATION_RELIABILITY,
+            CheckResult::Success => INTERROGATION_RELIABILITY,
+            _ => return result,
+        };
+        self.add_intel(
+            hero,
+            Intel {
+                note: note.into(),
+                reliability,
+            },
+        );
+        result
+    }
+
+    /// Files gossip about the hero the rumor is about.
+    pub fn hear_rumor(&mut self, rumor: Rumor) {
+        self.entry(&rumor.hero).rumors.push(rumor);
+    }
+
+    /// Accuracy bonus against the hero; 0 for unknown heroes.
+    pub fn accuracy_bonus(&self, hero: &str) -> u8 {
+        self.get(hero).map_or(0, HeroDossier::accuracy_bonus)
+    }
+
+    fn entry(&mut self, hero: &str) -> &mut HeroDossier {
+        self.heroes.entry(hero.into()).or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::MockGadget;
+    use crate::test_common;
+    use assertables::assert_none;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::time::Duration;
+
+    fn surveillance_gadget(rating: u8, uses: usize) -> MockGadget {
+        let mut gadget = MockGadget::new();
+        gadget.expect_rating().return_const(rating);
+        gadget.expect_do_stuff().times(uses).return_const(());
+        gadget
+    }
+
+    #[test]
+    fn dossier_keeps_sighting_history() {
+        let mut dossiers = Dossiers::new();
+        let start = SystemTime::UNIX_EPOCH;
+        dossiers.record_sighting_at(
+            test_common::HERO_NAME,
+            "Metropolis",
+            start + Duration::from_secs(20),
+        );
+        dossiers.record_sighting_at(test_common::HERO_NAME, test_common::FIRST_TARGET, start);
+
+        let Some(dossier) = dossiers.get(test_common::HERO_NAME) else {
+            panic!("Hero should have a dossier");
+        };
+        assert_eq!(dossier.sightings.len(), 2);
+        assert_eq!(
+            dossier
+                .last_seen()
+                .map(|sighting| sighting.location.as_str()),
+            Some("Metropolis")
+        );
+        assert_none!(dossiers.get("Batman"));
+    }
+
+    #[test]
+    fn only_surveillance_gadgets_gather_intel() {
+        let mut dossiers = Dossiers::new();
+        assert!(dossiers.gather_intel(
+            test_common::HERO_NAME,
+            "Flies",
+            &surveillance_gadget(80, 1)
+        ));
+        assert!(!dossiers.gather_intel(
+            test_common::HERO_NAME,
+            "Wears a cape",
+            &surveillance_gadget(0, 0)
+        ));
+        assert_eq!(
+            dossiers
+                .get(test_common::HERO_NAME)
+                .map(|dossier| dossier.intel.len()),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn research_gives_capped_accuracy_bonus() {
+        let mut dossiers = Dossiers::new();
+        assert_eq!(dossiers.accuracy_bonus(test_common::HERO_NAME), 0);
+        for _ in 0..3 {
+            dossiers.record_sighting(test_common::HERO_NAME, "Metropolis");
+        }
+        dossiers.add_weakness(test_common::HERO_NAME, "Kryptonite");
+        dossiers.add_weakness(test_common::HERO_NAME, "Kryptonite");
+        dossiers.gather_intel(test_common::HERO_NAME, "Flies", &surveillance_gadget(90, 1));
+        assert_eq!(dossiers.accuracy_bonus(test_common::HERO_NAME), 6 + 5 + 9);
+
+        for i in 0..20 {
+            dossiers.add_weakness(test_common::HERO_NAME, &format!("Weakness {i}"));
+        }
+        assert_eq!(dossiers.accuracy_bonus(test_common::HERO_NAME), 6 + 25 + 9);
+    }
+
+    #[test]
+    fn only_successful_interrogations_file_intel() {
+        let mut dossiers = Dossiers::new();
+        let mut rng = StdRng::seed_from_u64(1);
+        let hopeless = dossiers.interrogate(
+            test_common::HERO_NAME,
+            "Hides in a fortress",
+            0,
+            Difficulty::NEARLY_IMPOSSIBLE,
+            &mut rng,
+        );
+        assert!(!hopeless.is_success());
+        assert_none!(dossiers.get(test_common::HERO_NAME));
+
+        let easy = dossiers.interrogate(
+            test_common::HERO_NAME,
+            "Allergic to kryptonite",
+            100,
+            Difficulty::TRIVIAL,
+            &mut rng,
+        );
+        assert!(easy.is_success());
+        assert!(dossiers.accuracy_bonus(test_common::HERO_NAME) >= 6);
+    }
+}
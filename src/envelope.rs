@@ -0,0 +1,330 @@
+//! Module for the envelope ciphered messages travel in.
+#![allow(dead_code)]
+
+use std::io;
+use std::iter;
+use std::sync::Arc;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use hmac::Hmac;
+use hmac::Mac;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Sha256;
+
+use crate::redacted::Redacted;
+use crate::supervillain::EvilError;
+
+/// Version of the envelope format produced by this crate.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+const BINARY_MAGIC: &[u8; 3] = b"EVL";
+
+/// Room taken in JSON by everything but the text of the sender, cipher id, payload and tag.
+const JSON_OVERHEAD: usize = 90;
+
+/// Ciphered message plus the metadata needed to make sense of it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageEnvelope {
+    pub version: u8,
+    pub sender: String,
+    /// Seconds since the Unix epoch.
+    pub timestamp: u64,
+    pub cipher_id: String,
+    pub payload: Redacted<String>,
+    /// HMAC-SHA256 of everything else, in hex, once sealed with [`MessageEnvelope::seal`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+}
+
+impl MessageEnvelope {
+    /// Wraps a ciphered payload in an envelope stamped with the current time.
+    pub fn new(sender: &str, cipher_id: &str, payload: String) -> MessageEnvelope {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            version: ENVELOPE_VERSION,
+            sender: sender.into(),
+            timestamp,
+            cipher_id: cipher_id.into(),
+            payload: payload.into(),
+            tag: None,
+        }
+    }
+
+    /// Tags the envelope with an HMAC keyed with the given key, so that tampering with any
+    /// field in transit is caught by [`MessageEnvelope::verify`].
+    pub fn seal(mut self, key: &str) -> MessageEnvelope {
+        let tag = self.mac(key).finalize().into_bytes();
+        self.tag = Some(tag.iter().map(|byte| format!("{byte:02x}")).collect());
+        self
+    }
+
+    /// Checks the tag against the key. Envelopes that were never sealed fail too.
+    pub fn verify(&self, key: &str) -> Result<(), EvilError> {
+        let tag = self
+            .tag
+            .as_deref()
+            .and_then(decode_hex)
+            .ok_or(EvilError::IntegrityFailure)?;
+        self.mac(key)
+            .verify_slice(&tag)
+            .map_err(|_| EvilError::IntegrityFailure)
+    }
+
+    /// Fed the unsealed fields one by one, so sealing a large payload doesn't copy it.
+    fn mac(&self, key: &str) -> Hmac<Sha256> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(key.as_bytes()).expect("HMAC takes keys of any length");
+        self.write_unsealed(|bytes| mac.update(bytes));
+        mac
+    }
+
+    /// Checks that the envelope can be handled by this crate.
+    pub fn validate(&self) -> Result<(), EvilError> {
+        if self.version != ENVELOPE_VERSION {
+            return Err(envelope_error(&format!(
+                "Unsupported version {}",
+                self.version
+            )));
+        }
+        if self.sender.is_empty() {
+            return Err(envelope_error("Missing sender"));
+        }
+        if self.cipher_id.is_empty() {
+            return Err(envelope_error("Missing cipher id"));
+        }
+        Ok(())
+    }
+
+    /// # Panics
+    ///
+    /// Never in practice, as every field is serializable. Deprecated with the `strict` feature;
+    /// use [`MessageEnvelope::try_to_json`] instead.
+    #[cfg_attr(feature = "strict", deprecated = "Use `try_to_json` instead")]
+    pub fn to_json(&self) -> String {
+        self.try_to_json()
+            .expect("Envelope fields are always serializable")
+    }
+
+    /// Sized up front, so a large payload is copied once instead of every time the buffer grows.
+    pub fn try_to_json(&self) -> Result<String, EvilError> {
+        let mut json = Vec::with_capacity(
+            JSON_OVERHEAD
+                + self.sender.len()
+                + self.cipher_id.len()
+                + self.payload.expose_secret().len()
+                + self.tag.as_ref().map_or(0, String::len),
+        );
+        serde_json::to_writer(&mut json, self)
+            .map_err(|error| envelope_error(&error.to_string()))?;
+        String::from_utf8(json).map_err(|error| envelope_error(&error.to_string()))
+    }
+
+    /// JSON encoding in a buffer allocated once at its exact size, to be shared with whoever
+    /// it's told to instead of copied.
+    pub fn try_to_wire(&self) -> Result<Arc<[u8]>, EvilError> {
+        let mut counter = ByteCounter(0);
+        serde_json::to_writer(&mut counter, self)
+            .map_err(|error| envelope_error(&error.to_string()))?;
+        let mut wire = iter::repeat_n(0, counter.0).collect::<Arc<[u8]>>();
+        let buffer = Arc::get_mut(&mut wire).expect("Wire bytes aren't shared yet");
+        serde_json::to_writer(buffer, self).map_err(|error| envelope_error(&error.to_string()))?;
+        Ok(wire)
+    }
+
+    pub fn from_json(json: &str) -> Result<MessageEnvelope, EvilError> {
+        serde_json::from_str(json).map_err(|error| envelope_error(&error.to_string()))
+    }
+
+    /// Reads an envelope encoded by [`MessageEnvelope::try_to_wire`].
+    pub fn from_wire(wire: &[u8]) -> Result<MessageEnvelope, EvilError> {
+        serde_json::from_slice(wire).map_err(|error| envelope_error(&error.to_string()))
+    }
+
+    /// Encodes the envelope as: magic `EVL`, version byte, big-endian `u64` timestamp, and then
+    /// sender, cipher id, payload and, if sealed, tag, each as a big-endian `u32` length followed
+    /// by UTF-8 bytes.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(
+            BINARY_MAGIC.len()
+                + 9
+                + 16
+                + self.sender.len()
+                + self.cipher_id.len()
+                + self.payload.expose_secret().len()
+                + self.tag.as_ref().map_or(0, String::len),
+        );
+        self.write_unsealed(|field| bytes.extend_from_slice(field));
+        if let Some(ref tag) = self.tag {
+            bytes.extend_from_slice(&(tag.len() as u32).to_be_bytes());
+            bytes.extend_from_slice(tag.as_bytes());
+        }
+        bytes
+    }
+
+    /// Writes everything but the tag, which is what the tag is computed over.
+    fn write_unsealed(&self, mut write: impl FnMut(&[u8])) {
+        write(BINARY_MAGIC);
+        write(&[self.version]);
+        write(&self.timestamp.to_be_bytes());
+        for field in [&self.sender, &self.cipher_id, self.payload.expose_secret()] {
+            write(&(field.len() as u32).to_be_bytes());
+            write(field.as_bytes());
+        }
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<MessageEnvelope, EvilError> {
+        let mut reader = ByteReader { bytes };
+        if reader.take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(envelope_error("Not an envelope"));
+        }
+        let version = reader.take(1)?[0];
+        let timestamp = u64::from_be_bytes(reader.take(8)?.try_into().expect("Took 8 bytes"));
+        let sender = reader.string()?;
+        let cipher_id = reader.string()?;
+        let payload = reader.string()?;
+        let tag = if reader.bytes.is_empty() {
+            None
+        } else {
+            Some(reader.string()?)
+        };
+        if !reader.bytes.is_empty() {
+            return Err(envelope_error("Trailing bytes"));
+        }
+        Ok(Self {
+            version,
+            sender,
+            timestamp,
+            cipher_id,
+            payload: payload.into(),
+            tag,
+        })
+    }
+}
+
+/// Writer that only counts the bytes written to it.
+struct ByteCounter(usize);
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    fn take(&mut self, count: usize) -> Result<&'a [u8], EvilError> {
+        if self.bytes.len() < count {
+            return Err(envelope_error("Truncated envelope"));
+        }
+        let (taken, rest) = self.bytes.split_at(count);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn string(&mut self) -> Result<String, EvilError> {
+        let length = u32::from_be_bytes(self.take(4)?.try_into().expect("Took 4 bytes"));
+        let bytes = self.take(length as usize)?;
+        String::from_utf8(bytes.to_vec()).map_err(|_| envelope_error("Field is not UTF-8"))
+    }
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn envelope_error(reason: &str) -> EvilError {
+    EvilError::ParseError {
+        purpose: "envelope".into(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    #[test]
+    fn json_encoding_round_trips() -> Result<(), EvilError> {
+        let envelope = envelope();
+        assert_eq!(
+            MessageEnvelope::from_json(&envelope.try_to_json()?)?,
+            envelope
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn wire_encoding_is_the_json_encoding() -> Result<(), EvilError> {
+        let envelope = envelope().seal(test_common::SHARED_KEY);
+        let wire = envelope.try_to_wire()?;
+        assert_eq!(&*wire, envelope.try_to_json()?.as_bytes());
+        assert_eq!(MessageEnvelope::from_wire(&wire)?, envelope);
+        Ok(())
+    }
+
+    #[test]
+    fn binary_encoding_round_trips() -> Result<(), EvilError> {
+        let envelope = envelope();
+        assert_eq!(MessageEnvelope::from_bytes(&envelope.to_bytes())?, envelope);
+        Ok(())
+    }
+
+    #[test]
+    fn sealed_envelope_survives_binary_encoding() -> Result<(), EvilError> {
+        let envelope = envelope().seal(test_common::SHARED_KEY);
+        let decoded = MessageEnvelope::from_bytes(&envelope.to_bytes())?;
+        decoded.verify(test_common::SHARED_KEY)?;
+        assert_matches!(decoded.verify("Lead"), Err(EvilError::IntegrityFailure));
+        Ok(())
+    }
+
+    #[test]
+    fn truncated_binary_envelope_is_rejected() {
+        let bytes = envelope().to_bytes();
+        let result = MessageEnvelope::from_bytes(&bytes[..bytes.len() - 1]);
+        assert_matches!(result, Err(EvilError::ParseError { reason, .. }) if reason == "Truncated envelope");
+    }
+
+    #[test]
+    fn unknown_version_is_invalid() {
+        let envelope = MessageEnvelope {
+            version: ENVELOPE_VERSION + 1,
+            ..envelope()
+        };
+        assert_matches!(envelope.validate(), Err(EvilError::ParseError { purpose, .. }) if purpose == "envelope");
+    }
+
+    #[test]
+    fn debug_output_hides_the_payload() {
+        let debug = format!("{:?}", envelope());
+        assert!(!debug.contains(test_common::MAIN_CIPHERED_MESSAGE));
+    }
+
+    fn envelope() -> MessageEnvelope {
+        MessageEnvelope::new(
+            test_common::PRIMARY_FULL_NAME,
+            test_common::CIPHER_ID,
+            test_common::MAIN_CIPHERED_MESSAGE.into(),
+        )
+    }
+}
@@ -0,0 +1,165 @@
+//! Module for the weather and terrain conditions evil is done in.
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::ops::Range;
+
+const BASE_INTENSE_SHOTS: Range<i32> = 1..3;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Weather {
+    #[default]
+    Clear,
+    Rain,
+    Fog,
+    Storm,
+    Snow,
+}
+
+impl Weather {
+    fn attack_modifier(&self) -> i32 {
+        match self {
+            Weather::Clear | Weather::Rain => 0,
+            Weather::Fog | Weather::Storm | Weather::Snow => -1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Terrain {
+    #[default]
+    Urban,
+    Desert,
+    Mountains,
+    Ocean,
+    Jungle,
+}
+
+impl Terrain {
+    fn attack_modifier(&self) -> i32 {
+        match self {
+            Terrain::Desert => 1,
+            Terrain::Urban => 0,
+            Terrain::Mountains | Terrain::Ocean | Terrain::Jungle => -1,
+        }
+    }
+
+    fn weakness_modifier(&self) -> i32 {
+        match self {
+            Terrain::Urban => 2,
+            Terrain::Desert => 0,
+            Terrain::Ocean | Terrain::Jungle => -1,
+            Terrain::Mountains => -2,
+        }
+    }
+}
+
+/// Conditions where the villain operates.
+///
+/// Targets get the terrain registered for them, or the environment's terrain when unknown.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Environment {
+    pub weather: Weather,
+    pub terrain: Terrain,
+    target_terrains: HashMap<String, Terrain>,
+}
+
+impl Environment {
+    pub fn new(weather: Weather, terrain: Terrain) -> Environment {
+        Self {
+            weather,
+            terrain,
+            target_terrains: HashMap::new(),
+        }
+    }
+
+    pub fn set_target_terrain(&mut self, target: &str, terrain: Terrain) {
+        self.target_terrains.insert(target.into(), terrain);
+    }
+
+    pub fn terrain_of(&self, target: &str) -> Terrain {
+        self.target_terrains
+            .get(target)
+            .copied()
+            .unwrap_or(self.terrain)
+    }
+
+    /// Range of extra shots fired in an intense attack under these conditions.
+    pub fn intense_shots(&self) -> Range<u32> {
+        let modifier = self.weather.attack_modifier() + self.terrain.attack_modifier();
+        let start = (BASE_INTENSE_SHOTS.start + modifier).max(0);
+        let end = (BASE_INTENSE_SHOTS.end + modifier).max(start + 1);
+        start as u32..end as u32
+    }
+
+    /// How much weaker (positive) or stronger (negative) the target is under these conditions.
+    pub fn weakness_modifier(&self, target: &str) -> i32 {
+        let terrain = self.terrain_of(target);
+        let weather = match (self.weather, terrain) {
+            (Weather::Storm, Terrain::Ocean) => 2,
+            (Weather::Snow, Terrain::Mountains) => -2,
+            _ => 0,
+        };
+        terrain.weakness_modifier() + weather
+    }
+
+    /// Ranks targets from weakest to strongest. Targets come ordered weakest first, so that
+    /// order is the base score the environment adjusts.
+    pub fn rank_targets(&self, targets: Vec<String>) -> Vec<String> {
+        let count = targets.len() as i32;
+        let mut scored = targets
+            .into_iter()
+            .enumerate()
+            .map(|(position, target)| {
+                let score = count - position as i32 + self.weakness_modifier(&target);
+                (score, target)
+            })
+            .collect::<Vec<_>>();
+        scored.sort_by_key(|(score, _)| Reverse(*score));
+        scored.into_iter().map(|(_, target)| target).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+
+    #[test]
+    fn default_environment_keeps_base_intense_shots() {
+        assert_eq!(Environment::default().intense_shots(), 1..3);
+    }
+
+    #[test]
+    fn harsh_conditions_reduce_intense_shots() {
+        let environment = Environment::new(Weather::Storm, Terrain::Mountains);
+        assert_eq!(environment.intense_shots(), 0..1);
+    }
+
+    #[test]
+    fn open_terrain_increases_intense_shots() {
+        let environment = Environment::new(Weather::Clear, Terrain::Desert);
+        assert_eq!(environment.intense_shots(), 2..4);
+    }
+
+    #[test]
+    fn default_environment_keeps_target_order() {
+        let targets = test_common::TARGETS.map(String::from).to_vec();
+        assert_eq!(
+            Environment::default().rank_targets(targets.clone()),
+            targets
+        );
+    }
+
+    #[test]
+    fn unfavorable_terrain_demotes_target() {
+        let mut environment = Environment::default();
+        environment.set_target_terrain(test_common::FIRST_TARGET, Terrain::Mountains);
+        let ranked = environment.rank_targets(test_common::TARGETS.map(String::from).to_vec());
+        assert_eq!(
+            ranked,
+            vec!["Pamplona", "Vilnius", test_common::FIRST_TARGET]
+        );
+    }
+}
@@ -0,0 +1,371 @@
+//! Module with the error type shared by the whole crate.
+
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+use thiserror::Error;
+
+use crate::plan::StageOutcome;
+
+pub mod catalog;
+
+use catalog::Language;
+use catalog::MessageKey;
+
+#[derive(Error, Debug)]
+pub enum EvilError {
+    #[error("Parse error: purpose='{}', reason='{}'", .purpose, .reason)]
+    ParseError { purpose: String, reason: String },
+    #[error("Plan has a cycle: steps={:?}", .steps)]
+    PlanCycle { steps: Vec<String> },
+    #[error("Unknown plan step: id={}", .id)]
+    UnknownStep { id: usize },
+    #[error("No henchmen available")]
+    NoHenchmen,
+    #[error("Villain is locked up")]
+    Incarcerated,
+    #[error("Escape failed: reason='{}'", .reason)]
+    EscapeFailed { reason: String },
+    #[error("Storage error: reason='{}'", .reason)]
+    StorageError { reason: String },
+    #[error("Secret sharing failed: reason='{}'", .reason)]
+    SecretSharing { reason: String },
+    #[error("Weapon overheated: remaining={:?}", .remaining)]
+    WeaponOverheated { remaining: Duration },
+    #[error("Insufficient funds: balance={}, requested={}", .balance, .requested)]
+    InsufficientFunds { balance: u64, requested: u64 },
+    #[error("Too heavy: capacity={}, weight={}", .capacity, .weight)]
+    TooHeavy { capacity: u32, weight: u32 },
+    #[error("Not enough items: item='{}', available={}, requested={}", .item, .available, .requested)]
+    NotEnoughItems {
+        item: String,
+        available: u32,
+        requested: u32,
+    },
+    #[error("No sidekick")]
+    NoSidekick,
+    #[error("Candidate rejected: candidate='{}', reason='{}'", .candidate, .reason)]
+    CandidateRejected { candidate: String, reason: String },
+    #[error("Notification error: reason='{}'", .reason)]
+    NotificationError { reason: String },
+    #[error("Henchmen on strike")]
+    HenchmenOnStrike,
+    #[error("Henchman unreliable: failures={}", .failures)]
+    HenchmanUnreliable { failures: u32 },
+    #[error("Queue full: capacity={}", .capacity)]
+    QueueFull { capacity: usize },
+    #[error("Invalid parameter: parameter='{}', reason='{}'", .parameter, .reason)]
+    InvalidParameter { parameter: String, reason: String },
+    #[error("Unknown order: id={}", .id)]
+    UnknownOrder { id: String },
+    #[error("Audit log tampered: sequence={}", .sequence)]
+    AuditTampered { sequence: u64 },
+    #[error("Unknown tech: name='{}'", .name)]
+    UnknownTech { name: String },
+    #[error("Missing prerequisite: tech='{}', prerequisite='{}'", .tech, .prerequisite)]
+    MissingPrerequisite { tech: String, prerequisite: String },
+    #[error("Weapon jammed")]
+    WeaponJammed,
+    #[error("Sidekick unavailable: reason='{}', remaining={:?}", .reason, .remaining)]
+    SidekickUnavailable { reason: String, remaining: Duration },
+    #[error("Unknown villain: id={}", .id)]
+    UnknownVillain { id: String },
+    #[error("Message integrity check failed")]
+    IntegrityFailure,
+    #[error("No plan found: goal='{}'", .goal)]
+    NoPlanFound { goal: String },
+    #[error("Resource deadlock: villains={:?}", .villains)]
+    ResourceDeadlock { villains: Vec<String> },
+    #[error("No mole planted: syndicate='{}'", .syndicate)]
+    NoMole { syndicate: String },
+    #[error("Service not registered: service='{}'", .service)]
+    UnresolvedService { service: String },
+    #[error("Nothing to lend")]
+    NothingToLend,
+    #[error("Loan refused")]
+    LoanRefused,
+    #[error("No matching key: cipher='{}'", .cipher)]
+    NoMatchingKey { cipher: String },
+    #[error("No common cipher")]
+    NoCommonCipher,
+    #[error("Stage failed: stage='{}', outcome={:?}", .stage, .outcome)]
+    StageFailed {
+        stage: String,
+        outcome: StageOutcome,
+    },
+    #[error("HQ still standing: location='{}'", .location)]
+    HqStillStanding { location: String },
+    #[error("Stage out of order: stage='{}'", .stage)]
+    StageOutOfOrder { stage: String },
+    /// Steps left done by a rollback, along with the error that called for it.
+    #[error("Rollback failed: steps={:?}, cause='{}'", .steps, .source)]
+    RollbackFailed {
+        steps: Vec<String>,
+        source: Box<EvilError>,
+    },
+    /// An error with the operations it happened in, outermost first. See [`ErrorContext`].
+    #[error("{}: {}", .breadcrumbs.join(" > "), .source)]
+    WithContext {
+        breadcrumbs: Vec<String>,
+        source: Box<EvilError>,
+    },
+}
+
+/// Adds breadcrumbs to errors, telling what was going on when they happened.
+pub trait ErrorContext<T> {
+    /// Wraps the error with the operation it happened in, e.g. `"scanning targets for stage 1"`.
+    fn context(self, operation: &str) -> Result<T, EvilError>;
+}
+
+impl<T> ErrorContext<T> for Result<T, EvilError> {
+    fn context(self, operation: &str) -> Result<T, EvilError> {
+        self.map_err(|error| error.context(operation))
+    }
+}
+
+impl EvilError {
+    /// Wraps the error with the operation it happened in. Wrapping an error that already has
+    /// context adds the operation as its outermost breadcrumb.
+    pub fn context(self, operation: &str) -> EvilError {
+        match self {
+            EvilError::WithContext {
+                mut breadcrumbs,
+                source,
+            } => {
+                breadcrumbs.insert(0, operation.into());
+                EvilError::WithContext {
+                    breadcrumbs,
+                    source,
+                }
+            }
+            error => EvilError::WithContext {
+                breadcrumbs: vec![operation.into()],
+                source: Box::new(error),
+            },
+        }
+    }
+
+    /// Operations the error happened in, outermost first.
+    pub fn breadcrumbs(&self) -> &[String] {
+        match self {
+            EvilError::WithContext { breadcrumbs, .. } => breadcrumbs,
+            _ => &[],
+        }
+    }
+
+    /// The error without any context.
+    pub fn root_cause(&self) -> &EvilError {
+        match self {
+            EvilError::WithContext { source, .. } => source,
+            error => error,
+        }
+    }
+
+    /// Key of the message of this error in the [`catalog`].
+    pub fn message_key(&self) -> MessageKey {
+        match self {
+            EvilError::ParseError { .. } => MessageKey::ParseError,
+            EvilError::PlanCycle { .. } => MessageKey::PlanCycle,
+            EvilError::UnknownStep { .. } => MessageKey::UnknownStep,
+            EvilError::NoHenchmen => MessageKey::NoHenchmen,
+            EvilError::Incarcerated => MessageKey::Incarcerated,
+            EvilError::EscapeFailed { .. } => MessageKey::EscapeFailed,
+            EvilError::StorageError { .. } => MessageKey::StorageError,
+            EvilError::SecretSharing { .. } => MessageKey::SecretSharing,
+            EvilError::WeaponOverheated { .. } => MessageKey::WeaponOverheated,
+            EvilError::InsufficientFunds { .. } => MessageKey::InsufficientFunds,
+            EvilError::TooHeavy { .. } => MessageKey::TooHeavy,
+            EvilError::NotEnoughItems { .. } => MessageKey::NotEnoughItems,
+            EvilError::NoSidekick => MessageKey::NoSidekick,
+            EvilError::CandidateRejected { .. } => MessageKey::CandidateRejected,
+            EvilError::NotificationError { .. } => MessageKey::NotificationError,
+            EvilError::HenchmenOnStrike => MessageKey::HenchmenOnStrike,
+            EvilError::HenchmanUnreliable { .. } => MessageKey::HenchmanUnreliable,
+            EvilError::QueueFull { .. } => MessageKey::QueueFull,
+            EvilError::InvalidParameter { .. } => MessageKey::InvalidParameter,
+            EvilError::UnknownOrder { .. } => MessageKey::UnknownOrder,
+            EvilError::AuditTampered { .. } => MessageKey::AuditTampered,
+            EvilError::UnknownTech { .. } => MessageKey::UnknownTech,
+            EvilError::MissingPrerequisite { .. } => MessageKey::MissingPrerequisite,
+            EvilError::WeaponJammed => MessageKey::WeaponJammed,
+            EvilError::SidekickUnavailable { .. } => MessageKey::SidekickUnavailable,
+            EvilError::UnknownVillain { .. } => MessageKey::UnknownVillain,
+            EvilError::IntegrityFailure => MessageKey::IntegrityFailure,
+            EvilError::NoPlanFound { .. } => MessageKey::NoPlanFound,
+            EvilError::ResourceDeadlock { .. } => MessageKey::ResourceDeadlock,
+            EvilError::NoMole { .. } => MessageKey::NoMole,
+            EvilError::UnresolvedService { .. } => MessageKey::UnresolvedService,
+            EvilError::NothingToLend => MessageKey::NothingToLend,
+            EvilError::LoanRefused => MessageKey::LoanRefused,
+            EvilError::NoMatchingKey { .. } => MessageKey::NoMatchingKey,
+            EvilError::NoCommonCipher => MessageKey::NoCommonCipher,
+            EvilError::StageFailed { .. } => MessageKey::StageFailed,
+            EvilError::HqStillStanding { .. } => MessageKey::HqStillStanding,
+            EvilError::StageOutOfOrder { .. } => MessageKey::StageOutOfOrder,
+            EvilError::RollbackFailed { .. } => MessageKey::RollbackFailed,
+            EvilError::WithContext { source, .. } => source.message_key(),
+        }
+    }
+
+    /// The error message in the language of the locale, e.g. `"es-ES"`, or in English if the
+    /// language isn't supported.
+    pub fn to_localized_string(&self, locale: &str) -> String {
+        if let EvilError::WithContext {
+            breadcrumbs,
+            source,
+        } = self
+        {
+            return format!(
+                "{}: {}",
+                breadcrumbs.join(" > "),
+                source.to_localized_string(locale)
+            );
+        }
+        let template = self.message_key().template(Language::for_locale(locale));
+        catalog::render(template, &self.message_arguments())
+    }
+
+    fn message_arguments(&self) -> Vec<(&'static str, String)> {
+        match self {
+            EvilError::ParseError { purpose, reason } => {
+                vec![("purpose", purpose.clone()), ("reason", reason.clone())]
+            }
+            EvilError::PlanCycle { steps } => vec![("steps", format!("{steps:?}"))],
+            EvilError::UnknownStep { id } => vec![("id", id.to_string())],
+            EvilError::EscapeFailed { reason }
+            | EvilError::StorageError { reason }
+            | EvilError::SecretSharing { reason }
+            | EvilError::NotificationError { reason } => vec![("reason", reason.clone())],
+            EvilError::WeaponOverheated { remaining } => {
+                vec![("remaining", format!("{remaining:?}"))]
+            }
+            EvilError::InsufficientFunds { balance, requested } => vec![
+                ("balance", balance.to_string()),
+                ("requested", requested.to_string()),
+            ],
+            EvilError::TooHeavy { capacity, weight } => vec![
+                ("capacity", capacity.to_string()),
+                ("weight", weight.to_string()),
+            ],
+            EvilError::NotEnoughItems {
+                item,
+                available,
+                requested,
+            } => vec![
+                ("item", item.clone()),
+                ("available", available.to_string()),
+                ("requested", requested.to_string()),
+            ],
+            EvilError::CandidateRejected { candidate, reason } => {
+                vec![("candidate", candidate.clone()), ("reason", reason.clone())]
+            }
+            EvilError::HenchmanUnreliable { failures } => vec![("failures", failures.to_string())],
+            EvilError::QueueFull { capacity } => vec![("capacity", capacity.to_string())],
+            EvilError::InvalidParameter { parameter, reason } => {
+                vec![("parameter", parameter.clone()), ("reason", reason.clone())]
+            }
+            EvilError::UnknownOrder { id } => vec![("id", id.clone())],
+            EvilError::AuditTampered { sequence } => vec![("sequence", sequence.to_string())],
+            EvilError::UnknownTech { name } => vec![("name", name.clone())],
+            EvilError::MissingPrerequisite { tech, prerequisite } => {
+                vec![
+                    ("tech", tech.clone()),
+                    ("prerequisite", prerequisite.clone()),
+                ]
+            }
+            EvilError::SidekickUnavailable { reason, remaining } => vec![
+                ("reason", reason.clone()),
+                ("remaining", format!("{remaining:?}")),
+            ],
+            EvilError::UnknownVillain { id } => vec![("id", id.clone())],
+            EvilError::NoPlanFound { goal } => vec![("goal", goal.clone())],
+            EvilError::ResourceDeadlock { villains } => vec![("villains", format!("{villains:?}"))],
+            EvilError::NoMole { syndicate } => vec![("syndicate", syndicate.clone())],
+            EvilError::UnresolvedService { service } => vec![("service", service.clone())],
+            EvilError::NoMatchingKey { cipher } => vec![("cipher", cipher.clone())],
+            EvilError::StageFailed { stage, outcome } => {
+                vec![
+                    ("stage", stage.clone()),
+                    ("outcome", format!("{outcome:?}")),
+                ]
+            }
+            EvilError::HqStillStanding { location } => vec![("location", location.clone())],
+            EvilError::StageOutOfOrder { stage } => vec![("stage", stage.clone())],
+            EvilError::RollbackFailed { steps, source } => vec![
+                ("steps", format!("{steps:?}")),
+                ("cause", source.to_string()),
+            ],
+            EvilError::WithContext { source, .. } => source.message_arguments(),
+            EvilError::NoHenchmen
+            | EvilError::Incarcerated
+            | EvilError::NoSidekick
+            | EvilError::HenchmenOnStrike
+            | EvilError::WeaponJammed
+            | EvilError::IntegrityFailure
+            | EvilError::NothingToLend
+            | EvilError::LoanRefused
+            | EvilError::NoCommonCipher => Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn english_messages_match_display() {
+        let errors = [
+            EvilError::InsufficientFunds {
+                balance: 40,
+                requested: 60,
+            },
+            EvilError::PlanCycle {
+                steps: vec![String::from("hq"), String::from("device")],
+            },
+            EvilError::NoSidekick,
+        ];
+        for error in errors {
+            assert_eq!(error.to_localized_string("en-US"), error.to_string());
+        }
+    }
+
+    #[test]
+    fn messages_are_translated_with_english_fallback() {
+        let error = EvilError::EscapeFailed {
+            reason: String::from("Tunnel collapsed"),
+        };
+        assert_eq!(
+            error.to_localized_string("es-MX"),
+            "La fuga fracasó: motivo='Tunnel collapsed'"
+        );
+        assert_eq!(
+            error.to_localized_string("fr"),
+            "L'évasion a échoué : raison='Tunnel collapsed'"
+        );
+        assert_eq!(error.to_localized_string("tlh"), error.to_string());
+    }
+
+    #[test]
+    fn context_piles_up_breadcrumbs() {
+        let result: Result<(), EvilError> = Err(EvilError::NoSidekick);
+        let error = result
+            .context("scanning targets for stage 1")
+            .context("starting world domination")
+            .unwrap_err();
+        assert_eq!(
+            error.breadcrumbs(),
+            ["starting world domination", "scanning targets for stage 1"]
+        );
+        assert!(matches!(error.root_cause(), EvilError::NoSidekick));
+        assert_eq!(
+            error.to_string(),
+            "starting world domination > scanning targets for stage 1: No sidekick"
+        );
+        assert_eq!(error.message_key(), MessageKey::NoSidekick);
+    }
+}
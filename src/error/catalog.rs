@@ -0,0 +1,297 @@
+//! Catalog of error messages in every supported language.
+//!
+//! Every catalog matches on every [`MessageKey`], so adding an error without translating it
+//! doesn't compile. Templates name their arguments between braces, e.g. `{reason}`.
+
+use alloc::string::String;
+
+/// Languages error messages can be rendered in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Language {
+    #[default]
+    English,
+    Spanish,
+    French,
+}
+
+impl Language {
+    /// Language of a locale like `"es-MX"` or `"fr_FR"`. Unknown languages fall back to
+    /// English.
+    pub fn for_locale(locale: &str) -> Language {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        if language.eq_ignore_ascii_case("es") {
+            Language::Spanish
+        } else if language.eq_ignore_ascii_case("fr") {
+            Language::French
+        } else {
+            Language::English
+        }
+    }
+}
+
+/// Identifies the message of each [`EvilError`](super::EvilError) variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MessageKey {
+    ParseError,
+    PlanCycle,
+    UnknownStep,
+    NoHenchmen,
+    Incarcerated,
+    EscapeFailed,
+    StorageError,
+    SecretSharing,
+    WeaponOverheated,
+    InsufficientFunds,
+    TooHeavy,
+    NotEnoughItems,
+    NoSidekick,
+    CandidateRejected,
+    NotificationError,
+    HenchmenOnStrike,
+    HenchmanUnreliable,
+    QueueFull,
+    InvalidParameter,
+    UnknownOrder,
+    AuditTampered,
+    UnknownTech,
+    MissingPrerequisite,
+    WeaponJammed,
+    SidekickUnavailable,
+    UnknownVillain,
+    IntegrityFailure,
+    NoPlanFound,
+    ResourceDeadlock,
+    NoMole,
+    UnresolvedService,
+    NothingToLend,
+    LoanRefused,
+    NoMatchingKey,
+    NoCommonCipher,
+    StageFailed,
+    HqStillStanding,
+    StageOutOfOrder,
+    RollbackFailed,
+}
+
+impl MessageKey {
+    pub fn template(&self, language: Language) -> &'static str {
+        match language {
+            Language::English => english(*self),
+            Language::Spanish => spanish(*self),
+            Language::French => french(*self),
+        }
+    }
+}
+
+/// Replaces every `{name}` in the template with the value of the argument of that name.
+pub fn render(template: &str, arguments: &[(&str, String)]) -> String {
+    let mut message = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        message.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let value = placeholder.find('}').and_then(|end| {
+            let name = &placeholder[1..end];
+            arguments
+                .iter()
+                .find(|(argument, _)| *argument == name)
+                .map(|(_, value)| (value, end))
+        });
+        match value {
+            Some((value, end)) => {
+                message.push_str(value);
+                rest = &placeholder[end + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = &placeholder[1..];
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+fn english(key: MessageKey) -> &'static str {
+    match key {
+        MessageKey::ParseError => "Parse error: purpose='{purpose}', reason='{reason}'",
+        MessageKey::PlanCycle => "Plan has a cycle: steps={steps}",
+        MessageKey::UnknownStep => "Unknown plan step: id={id}",
+        MessageKey::NoHenchmen => "No henchmen available",
+        MessageKey::Incarcerated => "Villain is locked up",
+        MessageKey::EscapeFailed => "Escape failed: reason='{reason}'",
+        MessageKey::StorageError => "Storage error: reason='{reason}'",
+        MessageKey::SecretSharing => "Secret sharing failed: reason='{reason}'",
+        MessageKey::WeaponOverheated => "Weapon overheated: remaining={remaining}",
+        MessageKey::InsufficientFunds => {
+            "Insufficient funds: balance={balance}, requested={requested}"
+        }
+        MessageKey::TooHeavy => "Too heavy: capacity={capacity}, weight={weight}",
+        MessageKey::NotEnoughItems => {
+            "Not enough items: item='{item}', available={available}, requested={requested}"
+        }
+        MessageKey::NoSidekick => "No sidekick",
+        MessageKey::CandidateRejected => {
+            "Candidate rejected: candidate='{candidate}', reason='{reason}'"
+        }
+        MessageKey::NotificationError => "Notification error: reason='{reason}'",
+        MessageKey::HenchmenOnStrike => "Henchmen on strike",
+        MessageKey::HenchmanUnreliable => "Henchman unreliable: failures={failures}",
+        MessageKey::QueueFull => "Queue full: capacity={capacity}",
+        MessageKey::InvalidParameter => {
+            "Invalid parameter: parameter='{parameter}', reason='{reason}'"
+        }
+        MessageKey::UnknownOrder => "Unknown order: id={id}",
+        MessageKey::AuditTampered => "Audit log tampered: sequence={sequence}",
+        MessageKey::UnknownTech => "Unknown tech: name='{name}'",
+        MessageKey::MissingPrerequisite => {
+            "Missing prerequisite: tech='{tech}', prerequisite='{prerequisite}'"
+        }
+        MessageKey::WeaponJammed => "Weapon jammed",
+        MessageKey::SidekickUnavailable => {
+            "Sidekick unavailable: reason='{reason}', remaining={remaining}"
+        }
+        MessageKey::UnknownVillain => "Unknown villain: id={id}",
+        MessageKey::IntegrityFailure => "Message integrity check failed",
+        MessageKey::NoPlanFound => "No plan found: goal='{goal}'",
+        MessageKey::ResourceDeadlock => "Resource deadlock: villains={villains}",
+        MessageKey::NoMole => "No mole planted: syndicate='{syndicate}'",
+        MessageKey::UnresolvedService => "Service not registered: service='{service}'",
+        MessageKey::NothingToLend => "Nothing to lend",
+        MessageKey::LoanRefused => "Loan refused",
+        MessageKey::NoMatchingKey => "No matching key: cipher='{cipher}'",
+        MessageKey::NoCommonCipher => "No common cipher",
+        MessageKey::StageFailed => "Stage failed: stage='{stage}', outcome={outcome}",
+        MessageKey::HqStillStanding => "HQ still standing: location='{location}'",
+        MessageKey::StageOutOfOrder => "Stage out of order: stage='{stage}'",
+        MessageKey::RollbackFailed => "Rollback failed: steps={steps}, cause='{cause}'",
+    }
+}
+
+fn spanish(key: MessageKey) -> &'static str {
+    match key {
+        MessageKey::ParseError => "Error de análisis: propósito='{purpose}', motivo='{reason}'",
+        MessageKey::PlanCycle => "El plan tiene un ciclo: pasos={steps}",
+        MessageKey::UnknownStep => "Paso del plan desconocido: id={id}",
+        MessageKey::NoHenchmen => "No hay secuaces disponibles",
+        MessageKey::Incarcerated => "El villano está encerrado",
+        MessageKey::EscapeFailed => "La fuga fracasó: motivo='{reason}'",
+        MessageKey::StorageError => "Error de almacenamiento: motivo='{reason}'",
+        MessageKey::SecretSharing => "Falló el reparto del secreto: motivo='{reason}'",
+        MessageKey::WeaponOverheated => "Arma sobrecalentada: restante={remaining}",
+        MessageKey::InsufficientFunds => {
+            "Fondos insuficientes: saldo={balance}, solicitado={requested}"
+        }
+        MessageKey::TooHeavy => "Demasiado pesado: capacidad={capacity}, peso={weight}",
+        MessageKey::NotEnoughItems => {
+            "Objetos insuficientes: objeto='{item}', disponibles={available}, \
+             solicitados={requested}"
+        }
+        MessageKey::NoSidekick => "Sin compinche",
+        MessageKey::CandidateRejected => {
+            "Candidato rechazado: candidato='{candidate}', motivo='{reason}'"
+        }
+        MessageKey::NotificationError => "Error de notificación: motivo='{reason}'",
+        MessageKey::HenchmenOnStrike => "Secuaces en huelga",
+        MessageKey::HenchmanUnreliable => "Secuaz poco fiable: fallos={failures}",
+        MessageKey::QueueFull => "Cola llena: capacidad={capacity}",
+        MessageKey::InvalidParameter => {
+            "Parámetro no válido: parámetro='{parameter}', motivo='{reason}'"
+        }
+        MessageKey::UnknownOrder => "Orden desconocida: id={id}",
+        MessageKey::AuditTampered => "Registro de auditoría alterado: secuencia={sequence}",
+        MessageKey::UnknownTech => "Tecnología desconocida: nombre='{name}'",
+        MessageKey::MissingPrerequisite => {
+            "Falta un requisito: tecnología='{tech}', requisito='{prerequisite}'"
+        }
+        MessageKey::WeaponJammed => "Arma encasquillada",
+        MessageKey::SidekickUnavailable => {
+            "Compinche no disponible: motivo='{reason}', restante={remaining}"
+        }
+        MessageKey::UnknownVillain => "Villano desconocido: id={id}",
+        MessageKey::IntegrityFailure => "Falló la verificación de integridad del mensaje",
+        MessageKey::NoPlanFound => "Ningún plan encontrado: objetivo='{goal}'",
+        MessageKey::ResourceDeadlock => "Bloqueo mutuo de recursos: villanos={villains}",
+        MessageKey::NoMole => "Ningún topo infiltrado: sindicato='{syndicate}'",
+        MessageKey::UnresolvedService => "Servicio no registrado: servicio='{service}'",
+        MessageKey::NothingToLend => "Nada que prestar",
+        MessageKey::LoanRefused => "Préstamo rechazado",
+        MessageKey::NoMatchingKey => "Ninguna clave coincide: cifrado='{cipher}'",
+        MessageKey::NoCommonCipher => "Ningún cifrado en común",
+        MessageKey::StageFailed => "La etapa falló: etapa='{stage}', resultado={outcome}",
+        MessageKey::HqStillStanding => "La guarida sigue en pie: ubicación='{location}'",
+        MessageKey::StageOutOfOrder => "Etapa fuera de orden: etapa='{stage}'",
+        MessageKey::RollbackFailed => "La reversión falló: etapas={steps}, causa='{cause}'",
+    }
+}
+
+fn french(key: MessageKey) -> &'static str {
+    match key {
+        MessageKey::ParseError => "Erreur d'analyse : objet='{purpose}', raison='{reason}'",
+        MessageKey::PlanCycle => "Le plan contient un cycle : étapes={steps}",
+        MessageKey::UnknownStep => "Étape du plan inconnue : id={id}",
+        MessageKey::NoHenchmen => "Aucun homme de main disponible",
+        MessageKey::Incarcerated => "Le méchant est sous les verrous",
+        MessageKey::EscapeFailed => "L'évasion a échoué : raison='{reason}'",
+        MessageKey::StorageError => "Erreur de stockage : raison='{reason}'",
+        MessageKey::SecretSharing => "Échec du partage du secret : raison='{reason}'",
+        MessageKey::WeaponOverheated => "Arme en surchauffe : restant={remaining}",
+        MessageKey::InsufficientFunds => {
+            "Fonds insuffisants : solde={balance}, demandé={requested}"
+        }
+        MessageKey::TooHeavy => "Trop lourd : capacité={capacity}, poids={weight}",
+        MessageKey::NotEnoughItems => {
+            "Objets insuffisants : objet='{item}', disponibles={available}, \
+             demandés={requested}"
+        }
+        MessageKey::NoSidekick => "Pas d'acolyte",
+        MessageKey::CandidateRejected => {
+            "Candidat refusé : candidat='{candidate}', raison='{reason}'"
+        }
+        MessageKey::NotificationError => "Erreur de notification : raison='{reason}'",
+        MessageKey::HenchmenOnStrike => "Hommes de main en grève",
+        MessageKey::HenchmanUnreliable => "Homme de main peu fiable : échecs={failures}",
+        MessageKey::QueueFull => "File pleine : capacité={capacity}",
+        MessageKey::InvalidParameter => {
+            "Paramètre invalide : paramètre='{parameter}', raison='{reason}'"
+        }
+        MessageKey::UnknownOrder => "Ordre inconnu : id={id}",
+        MessageKey::AuditTampered => "Journal d'audit falsifié : séquence={sequence}",
+        MessageKey::UnknownTech => "Technologie inconnue : nom='{name}'",
+        MessageKey::MissingPrerequisite => {
+            "Prérequis manquant : technologie='{tech}', prérequis='{prerequisite}'"
+        }
+        MessageKey::WeaponJammed => "Arme enrayée",
+        MessageKey::SidekickUnavailable => {
+            "Acolyte indisponible : raison='{reason}', restant={remaining}"
+        }
+        MessageKey::UnknownVillain => "Méchant inconnu : id={id}",
+        MessageKey::IntegrityFailure => "Échec de la vérification d'intégrité du message",
+        MessageKey::NoPlanFound => "Aucun plan trouvé : objectif='{goal}'",
+        MessageKey::ResourceDeadlock => "Interblocage de ressources : méchants={villains}",
+        MessageKey::NoMole => "Aucune taupe infiltrée : syndicat='{syndicate}'",
+        MessageKey::UnresolvedService => "Service non enregistré : service='{service}'",
+        MessageKey::NothingToLend => "Rien à prêter",
+        MessageKey::LoanRefused => "Prêt refusé",
+        MessageKey::NoMatchingKey => "Aucune clé correspondante : chiffrement='{cipher}'",
+        MessageKey::NoCommonCipher => "Aucun chiffrement en commun",
+        MessageKey::StageFailed => "L'étape a échoué : étape='{stage}', résultat={outcome}",
+        MessageKey::HqStillStanding => "Le repaire est toujours debout : lieu='{location}'",
+        MessageKey::StageOutOfOrder => "Étape hors d'ordre : étape='{stage}'",
+        MessageKey::RollbackFailed => "L'annulation a échoué : étapes={steps}, cause='{cause}'",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_fills_known_placeholders_only() {
+        let message = render(
+            "{who} wants {what}, not {unknown}",
+            &[("who", "Lex".into()), ("what", "the moon".into())],
+        );
+        assert_eq!(message, "Lex wants the moon, not {unknown}");
+    }
+}
@@ -0,0 +1,259 @@
+//! Module for moles: henchmen planted in rival syndicates to report on them, until their cover
+//! gets too thin and they have to be pulled out.
+#![allow(dead_code)]
+
+use rand::Rng;
+
+use crate::id::HenchmanId;
+use crate::skillcheck::CheckResult;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+use crate::supervillain::EvilError;
+
+/// Turns between intel reports.
+pub const REPORT_INTERVAL: u32 = 3;
+/// Discovery risk, in percent, from which a mole asks to be extracted.
+pub const EXTRACTION_RISK: u8 = 50;
+/// Discovery risk a clumsy mole adds every turn undercover.
+const RISK_PER_TURN: u32 = 5;
+/// Discovery risk never gets any higher: there's always a chance to stay hidden.
+const MAX_RISK: u32 = 95;
+/// Reliability of the first report, growing every turn as the mole gets closer to the bosses.
+const FIRST_REPORT_RELIABILITY: u32 = 40;
+const RELIABILITY_PER_TURN: u32 = 5;
+
+/// Henchman working undercover in a rival syndicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mole {
+    pub henchman: HenchmanId,
+    pub syndicate: String,
+    /// Skill at staying hidden, from 0 to 100. It halves the risk at best.
+    pub skill: u8,
+    pub turns_undercover: u32,
+}
+
+impl Mole {
+    /// Chance, in percent, of being found out this turn. It grows with every turn undercover.
+    pub fn discovery_risk(&self) -> u8 {
+        let slowdown = 100 - u32::from(self.skill.min(100)) / 2;
+        let risk = self.turns_undercover.saturating_mul(RISK_PER_TURN) * slowdown / 100;
+        risk.min(MAX_RISK) as u8
+    }
+
+    pub fn is_about_to_blow(&self) -> bool {
+        self.discovery_risk() >= EXTRACTION_RISK
+    }
+
+    fn report(&self) -> IntelReport {
+        let reliability = FIRST_REPORT_RELIABILITY
+            + self.turns_undercover.saturating_sub(REPORT_INTERVAL) * RELIABILITY_PER_TURN;
+        IntelReport {
+            syndicate: self.syndicate.clone(),
+            mole: self.henchman,
+            turn: self.turns_undercover,
+            reliability: reliability.min(100) as u8,
+        }
+    }
+}
+
+/// What a mole found out about a syndicate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntelReport {
+    pub syndicate: String,
+    pub mole: HenchmanId,
+    /// Turns the mole had been undercover.
+    pub turn: u32,
+    pub reliability: u8,
+}
+
+/// What happened to a mole in a turn.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoleEvent {
+    Report(IntelReport),
+    /// The mole should be extracted before it's found out.
+    CoverAboutToBlow {
+        syndicate: String,
+        risk: u8,
+    },
+    /// The mole was found out, and is lost.
+    Discovered {
+        syndicate: String,
+        henchman: HenchmanId,
+    },
+}
+
+/// How an extraction mission went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extraction {
+    /// The henchman is back, free to work for the villain again.
+    Extracted(HenchmanId),
+    /// The henchman got caught on the way out.
+    Captured(HenchmanId),
+}
+
+/// Moles a villain has planted, one per rival syndicate.
+#[derive(Debug, Clone, Default)]
+pub struct SpyRing {
+    moles: Vec<Mole>,
+}
+
+impl SpyRing {
+    pub fn new() -> SpyRing {
+        Self::default()
+    }
+
+    /// Plants the henchman in the syndicate, replacing any mole already there.
+    pub fn plant(&mut self, henchman: HenchmanId, syndicate: &str, skill: u8) {
+        self.moles.retain(|mole| mole.syndicate != syndicate);
+        self.moles.push(Mole {
+            henchman,
+            syndicate: syndicate.into(),
+            skill: skill.min(100),
+            turns_undercover: 0,
+        });
+    }
+
+    pub fn mole(&self, syndicate: &str) -> Option<&Mole> {
+        self.moles.iter().find(|mole| mole.syndicate == syndicate)
+    }
+
+    pub fn moles(&self) -> &[Mole] {
+        &self.moles
+    }
+
+    /// Plays a turn for every mole: each one risks being found out, reports every
+    /// [`REPORT_INTERVAL`] turns and asks to be extracted once its cover is about to blow.
+    /// Moles found out are lost.
+    pub fn advance<R: Rng + ?Sized>(&mut self, rng: &mut R) -> Vec<MoleEvent> {
+        let mut events = Vec::new();
+        self.moles.retain_mut(|mole| {
+            mole.turns_undercover += 1;
+            if rng.random_ratio(u32::from(mole.discovery_risk()), 100) {
+                events.push(MoleEvent::Discovered {
+                    syndicate: mole.syndicate.clone(),
+                    henchman: mole.henchman,
+                });
+                return false;
+            }
+            if mole.turns_undercover % REPORT_INTERVAL == 0 {
+                events.push(MoleEvent::Report(mole.report()));
+            }
+            if mole.is_about_to_blow() {
+                events.push(MoleEvent::CoverAboutToBlow {
+                    syndicate: mole.syndicate.clone(),
+                    risk: mole.discovery_risk(),
+                });
+            }
+            true
+        });
+        events
+    }
+
+    /// Sends a team to pull the mole out of the syndicate, taking a skill check against the
+    /// discovery risk. Either way, the mole leaves the ring.
+    pub fn extract<R: Rng + ?Sized>(
+        &mut self,
+        syndicate: &str,
+        rng: &mut R,
+    ) -> Result<Extraction, EvilError> {
+        let Some(position) = self
+            .moles
+            .iter()
+            .position(|mole| mole.syndicate == syndicate)
+        else {
+            return Err(EvilError::NoMole {
+                syndicate: syndicate.into(),
+            });
+        };
+        let mole = self.moles.remove(position);
+        let difficulty = Difficulty::against(mole.discovery_risk());
+        Ok(match SkillCheck::roll(mole.skill, difficulty, rng) {
+            CheckResult::Success | CheckResult::CriticalSuccess => {
+                Extraction::Extracted(mole.henchman)
+            }
+            CheckResult::Failure | CheckResult::CriticalFailure => {
+                Extraction::Captured(mole.henchman)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const SYNDICATE: &str = "Legion of Doom";
+
+    #[test]
+    fn discovery_risk_grows_while_undercover() {
+        let mut mole = Mole {
+            henchman: HenchmanId::new(1),
+            syndicate: SYNDICATE.into(),
+            skill: 0,
+            turns_undercover: 2,
+        };
+        assert_eq!(mole.discovery_risk(), 10);
+        mole.skill = 100;
+        assert_eq!(mole.discovery_risk(), 5);
+        mole.turns_undercover = 100;
+        assert_eq!(mole.discovery_risk(), 95);
+        assert!(mole.is_about_to_blow());
+    }
+
+    #[test]
+    fn reports_get_more_reliable_deeper_in() {
+        let mut ring = SpyRing::new();
+        ring.plant(HenchmanId::new(1), SYNDICATE, 100);
+        let Some(mole) = ring.mole(SYNDICATE) else {
+            panic!("Mole should be planted");
+        };
+        let mut mole = mole.clone();
+        mole.turns_undercover = REPORT_INTERVAL;
+        assert_eq!(mole.report().reliability, 40);
+        mole.turns_undercover = 4 * REPORT_INTERVAL;
+        assert_eq!(mole.report().reliability, 85);
+
+        ring.plant(HenchmanId::new(2), SYNDICATE, 0);
+        assert_eq!(ring.moles().len(), 1);
+    }
+
+    #[test]
+    fn moles_report_every_interval_and_warn_before_their_cover_blows() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut ring = SpyRing::new();
+        ring.plant(HenchmanId::new(1), SYNDICATE, 100);
+        for _ in 1..REPORT_INTERVAL {
+            assert_eq!(ring.advance(&mut rng), Vec::new());
+        }
+        assert_eq!(
+            ring.advance(&mut rng),
+            vec![MoleEvent::Report(IntelReport {
+                syndicate: SYNDICATE.into(),
+                mole: HenchmanId::new(1),
+                turn: REPORT_INTERVAL,
+                reliability: 40,
+            })]
+        );
+
+        ring.moles[0].skill = 0;
+        ring.moles[0].turns_undercover = 9;
+        assert_eq!(
+            ring.advance(&mut rng),
+            vec![MoleEvent::CoverAboutToBlow {
+                syndicate: SYNDICATE.into(),
+                risk: EXTRACTION_RISK,
+            }]
+        );
+        assert_eq!(ring.moles().len(), 1);
+    }
+
+    #[test]
+    fn extracting_without_mole_fails() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let result = SpyRing::new().extract(SYNDICATE, &mut rng);
+        assert_matches!(result, Err(EvilError::NoMole { syndicate }) if syndicate == SYNDICATE);
+    }
+}
@@ -0,0 +1,64 @@
+//! Module for the events villains emit while doing evil, and who gets to hear about them.
+#![allow(dead_code)]
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::plan::StepId;
+use crate::scheduler::ScheduleId;
+use crate::sidekick::SidekickId;
+use crate::sidekick::Slot;
+
+/// Something noteworthy that happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvilEvent {
+    MonologueDelivered {
+        villain: String,
+        hero: String,
+        speech: String,
+    },
+    HqBuilt {
+        villain: String,
+        target: String,
+    },
+    SidekickFired {
+        villain: String,
+        sidekick: SidekickId,
+    },
+    GadgetSwapped {
+        sidekick: SidekickId,
+        slot: Slot,
+    },
+    StepFired {
+        schedule: ScheduleId,
+        step: StepId,
+    },
+}
+
+/// Anything interested in evil events.
+#[cfg_attr(test, automock)]
+pub trait EventListener {
+    fn on_event(&self, event: &EvilEvent);
+}
+
+/// Delivers every published event to all the subscribed listeners.
+#[derive(Default)]
+pub struct EventBus<'a> {
+    listeners: Vec<Box<dyn EventListener + Send + 'a>>,
+}
+
+impl<'a> EventBus<'a> {
+    pub fn new() -> EventBus<'a> {
+        Self::default()
+    }
+
+    pub fn subscribe<L: EventListener + Send + 'a>(&mut self, listener: L) {
+        self.listeners.push(Box::new(listener));
+    }
+
+    pub fn publish(&self, event: &EvilEvent) {
+        for listener in &self.listeners {
+            listener.on_event(event);
+        }
+    }
+}
@@ -1,10 +1,192 @@
 //! Module for gadgets and all the related functionality
 #![allow(dead_code)]
 
-#[cfg(test)]
-use mockall::automock;
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use dyn_clone::DynClone;
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
+
+pub mod appraisal;
+pub mod combinators;
+
+use appraisal::Appraiser;
+use combinators::Chain;
+
+#[cfg(any(test, feature = "test-utils"))]
+use mockall::mock;
+
+/// What a gadget can be used for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum Capability {
+    TargetDetection,
+    Surveillance,
+    Combat,
+    Escape,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 4] = [
+        Capability::TargetDetection,
+        Capability::Surveillance,
+        Capability::Combat,
+        Capability::Escape,
+    ];
+}
+
+/// How hard a gadget is to come by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Uncommon,
+    Rare,
+    Legendary,
+}
+
 /// Trait that represents a gadget.
-#[cfg_attr(test, automock)]
-pub trait Gadget: Send {
+///
+/// Gadgets are cloneable even behind a `Box<dyn Gadget>`, so whoever carries one can be cloned too.
+pub trait Gadget: Send + DynClone {
     fn do_stuff(&self);
+
+    /// How good the gadget is at the given capability, from 0 (not capable at all) to 100.
+    fn rating(&self, _capability: Capability) -> u8 {
+        0
+    }
+
+    /// How worn out the gadget is, from 0 (mint) to 100 (barely holding together).
+    fn wear(&self) -> u8 {
+        0
+    }
+
+    fn rarity(&self) -> Rarity {
+        Rarity::Common
+    }
+
+    /// Contraption that uses this gadget and then the next one.
+    fn chain<G: Gadget>(self, next: G) -> Chain<Self, G>
+    where
+        Self: Sized,
+    {
+        Chain::new(self, next)
+    }
+}
+
+dyn_clone::clone_trait_object!(Gadget);
+
+/// Collection of gadgets available to a villain.
+#[derive(Clone, Default)]
+pub struct GadgetInventory<'a> {
+    gadgets: Vec<Box<dyn Gadget + 'a>>,
+}
+
+impl<'a> GadgetInventory<'a> {
+    pub fn new() -> GadgetInventory<'a> {
+        Self::default()
+    }
+
+    pub fn add<G: Gadget + 'a>(&mut self, gadget: G) {
+        self.gadgets.push(Box::new(gadget));
+    }
+
+    pub fn len(&self) -> usize {
+        self.gadgets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gadgets.is_empty()
+    }
+
+    pub fn add_boxed(&mut self, gadget: Box<dyn Gadget + 'a>) {
+        self.gadgets.push(gadget);
+    }
+
+    /// Returns the gadget with the highest rating for the capability, if any is capable at all.
+    /// On ties, the gadget added first wins.
+    pub fn best_for(&self, capability: Capability) -> Option<&(dyn Gadget + 'a)> {
+        self.best_index_for(capability)
+            .map(|index| self.gadgets[index].as_ref())
+    }
+
+    /// Removes the gadget [`GadgetInventory::best_for`] would return.
+    pub fn take_best_for(&mut self, capability: Capability) -> Option<Box<dyn Gadget + 'a>> {
+        self.best_index_for(capability)
+            .map(|index| self.gadgets.remove(index))
+    }
+
+    /// What all the gadgets are worth, in coins, as priced by the appraiser.
+    pub fn appraise<A: Appraiser + ?Sized>(&self, appraiser: &A) -> u64 {
+        self.gadgets
+            .iter()
+            .map(|gadget| appraiser.appraise(gadget.as_ref()))
+            .fold(0, u64::saturating_add)
+    }
+
+    fn best_index_for(&self, capability: Capability) -> Option<usize> {
+        let mut best: Option<(usize, u8)> = None;
+        for (index, gadget) in self.gadgets.iter().enumerate() {
+            let rating = gadget.rating(capability);
+            if rating > 0 && best.is_none_or(|(_, best_rating)| rating > best_rating) {
+                best = Some((index, rating));
+            }
+        }
+        best.map(|(index, _)| index)
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+mock! {
+    pub Gadget {}
+    impl Clone for Gadget {
+        fn clone(&self) -> Self;
+    }
+    impl Gadget for Gadget {
+        fn do_stuff(&self);
+        fn rating(&self, capability: Capability) -> u8;
+        fn wear(&self) -> u8;
+        fn rarity(&self) -> Rarity;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::predicate::eq;
+
+    #[test]
+    fn best_for_picks_highest_rated_gadget() {
+        let mut inventory = GadgetInventory::new();
+        inventory.add(gadget_rated(Capability::TargetDetection, 30));
+        inventory.add(gadget_rated(Capability::TargetDetection, 80));
+        let Some(best) = inventory.best_for(Capability::TargetDetection) else {
+            panic!("Unexpected: no gadget selected");
+        };
+        assert_eq!(best.rating(Capability::TargetDetection), 80);
+    }
+
+    #[test]
+    fn best_for_ignores_incapable_gadgets() {
+        let mut inventory = GadgetInventory::new();
+        inventory.add(gadget_rated(Capability::TargetDetection, 0));
+        assert!(inventory.best_for(Capability::TargetDetection).is_none());
+    }
+
+    #[test]
+    fn best_for_in_empty_inventory_is_none() {
+        let inventory = GadgetInventory::new();
+        assert!(inventory.best_for(Capability::Combat).is_none());
+    }
+
+    fn gadget_rated(capability: Capability, rating: u8) -> MockGadget {
+        let mut gadget = MockGadget::new();
+        gadget
+            .expect_rating()
+            .with(eq(capability))
+            .return_const(rating);
+        gadget
+    }
 }
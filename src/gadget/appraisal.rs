@@ -0,0 +1,98 @@
+//! How much gadgets are worth, for valuations and the black market.
+
+use super::Capability;
+use super::Gadget;
+use super::Rarity;
+
+/// Coins every rating point of a gadget is worth by default.
+pub const DEFAULT_PRICE_PER_POINT: u64 = 10;
+
+/// Prices gadgets. Implement it, or pass a closure, to use a different pricing model.
+pub trait Appraiser {
+    /// Price of the gadget, in coins.
+    fn appraise(&self, gadget: &dyn Gadget) -> u64;
+}
+
+impl<F: Fn(&dyn Gadget) -> u64> Appraiser for F {
+    fn appraise(&self, gadget: &dyn Gadget) -> u64 {
+        self(gadget)
+    }
+}
+
+/// Prices gadgets by the sum of their ratings, marked up for rarity and down for wear.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StandardAppraiser {
+    pub price_per_point: u64,
+}
+
+impl StandardAppraiser {
+    /// Price markup for the rarity, in percent.
+    pub fn rarity_markup(rarity: Rarity) -> u64 {
+        match rarity {
+            Rarity::Common => 100,
+            Rarity::Uncommon => 150,
+            Rarity::Rare => 250,
+            Rarity::Legendary => 500,
+        }
+    }
+}
+
+impl Default for StandardAppraiser {
+    fn default() -> Self {
+        Self {
+            price_per_point: DEFAULT_PRICE_PER_POINT,
+        }
+    }
+}
+
+impl Appraiser for StandardAppraiser {
+    fn appraise(&self, gadget: &dyn Gadget) -> u64 {
+        let points = Capability::ALL
+            .iter()
+            .map(|&capability| u64::from(gadget.rating(capability)))
+            .sum::<u64>();
+        let condition = 100 - u64::from(gadget.wear().min(100));
+        points
+            .saturating_mul(self.price_per_point)
+            .saturating_mul(Self::rarity_markup(gadget.rarity()))
+            .saturating_mul(condition)
+            / 10_000
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::GadgetInventory;
+    use crate::gadget::MockGadget;
+
+    fn gadget(rating: u8, wear: u8, rarity: Rarity) -> MockGadget {
+        let mut gadget = MockGadget::new();
+        gadget.expect_rating().returning(move |capability| {
+            if capability == Capability::Combat {
+                rating
+            } else {
+                0
+            }
+        });
+        gadget.expect_wear().return_const(wear);
+        gadget.expect_rarity().return_const(rarity);
+        gadget
+    }
+
+    #[test]
+    fn rarity_marks_up_and_wear_marks_down() {
+        let appraiser = StandardAppraiser::default();
+        assert_eq!(appraiser.appraise(&gadget(50, 0, Rarity::Common)), 500);
+        assert_eq!(appraiser.appraise(&gadget(50, 0, Rarity::Rare)), 1_250);
+        assert_eq!(appraiser.appraise(&gadget(50, 40, Rarity::Rare)), 750);
+    }
+
+    #[test]
+    fn inventory_can_use_custom_pricing() {
+        let mut inventory = GadgetInventory::new();
+        inventory.add(MockGadget::new());
+        inventory.add(MockGadget::new());
+        assert_eq!(inventory.appraise(&|_: &dyn Gadget| 99), 198);
+    }
+}
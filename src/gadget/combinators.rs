@@ -0,0 +1,194 @@
+//! Combinators building contraptions out of simpler gadgets.
+
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+
+use super::Capability;
+use super::Gadget;
+
+const MAX_RATING: u8 = 100;
+
+/// Two gadgets used one after the other, as good as the better of them.
+///
+/// Built with [`Gadget::chain`].
+#[derive(Debug, Clone)]
+pub struct Chain<A, B> {
+    first: A,
+    second: B,
+}
+
+impl<A: Gadget, B: Gadget> Chain<A, B> {
+    pub fn new(first: A, second: B) -> Chain<A, B> {
+        Self { first, second }
+    }
+
+    pub fn into_inner(self) -> (A, B) {
+        (self.first, self.second)
+    }
+}
+
+impl<A: Gadget + Clone, B: Gadget + Clone> Gadget for Chain<A, B> {
+    fn do_stuff(&self) {
+        self.first.do_stuff();
+        self.second.do_stuff();
+    }
+
+    fn rating(&self, capability: Capability) -> u8 {
+        self.first
+            .rating(capability)
+            .max(self.second.rating(capability))
+    }
+}
+
+/// Gadget that relies on a backup for whatever the primary can't do.
+#[derive(Debug, Clone)]
+pub struct Fallback<P, B> {
+    primary: P,
+    backup: B,
+}
+
+/// Uses `primary`, unless it isn't capable of anything; then `backup` is used instead. Rated
+/// as the primary for its capabilities and as the backup for the rest.
+pub fn fallback<P: Gadget, B: Gadget>(primary: P, backup: B) -> Fallback<P, B> {
+    Fallback { primary, backup }
+}
+
+impl<P: Gadget + Clone, B: Gadget + Clone> Gadget for Fallback<P, B> {
+    fn do_stuff(&self) {
+        if Capability::ALL
+            .iter()
+            .any(|&capability| self.primary.rating(capability) > 0)
+        {
+            self.primary.do_stuff();
+        } else {
+            self.backup.do_stuff();
+        }
+    }
+
+    fn rating(&self, capability: Capability) -> u8 {
+        match self.primary.rating(capability) {
+            0 => self.backup.rating(capability),
+            rating => rating,
+        }
+    }
+}
+
+/// Gadgets used all at once, adding up their ratings.
+#[derive(Clone, Default)]
+pub struct Parallel<'a> {
+    gadgets: Vec<Box<dyn Gadget + 'a>>,
+}
+
+/// Combines the gadgets so they're all used together. Ratings add up, to at most 100.
+pub fn parallel<'a>(gadgets: impl IntoIterator<Item = Box<dyn Gadget + 'a>>) -> Parallel<'a> {
+    Parallel {
+        gadgets: gadgets.into_iter().collect(),
+    }
+}
+
+impl<'a> Parallel<'a> {
+    pub fn with<G: Gadget + 'a>(mut self, gadget: G) -> Parallel<'a> {
+        self.gadgets.push(Box::new(gadget));
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.gadgets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.gadgets.is_empty()
+    }
+}
+
+impl Gadget for Parallel<'_> {
+    fn do_stuff(&self) {
+        for gadget in &self.gadgets {
+            gadget.do_stuff();
+        }
+    }
+
+    fn rating(&self, capability: Capability) -> u8 {
+        self.gadgets
+            .iter()
+            .fold(0u8, |total, gadget| {
+                total.saturating_add(gadget.rating(capability))
+            })
+            .min(MAX_RATING)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::MockGadget;
+    use mockall::Sequence;
+    use mockall::predicate::eq;
+
+    fn gadget(capability: Capability, rating: u8, uses: usize) -> MockGadget {
+        let mut gadget = MockGadget::new();
+        gadget
+            .expect_rating()
+            .with(eq(capability))
+            .return_const(rating);
+        gadget.expect_rating().return_const(0u8);
+        gadget.expect_do_stuff().times(uses).return_const(());
+        gadget
+    }
+
+    #[test]
+    fn chain_uses_both_gadgets_in_order() {
+        let mut sequence = Sequence::new();
+        let mut first = MockGadget::new();
+        first
+            .expect_do_stuff()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        let mut second = MockGadget::new();
+        second
+            .expect_do_stuff()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        first.chain(second).do_stuff();
+    }
+
+    #[test]
+    fn chain_is_as_good_as_its_best_part() {
+        let chain = gadget(Capability::Combat, 40, 0).chain(gadget(Capability::Combat, 70, 0));
+        assert_eq!(chain.rating(Capability::Combat), 70);
+        assert_eq!(chain.rating(Capability::Escape), 0);
+    }
+
+    #[test]
+    fn fallback_uses_backup_when_primary_is_useless() {
+        let useless = gadget(Capability::Combat, 0, 0);
+        let backup = gadget(Capability::Escape, 60, 1);
+        let contraption = fallback(useless, backup);
+        assert_eq!(contraption.rating(Capability::Escape), 60);
+        contraption.do_stuff();
+    }
+
+    #[test]
+    fn fallback_prefers_primary() {
+        let primary = gadget(Capability::Combat, 30, 1);
+        let backup = gadget(Capability::Combat, 90, 0);
+        let contraption = fallback(primary, backup);
+        assert_eq!(contraption.rating(Capability::Combat), 30);
+        contraption.do_stuff();
+    }
+
+    #[test]
+    fn parallel_gadgets_add_up_to_a_limit() {
+        let contraption = parallel([
+            Box::new(gadget(Capability::Surveillance, 60, 1)) as Box<dyn Gadget>,
+            Box::new(gadget(Capability::Surveillance, 30, 1)),
+        ]);
+        assert_eq!(contraption.rating(Capability::Surveillance), 90);
+        let contraption = contraption.with(gadget(Capability::Surveillance, 50, 1));
+        assert_eq!(contraption.len(), 3);
+        assert_eq!(contraption.rating(Capability::Surveillance), 100);
+        contraption.do_stuff();
+    }
+}
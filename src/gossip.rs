@@ -0,0 +1,276 @@
+//! Module for the gossip sidekicks of allied villains spread about heroes.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+use rand::Rng;
+
+use crate::dossier::Intel;
+use crate::id::VillainId;
+use crate::supervillain::SuperVillain;
+
+const RELIABILITY_LOSS_PER_HOP: u8 = 15;
+const ALPHABET_LEN: u8 = 26;
+
+/// Something heard about a hero. It may have been distorted on its way, so it may or may not
+/// be true.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rumor {
+    pub hero: String,
+    pub note: String,
+    pub reliability: u8,
+    pub hops: u8,
+    pub distorted: bool,
+}
+
+impl Rumor {
+    pub fn new(hero: &str, note: &str, reliability: u8) -> Rumor {
+        Self {
+            hero: hero.into(),
+            note: note.into(),
+            reliability,
+            hops: 0,
+            distorted: false,
+        }
+    }
+
+    /// Rumor started from intel the villain gathered itself.
+    pub fn from_intel(hero: &str, intel: &Intel) -> Rumor {
+        Self::new(hero, &intel.note, intel.reliability)
+    }
+
+    /// The rumor as told by the next sidekick: less reliable and, by chance, with a letter of
+    /// the note changed.
+    fn relay<R: Rng + ?Sized>(&self, distortion_chance: f64, rng: &mut R) -> Rumor {
+        let mut rumor = Self {
+            hops: self.hops.saturating_add(1),
+            reliability: self.reliability.saturating_sub(RELIABILITY_LOSS_PER_HOP),
+            ..self.clone()
+        };
+        if rng.random_bool(distortion_chance) {
+            rumor.distorted |= distort(&mut rumor.note, rng);
+        }
+        rumor
+    }
+}
+
+/// Replaces a random letter of the note with a different one, returning whether there was any
+/// letter to replace.
+fn distort<R: Rng + ?Sized>(note: &mut String, rng: &mut R) -> bool {
+    let letters = note
+        .char_indices()
+        .filter(|(_, c)| c.is_ascii_alphabetic())
+        .map(|(index, _)| index)
+        .collect::<Vec<_>>();
+    if letters.is_empty() {
+        return false;
+    }
+    let index = letters[rng.random_range(0..letters.len())];
+    let original = note.as_bytes()[index].to_ascii_lowercase() - b'a';
+    let shift = rng.random_range(1..ALPHABET_LEN);
+    let replacement = char::from(b'a' + (original + shift) % ALPHABET_LEN);
+    note.replace_range(index..=index, replacement.encode_utf8(&mut [0; 4]));
+    true
+}
+
+/// Alliances along which sidekicks pass rumors on, and how far and how faithfully they do it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GossipNetwork {
+    alliances: BTreeMap<VillainId, BTreeSet<VillainId>>,
+    max_hops: u8,
+    distortion_chance: f64,
+}
+
+impl GossipNetwork {
+    /// Network where rumors travel up to `max_hops` alliances away from where they started and
+    /// get distorted on each hop with `distortion_chance`, between 0 and 1.
+    pub fn new(max_hops: u8, distortion_chance: f64) -> GossipNetwork {
+        Self {
+            alliances: BTreeMap::new(),
+            max_hops,
+            distortion_chance: distortion_chance.clamp(0.0, 1.0),
+        }
+    }
+
+    pub fn ally(&mut self, villain: VillainId, other: VillainId) {
+        if villain == other {
+            return;
+        }
+        self.alliances.entry(villain).or_default().insert(other);
+        self.alliances.entry(other).or_default().insert(villain);
+    }
+
+    pub fn break_alliance(&mut self, villain: VillainId, other: VillainId) {
+        for (from, to) in [(villain, other), (other, villain)] {
+            if let Some(allies) = self.alliances.get_mut(&from) {
+                allies.remove(&to);
+            }
+        }
+    }
+
+    pub fn allies(&self, villain: VillainId) -> impl Iterator<Item = VillainId> + '_ {
+        self.alliances.get(&villain).into_iter().flatten().copied()
+    }
+
+    /// Who hears the rumor started by `origin`, and what they hear. Rumors only go through
+    /// villains in `gossips`; each villain hears it once, from the closest ally.
+    pub fn propagate<R: Rng + ?Sized>(
+        &self,
+        origin: VillainId,
+        rumor: &Rumor,
+        gossips: &BTreeSet<VillainId>,
+        rng: &mut R,
+    ) -> Vec<(VillainId, Rumor)> {
+        let mut heard = Vec::new();
+        if !gossips.contains(&origin) {
+            return heard;
+        }
+        let mut reached = BTreeSet::from([origin]);
+        let mut frontier = vec![(origin, rumor.clone())];
+        for _ in 0..self.max_hops {
+            let mut next = Vec::new();
+            for (teller, told) in &frontier {
+                for ally in self.allies(*teller) {
+                    if gossips.contains(&ally) && reached.insert(ally) {
+                        next.push((ally, told.relay(self.distortion_chance, rng)));
+                    }
+                }
+            }
+            heard.extend(next.iter().cloned());
+            frontier = next;
+        }
+        heard
+    }
+
+    /// Has the sidekick of `origin` spread the rumor to the allies, adding what each villain
+    /// hears to its dossiers. Villains without a sidekick neither hear nor pass on gossip.
+    ///
+    /// Returns how many villains heard the rumor.
+    pub fn spread<R: Rng + ?Sized>(
+        &self,
+        origin: VillainId,
+        rumor: &Rumor,
+        villains: &mut [&mut SuperVillain],
+        rng: &mut R,
+    ) -> usize {
+        let gossips = villains
+            .iter()
+            .filter(|villain| villain.sidekick.is_some())
+            .map(|villain| villain.id)
+            .collect::<BTreeSet<_>>();
+        let heard = self.propagate(origin, rumor, &gossips, rng);
+        for (id, rumor) in &heard {
+            if let Some(villain) = villains.iter_mut().find(|villain| villain.id == *id) {
+                villain.dossiers.hear_rumor(rumor.clone());
+            }
+        }
+        heard.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sidekick::MockSidekick;
+    use crate::test_common;
+    use assertables::assert_none;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    const NOTE: &str = "Allergic to green rocks";
+
+    fn chain(length: u64) -> (GossipNetwork, Vec<VillainId>) {
+        let ids = (1..=length).map(VillainId::new).collect::<Vec<_>>();
+        let mut network = GossipNetwork::new(2, 0.0);
+        for pair in ids.windows(2) {
+            network.ally(pair[0], pair[1]);
+        }
+        (network, ids)
+    }
+
+    #[test]
+    fn rumors_stop_after_max_hops() {
+        let (network, ids) = chain(4);
+        let gossips = ids.iter().copied().collect();
+        let rumor = Rumor::new(test_common::HERO_NAME, NOTE, 90);
+
+        let heard = network.propagate(ids[0], &rumor, &gossips, &mut StdRng::seed_from_u64(1));
+
+        assert_eq!(
+            heard.iter().map(|(id, _)| *id).collect::<Vec<_>>(),
+            vec![ids[1], ids[2]]
+        );
+        assert_eq!(heard[1].1.hops, 2);
+        assert_eq!(heard[1].1.reliability, 60);
+        assert!(heard.iter().all(|(_, rumor)| rumor.note == NOTE));
+    }
+
+    #[test]
+    fn villains_without_sidekick_break_the_chain() {
+        let (network, ids) = chain(3);
+        let gossips = BTreeSet::from([ids[0], ids[2]]);
+        let rumor = Rumor::new(test_common::HERO_NAME, NOTE, 90);
+
+        let heard = network.propagate(ids[0], &rumor, &gossips, &mut StdRng::seed_from_u64(1));
+
+        assert!(heard.is_empty());
+    }
+
+    #[test]
+    fn distorted_rumors_differ_from_the_original() {
+        let mut network = GossipNetwork::new(1, 1.0);
+        let (villain, ally) = (VillainId::new(1), VillainId::new(2));
+        network.ally(villain, ally);
+        let rumor = Rumor::new(test_common::HERO_NAME, NOTE, 90);
+
+        let heard = network.propagate(
+            villain,
+            &rumor,
+            &BTreeSet::from([villain, ally]),
+            &mut StdRng::seed_from_u64(7),
+        );
+
+        let [(_, heard)] = &heard[..] else {
+            panic!("Ally should hear the rumor once");
+        };
+        assert!(heard.distorted);
+        assert_ne!(heard.note.to_lowercase(), NOTE.to_lowercase());
+        assert_eq!(heard.note.len(), NOTE.len());
+    }
+
+    #[test]
+    fn spread_rumors_reach_allied_dossiers() {
+        let mut teller = SuperVillain {
+            sidekick: Some(MockSidekick::new()),
+            ..Default::default()
+        };
+        let mut listener = SuperVillain {
+            sidekick: Some(MockSidekick::new()),
+            ..Default::default()
+        };
+        let mut loner = SuperVillain::default();
+        let mut network = GossipNetwork::new(3, 0.0);
+        network.ally(teller.id, listener.id);
+        network.ally(listener.id, loner.id);
+        let rumor = Rumor::new(test_common::HERO_NAME, NOTE, 90);
+
+        let heard = network.spread(
+            teller.id,
+            &rumor,
+            &mut [&mut teller, &mut listener, &mut loner],
+            &mut StdRng::seed_from_u64(1),
+        );
+
+        assert_eq!(heard, 1);
+        assert_eq!(
+            listener
+                .dossiers
+                .get(test_common::HERO_NAME)
+                .map(|dossier| dossier.rumors.len()),
+            Some(1)
+        );
+        assert_none!(loner.dossiers.get(test_common::HERO_NAME));
+        assert_none!(teller.dossiers.get(test_common::HERO_NAME));
+    }
+}
@@ -0,0 +1,174 @@
+//! Module to share a villain among tasks.
+#![allow(dead_code)]
+
+use std::sync::Arc;
+
+use tokio::sync::Mutex;
+
+use crate::mood::Mood;
+use crate::mood::MoodTrigger;
+use crate::plan::quality::Plan;
+use crate::supervillain::EvilError;
+use crate::supervillain::PLANNING_TIME;
+use crate::supervillain::SuperVillain;
+use crate::supervillain::VillainStatus;
+
+/// Cheaply cloneable handle to a villain that many tasks can use at once.
+///
+/// Every method locks the villain only for as long as it runs, and waiting for the lock
+/// doesn't block the thread. For anything not covered, use [`VillainHandle::read`] and
+/// [`VillainHandle::update`].
+#[derive(Debug, Clone)]
+pub struct VillainHandle<'a> {
+    villain: Arc<Mutex<SuperVillain<'a>>>,
+}
+
+impl<'a> VillainHandle<'a> {
+    pub fn new(villain: SuperVillain<'a>) -> VillainHandle<'a> {
+        Self {
+            villain: Arc::new(Mutex::new(villain)),
+        }
+    }
+
+    /// Runs `f` with the villain, for read-only access.
+    pub async fn read<T>(&self, f: impl FnOnce(&SuperVillain<'a>) -> T) -> T {
+        f(&*self.villain.lock().await)
+    }
+
+    /// Runs `f` with the villain, for changing it.
+    pub async fn update<T>(&self, f: impl FnOnce(&mut SuperVillain<'a>) -> T) -> T {
+        f(&mut *self.villain.lock().await)
+    }
+
+    pub async fn full_name(&self) -> String {
+        self.read(SuperVillain::full_name).await
+    }
+
+    /// Sets the villain's names from a full name, failing without changing them if it can't be
+    /// parsed. Never panics, as no task sharing the villain should bring the others down.
+    pub async fn set_full_name(&self, name: &str) -> Result<(), EvilError> {
+        self.update(|villain| villain.try_set_full_name(name)).await
+    }
+
+    /// Like [`SuperVillain::come_up_with_plan`], but lets other tasks use the villain while it
+    /// thinks.
+    pub async fn come_up_with_plan(&self) -> Result<Plan, EvilError> {
+        let started = self
+            .read(|villain| villain.ensure_at_large().map(|()| villain.now()))
+            .await?;
+        tokio::time::sleep(PLANNING_TIME).await;
+        Ok(self.read(|villain| villain.plan_hatched(started)).await)
+    }
+
+    pub async fn status(&self) -> VillainStatus {
+        self.read(|villain| villain.status.clone()).await
+    }
+
+    pub async fn mood(&self) -> Mood {
+        self.read(|villain| villain.mood).await
+    }
+
+    pub async fn react_to(&self, trigger: MoodTrigger) {
+        self.update(|villain| villain.react_to(trigger)).await;
+    }
+
+    pub async fn balance(&self) -> u64 {
+        self.read(|villain| villain.treasury.balance()).await
+    }
+
+    pub async fn deposit(&self, amount: u64) {
+        self.update(|villain| villain.treasury.deposit(amount))
+            .await;
+    }
+
+    pub async fn withdraw(&self, amount: u64) -> Result<(), EvilError> {
+        self.update(|villain| villain.treasury.withdraw(amount))
+            .await
+    }
+
+    pub async fn gain_infamy(&self, points: u32) {
+        self.update(|villain| villain.infamy.gain(points)).await;
+    }
+
+    /// Gets the villain back, unless other handles to it are still around.
+    pub fn try_into_inner(self) -> Result<SuperVillain<'a>, VillainHandle<'a>> {
+        Arc::try_unwrap(self.villain)
+            .map(Mutex::into_inner)
+            .map_err(|villain| Self { villain })
+    }
+}
+
+impl<'a> From<SuperVillain<'a>> for VillainHandle<'a> {
+    fn from(villain: SuperVillain<'a>) -> Self {
+        Self::new(villain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use crate::treasury::Treasury;
+    use assertables::assert_matches;
+    use std::time::Instant;
+
+    #[tokio::test]
+    async fn tasks_share_the_treasury() {
+        let handle = VillainHandle::new(SuperVillain {
+            treasury: Treasury::new(100),
+            ..Default::default()
+        });
+        let tasks = (0..10)
+            .map(|_| {
+                let handle = handle.clone();
+                tokio::spawn(async move { handle.deposit(10).await })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            task.await.expect("Deposit task panicked");
+        }
+        assert_eq!(handle.balance().await, 200);
+        assert_matches!(
+            handle.withdraw(500).await,
+            Err(EvilError::InsufficientFunds { .. })
+        );
+    }
+
+    #[tokio::test]
+    async fn villain_can_be_used_while_it_plans() -> Result<(), EvilError> {
+        let handle = VillainHandle::from(SuperVillain::default());
+        let started = Instant::now();
+        let (plan, balance_read_after) = tokio::join!(handle.come_up_with_plan(), async {
+            handle.balance().await;
+            started.elapsed()
+        });
+        assert_eq!(plan?.scheme, "Take over the world!");
+        assert!(balance_read_after < PLANNING_TIME);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn villain_comes_back_once_every_handle_is_gone() -> Result<(), EvilError> {
+        let handle = VillainHandle::from(SuperVillain::default());
+        handle.set_full_name(test_common::PRIMARY_FULL_NAME).await?;
+        let other = handle.clone();
+        let Err(handle) = handle.try_into_inner() else {
+            panic!("Villain taken while shared");
+        };
+        drop(other);
+        let Ok(villain) = handle.try_into_inner() else {
+            panic!("Villain still shared");
+        };
+        assert_eq!(villain.full_name(), test_common::PRIMARY_FULL_NAME);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn blank_names_are_rejected_without_panicking() {
+        let handle = VillainHandle::from(SuperVillain::default());
+        assert_matches!(
+            handle.set_full_name("  ").await,
+            Err(EvilError::ParseError { .. })
+        );
+    }
+}
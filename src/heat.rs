@@ -0,0 +1,211 @@
+//! Module for how much attention the villain draws from law enforcement, region by region.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+
+use crate::nemesis::Hero;
+use crate::nemesis::HeroTeam;
+use crate::nemesis::Stats;
+use crate::units::EvilDuration;
+
+const HEAT_LOST_PER_HOUR: u32 = 5;
+const PATROL_HEALTH: u32 = 60;
+const PATROL_ATTACK: u32 = 8;
+const PATROL_SPEED: u32 = 40;
+
+/// Villainous operations that draw attention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operation {
+    Attack,
+    Heist,
+}
+
+impl Operation {
+    /// Heat gained in the region where the operation takes place.
+    pub fn heat(&self) -> u32 {
+        match self {
+            Operation::Attack => 20,
+            Operation::Heist => 12,
+        }
+    }
+}
+
+/// How law enforcement responds to the heat in a region.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum HeatLevel {
+    #[default]
+    Cold,
+    Noticed,
+    Wanted,
+    Manhunt,
+}
+
+impl HeatLevel {
+    pub fn from_heat(heat: u32) -> HeatLevel {
+        match heat {
+            0..30 => HeatLevel::Cold,
+            30..60 => HeatLevel::Noticed,
+            60..90 => HeatLevel::Wanted,
+            _ => HeatLevel::Manhunt,
+        }
+    }
+
+    /// Hero patrols sent to the region.
+    pub fn patrols(&self) -> usize {
+        *self as usize
+    }
+
+    /// Percentage taken off the success chance of operations in the region.
+    pub fn penalty(&self) -> u8 {
+        match self {
+            HeatLevel::Cold => 0,
+            HeatLevel::Noticed => 10,
+            HeatLevel::Wanted => 25,
+            HeatLevel::Manhunt => 50,
+        }
+    }
+}
+
+/// Heat in a region and the patrols looking for the villain there.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionHeat {
+    pub heat: u32,
+    patrols: Vec<Hero>,
+}
+
+impl RegionHeat {
+    pub fn level(&self) -> HeatLevel {
+        HeatLevel::from_heat(self.heat)
+    }
+
+    pub fn patrols(&self) -> &[Hero] {
+        &self.patrols
+    }
+
+    /// Spawns or calls off patrols so there are as many as the heat level asks for.
+    fn dispatch(&mut self, region: &str) {
+        let wanted = self.level().patrols();
+        self.patrols.truncate(wanted);
+        while self.patrols.len() < wanted {
+            let number = self.patrols.len() + 1;
+            self.patrols.push(Hero::new(
+                &format!("{region} patrol {number}"),
+                Stats {
+                    health: PATROL_HEALTH,
+                    attack: PATROL_ATTACK * number as u32,
+                    defense: 0,
+                    speed: PATROL_SPEED,
+                },
+            ));
+        }
+    }
+}
+
+/// Heat of every region the villain has operated in.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeatMap {
+    regions: BTreeMap<String, RegionHeat>,
+}
+
+impl HeatMap {
+    pub fn new() -> HeatMap {
+        Self::default()
+    }
+
+    /// Raises the heat in the region, spawning patrols if a threshold is crossed. Returns the
+    /// new level when it changed.
+    pub fn record(&mut self, region: &str, operation: Operation) -> Option<HeatLevel> {
+        self.raise(region, operation.heat())
+    }
+
+    pub fn raise(&mut self, region: &str, heat: u32) -> Option<HeatLevel> {
+        let entry = self.regions.entry(region.into()).or_default();
+        let before = entry.level();
+        entry.heat = entry.heat.saturating_add(heat);
+        entry.dispatch(region);
+        Some(entry.level()).filter(|&level| level != before)
+    }
+
+    pub fn region(&self, region: &str) -> Option<&RegionHeat> {
+        self.regions.get(region)
+    }
+
+    pub fn level(&self, region: &str) -> HeatLevel {
+        self.region(region)
+            .map(RegionHeat::level)
+            .unwrap_or_default()
+    }
+
+    /// Penalty for operations in the region, see [`HeatLevel::penalty`].
+    pub fn penalty(&self, region: &str) -> u8 {
+        self.level(region).penalty()
+    }
+
+    /// Patrols in the region, as a team the villain can battle.
+    pub fn patrol_team(&self, region: &str) -> HeroTeam {
+        let mut team = HeroTeam::new(&format!("{region} law enforcement"));
+        for hero in self
+            .region(region)
+            .into_iter()
+            .flat_map(RegionHeat::patrols)
+        {
+            team.recruit(hero.clone());
+        }
+        team
+    }
+
+    /// The villain stays out of sight: heat cools down everywhere for every full hour, and
+    /// patrols are called off as it drops below their thresholds. Regions that cool down
+    /// completely are forgotten.
+    pub fn lay_low(&mut self, duration: EvilDuration) {
+        let hours = u32::try_from(duration.as_secs() / 3600).unwrap_or(u32::MAX);
+        let cooled = hours.saturating_mul(HEAT_LOST_PER_HOUR);
+        for (region, entry) in &mut self.regions {
+            entry.heat = entry.heat.saturating_sub(cooled);
+            entry.dispatch(region);
+        }
+        self.regions.retain(|_, entry| entry.heat > 0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+
+    #[test]
+    fn crossing_thresholds_spawns_patrols() {
+        let mut heat = HeatMap::new();
+        assert_eq!(
+            heat.record(test_common::FIRST_TARGET, Operation::Attack),
+            None
+        );
+        assert_eq!(
+            heat.record(test_common::FIRST_TARGET, Operation::Heist),
+            Some(HeatLevel::Noticed)
+        );
+        heat.raise(test_common::FIRST_TARGET, 30);
+
+        assert_eq!(heat.level(test_common::FIRST_TARGET), HeatLevel::Wanted);
+        assert_eq!(heat.penalty(test_common::FIRST_TARGET), 25);
+        assert_eq!(heat.patrol_team(test_common::FIRST_TARGET).len(), 2);
+        assert_eq!(heat.penalty("Gotham"), 0);
+    }
+
+    #[test]
+    fn laying_low_cools_down_and_calls_off_patrols() {
+        let mut heat = HeatMap::new();
+        heat.raise(test_common::FIRST_TARGET, 95);
+        heat.raise("Gotham", 10);
+
+        heat.lay_low(EvilDuration::from_hours(14));
+
+        assert_eq!(heat.level(test_common::FIRST_TARGET), HeatLevel::Cold);
+        assert_eq!(
+            heat.region(test_common::FIRST_TARGET)
+                .map(|region| region.patrols().len()),
+            Some(0)
+        );
+        assert!(heat.region("Gotham").is_none());
+    }
+}
@@ -1,13 +1,538 @@
 //! Module to define henchmen.
 #![allow(dead_code)]
 
-#[cfg(test)]
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cell::Cell;
+#[cfg(feature = "std")]
+use core::fmt;
+
+#[cfg(any(test, feature = "test-utils"))]
 use mockall::automock;
 
+use crate::error::EvilError;
+#[cfg(feature = "std")]
+use crate::gadget::Capability;
+#[cfg(feature = "std")]
+use crate::gadget::Gadget;
+#[cfg(feature = "std")]
+use crate::id::HenchmanId;
+#[cfg(feature = "std")]
+use crate::id::LoanId;
+#[cfg(feature = "std")]
+use crate::inventory::Inventory;
+#[cfg(feature = "std")]
+use crate::sidekick::SidekickStats;
+use crate::treasury::Treasury;
+
+pub mod layer;
+
+/// Below this morale henchmen down tools.
+pub const STRIKE_THRESHOLD: u8 = 20;
+const HARD_WORK_MORALE_COST: u8 = 15;
+const PIZZA_PARTY_BOOST: u8 = 25;
+/// Kilograms of loot a goon can carry.
+#[cfg(feature = "std")]
+pub const GOON_CARRY_CAPACITY: u32 = 40;
+
 /// Henchman trait.
-#[cfg_attr(test, automock)]
+#[cfg_attr(any(test, feature = "test-utils"), automock)]
 pub trait Henchman {
     fn build_secret_hq(&mut self, location: String);
     fn do_hard_things(&self);
     fn fight_enemies(&self);
+
+    /// Tears down the secret HQ built at the location, e.g. when the plan is called off.
+    /// Henchmen that can't tear HQs down leave them standing and say so.
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        Err(EvilError::HqStillStanding { location })
+    }
+
+    /// Reports how the work assigned to the henchman is going.
+    fn report_progress(&self) -> Progress {
+        Progress::default()
+    }
+
+    /// Whether the henchman refuses to work until morale is restored.
+    fn is_on_strike(&self) -> bool {
+        false
+    }
+}
+
+/// Henchman that gadgets living for `'a` can be lent to. Kept apart from [`Henchman`] so that
+/// lent gadgets only have to outlive the sidekick lending them, rather than be `'static`,
+/// without every henchman becoming generic over a lifetime.
+#[cfg(feature = "std")]
+pub trait Borrower<'a> {
+    /// Identity loans to the henchman are recorded under.
+    fn id(&self) -> HenchmanId;
+
+    /// Takes a gadget on loan, or hands it straight back if the henchman can't carry gadgets.
+    fn borrow_gadget(
+        &mut self,
+        _loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        Err(gadget)
+    }
+
+    /// Gives back the gadget borrowed under the loan, if the henchman has it.
+    fn return_gadget(&mut self, _loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        None
+    }
+
+    /// Loses loyalty, e.g. for keeping a borrowed gadget past its due date.
+    fn lose_loyalty(&mut self, _points: u8) {}
+}
+
+/// How willing a henchman is to keep working, from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Morale(u8);
+
+impl Morale {
+    pub const FULL: Morale = Morale(100);
+
+    pub fn new(level: u8) -> Morale {
+        Morale(level.min(Self::FULL.0))
+    }
+
+    pub fn level(&self) -> u8 {
+        self.0
+    }
+
+    pub fn is_on_strike(&self) -> bool {
+        self.0 < STRIKE_THRESHOLD
+    }
+
+    fn decay(self) -> Morale {
+        Morale(self.0.saturating_sub(HARD_WORK_MORALE_COST))
+    }
+
+    fn boost(self, amount: u8) -> Morale {
+        Morale::new(self.0.saturating_add(amount))
+    }
+}
+
+impl Default for Morale {
+    fn default() -> Self {
+        Self::FULL
+    }
+}
+
+/// Henchman whose morale drops with every hard thing it is told to do.
+#[derive(Debug, Default)]
+pub struct MotivatedHenchman<H> {
+    inner: H,
+    morale: Cell<Morale>,
+}
+
+impl<H: Henchman> MotivatedHenchman<H> {
+    pub fn new(inner: H) -> Self {
+        Self::with_morale(inner, Morale::FULL)
+    }
+
+    pub fn with_morale(inner: H, morale: Morale) -> Self {
+        Self {
+            inner,
+            morale: Cell::new(morale),
+        }
+    }
+
+    pub fn morale(&self) -> Morale {
+        self.morale.get()
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+
+    /// Pays the henchman out of the treasury, which fully restores morale.
+    pub fn raise_pay(&self, treasury: &mut Treasury, amount: u64) -> Result<(), EvilError> {
+        treasury.withdraw(amount)?;
+        self.morale.set(Morale::FULL);
+        Ok(())
+    }
+
+    /// Cheaper than a pay raise, but only restores some morale.
+    pub fn pizza_party(&self) {
+        self.morale.set(self.morale.get().boost(PIZZA_PARTY_BOOST));
+    }
+}
+
+impl<H: Henchman> Henchman for MotivatedHenchman<H> {
+    fn build_secret_hq(&mut self, location: String) {
+        self.inner.build_secret_hq(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        self.inner.demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        self.inner.do_hard_things();
+        self.morale.set(self.morale.get().decay());
+    }
+
+    fn fight_enemies(&self) {
+        self.inner.fight_enemies();
+    }
+
+    fn report_progress(&self) -> Progress {
+        self.inner.report_progress()
+    }
+
+    fn is_on_strike(&self) -> bool {
+        self.morale.get().is_on_strike()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, H: Borrower<'a>> Borrower<'a> for MotivatedHenchman<H> {
+    fn id(&self) -> HenchmanId {
+        self.inner.id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        self.inner.borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        self.inner.return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        self.inner.lose_loyalty(points);
+    }
+}
+
+/// Henchman with a name, skills and gadgets of its own, e.g. a demoted sidekick.
+#[cfg(feature = "std")]
+#[derive(Clone)]
+pub struct Goon<'a> {
+    pub id: HenchmanId,
+    pub name: String,
+    pub stats: SidekickStats,
+    pub gadgets: Vec<Box<dyn Gadget + 'a>>,
+    /// Gadgets on loan, to give back when asked.
+    pub borrowed: Vec<(LoanId, Box<dyn Gadget + 'a>)>,
+    pub hq: Option<String>,
+    /// Loot the goon is carrying.
+    pub loot: Inventory,
+}
+
+#[cfg(feature = "std")]
+impl<'a> Goon<'a> {
+    pub fn new(name: &str) -> Goon<'a> {
+        Self {
+            id: HenchmanId::next(),
+            name: name.into(),
+            stats: SidekickStats::default(),
+            gadgets: Vec::new(),
+            borrowed: Vec::new(),
+            hq: None,
+            loot: Inventory::with_capacity(GOON_CARRY_CAPACITY),
+        }
+    }
+
+    fn use_best_gadget_for(&self, capability: Capability) {
+        if let Some(gadget) = self
+            .gadgets
+            .iter()
+            .chain(self.borrowed.iter().map(|(_, gadget)| gadget))
+            .filter(|gadget| gadget.rating(capability) > 0)
+            .max_by_key(|gadget| gadget.rating(capability))
+        {
+            gadget.do_stuff();
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Henchman for Goon<'_> {
+    fn build_secret_hq(&mut self, location: String) {
+        self.hq = Some(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        if self.hq.as_ref() == Some(&location) {
+            self.hq = None;
+        }
+        Ok(())
+    }
+
+    fn do_hard_things(&self) {
+        self.use_best_gadget_for(Capability::Surveillance);
+    }
+
+    fn fight_enemies(&self) {
+        self.use_best_gadget_for(Capability::Combat);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a> Borrower<'a> for Goon<'a> {
+    fn id(&self) -> HenchmanId {
+        self.id
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        self.borrowed.push((loan, gadget));
+        Ok(())
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        let index = self
+            .borrowed
+            .iter()
+            .position(|(borrowed, _)| *borrowed == loan)?;
+        Some(self.borrowed.remove(index).1)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        self.stats.loyalty = self.stats.loyalty.saturating_sub(points);
+    }
+}
+
+#[cfg(feature = "std")]
+impl fmt::Debug for Goon<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Goon")
+            .field("name", &self.name)
+            .field("stats", &self.stats)
+            .field("gadgets", &self.gadgets.len())
+            .field("borrowed", &self.borrowed.len())
+            .field("hq", &self.hq)
+            .field("loot", &self.loot)
+            .finish()
+    }
+}
+
+impl<H: Henchman + ?Sized> Henchman for Box<H> {
+    fn build_secret_hq(&mut self, location: String) {
+        (**self).build_secret_hq(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        (**self).demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        (**self).do_hard_things();
+    }
+
+    fn fight_enemies(&self) {
+        (**self).fight_enemies();
+    }
+
+    fn report_progress(&self) -> Progress {
+        (**self).report_progress()
+    }
+
+    fn is_on_strike(&self) -> bool {
+        (**self).is_on_strike()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, H: Borrower<'a> + ?Sized> Borrower<'a> for Box<H> {
+    fn id(&self) -> HenchmanId {
+        (**self).id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        (**self).borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        (**self).return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        (**self).lose_loyalty(points);
+    }
+}
+
+impl<H: Henchman + ?Sized> Henchman for &mut H {
+    fn build_secret_hq(&mut self, location: String) {
+        (**self).build_secret_hq(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        (**self).demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        (**self).do_hard_things();
+    }
+
+    fn fight_enemies(&self) {
+        (**self).fight_enemies();
+    }
+
+    fn report_progress(&self) -> Progress {
+        (**self).report_progress()
+    }
+
+    fn is_on_strike(&self) -> bool {
+        (**self).is_on_strike()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, H: Borrower<'a> + ?Sized> Borrower<'a> for &mut H {
+    fn id(&self) -> HenchmanId {
+        (**self).id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        (**self).borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        (**self).return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        (**self).lose_loyalty(points);
+    }
+}
+
+/// Progress of the work done by one or more henchmen.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Progress {
+    pub percent_complete: u8,
+    pub casualties: u32,
+    pub blockers: Vec<String>,
+}
+
+impl Progress {
+    /// Combines several reports into one: completion is averaged, casualties and blockers add up.
+    pub fn aggregate<'a>(reports: impl IntoIterator<Item = &'a Progress>) -> Progress {
+        let mut total = Progress::default();
+        let mut percent_sum = 0u32;
+        let mut count = 0u32;
+        for report in reports {
+            percent_sum += u32::from(report.percent_complete);
+            count += 1;
+            total.casualties += report.casualties;
+            total.blockers.extend(report.blockers.iter().cloned());
+        }
+        if let Some(average) = percent_sum.checked_div(count) {
+            total.percent_complete = average as u8;
+        }
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::MockGadget;
+    use assertables::assert_matches;
+
+    fn idle_henchman() -> MockHenchman {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_do_hard_things().return_const(());
+        henchman
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn goon_fights_with_best_combat_gadget() {
+        let mut weak = MockGadget::new();
+        weak.expect_rating().return_const(2u8);
+        weak.expect_do_stuff().never();
+        let mut strong = MockGadget::new();
+        strong.expect_rating().return_const(5u8);
+        strong.expect_do_stuff().once().return_const(());
+
+        let mut goon = Goon::new("Igor");
+        goon.gadgets = vec![Box::new(weak), Box::new(strong)];
+        goon.fight_enemies();
+    }
+
+    #[test]
+    fn hard_work_lowers_morale_until_strike() {
+        let henchman = MotivatedHenchman::new(idle_henchman());
+        henchman.do_hard_things();
+        assert_eq!(henchman.morale().level(), 85);
+        assert!(!henchman.is_on_strike());
+        for _ in 0..5 {
+            henchman.do_hard_things();
+        }
+        assert_eq!(henchman.morale().level(), 10);
+        assert!(henchman.is_on_strike());
+    }
+
+    #[test]
+    fn raise_pay_restores_full_morale_and_costs_money() -> Result<(), EvilError> {
+        let henchman = MotivatedHenchman::with_morale(idle_henchman(), Morale::new(5));
+        let mut treasury = Treasury::new(100);
+        henchman.raise_pay(&mut treasury, 60)?;
+        assert_eq!(henchman.morale(), Morale::FULL);
+        assert_eq!(treasury.balance(), 40);
+        Ok(())
+    }
+
+    #[test]
+    fn raise_pay_without_funds_leaves_morale_alone() {
+        let henchman = MotivatedHenchman::with_morale(idle_henchman(), Morale::new(5));
+        let result = henchman.raise_pay(&mut Treasury::new(10), 60);
+        assert_matches!(result, Err(EvilError::InsufficientFunds { .. }));
+        assert!(henchman.is_on_strike());
+    }
+
+    #[test]
+    fn pizza_party_restores_some_morale() {
+        let henchman = MotivatedHenchman::with_morale(idle_henchman(), Morale::new(10));
+        henchman.pizza_party();
+        assert_eq!(henchman.morale().level(), 35);
+        assert!(!henchman.is_on_strike());
+        for _ in 0..5 {
+            henchman.pizza_party();
+        }
+        assert_eq!(henchman.morale(), Morale::FULL);
+    }
+
+    #[test]
+    fn aggregate_averages_completion_and_adds_up_the_rest() {
+        let reports = [
+            Progress {
+                percent_complete: 100,
+                casualties: 1,
+                blockers: vec![],
+            },
+            Progress {
+                percent_complete: 50,
+                casualties: 2,
+                blockers: vec![String::from("Hero in the way")],
+            },
+        ];
+        let total = Progress::aggregate(&reports);
+        assert_eq!(total.percent_complete, 75);
+        assert_eq!(total.casualties, 3);
+        assert_eq!(total.blockers, vec![String::from("Hero in the way")]);
+    }
+
+    #[test]
+    fn aggregate_of_nothing_is_no_progress() {
+        assert_eq!(Progress::aggregate(&[]), Progress::default());
+    }
 }
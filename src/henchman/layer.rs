@@ -0,0 +1,383 @@
+//! Layers wrapping henchmen with cross-cutting behavior (logging, counting, retries, morale)
+//! without touching their implementations, stacked with a [`HenchmanBuilder`].
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::Ordering;
+
+#[cfg(feature = "std")]
+use super::Borrower;
+use super::Henchman;
+use super::Morale;
+use super::MotivatedHenchman;
+use super::Progress;
+use crate::error::EvilError;
+#[cfg(feature = "std")]
+use crate::gadget::Gadget;
+#[cfg(feature = "std")]
+use crate::id::HenchmanId;
+#[cfg(feature = "std")]
+use crate::id::LoanId;
+
+/// Wraps a henchman into another one that adds some behavior.
+pub trait HenchmanLayer<H> {
+    type Henchman: Henchman;
+
+    fn layer(&self, inner: H) -> Self::Henchman;
+}
+
+/// Layer that adds nothing.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Identity;
+
+impl<H: Henchman> HenchmanLayer<H> for Identity {
+    type Henchman = H;
+
+    fn layer(&self, inner: H) -> H {
+        inner
+    }
+}
+
+/// Two layers, `outer` wrapping whatever `inner` wraps.
+#[derive(Debug, Clone, Copy)]
+pub struct Stack<Inner, Outer> {
+    inner: Inner,
+    outer: Outer,
+}
+
+impl<H, Inner, Outer> HenchmanLayer<H> for Stack<Inner, Outer>
+where
+    Inner: HenchmanLayer<H>,
+    Outer: HenchmanLayer<Inner::Henchman>,
+{
+    type Henchman = Outer::Henchman;
+
+    fn layer(&self, inner: H) -> Self::Henchman {
+        self.outer.layer(self.inner.layer(inner))
+    }
+}
+
+/// Stacks layers to wrap henchmen with. The first layer added is the outermost, so it sees the
+/// orders first.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HenchmanBuilder<L> {
+    layer: L,
+}
+
+impl HenchmanBuilder<Identity> {
+    pub fn new() -> HenchmanBuilder<Identity> {
+        Self::default()
+    }
+}
+
+impl<L> HenchmanBuilder<L> {
+    pub fn layer<T>(self, layer: T) -> HenchmanBuilder<Stack<T, L>> {
+        HenchmanBuilder {
+            layer: Stack {
+                inner: layer,
+                outer: self.layer,
+            },
+        }
+    }
+
+    /// Logs every order with the function.
+    pub fn logging<F: Fn(&str) + Clone>(
+        self,
+        log: F,
+    ) -> HenchmanBuilder<Stack<LoggingLayer<F>, L>> {
+        self.layer(LoggingLayer { log })
+    }
+
+    /// Counts the orders into the given counts.
+    pub fn counting(self, counts: &Arc<TaskCounts>) -> HenchmanBuilder<Stack<CountingLayer, L>> {
+        self.layer(CountingLayer {
+            counts: Arc::clone(counts),
+        })
+    }
+
+    /// Tracks morale, starting at the given one.
+    pub fn morale(self, morale: Morale) -> HenchmanBuilder<Stack<MoraleLayer, L>> {
+        self.layer(MoraleLayer { morale })
+    }
+
+    /// Tries again when the work doesn't get done.
+    #[cfg(feature = "std")]
+    pub fn retry(
+        self,
+        policy: crate::resilience::RetryPolicy,
+    ) -> HenchmanBuilder<Stack<RetryLayer, L>> {
+        self.layer(RetryLayer { policy })
+    }
+
+    /// Wraps the henchman with every layer.
+    pub fn build<H>(&self, henchman: H) -> L::Henchman
+    where
+        L: HenchmanLayer<H>,
+    {
+        self.layer.layer(henchman)
+    }
+}
+
+/// Layer logging every order given to the henchman.
+#[derive(Debug, Clone, Copy)]
+pub struct LoggingLayer<F> {
+    log: F,
+}
+
+impl<H: Henchman, F: Fn(&str) + Clone> HenchmanLayer<H> for LoggingLayer<F> {
+    type Henchman = Logged<H, F>;
+
+    fn layer(&self, inner: H) -> Logged<H, F> {
+        Logged {
+            inner,
+            log: self.log.clone(),
+        }
+    }
+}
+
+/// Henchman whose orders are logged before being passed on.
+#[derive(Debug, Clone)]
+pub struct Logged<H, F> {
+    inner: H,
+    log: F,
+}
+
+impl<H, F> Logged<H, F> {
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H: Henchman, F: Fn(&str)> Henchman for Logged<H, F> {
+    fn build_secret_hq(&mut self, location: String) {
+        (self.log)(&format!("build_secret_hq {location}"));
+        self.inner.build_secret_hq(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        (self.log)(&format!("demolish_secret_hq {location}"));
+        self.inner.demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        (self.log)("do_hard_things");
+        self.inner.do_hard_things();
+    }
+
+    fn fight_enemies(&self) {
+        (self.log)("fight_enemies");
+        self.inner.fight_enemies();
+    }
+
+    fn report_progress(&self) -> Progress {
+        self.inner.report_progress()
+    }
+
+    fn is_on_strike(&self) -> bool {
+        self.inner.is_on_strike()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, H: Borrower<'a>, F: Fn(&str)> Borrower<'a> for Logged<H, F> {
+    fn id(&self) -> HenchmanId {
+        self.inner.id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        self.inner.borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        self.inner.return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        self.inner.lose_loyalty(points);
+    }
+}
+
+/// Orders given to henchmen, shared by everyone counting them, e.g. to export as metrics.
+#[derive(Debug, Default)]
+pub struct TaskCounts {
+    hqs_built: AtomicU64,
+    hard_things: AtomicU64,
+    fights: AtomicU64,
+}
+
+impl TaskCounts {
+    pub fn hqs_built(&self) -> u64 {
+        self.hqs_built.load(Ordering::Relaxed)
+    }
+
+    pub fn hard_things(&self) -> u64 {
+        self.hard_things.load(Ordering::Relaxed)
+    }
+
+    pub fn fights(&self) -> u64 {
+        self.fights.load(Ordering::Relaxed)
+    }
+
+    fn increment(counter: &AtomicU64) {
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Layer counting the orders given to the henchman.
+#[derive(Debug, Clone)]
+pub struct CountingLayer {
+    counts: Arc<TaskCounts>,
+}
+
+impl<H: Henchman> HenchmanLayer<H> for CountingLayer {
+    type Henchman = Counted<H>;
+
+    fn layer(&self, inner: H) -> Counted<H> {
+        Counted {
+            inner,
+            counts: Arc::clone(&self.counts),
+        }
+    }
+}
+
+/// Henchman whose orders are counted before being passed on.
+#[derive(Debug)]
+pub struct Counted<H> {
+    inner: H,
+    counts: Arc<TaskCounts>,
+}
+
+impl<H> Counted<H> {
+    pub fn counts(&self) -> &TaskCounts {
+        &self.counts
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner
+    }
+}
+
+impl<H: Henchman> Henchman for Counted<H> {
+    fn build_secret_hq(&mut self, location: String) {
+        TaskCounts::increment(&self.counts.hqs_built);
+        self.inner.build_secret_hq(location);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        self.inner.demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        TaskCounts::increment(&self.counts.hard_things);
+        self.inner.do_hard_things();
+    }
+
+    fn fight_enemies(&self) {
+        TaskCounts::increment(&self.counts.fights);
+        self.inner.fight_enemies();
+    }
+
+    fn report_progress(&self) -> Progress {
+        self.inner.report_progress()
+    }
+
+    fn is_on_strike(&self) -> bool {
+        self.inner.is_on_strike()
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, H: Borrower<'a>> Borrower<'a> for Counted<H> {
+    fn id(&self) -> HenchmanId {
+        self.inner.id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        self.inner.borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        self.inner.return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        self.inner.lose_loyalty(points);
+    }
+}
+
+/// Layer tracking the morale of the henchman, as a [`MotivatedHenchman`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MoraleLayer {
+    morale: Morale,
+}
+
+impl<H: Henchman> HenchmanLayer<H> for MoraleLayer {
+    type Henchman = MotivatedHenchman<H>;
+
+    fn layer(&self, inner: H) -> MotivatedHenchman<H> {
+        MotivatedHenchman::with_morale(inner, self.morale)
+    }
+}
+
+/// Layer retrying the work of the henchman, as a [`Retry`](crate::resilience::Retry).
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RetryLayer {
+    policy: crate::resilience::RetryPolicy,
+}
+
+#[cfg(feature = "std")]
+impl<H: Henchman> HenchmanLayer<H> for RetryLayer {
+    type Henchman = crate::resilience::Retry<H>;
+
+    fn layer(&self, inner: H) -> crate::resilience::Retry<H> {
+        crate::resilience::Retry::with_policy(inner, self.policy)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::test_common;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::vec::Vec;
+
+    #[test]
+    fn layers_wrap_in_the_order_added() {
+        let log = Rc::new(RefCell::new(Vec::new()));
+        let counts = Arc::new(TaskCounts::default());
+        let builder = HenchmanBuilder::new()
+            .morale(Morale::new(30))
+            .logging({
+                let log = Rc::clone(&log);
+                move |line: &str| log.borrow_mut().push(String::from(line))
+            })
+            .counting(&counts);
+        let mut inner = MockHenchman::new();
+        inner.expect_build_secret_hq().once().return_const(());
+        inner.expect_do_hard_things().once().return_const(());
+        inner.expect_is_on_strike().never();
+
+        let mut henchman = builder.build(inner);
+        henchman.build_secret_hq(test_common::FIRST_TARGET.into());
+        henchman.do_hard_things();
+        assert!(henchman.is_on_strike());
+        assert_eq!(counts.hqs_built(), 1);
+        assert_eq!(counts.hard_things(), 1);
+        assert_eq!(*log.borrow(), ["build_secret_hq Tampa", "do_hard_things"]);
+    }
+}
@@ -0,0 +1,115 @@
+//! Module for the typed identifiers of villains, sidekicks and henchmen.
+#![allow(dead_code)]
+
+use std::fmt;
+#[cfg(not(feature = "uuid"))]
+use std::sync::atomic::AtomicU64;
+#[cfg(not(feature = "uuid"))]
+use std::sync::atomic::Ordering;
+
+/// Value behind every identifier: a counter, or a random UUID with the `uuid` feature.
+#[cfg(not(feature = "uuid"))]
+pub type RawId = u64;
+#[cfg(feature = "uuid")]
+pub type RawId = uuid::Uuid;
+
+macro_rules! typed_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(RawId);
+
+        impl $name {
+            /// Builds an identifier from a raw value, e.g. one restored from storage.
+            pub fn new(value: u64) -> $name {
+                Self(raw_from_u64(value))
+            }
+
+            pub fn raw(&self) -> RawId {
+                self.0
+            }
+
+            /// Hands out an identifier nobody else has.
+            pub(crate) fn next() -> $name {
+                #[cfg(not(feature = "uuid"))]
+                {
+                    static NEXT: AtomicU64 = AtomicU64::new(1);
+                    Self(NEXT.fetch_add(1, Ordering::Relaxed))
+                }
+                #[cfg(feature = "uuid")]
+                {
+                    Self(uuid::Uuid::new_v4())
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+    };
+}
+
+typed_id!(
+    /// Unique identity of a villain. A default villain gets a fresh one.
+    VillainId
+);
+
+typed_id!(
+    /// Unique identity of a sidekick. Clones of a sidekick share it.
+    SidekickId
+);
+
+typed_id!(
+    /// Unique identity of a henchman, handed out when it joins a [`Registry`](crate::registry::Registry)
+    /// or is hired as a [`Goon`](crate::henchman::Goon).
+    HenchmanId
+);
+
+typed_id!(
+    /// Unique identity of an order placed on the [`Market`](crate::market::Market).
+    OrderId
+);
+
+typed_id!(
+    /// Unique identity of a gadget loan, telling apart the gadgets a henchman borrowed, whichever
+    /// sidekick lent them.
+    LoanId
+);
+
+impl Default for VillainId {
+    fn default() -> Self {
+        Self::next()
+    }
+}
+
+#[cfg(not(feature = "uuid"))]
+fn raw_from_u64(value: u64) -> RawId {
+    value
+}
+
+#[cfg(feature = "uuid")]
+fn raw_from_u64(value: u64) -> RawId {
+    uuid::Uuid::from_u64_pair(0, value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_ids_are_unique() {
+        assert_ne!(VillainId::next(), VillainId::next());
+        assert_ne!(HenchmanId::next(), HenchmanId::next());
+    }
+
+    #[test]
+    fn ids_built_from_same_value_are_equal() {
+        assert_eq!(SidekickId::new(7), SidekickId::new(7));
+        assert_eq!(
+            SidekickId::new(7).to_string(),
+            SidekickId::new(7).raw().to_string()
+        );
+    }
+}
@@ -0,0 +1,24 @@
+//! Module for how feared and notorious a villain is.
+#![allow(dead_code)]
+
+/// Infamy points. They never go below zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Infamy(u32);
+
+impl Infamy {
+    pub fn new(points: u32) -> Infamy {
+        Self(points)
+    }
+
+    pub fn points(&self) -> u32 {
+        self.0
+    }
+
+    pub fn gain(&mut self, points: u32) {
+        self.0 = self.0.saturating_add(points);
+    }
+
+    pub fn lose(&mut self, points: u32) {
+        self.0 = self.0.saturating_sub(points);
+    }
+}
@@ -0,0 +1,288 @@
+//! Module for loot: stolen artifacts, weapons and materials, and where they're kept.
+#![allow(dead_code)]
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::EvilError;
+
+/// What kind of loot an item is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ItemKind {
+    Artifact,
+    Weapon,
+    Material,
+}
+
+/// Something worth stealing. Items that are equal stack together.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Item {
+    pub name: String,
+    pub kind: ItemKind,
+    /// Weight of a single unit, in kilograms.
+    pub weight: u32,
+    /// Value of a single unit, in coins.
+    pub value: u64,
+}
+
+impl Item {
+    pub fn new(name: &str, kind: ItemKind, weight: u32, value: u64) -> Item {
+        Self {
+            name: name.into(),
+            kind,
+            weight,
+            value,
+        }
+    }
+}
+
+/// How to turn materials into something better.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recipe {
+    pub inputs: Vec<(Item, u32)>,
+    pub output: Item,
+    pub quantity: u32,
+}
+
+/// Stacks of loot, optionally limited in the total weight they can hold.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Inventory {
+    capacity: Option<u32>,
+    stacks: BTreeMap<Item, u32>,
+}
+
+impl Inventory {
+    /// Inventory that holds any weight.
+    pub fn new() -> Inventory {
+        Self::default()
+    }
+
+    /// Inventory that holds up to `capacity` kilograms.
+    pub fn with_capacity(capacity: u32) -> Inventory {
+        Self {
+            capacity: Some(capacity),
+            stacks: BTreeMap::new(),
+        }
+    }
+
+    pub fn capacity(&self) -> Option<u32> {
+        self.capacity
+    }
+
+    /// Total weight of the loot, in kilograms.
+    pub fn weight(&self) -> u32 {
+        self.stacks
+            .iter()
+            .map(|(item, &quantity)| item.weight.saturating_mul(quantity))
+            .fold(0, u32::saturating_add)
+    }
+
+    /// What all the loot is worth, in coins.
+    pub fn appraise(&self) -> u64 {
+        self.stacks
+            .iter()
+            .map(|(item, &quantity)| item.value.saturating_mul(u64::from(quantity)))
+            .fold(0, u64::saturating_add)
+    }
+
+    pub fn quantity(&self, item: &Item) -> u32 {
+        self.stacks.get(item).copied().unwrap_or(0)
+    }
+
+    /// Finds a stacked item by name.
+    pub fn find(&self, name: &str) -> Option<&Item> {
+        self.stacks.keys().find(|item| item.name == name)
+    }
+
+    /// Stacks, sorted by item.
+    pub fn stacks(&self) -> impl Iterator<Item = (&Item, u32)> {
+        self.stacks.iter().map(|(item, &quantity)| (item, quantity))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stacks.is_empty()
+    }
+
+    /// Stores the items, as long as they fit and their stack can count them.
+    pub fn add(&mut self, item: Item, quantity: u32) -> Result<(), EvilError> {
+        self.ensure_fits(item.weight.saturating_mul(quantity), 0)?;
+        let stacked = self.stacked(&item, quantity)?;
+        if quantity > 0 {
+            self.stacks.insert(item, stacked);
+        }
+        Ok(())
+    }
+
+    /// Takes the items out, as long as there are enough of them.
+    pub fn remove(&mut self, item: &Item, quantity: u32) -> Result<(), EvilError> {
+        self.ensure_has(item, quantity)?;
+        self.take(item, quantity);
+        Ok(())
+    }
+
+    /// Moves the items to another inventory, e.g. from a lair to a henchman. Nothing moves if
+    /// there aren't enough items or they don't fit.
+    pub fn transfer_to(
+        &mut self,
+        other: &mut Inventory,
+        item: &Item,
+        quantity: u32,
+    ) -> Result<(), EvilError> {
+        self.ensure_has(item, quantity)?;
+        other.add(item.clone(), quantity)?;
+        self.take(item, quantity);
+        Ok(())
+    }
+
+    /// Consumes the inputs of the recipe to make its output. Nothing is consumed if an input is
+    /// missing or the output doesn't fit.
+    pub fn craft(&mut self, recipe: &Recipe) -> Result<(), EvilError> {
+        for (item, quantity) in &recipe.inputs {
+            self.ensure_has(item, *quantity)?;
+        }
+        let freed = recipe
+            .inputs
+            .iter()
+            .map(|(item, quantity)| item.weight.saturating_mul(*quantity))
+            .fold(0, u32::saturating_add);
+        self.ensure_fits(recipe.output.weight.saturating_mul(recipe.quantity), freed)?;
+        self.stacked(&recipe.output, recipe.quantity)?;
+        for (item, quantity) in &recipe.inputs {
+            self.take(item, *quantity);
+        }
+        self.add(recipe.output.clone(), recipe.quantity)
+    }
+
+    fn ensure_has(&self, item: &Item, quantity: u32) -> Result<(), EvilError> {
+        let available = self.quantity(item);
+        if available < quantity {
+            return Err(EvilError::NotEnoughItems {
+                item: item.name.clone(),
+                available,
+                requested: quantity,
+            });
+        }
+        Ok(())
+    }
+
+    /// Size of the item's stack once the quantity is added.
+    fn stacked(&self, item: &Item, quantity: u32) -> Result<u32, EvilError> {
+        self.quantity(item)
+            .checked_add(quantity)
+            .ok_or_else(|| EvilError::InvalidParameter {
+                parameter: "quantity".into(),
+                reason: format!("Stack of '{}' would overflow", item.name),
+            })
+    }
+
+    fn ensure_fits(&self, added: u32, freed: u32) -> Result<(), EvilError> {
+        let weight = self.weight().saturating_sub(freed).saturating_add(added);
+        match self.capacity {
+            Some(capacity) if weight > capacity => Err(EvilError::TooHeavy { capacity, weight }),
+            _ => Ok(()),
+        }
+    }
+
+    fn take(&mut self, item: &Item, quantity: u32) {
+        if let Some(stack) = self.stacks.get_mut(item) {
+            *stack -= quantity;
+            if *stack == 0 {
+                self.stacks.remove(item);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use assertables::assert_ok;
+
+    fn gold() -> Item {
+        Item::new("Gold bar", ItemKind::Material, 12, 600)
+    }
+
+    fn crown() -> Item {
+        Item::new("Crown jewels", ItemKind::Artifact, 3, 10_000)
+    }
+
+    #[test]
+    fn equal_items_stack() {
+        let mut vault = Inventory::new();
+        assert_ok!(vault.add(gold(), 2));
+        assert_ok!(vault.add(gold(), 3));
+        assert_eq!(vault.quantity(&gold()), 5);
+        assert_eq!(vault.stacks().count(), 1);
+        assert_eq!(vault.weight(), 60);
+        assert_eq!(vault.appraise(), 3_000);
+    }
+
+    #[test]
+    fn loot_over_capacity_is_rejected() {
+        let mut pack = Inventory::with_capacity(30);
+        assert_ok!(pack.add(gold(), 2));
+        let result = pack.add(crown(), 3);
+        assert_matches!(
+            result,
+            Err(EvilError::TooHeavy {
+                capacity: 30,
+                weight: 33
+            })
+        );
+        assert_eq!(pack.quantity(&crown()), 0);
+    }
+
+    #[test]
+    fn stacks_too_big_to_count_are_rejected() {
+        let feather = Item::new("Feather", ItemKind::Material, 0, 1);
+        let mut vault = Inventory::new();
+        assert_ok!(vault.add(feather.clone(), u32::MAX));
+        assert_matches!(
+            vault.add(feather.clone(), 1),
+            Err(EvilError::InvalidParameter { parameter, .. }) if parameter == "quantity"
+        );
+        assert_eq!(vault.quantity(&feather), u32::MAX);
+    }
+
+    #[test]
+    fn transfer_moves_items_only_if_they_fit() {
+        let mut vault = Inventory::new();
+        assert_ok!(vault.add(gold(), 4));
+        let mut pack = Inventory::with_capacity(30);
+
+        assert_ok!(vault.transfer_to(&mut pack, &gold(), 2));
+        assert_eq!(vault.quantity(&gold()), 2);
+        assert_eq!(pack.quantity(&gold()), 2);
+
+        assert_matches!(
+            vault.transfer_to(&mut pack, &gold(), 1),
+            Err(EvilError::TooHeavy { .. })
+        );
+        assert_matches!(
+            vault.transfer_to(&mut pack, &crown(), 1),
+            Err(EvilError::NotEnoughItems { available: 0, .. })
+        );
+        assert_eq!(vault.quantity(&gold()), 2);
+    }
+
+    #[test]
+    fn crafting_consumes_inputs() {
+        let laser = Item::new("Laser cannon", ItemKind::Weapon, 20, 50_000);
+        let recipe = Recipe {
+            inputs: vec![(gold(), 2), (crown(), 1)],
+            output: laser.clone(),
+            quantity: 1,
+        };
+        let mut vault = Inventory::with_capacity(30);
+        assert_ok!(vault.add(gold(), 2));
+        assert_ok!(vault.add(crown(), 1));
+
+        assert_ok!(vault.craft(&recipe));
+        assert_eq!(vault.quantity(&laser), 1);
+        assert!(vault.find("Gold bar").is_none());
+        assert_matches!(vault.craft(&recipe), Err(EvilError::NotEnoughItems { .. }));
+    }
+}
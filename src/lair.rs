@@ -0,0 +1,325 @@
+//! Module for the villain's lair and the traps defending it.
+#![allow(dead_code)]
+
+use std::str::FromStr;
+
+use rand::Rng;
+
+use crate::inventory::Inventory;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+use crate::supervillain::EvilError;
+use crate::trophies::TrophyRoom;
+
+/// Kinds of traps a lair can be defended with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TrapKind {
+    /// Dodged with agility.
+    Lasers,
+    /// Spotted in time with wits.
+    Pit,
+    /// Fought off with strength.
+    Sharks,
+}
+
+impl TrapKind {
+    /// Coins needed per point of trap strength.
+    pub fn cost_per_point(&self) -> u64 {
+        match self {
+            TrapKind::Lasers => 150,
+            TrapKind::Pit => 40,
+            TrapKind::Sharks => 300,
+        }
+    }
+}
+
+impl FromStr for TrapKind {
+    type Err = EvilError;
+
+    fn from_str(kind: &str) -> Result<Self, Self::Err> {
+        match kind {
+            "Lasers" => Ok(TrapKind::Lasers),
+            "Pit" => Ok(TrapKind::Pit),
+            "Sharks" => Ok(TrapKind::Sharks),
+            _ => Err(EvilError::ParseError {
+                purpose: "trap_kind".into(),
+                reason: "Unknown trap kind".into(),
+            }),
+        }
+    }
+}
+
+/// A trap and how hard it is to get past, from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Trap {
+    pub kind: TrapKind,
+    pub strength: u8,
+}
+
+impl Trap {
+    pub fn new(kind: TrapKind, strength: u8) -> Trap {
+        Self {
+            kind,
+            strength: strength.min(100),
+        }
+    }
+
+    pub fn cost(&self) -> u64 {
+        self.kind.cost_per_point() * u64::from(self.strength)
+    }
+
+    /// Difficulty of the skill check to get past this trap.
+    pub fn difficulty(&self) -> Difficulty {
+        Difficulty::against(self.strength)
+    }
+
+    /// Chance the intruder gets past this trap, between 0 and 1.
+    pub fn evasion_chance(&self, intruder: &Intruder) -> f64 {
+        SkillCheck::default().success_chance(intruder.skill_against(self.kind), self.difficulty())
+    }
+
+    fn is_evaded_by<R: Rng + ?Sized>(&self, intruder: &Intruder, rng: &mut R) -> bool {
+        SkillCheck::roll(intruder.skill_against(self.kind), self.difficulty(), rng).is_success()
+    }
+}
+
+/// Someone trying to get into the lair, usually a hero. Skills go from 0 to 100.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intruder {
+    pub name: String,
+    pub agility: u8,
+    pub strength: u8,
+    pub wits: u8,
+}
+
+impl Intruder {
+    pub fn new(name: &str, agility: u8, strength: u8, wits: u8) -> Intruder {
+        Self {
+            name: name.into(),
+            agility,
+            strength,
+            wits,
+        }
+    }
+
+    fn skill_against(&self, kind: TrapKind) -> u8 {
+        match kind {
+            TrapKind::Lasers => self.agility,
+            TrapKind::Pit => self.wits,
+            TrapKind::Sharks => self.strength,
+        }
+    }
+}
+
+/// How a simulated breach ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreachOutcome {
+    GotThrough,
+    Caught { trap: TrapKind, traps_passed: usize },
+}
+
+/// Secret lair, defended by traps the intruder has to get past in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lair {
+    pub location: String,
+    traps: Vec<Trap>,
+    /// Where the loot is kept.
+    pub vault: Inventory,
+    /// Where mementos of defeated heroes and heists are shown off.
+    pub trophy_room: TrophyRoom,
+}
+
+impl Lair {
+    pub fn new(location: &str) -> Lair {
+        Self {
+            location: location.into(),
+            traps: Vec::new(),
+            vault: Inventory::new(),
+            trophy_room: TrophyRoom::new(),
+        }
+    }
+
+    pub fn builder(location: &str) -> LairBuilder {
+        LairBuilder::new(location)
+    }
+
+    /// Lair whose vault holds up to `capacity` kilograms of loot.
+    pub fn with_vault_capacity(location: &str, capacity: u32) -> Lair {
+        Self {
+            vault: Inventory::with_capacity(capacity),
+            ..Self::new(location)
+        }
+    }
+
+    pub fn add_trap(&mut self, trap: Trap) {
+        self.traps.push(trap);
+    }
+
+    pub fn traps(&self) -> &[Trap] {
+        &self.traps
+    }
+
+    /// What all the traps cost.
+    pub fn defense_budget(&self) -> u64 {
+        self.traps.iter().map(Trap::cost).sum()
+    }
+
+    /// Chance the intruder gets through every trap, between 0 and 1.
+    pub fn breach_chance(&self, intruder: &Intruder) -> f64 {
+        self.traps
+            .iter()
+            .map(|trap| trap.evasion_chance(intruder))
+            .product()
+    }
+
+    /// Sends the intruder through the traps, returning whether the hero gets through.
+    pub fn simulate_breach<R: Rng + ?Sized>(
+        &self,
+        intruder: &Intruder,
+        rng: &mut R,
+    ) -> BreachOutcome {
+        for (traps_passed, trap) in self.traps.iter().enumerate() {
+            if !trap.is_evaded_by(intruder, rng) {
+                return BreachOutcome::Caught {
+                    trap: trap.kind,
+                    traps_passed,
+                };
+            }
+        }
+        BreachOutcome::GotThrough
+    }
+}
+
+/// Builder of [`Lair`]s. The vault holds any weight and the trophy room any number of trophies
+/// unless given a capacity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LairBuilder {
+    location: String,
+    vault_capacity: Option<u32>,
+    trophy_space: Option<u32>,
+    traps: Vec<Trap>,
+}
+
+impl LairBuilder {
+    pub fn new(location: &str) -> LairBuilder {
+        Self {
+            location: location.into(),
+            vault_capacity: None,
+            trophy_space: None,
+            traps: Vec::new(),
+        }
+    }
+
+    pub fn vault_capacity(mut self, capacity: u32) -> LairBuilder {
+        self.vault_capacity = Some(capacity);
+        self
+    }
+
+    /// Room for trophies, in square meters.
+    pub fn trophy_space(mut self, space: u32) -> LairBuilder {
+        self.trophy_space = Some(space);
+        self
+    }
+
+    pub fn trap(mut self, trap: Trap) -> LairBuilder {
+        self.traps.push(trap);
+        self
+    }
+
+    /// Builds the lair, failing if it has no location.
+    pub fn build(self) -> Result<Lair, EvilError> {
+        if self.location.trim().is_empty() {
+            return Err(EvilError::ParseError {
+                purpose: "lair".into(),
+                reason: "Missing location".into(),
+            });
+        }
+        let mut lair = match self.vault_capacity {
+            Some(capacity) => Lair::with_vault_capacity(&self.location, capacity),
+            None => Lair::new(&self.location),
+        };
+        if let Some(space) = self.trophy_space {
+            lair.trophy_room = TrophyRoom::with_space(space);
+        }
+        lair.traps = self.traps;
+        Ok(lair)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::Goon;
+    use crate::inventory::Item;
+    use crate::inventory::ItemKind;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use assertables::assert_ok;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn lair() -> Lair {
+        let mut lair = Lair::new(test_common::FIRST_TARGET);
+        lair.add_trap(Trap::new(TrapKind::Pit, 10));
+        lair.add_trap(Trap::new(TrapKind::Lasers, 50));
+        lair.add_trap(Trap::new(TrapKind::Sharks, 100));
+        lair
+    }
+
+    #[test]
+    fn defense_budget_adds_up_trap_costs() {
+        assert_eq!(lair().defense_budget(), 400 + 7_500 + 30_000);
+    }
+
+    #[test]
+    fn hero_without_strength_is_caught_by_sharks() {
+        let hero = Intruder::new(test_common::HERO_NAME, 100, 0, 100);
+        let mut lair = Lair::new(test_common::FIRST_TARGET);
+        lair.add_trap(Trap::new(TrapKind::Pit, 10));
+        lair.add_trap(Trap::new(TrapKind::Sharks, 100));
+        let outcome = lair.simulate_breach(&hero, &mut StdRng::seed_from_u64(1));
+        assert_eq!(
+            outcome,
+            BreachOutcome::Caught {
+                trap: TrapKind::Sharks,
+                traps_passed: 1
+            }
+        );
+        assert_eq!(lair.breach_chance(&hero), 0.0);
+    }
+
+    #[test]
+    fn undefended_lair_is_always_breached() {
+        let hero = Intruder::new(test_common::HERO_NAME, 0, 0, 0);
+        let lair = Lair::new(test_common::FIRST_TARGET);
+        let outcome = lair.simulate_breach(&hero, &mut StdRng::seed_from_u64(1));
+        assert_eq!(outcome, BreachOutcome::GotThrough);
+        assert_eq!(lair.breach_chance(&hero), 1.0);
+    }
+
+    #[test]
+    fn breach_chance_multiplies_evasion_chances() {
+        let hero = Intruder::new(test_common::HERO_NAME, 50, 100, 90);
+        let chance = lair().breach_chance(&hero);
+        assert!((chance - 0.55 * 0.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn loot_moves_from_vault_to_goon_within_limits() {
+        let statue = Item::new("Golden statue", ItemKind::Artifact, 30, 5_000);
+        let mut lair = Lair::with_vault_capacity(test_common::FIRST_TARGET, 100);
+        assert_ok!(lair.vault.add(statue.clone(), 3));
+        assert_matches!(
+            lair.vault.add(statue.clone(), 1),
+            Err(EvilError::TooHeavy { .. })
+        );
+
+        let mut goon = Goon::new("Igor");
+        assert_ok!(lair.vault.transfer_to(&mut goon.loot, &statue, 1));
+        assert_matches!(
+            lair.vault.transfer_to(&mut goon.loot, &statue, 1),
+            Err(EvilError::TooHeavy { .. })
+        );
+        assert_eq!(lair.vault.quantity(&statue), 2);
+    }
+}
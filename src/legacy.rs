@@ -0,0 +1,202 @@
+//! Module for villains retiring and leaving their evil empire to an heir.
+#![allow(dead_code)]
+
+use crate::gadget::Capability;
+use crate::id::VillainId;
+use crate::infamy::Infamy;
+use crate::supervillain::EvilError;
+use crate::supervillain::SuperVillain;
+
+/// A retired villain, as remembered by its heirs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ancestor {
+    pub id: VillainId,
+    pub name: String,
+    /// Infamy when it retired.
+    pub infamy: Infamy,
+}
+
+/// What a retiring villain leaves to its heir, besides the lair, which is always handed over.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Will {
+    /// Percentage of the treasury, from 0 to 100.
+    pub treasury_share: u8,
+    /// Percentage of the infamy, from 0 to 100.
+    pub infamy_share: u8,
+    /// Capabilities whose best gadget goes to the heir.
+    pub gadgets: Vec<Capability>,
+}
+
+impl Default for Will {
+    fn default() -> Self {
+        Self {
+            treasury_share: 50,
+            infamy_share: 25,
+            gadgets: Vec::new(),
+        }
+    }
+}
+
+/// What the heir got.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Inheritance {
+    pub coins: u64,
+    pub infamy: u32,
+    pub gadgets: usize,
+    pub lair: bool,
+}
+
+/// Retires the villain, handing its heir the lair and the shares of treasury, infamy and
+/// gadgets in the will. The heir's lineage gets the villain's, followed by the villain.
+///
+/// Villains can't retire while locked up: they have to escape first.
+pub fn retire<'a>(
+    mut villain: SuperVillain<'a>,
+    heir: &mut SuperVillain<'a>,
+    will: &Will,
+) -> Result<Inheritance, EvilError> {
+    if villain.status.is_locked_up() {
+        return Err(EvilError::Incarcerated);
+    }
+    let coins = share(villain.treasury.balance(), will.treasury_share);
+    villain.treasury.withdraw(coins)?;
+    heir.treasury.deposit(coins);
+
+    let infamy = share(u64::from(villain.infamy.points()), will.infamy_share) as u32;
+    heir.infamy.gain(infamy);
+
+    let mut gadgets = 0;
+    for &capability in &will.gadgets {
+        if let Some(gadget) = villain.gadgets.take_best_for(capability) {
+            heir.gadgets.add_boxed(gadget);
+            gadgets += 1;
+        }
+    }
+
+    let lair = villain.lair.take().is_some_and(|lair| {
+        heir.lair = Some(lair);
+        true
+    });
+
+    heir.lineage = std::mem::take(&mut villain.lineage);
+    heir.lineage.push(Ancestor {
+        id: villain.id,
+        name: villain.full_name(),
+        infamy: villain.infamy,
+    });
+    Ok(Inheritance {
+        coins,
+        infamy,
+        gadgets,
+        lair,
+    })
+}
+
+fn share(amount: u64, percentage: u8) -> u64 {
+    (u128::from(amount) * u128::from(percentage.min(100)) / 100) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::MockGadget;
+    use crate::lair::Lair;
+    use crate::supervillain::VillainStatus;
+    use crate::test_common;
+    use crate::treasury::Treasury;
+    use assertables::assert_matches;
+
+    fn rated(capability: Capability, rating: u8) -> MockGadget {
+        let mut gadget = MockGadget::new();
+        gadget
+            .expect_rating()
+            .returning(move |asked| if asked == capability { rating } else { 0 });
+        gadget
+    }
+
+    fn villain(id: u64, first_name: &str, last_name: &str) -> SuperVillain<'static> {
+        SuperVillain {
+            id: VillainId::new(id),
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn heir_inherits_what_the_will_says() -> Result<(), EvilError> {
+        let mut lex = villain(
+            1,
+            test_common::PRIMARY_FIRST_NAME,
+            test_common::PRIMARY_LAST_NAME,
+        );
+        lex.treasury = Treasury::new(1_000);
+        lex.infamy = Infamy::new(80);
+        lex.lair = Some(Lair::new("Metropolis"));
+        lex.gadgets.add(rated(Capability::Combat, 90));
+        lex.gadgets.add(rated(Capability::Escape, 40));
+        let mut heir = villain(2, "Alexander", test_common::PRIMARY_LAST_NAME);
+
+        let inheritance = retire(
+            lex,
+            &mut heir,
+            &Will {
+                gadgets: vec![Capability::Combat, Capability::Surveillance],
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(
+            inheritance,
+            Inheritance {
+                coins: 500,
+                infamy: 20,
+                gadgets: 1,
+                lair: true,
+            }
+        );
+        assert_eq!(heir.treasury.balance(), 500);
+        assert_eq!(heir.gadgets.len(), 1);
+        assert_eq!(
+            heir.lair.map(|lair| lair.location),
+            Some("Metropolis".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ancestors_go_from_parent_to_founder() -> Result<(), EvilError> {
+        let founder = villain(1, "Lionel", test_common::PRIMARY_LAST_NAME);
+        let mut lex = villain(
+            2,
+            test_common::PRIMARY_FIRST_NAME,
+            test_common::PRIMARY_LAST_NAME,
+        );
+        retire(founder, &mut lex, &Will::default())?;
+        let mut heir = villain(3, "Alexander", test_common::PRIMARY_LAST_NAME);
+        retire(lex, &mut heir, &Will::default())?;
+
+        let names = heir
+            .ancestors()
+            .map(|ancestor| ancestor.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, [test_common::PRIMARY_FULL_NAME, "Lionel Luthor"]);
+        Ok(())
+    }
+
+    #[test]
+    fn locked_up_villains_cannot_retire() {
+        let mut lex = villain(
+            1,
+            test_common::PRIMARY_FIRST_NAME,
+            test_common::PRIMARY_LAST_NAME,
+        );
+        lex.status = VillainStatus::Captured;
+        let mut heir = villain(2, "Alexander", test_common::PRIMARY_LAST_NAME);
+        assert_matches!(
+            retire(lex, &mut heir, &Will::default()),
+            Err(EvilError::Incarcerated)
+        );
+        assert!(heir.lineage.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+//! Module for the ledger of gadgets sidekicks lend to henchmen, so shared equipment is tracked
+//! instead of silently lost.
+#![allow(dead_code)]
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::container::SharedClock;
+use crate::id::HenchmanId;
+use crate::id::LoanId;
+use crate::sidekick::Slot;
+
+/// Loyalty a henchman loses for every gadget recalled past its due date.
+pub const OVERDUE_LOYALTY_PENALTY: u8 = 10;
+
+/// Gadget lent out of a slot to a henchman, and when it's due back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Loan {
+    pub id: LoanId,
+    /// Slot the gadget goes back to.
+    pub slot: Slot,
+    /// Henchman holding the gadget.
+    pub borrower: HenchmanId,
+    pub lent_at: Instant,
+    pub due: Instant,
+}
+
+impl Loan {
+    pub fn is_overdue(&self, now: Instant) -> bool {
+        now > self.due
+    }
+}
+
+/// Loans still out, and the clock telling when they're due.
+#[derive(Clone)]
+pub struct LendingLedger {
+    clock: SharedClock,
+    loans: Vec<Loan>,
+}
+
+impl LendingLedger {
+    pub fn new() -> LendingLedger {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    pub fn with_clock(clock: SharedClock) -> LendingLedger {
+        Self {
+            clock,
+            loans: Vec::new(),
+        }
+    }
+
+    pub fn loans(&self) -> &[Loan] {
+        &self.loans
+    }
+
+    pub fn loan(&self, id: LoanId) -> Option<&Loan> {
+        self.loans.iter().find(|loan| loan.id == id)
+    }
+
+    /// Loans past their due date, oldest first.
+    pub fn overdue(&self) -> Vec<Loan> {
+        let now = self.clock.now();
+        self.loans
+            .iter()
+            .filter(|loan| loan.is_overdue(now))
+            .copied()
+            .collect()
+    }
+
+    pub(crate) fn open(&mut self, slot: Slot, borrower: HenchmanId, duration: Duration) -> Loan {
+        let lent_at = self.clock.now();
+        let loan = Loan {
+            id: LoanId::next(),
+            slot,
+            borrower,
+            lent_at,
+            due: lent_at + duration,
+        };
+        self.loans.push(loan);
+        loan
+    }
+
+    pub(crate) fn close(&mut self, id: LoanId) -> Option<Loan> {
+        let index = self.loans.iter().position(|loan| loan.id == id)?;
+        Some(self.loans.remove(index))
+    }
+}
+
+impl Default for LendingLedger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Debug for LendingLedger {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LendingLedger")
+            .field("loans", &self.loans)
+            .finish_non_exhaustive()
+    }
+}
@@ -1,14 +1,185 @@
+//! Evil plans, villains and their henchmen.
+//!
+//! The `std` feature is on by default. Without it, the crate is `no_std` and only keeps the
+//! core types that need nothing but `alloc`: ciphers, gadgets, henchmen, moods, monologues,
+//! plans, treasuries, trophies and units. Villains, sidekicks and everything relying on I/O,
+//! clocks, or a thread RNG need `std`. Async APIs running on `tokio` need the `async` feature,
+//! also on by default; each has a blocking equivalent for consumers without a runtime.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+
+pub mod ai;
+#[cfg(feature = "std")]
+pub mod audit;
+#[cfg(feature = "std")]
+pub mod blueprint;
+#[cfg(feature = "std")]
+pub mod broadcast;
+#[cfg(feature = "std")]
+pub mod calendar;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod cipher;
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
+pub mod container;
+pub mod coordinator;
+#[cfg(feature = "std")]
+pub mod damage;
+#[cfg(feature = "std")]
+pub mod dashboard;
+#[cfg(feature = "std")]
+pub mod device;
+#[cfg(feature = "std")]
+pub mod disguise;
+#[cfg(feature = "std")]
+pub mod domination;
+#[cfg(feature = "std")]
+pub mod dossier;
+#[cfg(feature = "std")]
+pub mod envelope;
+#[cfg(feature = "std")]
+pub mod environment;
+pub mod error;
+#[cfg(feature = "std")]
+pub mod espionage;
+#[cfg(feature = "std")]
+pub mod event;
 pub mod gadget;
+#[cfg(feature = "std")]
+pub mod gossip;
+#[cfg(feature = "async")]
+pub mod handle;
+#[cfg(feature = "std")]
+pub mod heat;
 pub mod henchman;
+#[cfg(feature = "std")]
+pub mod id;
+pub mod infamy;
+pub mod inventory;
+#[cfg(feature = "std")]
+pub mod lair;
+#[cfg(feature = "std")]
+pub mod legacy;
+#[cfg(feature = "std")]
+pub mod lending;
+#[cfg(feature = "std")]
+pub mod market;
+#[cfg(feature = "std")]
+pub mod message;
+#[cfg(feature = "metrics")]
+pub mod metrics;
+pub mod minion;
+pub mod monologue;
+pub mod mood;
+pub mod names;
+#[cfg(feature = "std")]
+pub mod narrator;
+#[cfg(feature = "std")]
+pub mod nemesis;
+#[cfg(feature = "std")]
+pub mod notify;
+pub mod personality;
+#[cfg(feature = "std")]
+pub mod pipeline;
+pub mod plan;
+#[cfg(feature = "std")]
+pub mod presets;
+#[cfg(feature = "std")]
+pub mod ransom;
+pub mod redacted;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+pub mod replay;
+#[cfg(feature = "std")]
+pub mod repository;
+pub mod research;
+#[cfg(feature = "std")]
+pub mod resilience;
+#[cfg(feature = "std")]
+pub mod roster;
+#[cfg(feature = "std")]
+pub mod scheduler;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "std")]
 pub mod sidekick;
+#[cfg(feature = "std")]
+pub mod simulation;
+pub mod skillcheck;
+#[cfg(feature = "testing")]
+pub mod snapshot;
+#[cfg(feature = "std")]
 pub mod supervillain;
+#[cfg(feature = "std")]
+pub mod targeting;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "std")]
+pub mod transaction;
+pub mod treasury;
+pub mod trophies;
+pub mod units;
+#[cfg(feature = "std")]
+pub mod workforce;
 
 #[cfg(test)]
 mod test_common;
 
+pub use cipher::AsyncCipher;
 pub use cipher::Cipher;
+pub use cipher::handshake::CipherSuite;
+#[cfg(feature = "std")]
+pub use clock::Clock;
+#[cfg(feature = "std")]
+pub use device::DoomsdayDevice;
+#[cfg(feature = "std")]
+pub use environment::Environment;
+pub use error::EvilError;
+#[cfg(feature = "std")]
+pub use event::EventBus;
+#[cfg(feature = "std")]
+pub use event::EvilEvent;
+#[cfg(feature = "derive")]
+pub use evil_derive::Henchman;
 pub use gadget::Gadget;
+pub use gadget::GadgetInventory;
+#[cfg(feature = "std")]
+pub use henchman::Borrower;
 pub use henchman::Henchman;
+pub use henchman::Morale;
+pub use henchman::MotivatedHenchman;
+pub use henchman::Progress;
+#[cfg(feature = "std")]
+pub use id::HenchmanId;
+#[cfg(feature = "std")]
+pub use id::SidekickId;
+#[cfg(feature = "std")]
+pub use id::VillainId;
+pub use infamy::Infamy;
+pub use inventory::Inventory;
+#[cfg(feature = "std")]
+pub use lair::Lair;
+pub use mood::Mood;
+pub use names::NameOrder;
+#[cfg(feature = "std")]
+pub use pipeline::DominationPipeline;
+#[cfg(feature = "std")]
+pub use pipeline::DominationStage;
+pub use plan::PlanGraph;
+#[cfg(feature = "std")]
+pub use registry::Registry;
+#[cfg(feature = "std")]
+pub use replay::ReplayRecorder;
+#[cfg(feature = "std")]
+pub use repository::VillainRepository;
+#[cfg(feature = "std")]
 pub use sidekick::Sidekick;
+#[cfg(feature = "std")]
 pub use supervillain::SuperVillain;
+pub use treasury::Treasury;
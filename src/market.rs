@@ -0,0 +1,368 @@
+//! Module for the black market where villains trade gadgets, blueprints and intel.
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use crate::gadget::Gadget;
+use crate::gadget::appraisal::Appraiser;
+use crate::heat::HeatMap;
+use crate::id::OrderId;
+use crate::id::VillainId;
+use crate::supervillain::EvilError;
+use crate::treasury::Treasury;
+
+/// Price increase for every open bid for the same contraband, in percent.
+const DEMAND_PREMIUM: u64 = 10;
+/// Price decrease for every open listing of the same contraband, in percent.
+const SUPPLY_DISCOUNT: u64 = 5;
+/// Prices never fall below this percentage of the base price.
+const PRICE_FLOOR: u64 = 50;
+
+/// What kind of contraband is traded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContrabandKind {
+    Gadget,
+    Blueprint,
+    Intel,
+}
+
+/// Something traded on the market. Contraband with the same name is interchangeable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Contraband {
+    pub name: String,
+    pub kind: ContrabandKind,
+    /// Region it comes from. It's hard to move while the region is hot.
+    pub region: String,
+    /// Price in a quiet market, in coins.
+    pub base_price: u64,
+}
+
+impl Contraband {
+    pub fn new(name: &str, kind: ContrabandKind, region: &str, base_price: u64) -> Contraband {
+        Self {
+            name: name.into(),
+            kind,
+            region: region.into(),
+            base_price,
+        }
+    }
+
+    /// Gadget for sale, with the base price set by the appraiser.
+    pub fn gadget<A: Appraiser + ?Sized>(
+        name: &str,
+        region: &str,
+        gadget: &dyn Gadget,
+        appraiser: &A,
+    ) -> Contraband {
+        Self::new(
+            name,
+            ContrabandKind::Gadget,
+            region,
+            appraiser.appraise(gadget),
+        )
+    }
+}
+
+/// Offer to sell contraband at a fixed price.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Listing {
+    pub id: OrderId,
+    pub seller: VillainId,
+    pub contraband: Contraband,
+    pub price: u64,
+}
+
+/// Offer to buy contraband with the given name. Its coins are held in escrow while it's open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bid {
+    pub id: OrderId,
+    pub buyer: VillainId,
+    pub name: String,
+    pub price: u64,
+}
+
+/// What happened to a bid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BidOutcome {
+    /// Bought a listing right away.
+    Filled(Contraband),
+    /// Waiting for a seller.
+    Open(OrderId),
+}
+
+/// What happened to a listing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListOutcome {
+    /// Sold right away to the bid of the buyer, at the bid's price.
+    Filled { price: u64, buyer: VillainId },
+    /// Waiting for a buyer.
+    Listed(OrderId),
+}
+
+/// What a villain has waiting to be collected: coins from sales and refunds, and goods bought
+/// while away.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Settlement {
+    pub coins: u64,
+    pub goods: Vec<Contraband>,
+}
+
+/// Listings and bids among villains, with the coins of every open bid and uncollected sale
+/// held in escrow.
+#[derive(Debug, Default)]
+pub struct Market {
+    listings: Vec<Listing>,
+    bids: Vec<Bid>,
+    escrow: Treasury,
+    pending: HashMap<VillainId, Settlement>,
+}
+
+impl Market {
+    pub fn new() -> Market {
+        Self::default()
+    }
+
+    pub fn listings(&self) -> &[Listing] {
+        &self.listings
+    }
+
+    pub fn bids(&self) -> &[Bid] {
+        &self.bids
+    }
+
+    /// Coins held for open bids and uncollected sales.
+    pub fn escrow(&self) -> u64 {
+        self.escrow.balance()
+    }
+
+    pub fn pending(&self, villain: VillainId) -> Option<&Settlement> {
+        self.pending.get(&villain)
+    }
+
+    /// Fair price of the contraband right now: the base price, up with every open bid for it,
+    /// down with every listing of it and with the heat on its region. Never below half the
+    /// base price.
+    pub fn quote(&self, contraband: &Contraband, heat: &HeatMap) -> u64 {
+        let demand = self
+            .bids
+            .iter()
+            .filter(|bid| bid.name == contraband.name)
+            .count() as u64;
+        let supply = self
+            .listings
+            .iter()
+            .filter(|listing| listing.contraband.name == contraband.name)
+            .count() as u64;
+        let percentage = (100 + DEMAND_PREMIUM * demand)
+            .saturating_sub(SUPPLY_DISCOUNT * supply)
+            .saturating_sub(u64::from(heat.penalty(&contraband.region)))
+            .max(PRICE_FLOOR);
+        contraband.base_price.saturating_mul(percentage) / 100
+    }
+
+    /// Offers the contraband at a fixed price. If there are bids for it at that price or
+    /// higher, the highest one, or the oldest among equals, buys it at its price right away.
+    pub fn list(&mut self, seller: VillainId, contraband: Contraband, price: u64) -> ListOutcome {
+        let best = self
+            .bids
+            .iter()
+            .enumerate()
+            .filter(|(_, bid)| bid.name == contraband.name && bid.price >= price)
+            .min_by_key(|(_, bid)| Reverse(bid.price))
+            .map(|(index, _)| index);
+        match best {
+            Some(index) => {
+                let bid = self.bids.remove(index);
+                self.pending.entry(seller).or_default().coins += bid.price;
+                self.pending
+                    .entry(bid.buyer)
+                    .or_default()
+                    .goods
+                    .push(contraband);
+                ListOutcome::Filled {
+                    price: bid.price,
+                    buyer: bid.buyer,
+                }
+            }
+            None => {
+                let id = OrderId::next();
+                self.listings.push(Listing {
+                    id,
+                    seller,
+                    contraband,
+                    price,
+                });
+                ListOutcome::Listed(id)
+            }
+        }
+    }
+
+    /// Buys a listing at its price. The seller collects the coins later.
+    pub fn buy(
+        &mut self,
+        listing: OrderId,
+        treasury: &mut Treasury,
+    ) -> Result<Contraband, EvilError> {
+        let index = self
+            .listings
+            .iter()
+            .position(|open| open.id == listing)
+            .ok_or_else(|| unknown_order(listing))?;
+        treasury.withdraw(self.listings[index].price)?;
+        let listing = self.listings.remove(index);
+        self.escrow.deposit(listing.price);
+        self.pending.entry(listing.seller).or_default().coins += listing.price;
+        Ok(listing.contraband)
+    }
+
+    /// Bids for contraband with that name. Buys the cheapest listing at that price or lower
+    /// right away, or else holds the coins in escrow until a seller comes.
+    pub fn bid(
+        &mut self,
+        buyer: VillainId,
+        name: &str,
+        price: u64,
+        treasury: &mut Treasury,
+    ) -> Result<BidOutcome, EvilError> {
+        let cheapest = self
+            .listings
+            .iter()
+            .filter(|listing| listing.contraband.name == name && listing.price <= price)
+            .min_by_key(|listing| listing.price)
+            .map(|listing| listing.id);
+        if let Some(listing) = cheapest {
+            return self.buy(listing, treasury).map(BidOutcome::Filled);
+        }
+        treasury.withdraw(price)?;
+        self.escrow.deposit(price);
+        let id = OrderId::next();
+        self.bids.push(Bid {
+            id,
+            buyer,
+            name: name.into(),
+            price,
+        });
+        Ok(BidOutcome::Open(id))
+    }
+
+    /// Withdraws an open order. The coins of a bid, or the goods of a listing, are left for
+    /// whoever placed it to collect.
+    pub fn cancel(&mut self, order: OrderId) -> Result<(), EvilError> {
+        if let Some(index) = self.bids.iter().position(|bid| bid.id == order) {
+            let bid = self.bids.remove(index);
+            self.pending.entry(bid.buyer).or_default().coins += bid.price;
+            return Ok(());
+        }
+        if let Some(index) = self.listings.iter().position(|listing| listing.id == order) {
+            let listing = self.listings.remove(index);
+            self.pending
+                .entry(listing.seller)
+                .or_default()
+                .goods
+                .push(listing.contraband);
+            return Ok(());
+        }
+        Err(unknown_order(order))
+    }
+
+    /// Pays the coins waiting for the villain into its treasury and hands over its goods.
+    pub fn collect(&mut self, villain: VillainId, treasury: &mut Treasury) -> Vec<Contraband> {
+        let Some(settlement) = self.pending.remove(&villain) else {
+            return Vec::new();
+        };
+        // Pending coins are always in escrow.
+        if self.escrow.withdraw(settlement.coins).is_ok() {
+            treasury.deposit(settlement.coins);
+        }
+        settlement.goods
+    }
+}
+
+fn unknown_order(order: OrderId) -> EvilError {
+    EvilError::UnknownOrder {
+        id: order.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    fn freeze_ray() -> Contraband {
+        Contraband::new(
+            "Freeze ray",
+            ContrabandKind::Gadget,
+            test_common::FIRST_TARGET,
+            1_000,
+        )
+    }
+
+    #[test]
+    fn quote_follows_supply_demand_and_heat() -> Result<(), EvilError> {
+        let mut market = Market::new();
+        let mut heat = HeatMap::new();
+        let mut treasury = Treasury::new(10_000);
+        assert_eq!(market.quote(&freeze_ray(), &heat), 1_000);
+
+        market.bid(VillainId::new(1), "Freeze ray", 900, &mut treasury)?;
+        market.bid(VillainId::new(2), "Freeze ray", 900, &mut treasury)?;
+        assert_eq!(market.quote(&freeze_ray(), &heat), 1_200);
+
+        market.list(VillainId::new(3), freeze_ray(), 1_500);
+        heat.raise(test_common::FIRST_TARGET, 60);
+        assert_eq!(market.quote(&freeze_ray(), &heat), 900);
+        Ok(())
+    }
+
+    #[test]
+    fn listing_sells_to_highest_bid_through_escrow() -> Result<(), EvilError> {
+        let (buyer, rival, seller) = (VillainId::new(1), VillainId::new(2), VillainId::new(3));
+        let mut market = Market::new();
+        let mut buyer_treasury = Treasury::new(1_000);
+        let mut rival_treasury = Treasury::new(1_000);
+        market.bid(buyer, "Freeze ray", 800, &mut buyer_treasury)?;
+        let BidOutcome::Open(rival_bid) =
+            market.bid(rival, "Freeze ray", 600, &mut rival_treasury)?
+        else {
+            panic!("Bid filled without listings");
+        };
+        assert_eq!(market.escrow(), 1_400);
+
+        assert_eq!(
+            market.list(seller, freeze_ray(), 700),
+            ListOutcome::Filled { price: 800, buyer }
+        );
+        market.cancel(rival_bid)?;
+        let mut seller_treasury = Treasury::new(0);
+        assert!(market.collect(seller, &mut seller_treasury).is_empty());
+        assert_eq!(market.collect(buyer, &mut buyer_treasury), [freeze_ray()]);
+        market.collect(rival, &mut rival_treasury);
+
+        assert_eq!(seller_treasury.balance(), 800);
+        assert_eq!(buyer_treasury.balance(), 200);
+        assert_eq!(rival_treasury.balance(), 1_000);
+        assert_eq!(market.escrow(), 0);
+        Ok(())
+    }
+
+    #[test]
+    fn bid_buys_cheapest_listing_right_away() -> Result<(), EvilError> {
+        let mut market = Market::new();
+        market.list(VillainId::new(1), freeze_ray(), 900);
+        let outcome = market.list(VillainId::new(2), freeze_ray(), 700);
+        assert_matches!(outcome, ListOutcome::Listed(id) if market.listings()[1].id == id);
+        let mut treasury = Treasury::new(1_000);
+        let outcome = market.bid(VillainId::new(3), "Freeze ray", 800, &mut treasury)?;
+        assert_eq!(outcome, BidOutcome::Filled(freeze_ray()));
+        assert_eq!(treasury.balance(), 300);
+        assert_eq!(market.listings().len(), 1);
+        assert_matches!(
+            market.cancel(OrderId::new(0)),
+            Err(EvilError::UnknownOrder { .. })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,352 @@
+//! Module for what villains tell their sidekicks: plain text, plans, targets or raw bytes.
+#![allow(dead_code)]
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::plan::MissionRequirements;
+use crate::plan::PlanGraph;
+use crate::plan::PlanStep;
+use crate::plan::StepId;
+use crate::sidekick::TargetUpdate;
+use crate::supervillain::EvilError;
+
+/// Something that can be told to a sidekick.
+pub trait EvilMessage {
+    /// Kind of message, so receivers know how to decode it.
+    fn kind(&self) -> &'static str;
+
+    /// Bytes sent over the wire.
+    fn to_bytes(&self) -> Vec<u8>;
+
+    /// Bytes sent over the wire, borrowed instead of copied when the message already holds
+    /// them, so large messages reach sidekicks without copies.
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(self.to_bytes())
+    }
+
+    /// Bytes sent over the wire, for a receiver to keep. Shared instead of copied when the
+    /// message already is.
+    fn shared_bytes(&self) -> Arc<[u8]> {
+        Arc::from(self.wire_bytes())
+    }
+}
+
+/// Message that can be rebuilt from the bytes sent.
+pub trait DecodeMessage: Sized {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EvilError>;
+}
+
+impl EvilMessage for str {
+    fn kind(&self) -> &'static str {
+        "text"
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_bytes().to_vec()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self.as_bytes())
+    }
+}
+
+impl EvilMessage for String {
+    fn kind(&self) -> &'static str {
+        self.as_str().kind()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.as_str().to_bytes()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_str().wire_bytes()
+    }
+}
+
+/// Text shared with whoever else holds it, e.g. a dossier told to several sidekicks.
+impl EvilMessage for Arc<str> {
+    fn kind(&self) -> &'static str {
+        (**self).kind()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        (**self).to_bytes()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        (**self).wire_bytes()
+    }
+}
+
+impl DecodeMessage for String {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EvilError> {
+        text(bytes, "text").map(String::from)
+    }
+}
+
+impl EvilMessage for [u8] {
+    fn kind(&self) -> &'static str {
+        "bytes"
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_vec()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(self)
+    }
+}
+
+impl EvilMessage for Vec<u8> {
+    fn kind(&self) -> &'static str {
+        self.as_slice().kind()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        self.clone()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        self.as_slice().wire_bytes()
+    }
+}
+
+/// Bytes shared with whoever else holds them. Cloning the [`Arc`] to tell them again copies
+/// nothing.
+impl EvilMessage for Arc<[u8]> {
+    fn kind(&self) -> &'static str {
+        (**self).kind()
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        (**self).to_bytes()
+    }
+
+    fn wire_bytes(&self) -> Cow<'_, [u8]> {
+        (**self).wire_bytes()
+    }
+
+    fn shared_bytes(&self) -> Arc<[u8]> {
+        Arc::clone(self)
+    }
+}
+
+impl DecodeMessage for Vec<u8> {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EvilError> {
+        Ok(bytes.to_vec())
+    }
+}
+
+/// Sent as `target` or `stronghold`, a tab, and the target's name.
+impl EvilMessage for TargetUpdate {
+    fn kind(&self) -> &'static str {
+        "target"
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let state = if self.weak { "target" } else { "stronghold" };
+        format!("{state}\t{}", self.target).into_bytes()
+    }
+}
+
+impl DecodeMessage for TargetUpdate {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EvilError> {
+        let (state, target) = text(bytes, "target")?
+            .split_once('\t')
+            .ok_or_else(|| parse_error("target", "Missing target name"))?;
+        let weak = match state {
+            "target" => true,
+            "stronghold" => false,
+            _ => return Err(parse_error("target", "Unknown target state")),
+        };
+        Ok(TargetUpdate {
+            target: target.into(),
+            weak,
+        })
+    }
+}
+
+/// Plan as sent over the wire: steps in the order they were added, dependencies as pairs of
+/// step indexes, and what the plan needs before it can start.
+#[derive(Serialize, Deserialize)]
+struct PlanWire {
+    steps: Vec<PlanStep>,
+    dependencies: Vec<(usize, usize)>,
+    requirements: MissionRequirements,
+    deadline: Option<u32>,
+}
+
+/// Sent as JSON, so names and locations may hold any text, tabs and newlines included.
+impl EvilMessage for PlanGraph {
+    fn kind(&self) -> &'static str {
+        "plan"
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut wire = PlanWire {
+            steps: Vec::with_capacity(self.len()),
+            dependencies: Vec::new(),
+            requirements: self.requirements().clone(),
+            deadline: self.deadline(),
+        };
+        for (id, step) in self.steps() {
+            wire.steps.push(step.clone());
+            for dependency in self.dependencies(id) {
+                wire.dependencies.push((id.index(), dependency.index()));
+            }
+        }
+        serde_json::to_vec(&wire).expect("Plans are always serializable")
+    }
+}
+
+impl DecodeMessage for PlanGraph {
+    fn from_bytes(bytes: &[u8]) -> Result<Self, EvilError> {
+        let wire: PlanWire = serde_json::from_slice(bytes)
+            .map_err(|error| parse_error("plan", &error.to_string()))?;
+        let mut plan = PlanGraph::new();
+        let ids = wire
+            .steps
+            .into_iter()
+            .map(|step| plan.add_step(step))
+            .collect::<Vec<_>>();
+        for (step, depends_on) in wire.dependencies {
+            plan.add_dependency(step_id(&ids, step)?, step_id(&ids, depends_on)?)?;
+        }
+        plan.set_requirements(wire.requirements);
+        if let Some(turn) = wire.deadline {
+            plan.set_deadline(turn);
+        }
+        Ok(plan)
+    }
+}
+
+fn step_id(ids: &[StepId], index: usize) -> Result<StepId, EvilError> {
+    ids.get(index)
+        .copied()
+        .ok_or_else(|| parse_error("plan", "Unknown step"))
+}
+
+fn text<'b>(bytes: &'b [u8], purpose: &str) -> Result<&'b str, EvilError> {
+    std::str::from_utf8(bytes).map_err(|_| parse_error(purpose, "Not UTF-8"))
+}
+
+fn parse_error(purpose: &str, reason: &str) -> EvilError {
+    EvilError::ParseError {
+        purpose: purpose.into(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gadget::Capability;
+    use crate::plan::StepAction;
+    use crate::test_common;
+    use crate::units::EvilDuration;
+    use assertables::assert_matches;
+
+    #[test]
+    fn plan_survives_the_trip() -> Result<(), EvilError> {
+        let mut plan = PlanGraph::new();
+        let hq = plan.add_step(PlanStep::new(
+            "Dig in",
+            EvilDuration::from_secs(3600),
+            StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+        ));
+        let fight = plan.add_step(PlanStep::new(
+            "Storm the vault",
+            EvilDuration::from_secs(60),
+            StepAction::FightEnemies,
+        ));
+        plan.add_dependency(fight, hq)?;
+
+        let decoded = PlanGraph::from_bytes(&plan.to_bytes())?;
+        assert_eq!(plan.kind(), "plan");
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded.step(fight), plan.step(fight));
+        assert_eq!(decoded.dependencies(fight).collect::<Vec<_>>(), [hq]);
+        Ok(())
+    }
+
+    #[test]
+    fn plan_with_tabs_newlines_requirements_and_deadline_survives_the_trip() -> Result<(), EvilError>
+    {
+        let mut plan = PlanGraph::new();
+        let hq = plan.add_step(PlanStep::new(
+            "Dig in\tdeep",
+            EvilDuration::from_secs(3600),
+            StepAction::BuildSecretHq(String::from("Tampa\nunder the bay")),
+        ));
+        let fight = plan.add_step(PlanStep::new(
+            "Storm\nthe vault",
+            EvilDuration::from_secs(60),
+            StepAction::FightEnemies,
+        ));
+        plan.add_dependency(fight, hq)?;
+        plan.set_requirements(MissionRequirements {
+            gadgets: vec![(Capability::Escape, 40)],
+            ammo: 3,
+            henchmen: 2,
+            budget: 1_000,
+        });
+        plan.set_deadline(7);
+
+        let decoded = PlanGraph::from_bytes(&plan.to_bytes())?;
+        assert_eq!(decoded.step(hq), plan.step(hq));
+        assert_eq!(decoded.step(fight), plan.step(fight));
+        assert_eq!(decoded.dependencies(fight).collect::<Vec<_>>(), [hq]);
+        assert_eq!(decoded.requirements(), plan.requirements());
+        assert_eq!(decoded.deadline(), Some(7));
+        Ok(())
+    }
+
+    #[test]
+    fn plan_with_unknown_step_is_rejected() {
+        let bytes = br#"{"steps":[],"dependencies":[[1,0]],"requirements":{"gadgets":[],"ammo":0,"henchmen":0,"budget":0},"deadline":null}"#;
+        assert_matches!(
+            PlanGraph::from_bytes(bytes),
+            Err(EvilError::ParseError { reason, .. }) if reason == "Unknown step"
+        );
+    }
+
+    #[test]
+    fn target_survives_the_trip() -> Result<(), EvilError> {
+        let target = TargetUpdate {
+            target: test_common::FIRST_TARGET.into(),
+            weak: false,
+        };
+        assert_eq!(TargetUpdate::from_bytes(&target.to_bytes())?, target);
+        assert_matches!(
+            TargetUpdate::from_bytes(b"fortress\tTampa"),
+            Err(EvilError::ParseError { .. })
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn text_and_bytes_are_sent_as_is() {
+        assert_eq!("plan".to_bytes(), b"plan");
+        assert_eq!([0xff_u8, 0x00][..].kind(), "bytes");
+        assert!(String::from_bytes(&[0xff]).is_err());
+    }
+
+    #[test]
+    fn shared_messages_are_borrowed_not_copied() {
+        let dossier: Arc<[u8]> = Arc::from(&b"Tampa is weak"[..]);
+        let told = Arc::clone(&dossier);
+        assert_matches!(told.wire_bytes(), Cow::Borrowed(bytes) if std::ptr::eq(bytes, &*dossier));
+        assert!(Arc::ptr_eq(&told.shared_bytes(), &dossier));
+        assert_eq!(Arc::<str>::from("plan").kind(), "text");
+        assert_matches!(
+            test_common::FIRST_TARGET.wire_bytes(),
+            Cow::Borrowed(b"Tampa")
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! Module to export telemetry about villains through the `metrics` crate, so any recorder
+//! (e.g. a Prometheus exporter) can pick it up.
+
+use std::time::Duration;
+
+use ::metrics::Unit;
+use ::metrics::counter;
+use ::metrics::describe_counter;
+use ::metrics::describe_histogram;
+use ::metrics::histogram;
+
+pub const SHOTS_FIRED: &str = "evil_shots_fired_total";
+pub const PLANS_HATCHED: &str = "evil_plans_hatched_total";
+pub const SIDEKICKS_FIRED: &str = "evil_sidekicks_fired_total";
+pub const PLAN_LATENCY: &str = "evil_plan_latency_seconds";
+
+/// Registers the descriptions of every metric. Call it once after installing the recorder.
+pub fn describe() {
+    describe_counter!(
+        SHOTS_FIRED,
+        Unit::Count,
+        "Shots fired by weapons in attacks"
+    );
+    describe_counter!(PLANS_HATCHED, Unit::Count, "Plans villains came up with");
+    describe_counter!(
+        SIDEKICKS_FIRED,
+        Unit::Count,
+        "Sidekicks fired for disloyalty"
+    );
+    describe_histogram!(
+        PLAN_LATENCY,
+        Unit::Seconds,
+        "Time villains took to come up with a plan"
+    );
+}
+
+pub(crate) fn record_shots(villain: &str, shots: u32) {
+    counter!(SHOTS_FIRED, "villain" => villain.to_owned()).increment(u64::from(shots));
+}
+
+pub(crate) fn record_plan_hatched(villain: &str, latency: Duration) {
+    counter!(PLANS_HATCHED, "villain" => villain.to_owned()).increment(1);
+    histogram!(PLAN_LATENCY, "villain" => villain.to_owned()).record(latency.as_secs_f64());
+}
+
+pub(crate) fn record_sidekick_fired(villain: &str) {
+    counter!(SIDEKICKS_FIRED, "villain" => villain.to_owned()).increment(1);
+}
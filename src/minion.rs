@@ -0,0 +1,189 @@
+//! Module for minions: cheap workers spawned by the hundred, for work that doesn't need a
+//! henchman's skill.
+#![allow(dead_code)]
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cell::Cell;
+
+use crate::error::EvilError;
+use crate::henchman::Henchman;
+use crate::henchman::Progress;
+use crate::treasury::Treasury;
+
+/// Minion-hours worth as much as one task done by a henchman.
+pub const MINION_HOURS_PER_TASK: u64 = 40;
+/// Coins it costs to spawn one minion.
+pub const SPAWN_COST: u64 = 5;
+const SHIFT_HOURS: u64 = 8;
+/// Percentage of the swarm lost every shift.
+const ATTRITION: u32 = 10;
+/// Fighting costs this many times the usual attrition.
+const COMBAT_ATTRITION_FACTOR: u32 = 3;
+
+/// Minion-hours it takes to do the work of that many henchman tasks.
+pub fn minion_hours_for(tasks: u32) -> u64 {
+    u64::from(tasks) * MINION_HOURS_PER_TASK
+}
+
+/// Henchman tasks that many minion-hours are worth. Leftover hours are worth nothing.
+pub fn henchman_tasks_for(minion_hours: u64) -> u32 {
+    u32::try_from(minion_hours / MINION_HOURS_PER_TASK).unwrap_or(u32::MAX)
+}
+
+/// Minions working as one. Every shift adds the labor of the whole swarm and costs a share of
+/// it; a swarm that dies out stops working.
+#[derive(Debug, Default)]
+pub struct MinionSwarm {
+    count: Cell<u32>,
+    lost: Cell<u32>,
+    labor: Cell<u64>,
+    /// Minion-hours the current job takes.
+    quota: u64,
+    sites: Vec<String>,
+}
+
+impl MinionSwarm {
+    /// Swarm with no minions yet, for a job of the given minion-hours.
+    pub fn new(quota: u64) -> MinionSwarm {
+        Self {
+            quota,
+            ..Default::default()
+        }
+    }
+
+    /// Swarm for a job a henchman would do in that many tasks.
+    pub fn for_tasks(tasks: u32) -> MinionSwarm {
+        Self::new(minion_hours_for(tasks))
+    }
+
+    pub fn count(&self) -> u32 {
+        self.count.get()
+    }
+
+    /// Minions lost since the swarm was spawned.
+    pub fn lost(&self) -> u32 {
+        self.lost.get()
+    }
+
+    /// Minion-hours of work done so far.
+    pub fn labor(&self) -> u64 {
+        self.labor.get()
+    }
+
+    /// Where the swarm built secret HQs.
+    pub fn sites(&self) -> &[String] {
+        &self.sites
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.labor.get() >= self.quota
+    }
+
+    /// Spawns a batch of minions, paid from the treasury.
+    pub fn spawn(&mut self, batch: u32, treasury: &mut Treasury) -> Result<(), EvilError> {
+        treasury.withdraw(u64::from(batch).saturating_mul(SPAWN_COST))?;
+        self.count.set(self.count.get().saturating_add(batch));
+        Ok(())
+    }
+
+    /// Works a shift, returning the minion-hours done. Some minions don't come back.
+    pub fn work_shift(&self) -> u64 {
+        self.shift(ATTRITION)
+    }
+
+    fn shift(&self, attrition: u32) -> u64 {
+        let count = self.count.get();
+        let hours = u64::from(count) * SHIFT_HOURS;
+        self.labor.set(self.labor.get().saturating_add(hours));
+        let lost = (u64::from(count) * u64::from(attrition.min(100))).div_ceil(100) as u32;
+        self.count.set(count - lost);
+        self.lost.set(self.lost.get().saturating_add(lost));
+        hours
+    }
+}
+
+impl Henchman for MinionSwarm {
+    fn build_secret_hq(&mut self, location: String) {
+        self.work_shift();
+        self.sites.push(location);
+    }
+
+    fn do_hard_things(&self) {
+        self.work_shift();
+    }
+
+    fn fight_enemies(&self) {
+        self.shift(ATTRITION * COMBAT_ATTRITION_FACTOR);
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        self.sites.retain(|site| *site != location);
+        Ok(())
+    }
+
+    fn report_progress(&self) -> Progress {
+        let percent_complete = match self.quota {
+            0 => 100,
+            quota => (self.labor.get().saturating_mul(100) / quota).min(100) as u8,
+        };
+        let blockers = if self.count.get() == 0 && !self.is_done() {
+            vec![format!(
+                "Swarm wiped out with {} minion-hours to go",
+                self.quota - self.labor.get()
+            )]
+        } else {
+            Vec::new()
+        };
+        Progress {
+            percent_complete,
+            casualties: self.lost.get(),
+            blockers,
+        }
+    }
+
+    /// A swarm with no minions left can't work.
+    fn is_on_strike(&self) -> bool {
+        self.count.get() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+
+    #[test]
+    fn swarm_labor_adds_up_while_minions_drop() -> Result<(), EvilError> {
+        let mut swarm = MinionSwarm::for_tasks(20);
+        let mut treasury = Treasury::new(1_000);
+        swarm.spawn(100, &mut treasury)?;
+        assert_eq!(treasury.balance(), 500);
+
+        assert_eq!(swarm.work_shift(), 800);
+        assert_eq!(swarm.count(), 90);
+        swarm.do_hard_things();
+        assert_eq!(swarm.labor(), 1_520);
+        assert_eq!(henchman_tasks_for(swarm.labor()), 38);
+        assert!(swarm.is_done());
+        assert_eq!(swarm.report_progress().casualties, 19);
+        Ok(())
+    }
+
+    #[test]
+    fn wiped_out_swarm_stops_working() -> Result<(), EvilError> {
+        let mut swarm = MinionSwarm::for_tasks(1);
+        let mut treasury = Treasury::new(5);
+        swarm.spawn(1, &mut treasury)?;
+        swarm.fight_enemies();
+        assert!(swarm.is_on_strike());
+        assert_eq!(swarm.report_progress().blockers.len(), 1);
+        assert_matches!(
+            swarm.spawn(1, &mut treasury),
+            Err(EvilError::InsufficientFunds { .. })
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,152 @@
+//! Module for the dramatic speeches villains deliver before attacking.
+#![allow(dead_code)]
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::error::EvilError;
+
+const PLACEHOLDERS: [&str; 4] = ["villain", "hero", "plan", "device"];
+
+const BUILT_IN_CORPUS: [&str; 4] = [
+    "{hero}, you fool! Did you really think you could stop {villain}? Soon my {device} will {plan}!",
+    "Ah, {hero}. I've been expecting you. Behold the {device}: with it I shall {plan}.",
+    "Nobody can stop {villain} now, {hero}. Not even you. The {device} is armed and I will {plan}.",
+    "Before you die, {hero}, you deserve to know the truth: the {device} was never a weapon. It was the key to {plan}.",
+];
+
+/// Values that fill the placeholders of a [`MonologueTemplate`].
+#[derive(Debug, Clone, Copy)]
+pub struct MonologueContext<'a> {
+    pub villain: &'a str,
+    pub hero: &'a str,
+    pub plan: &'a str,
+    pub device: &'a str,
+}
+
+impl MonologueContext<'_> {
+    fn value(&self, placeholder: &str) -> Option<&str> {
+        match placeholder {
+            "villain" => Some(self.villain),
+            "hero" => Some(self.hero),
+            "plan" => Some(self.plan),
+            "device" => Some(self.device),
+            _ => None,
+        }
+    }
+}
+
+/// Text of a monologue with `{villain}`, `{hero}`, `{plan}` and `{device}` placeholders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonologueTemplate {
+    text: String,
+}
+
+enum Piece<'a> {
+    Literal(&'a str),
+    Placeholder(&'a str),
+}
+
+impl MonologueTemplate {
+    /// Creates a template, checking that all its placeholders are known and closed.
+    pub fn new(text: &str) -> Result<MonologueTemplate, EvilError> {
+        for piece in pieces(text)? {
+            if let Piece::Placeholder(name) = piece
+                && !PLACEHOLDERS.contains(&name)
+            {
+                return Err(EvilError::ParseError {
+                    purpose: "monologue".into(),
+                    reason: format!("Unknown placeholder '{name}'"),
+                });
+            }
+        }
+        Ok(Self { text: text.into() })
+    }
+
+    /// Templates shipped with the crate.
+    pub fn built_in() -> Vec<MonologueTemplate> {
+        BUILT_IN_CORPUS
+            .iter()
+            .map(|text| Self {
+                text: String::from(*text),
+            })
+            .collect()
+    }
+
+    pub fn render(&self, context: &MonologueContext) -> String {
+        let mut speech = String::with_capacity(self.text.len());
+        for piece in pieces(&self.text).unwrap_or_default() {
+            match piece {
+                Piece::Literal(literal) => speech.push_str(literal),
+                Piece::Placeholder(name) => speech.push_str(context.value(name).unwrap_or(name)),
+            }
+        }
+        speech
+    }
+}
+
+fn pieces(text: &str) -> Result<Vec<Piece<'_>>, EvilError> {
+    let mut pieces = vec![];
+    let mut rest = text;
+    while let Some(open) = rest.find('{') {
+        let Some(close) = rest[open..].find('}') else {
+            return Err(EvilError::ParseError {
+                purpose: "monologue".into(),
+                reason: "Unclosed placeholder".into(),
+            });
+        };
+        pieces.push(Piece::Literal(&rest[..open]));
+        pieces.push(Piece::Placeholder(&rest[open + 1..open + close]));
+        rest = &rest[open + close + 1..];
+    }
+    pieces.push(Piece::Literal(rest));
+    Ok(pieces)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    #[test]
+    fn render_fills_all_placeholders() -> Result<(), EvilError> {
+        let template = MonologueTemplate::new("{hero}, {villain} will {plan} with the {device}!")?;
+        let speech = template.render(&context());
+        assert_eq!(
+            speech,
+            "Superman, Lex Luthor will take over the world with the doomsday device!"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_placeholder_is_rejected() {
+        let result = MonologueTemplate::new("Hello {sidekick}");
+        assert_matches!(result, Err(EvilError::ParseError { purpose, .. }) if purpose == "monologue");
+    }
+
+    #[test]
+    fn unclosed_placeholder_is_rejected() {
+        let result = MonologueTemplate::new("Hello {hero");
+        assert_matches!(result, Err(EvilError::ParseError { reason, .. }) if reason == "Unclosed placeholder");
+    }
+
+    #[test]
+    fn built_in_templates_mention_the_hero() {
+        for template in MonologueTemplate::built_in() {
+            assert!(template.render(&context()).contains(test_common::HERO_NAME));
+        }
+    }
+
+    fn context() -> MonologueContext<'static> {
+        MonologueContext {
+            villain: test_common::PRIMARY_FULL_NAME,
+            hero: test_common::HERO_NAME,
+            plan: "take over the world",
+            device: "doomsday device",
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Module for the temper of a villain and how it changes with what happens.
+#![allow(dead_code)]
+
+/// How the villain feels, which changes how it behaves.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mood {
+    #[default]
+    Calm,
+    /// Always attacks intensely.
+    Furious,
+    Scheming,
+    /// Accepts worse alliance terms.
+    Desperate,
+}
+
+/// Things that happen to a villain and change its mood.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MoodTrigger {
+    HeistSucceeded,
+    HeistFailed,
+    PlanHatched,
+    Broke,
+    Captured,
+}
+
+impl Mood {
+    /// Mood after the trigger. Failing while already furious leads to desperation.
+    pub fn after(self, trigger: MoodTrigger) -> Mood {
+        match (self, trigger) {
+            (Mood::Desperate, MoodTrigger::HeistSucceeded) => Mood::Scheming,
+            (_, MoodTrigger::HeistSucceeded) => Mood::Calm,
+            (Mood::Furious | Mood::Desperate, MoodTrigger::HeistFailed) => Mood::Desperate,
+            (_, MoodTrigger::HeistFailed) => Mood::Furious,
+            (Mood::Desperate, MoodTrigger::PlanHatched) => Mood::Desperate,
+            (_, MoodTrigger::PlanHatched) => Mood::Scheming,
+            (_, MoodTrigger::Broke | MoodTrigger::Captured) => Mood::Desperate,
+        }
+    }
+
+    pub fn always_attacks_intensely(&self) -> bool {
+        *self == Mood::Furious
+    }
+
+    /// Smallest share of the spoils, in percent, the villain agrees to in an alliance.
+    pub fn minimum_alliance_share(&self) -> u8 {
+        match self {
+            Mood::Calm => 50,
+            Mood::Scheming => 60,
+            Mood::Furious => 70,
+            Mood::Desperate => 20,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_heist_makes_villain_furious_then_desperate() {
+        let mood = Mood::Calm.after(MoodTrigger::HeistFailed);
+        assert_eq!(mood, Mood::Furious);
+        assert_eq!(mood.after(MoodTrigger::HeistFailed), Mood::Desperate);
+    }
+
+    #[test]
+    fn success_calms_villain_down_except_when_desperate() {
+        assert_eq!(Mood::Furious.after(MoodTrigger::HeistSucceeded), Mood::Calm);
+        assert_eq!(
+            Mood::Desperate.after(MoodTrigger::HeistSucceeded),
+            Mood::Scheming
+        );
+    }
+
+    #[test]
+    fn desperate_villains_settle_for_less() {
+        assert!(Mood::Desperate.minimum_alliance_share() < Mood::Calm.minimum_alliance_share());
+    }
+}
@@ -0,0 +1,204 @@
+//! Module for writing villain names the way each locale expects them.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::EvilError;
+
+/// Languages that put the family name before the given name.
+const FAMILY_FIRST_LANGUAGES: [&str; 6] = ["hu", "ja", "ko", "mn", "vi", "zh"];
+/// Words starting a stage name, like `"The Riddler"`.
+const ARTICLES: [&str; 4] = ["The", "El", "La", "Le"];
+const NO_NAME: &str = "Too few arguments";
+const NOT_TWO_PARTS: &str = "Name must have first and last name, separated by a space";
+
+/// Which name goes first in a full name.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum NameOrder {
+    /// `"Lex Luthor"`.
+    #[default]
+    GivenFirst,
+    /// `"Luthor Lex"`.
+    FamilyFirst,
+}
+
+impl NameOrder {
+    /// Order used in a locale like `"ja-JP"`, `"hu_HU"` or `"en"`. Unknown locales put the
+    /// given name first.
+    pub fn for_locale(locale: &str) -> NameOrder {
+        let language = locale.split(['-', '_']).next().unwrap_or_default();
+        if FAMILY_FIRST_LANGUAGES
+            .iter()
+            .any(|family_first| family_first.eq_ignore_ascii_case(language))
+        {
+            NameOrder::FamilyFirst
+        } else {
+            NameOrder::GivenFirst
+        }
+    }
+
+    /// Joins the names with a space, in this order. An empty name is left out, so mononyms
+    /// don't get a stray space.
+    pub fn join(&self, first_name: &str, last_name: &str) -> String {
+        let (first, second) = match self {
+            NameOrder::GivenFirst => (first_name, last_name),
+            NameOrder::FamilyFirst => (last_name, first_name),
+        };
+        let mut full_name = String::with_capacity(first.len() + 1 + second.len());
+        full_name.push_str(first);
+        if !first.is_empty() && !second.is_empty() {
+            full_name.push(' ');
+        }
+        full_name.push_str(second);
+        full_name
+    }
+}
+
+/// Splits full names into first and last names.
+///
+/// Besides `"Lex Luthor"`, it accepts mononyms like `"Sinestro"` and stage names starting with
+/// an article like `"The Riddler"`, both kept whole as the first name with an empty last name.
+/// Longer names keep everything after the first word as the last name, e.g. `"Von Doom"`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct NameParser {
+    /// Only accept names made of exactly a first and a last name.
+    pub require_two_parts: bool,
+}
+
+impl NameParser {
+    /// Parser only accepting names made of exactly a first and a last name.
+    pub fn strict() -> NameParser {
+        Self {
+            require_two_parts: true,
+        }
+    }
+
+    /// First and last names of the full name, with whitespace between words collapsed so that
+    /// joining them gives the name back.
+    pub fn parse(&self, name: &str) -> Result<(String, String), EvilError> {
+        let words = name.split_whitespace().collect::<Vec<_>>();
+        let fail = |reason: &str| EvilError::ParseError {
+            purpose: "full_name".into(),
+            reason: reason.into(),
+        };
+        match words[..] {
+            [] => Err(fail(NO_NAME)),
+            [first_name, last_name] if !Self::is_article(first_name) || self.require_two_parts => {
+                Ok((first_name.into(), last_name.into()))
+            }
+            _ if self.require_two_parts => Err(fail(NOT_TWO_PARTS)),
+            [stage_name] => Ok((stage_name.into(), String::new())),
+            [article, ..] if Self::is_article(article) => Ok((words.join(" "), String::new())),
+            [first_name, ..] => Ok((first_name.into(), words[1..].join(" "))),
+        }
+    }
+
+    fn is_article(word: &str) -> bool {
+        ARTICLES
+            .iter()
+            .any(|article| article.eq_ignore_ascii_case(word))
+    }
+}
+
+/// ASCII version of a name, e.g. for systems that can't store anything else. Latin letters
+/// lose their diacritics; characters without an ASCII equivalent become `?`.
+pub fn transliterate(name: &str) -> String {
+    let mut ascii = String::with_capacity(name.len());
+    for c in name.chars() {
+        if c.is_ascii() {
+            ascii.push(c);
+        } else {
+            ascii.push_str(transliterate_char(c));
+        }
+    }
+    ascii
+}
+
+fn transliterate_char(c: char) -> &'static str {
+    match c {
+        'À'..='Å' => "A",
+        'à'..='å' | 'ā' | 'ă' | 'ą' => "a",
+        'Æ' => "AE",
+        'æ' => "ae",
+        'Ç' | 'Ć' | 'Č' => "C",
+        'ç' | 'ć' | 'č' => "c",
+        'Ð' | 'Ď' | 'Đ' => "D",
+        'ð' | 'ď' | 'đ' => "d",
+        'È'..='Ë' | 'Ē' | 'Ė' | 'Ę' | 'Ě' => "E",
+        'è'..='ë' | 'ē' | 'ė' | 'ę' | 'ě' => "e",
+        'Ğ' => "G",
+        'ğ' => "g",
+        'Ì'..='Ï' | 'İ' => "I",
+        'ì'..='ï' | 'ı' => "i",
+        'Ł' => "L",
+        'ł' => "l",
+        'Ñ' | 'Ń' | 'Ň' => "N",
+        'ñ' | 'ń' | 'ň' => "n",
+        'Ò'..='Ö' | 'Ø' | 'Ő' => "O",
+        'ò'..='ö' | 'ø' | 'ő' => "o",
+        'Œ' => "OE",
+        'œ' => "oe",
+        'Ř' => "R",
+        'ř' => "r",
+        'Ś' | 'Š' | 'Ş' => "S",
+        'ś' | 'š' | 'ş' => "s",
+        'ß' => "ss",
+        'Ť' => "T",
+        'ť' => "t",
+        'Þ' => "TH",
+        'þ' => "th",
+        'Ù'..='Ü' | 'Ů' | 'Ű' => "U",
+        'ù'..='ü' | 'ů' | 'ű' => "u",
+        'Ý' | 'Ÿ' => "Y",
+        'ý' | 'ÿ' => "y",
+        'Ź' | 'Ż' | 'Ž' => "Z",
+        'ź' | 'ż' | 'ž' => "z",
+        _ => "?",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn family_first_locales_swap_names() {
+        assert_eq!(NameOrder::for_locale("ja-JP"), NameOrder::FamilyFirst);
+        assert_eq!(NameOrder::for_locale("HU_hu"), NameOrder::FamilyFirst);
+        assert_eq!(NameOrder::for_locale("en-US"), NameOrder::GivenFirst);
+        assert_eq!(NameOrder::for_locale(""), NameOrder::GivenFirst);
+        assert_eq!(NameOrder::FamilyFirst.join("Lex", "Luthor"), "Luthor Lex");
+        assert_eq!(NameOrder::FamilyFirst.join("Sinestro", ""), "Sinestro");
+    }
+
+    #[test]
+    fn mononyms_and_stage_names_are_kept_whole() -> Result<(), EvilError> {
+        let parser = NameParser::default();
+        assert_eq!(
+            parser.parse("Sinestro")?,
+            ("Sinestro".into(), String::new())
+        );
+        assert_eq!(
+            parser.parse("The  Mad Hatter")?,
+            ("The Mad Hatter".into(), String::new())
+        );
+        assert_eq!(
+            parser.parse("Victor Von Doom")?,
+            ("Victor".into(), "Von Doom".into())
+        );
+        assert!(parser.parse(" ").is_err());
+        assert!(NameParser::strict().parse("Sinestro").is_err());
+        assert_eq!(
+            NameParser::strict().parse("The Riddler")?,
+            ("The".into(), "Riddler".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn transliteration_strips_diacritics() {
+        assert_eq!(transliterate("Dr. Müller-Ødegård"), "Dr. Muller-Odegard");
+        assert_eq!(transliterate("Großwürfel"), "Grosswurfel");
+        assert_eq!(transliterate("Lex 卢"), "Lex ?");
+    }
+}
@@ -0,0 +1,257 @@
+//! Module to tell what villains did as a story, for game UIs and logs.
+#![allow(dead_code)]
+
+use crate::nemesis::BattleLog;
+use crate::nemesis::BattleOutcome;
+use crate::nemesis::Side;
+use crate::plan::PlanGraph;
+use crate::plan::StepAction;
+use crate::supervillain::AttackReport;
+use crate::supervillain::WeaponStatus;
+
+const NUMBER_WORDS: [&str; 13] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve",
+];
+
+/// How dramatic the story is.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Tone {
+    /// Exclamation marks and cackling.
+    #[default]
+    Campy,
+    /// Doom and gloom.
+    Grimdark,
+    /// As filed with the Ministry of Evil.
+    Bureaucratic,
+}
+
+/// Something that can be told as a story.
+pub trait Narrate {
+    fn narrate(&self, tone: Tone) -> String;
+}
+
+impl Narrate for PlanGraph {
+    /// Tells the steps in the order they'd run, or as added if they can't run in any order.
+    fn narrate(&self, tone: Tone) -> String {
+        let order = self
+            .topological_order()
+            .unwrap_or_else(|_| self.steps().map(|(id, _)| id).collect());
+        let mut lines = vec![
+            match tone {
+                Tone::Campy => "At the stroke of midnight, the scheme is set in motion!",
+                Tone::Grimdark => "In the darkness, the plan begins. There is no turning back.",
+                Tone::Bureaucratic => "Plan of operations, for approval.",
+            }
+            .to_string(),
+        ];
+        let last = order.len().saturating_sub(1);
+        for (position, step) in order.iter().filter_map(|&id| self.step(id)).enumerate() {
+            let deed = match &step.action {
+                StepAction::BuildSecretHq(location) => format!("a secret lair rises in {location}"),
+                StepAction::DoHardThings => String::from("the henchmen toil without rest"),
+                StepAction::FightEnemies => String::from("the henchmen clash with the heroes"),
+            };
+            lines.push(match tone {
+                Tone::Campy => {
+                    let when = match position {
+                        0 => "First",
+                        _ if position == last => "And finally",
+                        _ => "Then",
+                    };
+                    format!("{when}, {}: {deed}!", step.name)
+                }
+                Tone::Grimdark => format!("{}. For {}, {deed}.", step.name, step.duration),
+                Tone::Bureaucratic => format!(
+                    "Item {}: {}. Estimated duration: {}.",
+                    position + 1,
+                    step.name,
+                    step.duration
+                ),
+            });
+        }
+        lines.join(" ")
+    }
+}
+
+impl Narrate for AttackReport {
+    fn narrate(&self, tone: Tone) -> String {
+        let shots = count(self.shots_fired);
+        let plural = if self.shots_fired == 1 { "" } else { "s" };
+        let cooldown = match self.weapon_status {
+            WeaponStatus::Ready => None,
+            WeaponStatus::CoolingDown { remaining } => Some(remaining.as_secs()),
+        };
+        match (tone, cooldown) {
+            (Tone::Campy, None) => {
+                format!(
+                    "KABOOM! {} shot{plural} rang out, and the weapon is hungry for more!",
+                    capitalize(&shots)
+                )
+            }
+            (Tone::Campy, Some(seconds)) => format!(
+                "KABOOM! {} shot{plural} rang out! The weapon sizzles, needing {seconds} seconds to cool down.",
+                capitalize(&shots)
+            ),
+            (Tone::Grimdark, None) => format!(
+                "{} shot{plural} tore through the silence. The weapon waits, ready to kill again.",
+                capitalize(&shots)
+            ),
+            (Tone::Grimdark, Some(seconds)) => format!(
+                "{} shot{plural} tore through the silence. For {seconds} seconds, the weapon smolders.",
+                capitalize(&shots)
+            ),
+            (Tone::Bureaucratic, None) => format!(
+                "Shots discharged: {}. Weapon status: ready. Time taken: {} ms.",
+                self.shots_fired,
+                self.duration.as_millis()
+            ),
+            (Tone::Bureaucratic, Some(seconds)) => format!(
+                "Shots discharged: {}. Weapon status: cooling down, {seconds} s remaining. Time taken: {} ms.",
+                self.shots_fired,
+                self.duration.as_millis()
+            ),
+        }
+    }
+}
+
+impl Narrate for BattleOutcome {
+    fn narrate(&self, tone: Tone) -> String {
+        match (tone, self) {
+            (Tone::Campy, BattleOutcome::Won(Side::Villains)) => "Evil triumphs! Mwahahaha!",
+            (Tone::Campy, BattleOutcome::Won(Side::Heroes)) => {
+                "Curses! Foiled again by those meddling heroes!"
+            }
+            (Tone::Campy, BattleOutcome::Stalemate) => "Both sides stagger away, vowing revenge!",
+            (Tone::Grimdark, BattleOutcome::Won(Side::Villains)) => {
+                "The heroes lie broken. Nobody is coming to save this city."
+            }
+            (Tone::Grimdark, BattleOutcome::Won(Side::Heroes)) => {
+                "The villains fall, but the darkness only waits."
+            }
+            (Tone::Grimdark, BattleOutcome::Stalemate) => {
+                "Nobody wins. The killing will resume at dawn."
+            }
+            (Tone::Bureaucratic, BattleOutcome::Won(Side::Villains)) => {
+                "Outcome: victory for the villains."
+            }
+            (Tone::Bureaucratic, BattleOutcome::Won(Side::Heroes)) => {
+                "Outcome: victory for the heroes. Incident report to follow."
+            }
+            (Tone::Bureaucratic, BattleOutcome::Stalemate) => "Outcome: inconclusive.",
+        }
+        .to_string()
+    }
+}
+
+impl Narrate for BattleLog {
+    fn narrate(&self, tone: Tone) -> String {
+        let rounds = self.rounds.len() as u32;
+        let knockouts = self
+            .rounds
+            .iter()
+            .flat_map(|round| &round.blows)
+            .filter(|blow| blow.knocked_out)
+            .count() as u32;
+        let plural = if rounds == 1 { "" } else { "s" };
+        let opening = match tone {
+            Tone::Campy => format!(
+                "After {} round{plural} of glorious mayhem, {} fell!",
+                count(rounds),
+                count(knockouts)
+            ),
+            Tone::Grimdark => format!(
+                "{} round{plural}. {} bodies in the dust.",
+                capitalize(&count(rounds)),
+                capitalize(&count(knockouts))
+            ),
+            Tone::Bureaucratic => {
+                format!("Rounds fought: {rounds}. Combatants incapacitated: {knockouts}.")
+            }
+        };
+        format!("{opening} {}", self.outcome.narrate(tone))
+    }
+}
+
+/// The number in words, when it's small enough to read well that way.
+fn count(number: u32) -> String {
+    NUMBER_WORDS
+        .get(number as usize)
+        .map_or_else(|| number.to_string(), |word| (*word).into())
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+    use crate::test_common;
+    use assertables::assert_ok;
+    use std::time::Duration;
+
+    #[test]
+    fn plan_is_told_in_running_order() {
+        let mut plan = PlanGraph::new();
+        let fight = plan.add_step(PlanStep::new(
+            "Storm the vault",
+            Duration::from_secs(60),
+            StepAction::FightEnemies,
+        ));
+        let hq = plan.add_step(PlanStep::new(
+            "Dig in",
+            Duration::from_secs(3600),
+            StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+        ));
+        assert_ok!(plan.add_dependency(fight, hq));
+
+        let story = plan.narrate(Tone::Campy);
+        assert!(story.starts_with("At the stroke of midnight"));
+        assert!(story.contains("First, Dig in: a secret lair rises in Tampa!"));
+        assert!(
+            story.ends_with("And finally, Storm the vault: the henchmen clash with the heroes!")
+        );
+        assert!(
+            plan.narrate(Tone::Bureaucratic)
+                .contains("Item 1: Dig in. Estimated duration: 1h 0m 0s.")
+        );
+    }
+
+    #[test]
+    fn attack_counts_shots_in_words() {
+        let report = AttackReport {
+            shots_fired: 3,
+            weapon_status: WeaponStatus::CoolingDown {
+                remaining: Duration::from_secs(30),
+            },
+            duration: Duration::from_millis(250),
+        };
+        assert_eq!(
+            report.narrate(Tone::Grimdark),
+            "Three shots tore through the silence. For 30 seconds, the weapon smolders."
+        );
+        assert!(
+            report
+                .narrate(Tone::Bureaucratic)
+                .starts_with("Shots discharged: 3.")
+        );
+    }
+
+    #[test]
+    fn battle_outcome_depends_on_tone() {
+        let outcome = BattleOutcome::Won(Side::Villains);
+        assert_ne!(
+            outcome.narrate(Tone::Campy),
+            outcome.narrate(Tone::Grimdark)
+        );
+        assert_eq!(
+            BattleOutcome::Stalemate.narrate(Tone::Bureaucratic),
+            "Outcome: inconclusive."
+        );
+    }
+}
@@ -0,0 +1,648 @@
+//! Module for the heroes villains keep running into, and the battles against them.
+#![allow(dead_code)]
+
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+
+use rand::Rng;
+
+use crate::damage;
+use crate::damage::DamageKind;
+use crate::damage::Resistances;
+use crate::gadget::Capability;
+use crate::henchman::Goon;
+use crate::sidekick::Sidekick;
+use crate::sidekick::SidekickStats;
+use crate::skillcheck::CheckResult;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+use crate::supervillain::SuperVillain;
+
+pub mod replay;
+
+const VILLAIN_HEALTH: u32 = 120;
+const SIDEKICK_HEALTH: u32 = 70;
+const GOON_HEALTH: u32 = 40;
+const BASE_ATTACK: u32 = 10;
+const VILLAIN_SPEED: u32 = 50;
+
+/// Which side of the battle a combatant fights for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Side {
+    Heroes,
+    Villains,
+}
+
+/// Fighting abilities of anyone taking part in a battle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub health: u32,
+    pub attack: u32,
+    pub defense: u32,
+    pub speed: u32,
+}
+
+/// A hero, as strong as its stats.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hero {
+    pub name: String,
+    pub stats: Stats,
+    pub damage_kind: DamageKind,
+    pub resistances: Resistances,
+}
+
+impl Hero {
+    pub fn new(name: &str, stats: Stats) -> Hero {
+        Self {
+            name: name.into(),
+            stats,
+            damage_kind: DamageKind::default(),
+            resistances: Resistances::none(),
+        }
+    }
+
+    pub fn with_damage_kind(mut self, kind: DamageKind) -> Hero {
+        self.damage_kind = kind;
+        self
+    }
+
+    pub fn with_resistances(mut self, resistances: Resistances) -> Hero {
+        self.resistances = resistances;
+        self
+    }
+}
+
+/// Heroes that fight together.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HeroTeam {
+    pub name: String,
+    heroes: Vec<Hero>,
+}
+
+impl HeroTeam {
+    pub fn new(name: &str) -> HeroTeam {
+        Self {
+            name: name.into(),
+            heroes: Vec::new(),
+        }
+    }
+
+    pub fn recruit(&mut self, hero: Hero) {
+        self.heroes.push(hero);
+    }
+
+    pub fn heroes(&self) -> &[Hero] {
+        &self.heroes
+    }
+
+    pub fn len(&self) -> usize {
+        self.heroes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heroes.is_empty()
+    }
+}
+
+/// Anyone in a battle, with the health left.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Combatant {
+    pub name: String,
+    pub side: Side,
+    pub stats: Stats,
+    pub health: u32,
+    pub damage_kind: DamageKind,
+    pub resistances: Resistances,
+    /// Extra accuracy, from 0 to 100, against enemies by name, e.g. heroes the villain
+    /// researched.
+    pub accuracy: BTreeMap<String, u8>,
+}
+
+impl Combatant {
+    pub fn new(name: &str, side: Side, stats: Stats) -> Combatant {
+        Self {
+            name: name.into(),
+            side,
+            stats,
+            health: stats.health,
+            damage_kind: DamageKind::default(),
+            resistances: Resistances::none(),
+            accuracy: BTreeMap::new(),
+        }
+    }
+
+    /// Extra accuracy against the enemy; 0 for enemies nothing is known about.
+    pub fn accuracy_against(&self, enemy: &Combatant) -> u8 {
+        self.accuracy
+            .get(&enemy.name)
+            .copied()
+            .unwrap_or(0)
+            .min(100)
+    }
+
+    pub fn with_damage_kind(mut self, kind: DamageKind) -> Combatant {
+        self.damage_kind = kind;
+        self
+    }
+
+    pub fn with_resistances(mut self, resistances: Resistances) -> Combatant {
+        self.resistances = resistances;
+        self
+    }
+
+    pub fn is_standing(&self) -> bool {
+        self.health > 0
+    }
+
+    fn from_sidekick_stats(name: &str, stats: &SidekickStats, health: u32) -> Combatant {
+        Self::new(
+            name,
+            Side::Villains,
+            Stats {
+                health,
+                attack: BASE_ATTACK + u32::from(stats.competence) / 2,
+                defense: u32::from(stats.loyalty) / 4,
+                speed: u32::from(stats.dramatic_flair),
+            },
+        )
+    }
+}
+
+/// The villain and whoever it brings along to face the heroes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VillainTeam {
+    members: Vec<Combatant>,
+}
+
+impl VillainTeam {
+    /// Team led by the villain, who hits as hard as its best combat gadget, and as accurately
+    /// as its research on each hero allows.
+    pub fn led_by(villain: &SuperVillain) -> VillainTeam {
+        let combat = villain
+            .gadgets
+            .best_for(Capability::Combat)
+            .map_or(0, |gadget| gadget.rating(Capability::Combat));
+        let escape = villain
+            .gadgets
+            .best_for(Capability::Escape)
+            .map_or(0, |gadget| gadget.rating(Capability::Escape));
+        let mut leader = Combatant::new(
+            &villain.full_name(),
+            Side::Villains,
+            Stats {
+                health: VILLAIN_HEALTH,
+                attack: BASE_ATTACK + u32::from(combat),
+                defense: u32::from(escape) / 2,
+                speed: VILLAIN_SPEED,
+            },
+        );
+        leader.accuracy = villain
+            .dossiers
+            .heroes()
+            .map(|hero| (hero.to_string(), villain.accuracy_against(hero)))
+            .filter(|&(_, accuracy)| accuracy > 0)
+            .collect();
+        Self {
+            members: vec![leader],
+        }
+    }
+
+    pub fn with_sidekick(mut self, sidekick: &Sidekick) -> VillainTeam {
+        self.members.push(Combatant::from_sidekick_stats(
+            sidekick.name(),
+            sidekick.stats(),
+            SIDEKICK_HEALTH,
+        ));
+        self
+    }
+
+    pub fn with_henchman(mut self, goon: &Goon) -> VillainTeam {
+        self.members.push(Combatant::from_sidekick_stats(
+            &goon.name,
+            &goon.stats,
+            GOON_HEALTH,
+        ));
+        self
+    }
+
+    pub fn with_combatant(mut self, combatant: Combatant) -> VillainTeam {
+        self.members.push(Combatant {
+            side: Side::Villains,
+            ..combatant
+        });
+        self
+    }
+
+    pub fn members(&self) -> &[Combatant] {
+        &self.members
+    }
+}
+
+/// How much damage an attack does. Implemented by closures too, so battles can be tuned
+/// without new types.
+pub trait DamageFormula {
+    fn damage(&self, attacker: &Combatant, defender: &Combatant, round: u32) -> u32;
+}
+
+impl<F: Fn(&Combatant, &Combatant, u32) -> u32> DamageFormula for F {
+    fn damage(&self, attacker: &Combatant, defender: &Combatant, round: u32) -> u32 {
+        self(attacker, defender, round)
+    }
+}
+
+/// Attack minus half the defense, but always at least 1, then scaled by the defender's
+/// resistance to the attacker's kind of damage. Accurate attackers get past that much of the
+/// defense: 50 accuracy halves it.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StandardDamage;
+
+impl DamageFormula for StandardDamage {
+    fn damage(&self, attacker: &Combatant, defender: &Combatant, _round: u32) -> u32 {
+        let accuracy = u32::from(attacker.accuracy_against(defender));
+        let defense = defender.stats.defense / 2 * (100 - accuracy) / 100;
+        let base = attacker.stats.attack.saturating_sub(defense).max(1);
+        damage::calculate(base, attacker.damage_kind, &defender.resistances)
+    }
+}
+
+/// Like [`StandardDamage`], but every blow takes a skill check of the attack, plus the
+/// attacker's accuracy, against the defense: critical hits do double damage, and failed checks
+/// miss.
+#[derive(Debug)]
+pub struct CheckedDamage<R> {
+    check: SkillCheck,
+    rng: RefCell<R>,
+}
+
+impl<R: Rng> CheckedDamage<R> {
+    pub fn new(check: SkillCheck, rng: R) -> CheckedDamage<R> {
+        Self {
+            check,
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+impl<R: Rng> DamageFormula for CheckedDamage<R> {
+    fn damage(&self, attacker: &Combatant, defender: &Combatant, round: u32) -> u32 {
+        let skill =
+            (attacker.stats.attack + u32::from(attacker.accuracy_against(defender))).min(100) as u8;
+        let difficulty = Difficulty::against(defender.stats.defense.min(100) as u8);
+        let result = self
+            .check
+            .check(skill, difficulty, &mut *self.rng.borrow_mut());
+        match result {
+            CheckResult::CriticalSuccess => StandardDamage
+                .damage(attacker, defender, round)
+                .saturating_mul(2),
+            CheckResult::Success => StandardDamage.damage(attacker, defender, round),
+            CheckResult::Failure | CheckResult::CriticalFailure => 0,
+        }
+    }
+}
+
+/// A single blow.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Blow {
+    pub attacker: String,
+    pub defender: String,
+    pub damage: u32,
+    pub knocked_out: bool,
+}
+
+/// Every blow of a round, in initiative order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round {
+    pub number: u32,
+    pub blows: Vec<Blow>,
+}
+
+/// How a battle ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleOutcome {
+    Won(Side),
+    /// Both sides were still standing after the last round.
+    Stalemate,
+}
+
+/// Something that happened in a battle, in the order it happened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BattleEvent {
+    /// A combatant took part, as it was before the fight. Everyone joins before the first
+    /// round, in initiative order.
+    Joined(Combatant),
+    RoundStarted {
+        round: u32,
+    },
+    Struck {
+        attacker: String,
+        defender: String,
+        damage: u32,
+        health_left: u32,
+    },
+    KnockedOut {
+        combatant: String,
+    },
+    Ended(BattleOutcome),
+}
+
+/// What happened in a battle, round by round.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BattleLog {
+    pub outcome: BattleOutcome,
+    pub rounds: Vec<Round>,
+    pub survivors: Vec<Combatant>,
+    /// Every event, to step through the battle with a [`Replay`](replay::Replay).
+    pub events: Vec<BattleEvent>,
+}
+
+impl BattleLog {
+    pub fn replay(&self) -> replay::Replay<'_> {
+        replay::Replay::new(&self.events)
+    }
+}
+
+/// Battle between a hero team and a villain team.
+pub struct Battle<D = StandardDamage> {
+    combatants: Vec<Combatant>,
+    formula: D,
+    max_rounds: u32,
+}
+
+impl Battle {
+    pub fn new(heroes: &HeroTeam, villains: VillainTeam, max_rounds: u32) -> Battle {
+        Self::with_formula(heroes, villains, max_rounds, StandardDamage)
+    }
+}
+
+impl<D: DamageFormula> Battle<D> {
+    pub fn with_formula(
+        heroes: &HeroTeam,
+        villains: VillainTeam,
+        max_rounds: u32,
+        formula: D,
+    ) -> Battle<D> {
+        let mut combatants = heroes
+            .heroes()
+            .iter()
+            .map(|hero| {
+                Combatant::new(&hero.name, Side::Heroes, hero.stats)
+                    .with_damage_kind(hero.damage_kind)
+                    .with_resistances(hero.resistances)
+            })
+            .chain(villains.members)
+            .collect::<Vec<_>>();
+        // Stable sort: on equal speed, heroes go first and then everyone as they joined.
+        combatants.sort_by_key(|combatant| Reverse(combatant.stats.speed));
+        Self {
+            combatants,
+            formula,
+            max_rounds,
+        }
+    }
+
+    /// Combatants in the order they act every round: fastest first.
+    pub fn initiative(&self) -> impl Iterator<Item = &Combatant> {
+        self.combatants.iter()
+    }
+
+    /// Fights until a side falls or the rounds run out. Everyone standing attacks the weakest
+    /// standing enemy on their turn.
+    pub fn fight(mut self) -> BattleLog {
+        let mut rounds = Vec::new();
+        let mut events = self
+            .combatants
+            .iter()
+            .cloned()
+            .map(BattleEvent::Joined)
+            .collect::<Vec<_>>();
+        let mut outcome = self.outcome();
+        let mut number = 0;
+        while outcome == BattleOutcome::Stalemate && number < self.max_rounds {
+            number += 1;
+            events.push(BattleEvent::RoundStarted { round: number });
+            let mut blows = Vec::new();
+            for attacker in 0..self.combatants.len() {
+                if !self.combatants[attacker].is_standing() {
+                    continue;
+                }
+                let Some(defender) = self.weakest_enemy_of(self.combatants[attacker].side) else {
+                    break;
+                };
+                let damage = self.formula.damage(
+                    &self.combatants[attacker],
+                    &self.combatants[defender],
+                    number,
+                );
+                let target = &mut self.combatants[defender];
+                target.health = target.health.saturating_sub(damage);
+                events.push(BattleEvent::Struck {
+                    attacker: self.combatants[attacker].name.clone(),
+                    defender: self.combatants[defender].name.clone(),
+                    damage,
+                    health_left: self.combatants[defender].health,
+                });
+                if !self.combatants[defender].is_standing() {
+                    events.push(BattleEvent::KnockedOut {
+                        combatant: self.combatants[defender].name.clone(),
+                    });
+                }
+                blows.push(Blow {
+                    attacker: self.combatants[attacker].name.clone(),
+                    defender: self.combatants[defender].name.clone(),
+                    damage,
+                    knocked_out: !self.combatants[defender].is_standing(),
+                });
+            }
+            rounds.push(Round { number, blows });
+            outcome = self.outcome();
+        }
+        events.push(BattleEvent::Ended(outcome));
+        BattleLog {
+            outcome,
+            rounds,
+            survivors: self
+                .combatants
+                .into_iter()
+                .filter(Combatant::is_standing)
+                .collect(),
+            events,
+        }
+    }
+
+    fn weakest_enemy_of(&self, side: Side) -> Option<usize> {
+        self.combatants
+            .iter()
+            .enumerate()
+            .filter(|(_, combatant)| combatant.side != side && combatant.is_standing())
+            .min_by_key(|(_, combatant)| combatant.health)
+            .map(|(i, _)| i)
+    }
+
+    fn outcome(&self) -> BattleOutcome {
+        let standing = |side| {
+            self.combatants
+                .iter()
+                .any(|combatant| combatant.side == side && combatant.is_standing())
+        };
+        match (standing(Side::Heroes), standing(Side::Villains)) {
+            (true, false) => BattleOutcome::Won(Side::Heroes),
+            (false, true) => BattleOutcome::Won(Side::Villains),
+            _ => BattleOutcome::Stalemate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn hero(name: &str, health: u32, attack: u32, speed: u32) -> Hero {
+        Hero::new(
+            name,
+            Stats {
+                health,
+                attack,
+                defense: 0,
+                speed,
+            },
+        )
+    }
+
+    fn villains() -> VillainTeam {
+        let villain = SuperVillain {
+            first_name: test_common::PRIMARY_FIRST_NAME.into(),
+            last_name: test_common::PRIMARY_LAST_NAME.into(),
+            ..Default::default()
+        };
+        VillainTeam::led_by(&villain).with_henchman(&Goon::new("Igor"))
+    }
+
+    #[test]
+    fn fastest_combatants_act_first() {
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(hero("Flash", 100, 5, 100));
+        league.recruit(hero(test_common::HERO_NAME, 100, 5, 10));
+        let battle = Battle::new(&league, villains(), 1);
+        let order = battle
+            .initiative()
+            .map(|combatant| combatant.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            order,
+            vec!["Flash", "Lex Luthor", "Igor", test_common::HERO_NAME]
+        );
+    }
+
+    #[test]
+    fn strong_heroes_defeat_the_villains() {
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(hero(test_common::HERO_NAME, 500, 200, 100));
+        let log = Battle::new(&league, villains(), 10).fight();
+        assert_eq!(log.outcome, BattleOutcome::Won(Side::Heroes));
+        assert_eq!(log.rounds.len(), 2);
+        let first = &log.rounds[0].blows[0];
+        assert_eq!(first.defender, "Igor");
+        assert!(first.knocked_out);
+        assert_eq!(log.survivors.len(), 1);
+    }
+
+    #[test]
+    fn immune_heroes_take_no_damage() {
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(
+            hero(test_common::HERO_NAME, 100, 5, 100)
+                .with_resistances(Resistances::none().with(DamageKind::Kinetic, 100)),
+        );
+        let log = Battle::new(&league, villains(), 3).fight();
+        assert!(
+            log.rounds
+                .iter()
+                .flat_map(|round| &round.blows)
+                .filter(|blow| blow.defender == test_common::HERO_NAME)
+                .all(|blow| blow.damage == 0)
+        );
+        assert_eq!(log.survivors[0].health, 100);
+    }
+
+    #[test]
+    fn battles_end_in_stalemate_when_rounds_run_out() {
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(hero(test_common::HERO_NAME, 500, 1, 100));
+        let log = Battle::with_formula(
+            &league,
+            villains(),
+            3,
+            |_: &Combatant, _: &Combatant, _: u32| 0,
+        )
+        .fight();
+        assert_eq!(log.outcome, BattleOutcome::Stalemate);
+        assert_eq!(log.rounds.len(), 3);
+        assert!(log.rounds[0].blows.iter().all(|blow| blow.damage == 0));
+    }
+
+    #[test]
+    fn checked_blows_miss_the_well_defended() {
+        let formula = CheckedDamage::new(SkillCheck::default(), StdRng::seed_from_u64(1));
+        let goon = Combatant::new("Igor", Side::Villains, Stats::default());
+        let tank = Combatant::new(
+            test_common::HERO_NAME,
+            Side::Heroes,
+            Stats {
+                attack: 100,
+                defense: 100,
+                ..Default::default()
+            },
+        );
+        assert!((1..=10).all(|round| formula.damage(&goon, &tank, round) == 0));
+        assert!([100, 200].contains(&formula.damage(&tank, &goon, 1)));
+    }
+
+    #[test]
+    fn researched_heroes_are_hit_harder_and_more_often() {
+        let mut villain = SuperVillain::default();
+        let armored = Hero::new(
+            test_common::HERO_NAME,
+            Stats {
+                health: 1_000,
+                attack: 0,
+                defense: 16,
+                speed: 0,
+            },
+        );
+        let hero = Combatant::new(test_common::HERO_NAME, Side::Heroes, armored.stats);
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(armored);
+        let fight = |villain: &SuperVillain| {
+            let formula = CheckedDamage::new(SkillCheck::default(), StdRng::seed_from_u64(1));
+            Battle::with_formula(&league, VillainTeam::led_by(villain), 20, formula)
+                .fight()
+                .rounds
+                .iter()
+                .flat_map(|round| &round.blows)
+                .filter(|blow| blow.defender == test_common::HERO_NAME)
+                .map(|blow| blow.damage)
+                .collect::<Vec<_>>()
+        };
+        let unresearched = VillainTeam::led_by(&villain).members()[0].clone();
+        let unresearched_blows = fight(&villain);
+
+        for weakness in ["Kryptonite", "Magic", "Red sun", "Lead", "Lois"] {
+            villain
+                .dossiers
+                .add_weakness(test_common::HERO_NAME, weakness);
+        }
+        let researched = VillainTeam::led_by(&villain).members()[0].clone();
+        let researched_blows = fight(&villain);
+
+        assert_eq!(researched.accuracy_against(&hero), 25);
+        assert_eq!(StandardDamage.damage(&unresearched, &hero, 1), 2);
+        assert_eq!(StandardDamage.damage(&researched, &hero, 1), 4);
+        let hits = |blows: &[u32]| blows.iter().filter(|&&damage| damage > 0).count();
+        assert!(hits(&researched_blows) > hits(&unresearched_blows));
+    }
+}
@@ -0,0 +1,174 @@
+//! Stepping through a battle event by event, or jumping to a round, to see how everyone stood
+//! at any point.
+
+use super::BattleEvent;
+use super::BattleOutcome;
+use super::Combatant;
+
+/// Position in the events of a battle, with the state of every combatant at that point.
+#[derive(Debug, Clone)]
+pub struct Replay<'a> {
+    events: &'a [BattleEvent],
+    position: usize,
+    round: u32,
+    combatants: Vec<Combatant>,
+    outcome: Option<BattleOutcome>,
+}
+
+impl<'a> Replay<'a> {
+    /// Replay at the very start, before anyone joined.
+    pub fn new(events: &'a [BattleEvent]) -> Replay<'a> {
+        Self {
+            events,
+            position: 0,
+            round: 0,
+            combatants: Vec::new(),
+            outcome: None,
+        }
+    }
+
+    /// Applies the next event and returns it, or `None` at the end.
+    pub fn step(&mut self) -> Option<&'a BattleEvent> {
+        let event = self.events.get(self.position)?;
+        self.position += 1;
+        match event {
+            BattleEvent::Joined(combatant) => self.combatants.push(combatant.clone()),
+            BattleEvent::RoundStarted { round } => self.round = *round,
+            BattleEvent::Struck {
+                defender,
+                health_left,
+                ..
+            } => {
+                if let Some(defender) = self.combatant_mut(defender) {
+                    defender.health = *health_left;
+                }
+            }
+            BattleEvent::KnockedOut { .. } => {}
+            BattleEvent::Ended(outcome) => self.outcome = Some(*outcome),
+        }
+        Some(event)
+    }
+
+    /// Moves to the end of the round, or the start of the battle for round 0. Rounds past the
+    /// last one move to the end of the battle.
+    pub fn seek(&mut self, round: u32) {
+        *self = Self::new(self.events);
+        while let Some(next) = self.events.get(self.position) {
+            if matches!(next, BattleEvent::RoundStarted { round: next } if *next > round) {
+                break;
+            }
+            self.step();
+        }
+    }
+
+    /// Round being played, or 0 before the first one.
+    pub fn round(&self) -> u32 {
+        self.round
+    }
+
+    /// Events applied so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Everyone who joined so far, with their health at this point.
+    pub fn combatants(&self) -> &[Combatant] {
+        &self.combatants
+    }
+
+    pub fn combatant(&self, name: &str) -> Option<&Combatant> {
+        self.combatants
+            .iter()
+            .find(|combatant| combatant.name == name)
+    }
+
+    /// How the battle ended, once the replay gets there.
+    pub fn outcome(&self) -> Option<BattleOutcome> {
+        self.outcome
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.position == self.events.len()
+    }
+
+    fn combatant_mut(&mut self, name: &str) -> Option<&mut Combatant> {
+        self.combatants
+            .iter_mut()
+            .find(|combatant| combatant.name == name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::Goon;
+    use crate::nemesis::Battle;
+    use crate::nemesis::BattleLog;
+    use crate::nemesis::Hero;
+    use crate::nemesis::HeroTeam;
+    use crate::nemesis::Side;
+    use crate::nemesis::Stats;
+    use crate::nemesis::VillainTeam;
+    use crate::supervillain::SuperVillain;
+    use crate::test_common;
+
+    fn log() -> BattleLog {
+        let mut league = HeroTeam::new("Justice League");
+        league.recruit(Hero::new(
+            test_common::HERO_NAME,
+            Stats {
+                health: 500,
+                attack: 200,
+                defense: 0,
+                speed: 100,
+            },
+        ));
+        let villain = SuperVillain {
+            first_name: test_common::PRIMARY_FIRST_NAME.into(),
+            last_name: test_common::PRIMARY_LAST_NAME.into(),
+            ..Default::default()
+        };
+        let villains = VillainTeam::led_by(&villain).with_henchman(&Goon::new("Igor"));
+        Battle::new(&league, villains, 10).fight()
+    }
+
+    #[test]
+    fn stepping_through_ends_with_the_survivors() {
+        let log = log();
+        let mut replay = log.replay();
+        while replay.step().is_some() {}
+        assert!(replay.is_finished());
+        assert_eq!(replay.outcome(), Some(log.outcome));
+        let standing = replay
+            .combatants()
+            .iter()
+            .filter(|combatant| combatant.is_standing())
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(standing, log.survivors);
+    }
+
+    #[test]
+    fn seeking_shows_the_state_after_the_round() {
+        let log = log();
+        let mut replay = log.replay();
+        replay.seek(1);
+        assert_eq!(replay.round(), 1);
+        assert_eq!(replay.outcome(), None);
+        let Some(igor) = replay.combatant("Igor") else {
+            panic!("Igor should have joined");
+        };
+        assert!(!igor.is_standing());
+
+        replay.seek(0);
+        assert_eq!(replay.round(), 0);
+        assert!(replay.combatants().iter().all(Combatant::is_standing));
+        assert_eq!(replay.combatants().len(), 3);
+        assert!(
+            replay
+                .combatants()
+                .iter()
+                .any(|combatant| combatant.side == Side::Heroes)
+        );
+    }
+}
@@ -0,0 +1,220 @@
+//! Module to notify the villain's ops team about what's going on.
+#![allow(dead_code)]
+
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::event::EventListener;
+use crate::event::EvilEvent;
+use crate::supervillain::EvilError;
+
+/// Destination of notifications.
+#[cfg_attr(test, automock)]
+pub trait NotificationSink {
+    fn send(&self, message: &str) -> Result<(), EvilError>;
+}
+
+/// Sink that prints notifications to the standard output.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct StdoutSink;
+
+impl NotificationSink for StdoutSink {
+    fn send(&self, message: &str) -> Result<(), EvilError> {
+        println!("{message}");
+        Ok(())
+    }
+}
+
+/// Sink that appends notifications to a file, one per line.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileSink {
+        Self { path: path.into() }
+    }
+}
+
+impl NotificationSink for FileSink {
+    fn send(&self, message: &str) -> Result<(), EvilError> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .and_then(|mut file| writeln!(file, "{message}"))
+            .map_err(|error| EvilError::NotificationError {
+                reason: error.to_string(),
+            })
+    }
+}
+
+/// Sink that posts notifications as JSON (`{"text": "..."}`) to a webhook.
+#[cfg(feature = "webhook")]
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    url: String,
+    client: reqwest::blocking::Client,
+}
+
+#[cfg(feature = "webhook")]
+impl WebhookSink {
+    pub fn new(url: &str) -> WebhookSink {
+        Self {
+            url: url.into(),
+            client: reqwest::blocking::Client::new(),
+        }
+    }
+}
+
+#[cfg(feature = "webhook")]
+impl NotificationSink for WebhookSink {
+    fn send(&self, message: &str) -> Result<(), EvilError> {
+        self.client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(format!("{{\"text\":\"{}\"}}", escape_json(message)))
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .map(|_| ())
+            .map_err(|error| EvilError::NotificationError {
+                reason: error.to_string(),
+            })
+    }
+}
+
+#[cfg(feature = "webhook")]
+fn escape_json(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if c.is_control() => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Event listener that turns the events the ops team cares about into notifications.
+///
+/// Deliveries that fail are counted, but don't stop the other sinks from being notified.
+#[derive(Default)]
+pub struct Notifier<'a> {
+    sinks: Vec<Box<dyn NotificationSink + Send + 'a>>,
+    failed_deliveries: AtomicUsize,
+}
+
+impl<'a> Notifier<'a> {
+    pub fn new() -> Notifier<'a> {
+        Self::default()
+    }
+
+    pub fn add_sink<S: NotificationSink + Send + 'a>(&mut self, sink: S) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    pub fn failed_deliveries(&self) -> usize {
+        self.failed_deliveries.load(Ordering::Relaxed)
+    }
+}
+
+impl EventListener for Notifier<'_> {
+    fn on_event(&self, event: &EvilEvent) {
+        let message = match event {
+            EvilEvent::HqBuilt { villain, target } => {
+                format!("{villain} built a secret HQ in {target}")
+            }
+            EvilEvent::SidekickFired { villain, sidekick } => {
+                format!("{villain} fired sidekick {sidekick:?}")
+            }
+            _ => return,
+        };
+        for sink in &self.sinks {
+            if sink.send(&message).is_err() {
+                self.failed_deliveries.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sidekick::SidekickId;
+    use crate::sidekick::Slot;
+    use crate::test_common;
+    use mockall::predicate::eq;
+    use std::fs;
+
+    #[test]
+    fn hq_built_is_sent_to_every_sink() {
+        let mut notifier = Notifier::new();
+        for _ in 0..2 {
+            let mut sink = MockNotificationSink::new();
+            sink.expect_send()
+                .with(eq(String::from("Lex Luthor built a secret HQ in Tampa")))
+                .once()
+                .returning(|_| Ok(()));
+            notifier.add_sink(sink);
+        }
+        notifier.on_event(&hq_built());
+    }
+
+    #[test]
+    fn uninteresting_events_are_not_sent() {
+        let mut sink = MockNotificationSink::new();
+        sink.expect_send().never();
+        let mut notifier = Notifier::new();
+        notifier.add_sink(sink);
+        notifier.on_event(&EvilEvent::GadgetSwapped {
+            sidekick: SidekickId::new(1),
+            slot: Slot::Primary,
+        });
+    }
+
+    #[test]
+    fn failed_deliveries_are_counted() {
+        let mut sink = MockNotificationSink::new();
+        sink.expect_send().returning(|_| {
+            Err(EvilError::NotificationError {
+                reason: "Unreachable".into(),
+            })
+        });
+        let mut notifier = Notifier::new();
+        notifier.add_sink(sink);
+        notifier.on_event(&EvilEvent::SidekickFired {
+            villain: test_common::PRIMARY_FULL_NAME.into(),
+            sidekick: SidekickId::new(1),
+        });
+        assert_eq!(notifier.failed_deliveries(), 1);
+    }
+
+    #[test]
+    fn file_sink_appends_lines() -> Result<(), EvilError> {
+        let path = std::env::temp_dir().join(format!("evil-notify-{}.log", std::process::id()));
+        let sink = FileSink::new(&path);
+        sink.send("first")?;
+        sink.send("second")?;
+        let content = fs::read_to_string(&path).unwrap_or_default();
+        let _ = fs::remove_file(&path);
+        assert_eq!(content, "first\nsecond\n");
+        Ok(())
+    }
+
+    fn hq_built() -> EvilEvent {
+        EvilEvent::HqBuilt {
+            villain: test_common::PRIMARY_FULL_NAME.into(),
+            target: test_common::FIRST_TARGET.into(),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! Module for the personality traits of a villain, which bias the decisions it makes.
+#![allow(dead_code)]
+
+use core::time::Duration;
+
+/// Score from which a trait dominates the villain's behavior.
+pub const STRONG_TRAIT: u8 = 70;
+/// How long a trusting villain keeps using a session key.
+const LONGEST_KEY_ROTATION: Duration = Duration::from_secs(24 * 3600);
+/// How long a completely paranoid villain keeps using a session key.
+const SHORTEST_KEY_ROTATION: Duration = Duration::from_secs(3600);
+
+/// Trait scores of a villain, from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Personality {
+    /// Drive to go big: ambitious villains always attack intensely.
+    pub ambition: u8,
+    /// Distrust: paranoid villains rotate keys more often and build their HQ where it is
+    /// easiest to defend.
+    pub paranoia: u8,
+    /// Flair for drama: theatrical villains always monologue before attacking.
+    pub theatricality: u8,
+}
+
+impl Personality {
+    /// Scores above 100 are taken as 100.
+    pub fn new(ambition: u8, paranoia: u8, theatricality: u8) -> Personality {
+        Self {
+            ambition: ambition.min(100),
+            paranoia: paranoia.min(100),
+            theatricality: theatricality.min(100),
+        }
+    }
+
+    pub fn is_ambitious(&self) -> bool {
+        self.ambition >= STRONG_TRAIT
+    }
+
+    pub fn is_paranoid(&self) -> bool {
+        self.paranoia >= STRONG_TRAIT
+    }
+
+    pub fn is_theatrical(&self) -> bool {
+        self.theatricality >= STRONG_TRAIT
+    }
+
+    /// How long a session key is used before handshaking again: a day without paranoia, down
+    /// to an hour with all of it.
+    pub fn key_rotation_interval(&self) -> Duration {
+        let paranoia = u32::from(self.paranoia.min(100));
+        LONGEST_KEY_ROTATION - (LONGEST_KEY_ROTATION - SHORTEST_KEY_ROTATION) * paranoia / 100
+    }
+}
+
+/// Middle of the road in every trait.
+impl Default for Personality {
+    fn default() -> Self {
+        Self::new(50, 50, 50)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn paranoid_villains_rotate_keys_more_often() {
+        let trusting = Personality::new(50, 0, 50);
+        let paranoid = Personality::new(50, 100, 50);
+        assert_eq!(trusting.key_rotation_interval(), LONGEST_KEY_ROTATION);
+        assert_eq!(paranoid.key_rotation_interval(), SHORTEST_KEY_ROTATION);
+        assert!(paranoid.is_paranoid());
+        assert!(!Personality::default().is_paranoid());
+    }
+
+    #[test]
+    fn scores_are_capped() {
+        assert_eq!(Personality::new(200, 0, 0).ambition, 100);
+    }
+}
@@ -0,0 +1,381 @@
+//! Module to run world domination as a pipeline of stages.
+#![allow(dead_code)]
+
+use crate::henchman::Henchman;
+use crate::henchman::Progress;
+use crate::plan::PlanGraph;
+use crate::plan::ReadinessIssue;
+use crate::plan::StageOutcome;
+use crate::supervillain::ErrorContext;
+use crate::supervillain::EvilError;
+use crate::supervillain::MegaWeapon;
+use crate::supervillain::SuperVillain;
+
+/// Everything the stages of a pipeline share: the villain, with its gadgets and treasury, the
+/// henchmen doing the work and the HQ they work from, once built.
+#[derive(Default)]
+pub struct DominationContext<'a> {
+    pub villain: SuperVillain<'a>,
+    pub henchmen: Vec<Box<dyn Henchman + 'a>>,
+    pub hq: Option<String>,
+}
+
+impl<'a> DominationContext<'a> {
+    pub fn new(villain: SuperVillain<'a>) -> DominationContext<'a> {
+        Self {
+            villain,
+            henchmen: Vec::new(),
+            hq: None,
+        }
+    }
+
+    pub fn hire<H: Henchman + 'a>(&mut self, henchman: H) {
+        self.henchmen.push(Box::new(henchman));
+    }
+
+    /// Like [`SuperVillain::validate_readiness`], but also checks the henchmen hired and the
+    /// ammo of the weapon to use.
+    pub fn validate_readiness(
+        &self,
+        plan: &PlanGraph,
+        weapon: &impl MegaWeapon,
+    ) -> Vec<ReadinessIssue> {
+        let requirements = plan.requirements();
+        let mut issues = self.villain.validate_readiness(plan);
+        if self.henchmen.len() < requirements.henchmen {
+            issues.push(ReadinessIssue::NotEnoughHenchmen {
+                required: requirements.henchmen,
+                available: self.henchmen.len(),
+            });
+        }
+        if let Some(available) = weapon.ammo()
+            && available < requirements.ammo
+        {
+            issues.push(ReadinessIssue::NotEnoughAmmo {
+                required: requirements.ammo,
+                available,
+            });
+        }
+        issues
+    }
+}
+
+/// A step on the way to world domination. Implement it to add custom stages to a pipeline.
+pub trait DominationStage<'a> {
+    fn name(&self) -> &str;
+
+    fn execute(&mut self, context: &mut DominationContext<'a>) -> Result<StageOutcome, EvilError>;
+}
+
+/// Builds the secret HQ with the first henchman, using the best target detection gadget in the
+/// villain's inventory.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct BuildHqStage;
+
+impl<'a> DominationStage<'a> for BuildHqStage {
+    fn name(&self) -> &str {
+        "build-hq"
+    }
+
+    fn execute(&mut self, context: &mut DominationContext<'a>) -> Result<StageOutcome, EvilError> {
+        let Some(henchman) = context.henchmen.first_mut() else {
+            return Err(EvilError::NoHenchmen);
+        };
+        let outcome = context
+            .villain
+            .start_world_domination_stage1_with_inventory(henchman, &context.villain.gadgets)
+            .context("scanning targets for stage 1")?;
+        match outcome {
+            StageOutcome::HqBuilt { target } => {
+                context.hq = Some(target.clone());
+                Ok(StageOutcome::HqBuilt { target })
+            }
+            outcome => Err(EvilError::StageFailed {
+                stage: self.name().into(),
+                outcome,
+            }),
+        }
+    }
+}
+
+/// Sends every henchman out of the HQ to fight and do the hard work. Fails with
+/// [`EvilError::StageOutOfOrder`] if no HQ was built yet.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct DeployHenchmenStage;
+
+impl<'a> DominationStage<'a> for DeployHenchmenStage {
+    fn name(&self) -> &str {
+        "deploy-henchmen"
+    }
+
+    fn execute(&mut self, context: &mut DominationContext<'a>) -> Result<StageOutcome, EvilError> {
+        let Some(hq) = context.hq.clone() else {
+            return Err(EvilError::StageOutOfOrder {
+                stage: self.name().into(),
+            });
+        };
+        if context.henchmen.is_empty() {
+            return Err(EvilError::NoHenchmen);
+        }
+        let mut reports = Vec::with_capacity(context.henchmen.len());
+        for henchman in &mut context.henchmen {
+            if let StageOutcome::HenchmenDeployed { progress, .. } = context
+                .villain
+                .start_world_domination_stage2(&hq, henchman.as_mut())?
+            {
+                reports.push(progress);
+            }
+        }
+        Ok(StageOutcome::HenchmenDeployed {
+            hq,
+            count: reports.len(),
+            progress: Progress::aggregate(&reports),
+        })
+    }
+}
+
+/// Stages run in the order they were added. When one fails, running the pipeline again resumes
+/// from the failed stage instead of starting over.
+#[derive(Default)]
+pub struct DominationPipeline<'a> {
+    stages: Vec<Box<dyn DominationStage<'a> + 'a>>,
+    outcomes: Vec<StageOutcome>,
+}
+
+impl<'a> DominationPipeline<'a> {
+    pub fn new() -> DominationPipeline<'a> {
+        Self::default()
+    }
+
+    /// Pipeline with the built-in stages: building the HQ, then deploying the henchmen.
+    pub fn standard() -> DominationPipeline<'a> {
+        let mut pipeline = Self::new();
+        pipeline.add_stage(BuildHqStage);
+        pipeline.add_stage(DeployHenchmenStage);
+        pipeline
+    }
+
+    pub fn add_stage<S: DominationStage<'a> + 'a>(&mut self, stage: S) {
+        self.stages.push(Box::new(stage));
+    }
+
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Outcomes of the stages completed so far.
+    pub fn outcomes(&self) -> &[StageOutcome] {
+        &self.outcomes
+    }
+
+    /// Name of the stage that will run next, if any is left.
+    pub fn next_stage(&self) -> Option<&str> {
+        self.stages
+            .get(self.outcomes.len())
+            .map(|stage| stage.name())
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.outcomes.len() == self.stages.len()
+    }
+
+    /// Runs the pending stages, stopping at the first one that fails.
+    pub fn run(
+        &mut self,
+        context: &mut DominationContext<'a>,
+    ) -> Result<&[StageOutcome], EvilError> {
+        while let Some(stage) = self.stages.get_mut(self.outcomes.len()) {
+            let outcome = stage.execute(context)?;
+            self.outcomes.push(outcome);
+        }
+        Ok(&self.outcomes)
+    }
+
+    /// Forgets the completed stages, so the next run starts over.
+    pub fn reset(&mut self) {
+        self.outcomes.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::plan::MissionRequirements;
+    use crate::supervillain::MockMegaWeapon;
+    use assertables::assert_matches;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    /// Stage that fails until it's been tried the given number of times.
+    struct FlakyStage {
+        runs: Rc<Cell<u32>>,
+        failures: u32,
+    }
+
+    impl<'a> DominationStage<'a> for FlakyStage {
+        fn name(&self) -> &str {
+            "flaky"
+        }
+
+        fn execute(&mut self, _: &mut DominationContext<'a>) -> Result<StageOutcome, EvilError> {
+            self.runs.set(self.runs.get() + 1);
+            if self.runs.get() <= self.failures {
+                return Err(EvilError::NoHenchmen);
+            }
+            Ok(StageOutcome::NoTargets)
+        }
+    }
+
+    /// Custom stage that spends from the shared treasury.
+    struct BribeStage(u64);
+
+    impl<'a> DominationStage<'a> for BribeStage {
+        fn name(&self) -> &str {
+            "bribe"
+        }
+
+        fn execute(
+            &mut self,
+            context: &mut DominationContext<'a>,
+        ) -> Result<StageOutcome, EvilError> {
+            context.villain.treasury.withdraw(self.0)?;
+            Ok(StageOutcome::NoTargets)
+        }
+    }
+
+    fn working_henchman(percent_complete: u8) -> MockHenchman {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_is_on_strike().return_const(false);
+        henchman.expect_fight_enemies().return_const(());
+        henchman.expect_do_hard_things().return_const(());
+        henchman.expect_report_progress().return_const(Progress {
+            percent_complete,
+            ..Default::default()
+        });
+        henchman
+    }
+
+    #[test]
+    fn failed_pipeline_resumes_from_failed_stage() {
+        let first_runs = Rc::new(Cell::new(0));
+        let flaky_runs = Rc::new(Cell::new(0));
+        let mut pipeline = DominationPipeline::new();
+        pipeline.add_stage(FlakyStage {
+            runs: first_runs.clone(),
+            failures: 0,
+        });
+        pipeline.add_stage(FlakyStage {
+            runs: flaky_runs.clone(),
+            failures: 1,
+        });
+        let mut context = DominationContext::default();
+
+        assert!(pipeline.run(&mut context).is_err());
+        assert_eq!(pipeline.outcomes().len(), 1);
+        assert_eq!(pipeline.next_stage(), Some("flaky"));
+
+        assert!(pipeline.run(&mut context).is_ok());
+        assert!(pipeline.is_complete());
+        assert_eq!(first_runs.get(), 1);
+        assert_eq!(flaky_runs.get(), 2);
+    }
+
+    #[test]
+    fn readiness_checks_henchmen_and_ammo() {
+        let mut context = DominationContext::default();
+        context.hire(MockHenchman::new());
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_ammo().return_const(Some(2));
+        let mut plan = PlanGraph::new();
+        plan.set_requirements(MissionRequirements {
+            ammo: 5,
+            henchmen: 3,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            context.validate_readiness(&plan, &weapon),
+            vec![
+                ReadinessIssue::NotEnoughHenchmen {
+                    required: 3,
+                    available: 1
+                },
+                ReadinessIssue::NotEnoughAmmo {
+                    required: 5,
+                    available: 2
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn custom_stages_share_the_treasury() {
+        let mut context = DominationContext::default();
+        context.villain.treasury.deposit(100);
+        let mut pipeline = DominationPipeline::new();
+        pipeline.add_stage(BribeStage(60));
+        pipeline.add_stage(BribeStage(60));
+
+        let result = pipeline.run(&mut context);
+        assert_matches!(
+            result,
+            Err(EvilError::InsufficientFunds {
+                balance: 40,
+                requested: 60
+            })
+        );
+        assert_eq!(context.villain.treasury.balance(), 40);
+    }
+
+    #[test]
+    fn deploy_stage_aggregates_progress_of_all_henchmen() {
+        let mut context = DominationContext::default();
+        context.hire(working_henchman(100));
+        context.hire(working_henchman(50));
+        context.hq = Some(String::from("Volcano"));
+        let mut pipeline = DominationPipeline::new();
+        pipeline.add_stage(DeployHenchmenStage);
+
+        let Ok(outcomes) = pipeline.run(&mut context) else {
+            panic!("Deployment should succeed");
+        };
+        assert_matches!(
+            &outcomes[0],
+            StageOutcome::HenchmenDeployed { count: 2, progress, .. }
+                if progress.percent_complete == 75
+        );
+    }
+
+    #[test]
+    fn deploy_stage_without_hq_is_out_of_order() {
+        let mut context = DominationContext::default();
+        context.hire(working_henchman(100));
+        let mut pipeline = DominationPipeline::new();
+        pipeline.add_stage(DeployHenchmenStage);
+
+        let result = pipeline.run(&mut context);
+        assert_matches!(
+            result,
+            Err(EvilError::StageOutOfOrder { stage }) if stage == "deploy-henchmen"
+        );
+    }
+
+    #[test]
+    fn standard_pipeline_without_gadgets_stops_at_hq() {
+        let mut context = DominationContext::default();
+        context.hire(MockHenchman::new());
+        let mut pipeline = DominationPipeline::standard();
+
+        let result = pipeline.run(&mut context);
+        assert_matches!(
+            result,
+            Err(EvilError::StageFailed { stage, outcome: StageOutcome::NoSuitableGadget }) if stage == "build-hq"
+        );
+        assert_eq!(pipeline.next_stage(), Some("build-hq"));
+    }
+}
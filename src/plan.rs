@@ -0,0 +1,423 @@
+//! Module for evil plans, the steps they're made of and the order to execute them.
+#![allow(dead_code)]
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::time::Duration;
+
+use crate::Henchman;
+use crate::error::EvilError;
+use crate::gadget::Capability;
+use crate::henchman::Progress;
+use crate::units::EvilDuration;
+
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
+
+pub mod estimate;
+pub mod merge;
+pub mod portfolio;
+pub mod quality;
+pub mod templates;
+#[cfg(feature = "std")]
+pub mod timeline;
+
+/// Identifier of a step inside a [`PlanGraph`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct StepId(usize);
+
+impl StepId {
+    /// Position of the step in the order it was added.
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+/// What a henchman has to do to complete a step.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StepAction {
+    BuildSecretHq(String),
+    DoHardThings,
+    FightEnemies,
+}
+
+impl StepAction {
+    pub(crate) fn perform<H: Henchman>(&self, henchman: &mut H) {
+        match self {
+            StepAction::BuildSecretHq(location) => henchman.build_secret_hq(location.clone()),
+            StepAction::DoHardThings => henchman.do_hard_things(),
+            StepAction::FightEnemies => henchman.fight_enemies(),
+        }
+    }
+}
+
+/// A single step of a plan.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlanStep {
+    pub name: String,
+    pub duration: EvilDuration,
+    pub action: StepAction,
+}
+
+impl PlanStep {
+    pub fn new(name: &str, duration: impl Into<EvilDuration>, action: StepAction) -> PlanStep {
+        Self {
+            name: name.into(),
+            duration: duration.into(),
+            action,
+        }
+    }
+}
+
+/// What a plan needs before it can start.
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MissionRequirements {
+    /// Capabilities some gadget must have, with the minimum rating.
+    pub gadgets: Vec<(Capability, u8)>,
+    /// Shots the weapon must have left.
+    pub ammo: u32,
+    pub henchmen: usize,
+    /// Coins the treasury must hold.
+    pub budget: u64,
+}
+
+/// Reason the villain isn't ready to carry out a plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReadinessIssue {
+    Incarcerated,
+    MissingGadget {
+        capability: Capability,
+        required: u8,
+        best: u8,
+    },
+    WeaponCoolingDown {
+        remaining: Duration,
+    },
+    NotEnoughAmmo {
+        required: u32,
+        available: u32,
+    },
+    NotEnoughHenchmen {
+        required: usize,
+        available: usize,
+    },
+    InsufficientFunds {
+        required: u64,
+        balance: u64,
+    },
+}
+
+/// Directed acyclic graph of plan steps, where edges are dependencies between steps.
+#[derive(Debug, Clone, Default)]
+pub struct PlanGraph {
+    steps: Vec<PlanStep>,
+    dependencies: Vec<Vec<usize>>,
+    dependents: Vec<Vec<usize>>,
+    requirements: MissionRequirements,
+    deadline: Option<u32>,
+}
+
+impl PlanGraph {
+    pub fn new() -> PlanGraph {
+        Self::default()
+    }
+
+    pub fn add_step(&mut self, step: PlanStep) -> StepId {
+        self.steps.push(step);
+        self.dependencies.push(vec![]);
+        self.dependents.push(vec![]);
+        StepId(self.steps.len() - 1)
+    }
+
+    /// Declares that `step` can't start until `depends_on` is done.
+    pub fn add_dependency(&mut self, step: StepId, depends_on: StepId) -> Result<(), EvilError> {
+        for id in [step, depends_on] {
+            if id.0 >= self.steps.len() {
+                return Err(EvilError::UnknownStep { id: id.0 });
+            }
+        }
+        if !self.dependencies[step.0].contains(&depends_on.0) {
+            self.dependencies[step.0].push(depends_on.0);
+            self.dependents[depends_on.0].push(step.0);
+        }
+        Ok(())
+    }
+
+    pub fn requirements(&self) -> &MissionRequirements {
+        &self.requirements
+    }
+
+    pub fn set_requirements(&mut self, requirements: MissionRequirements) {
+        self.requirements = requirements;
+    }
+
+    /// Turn by which the plan has to be done, if there's a rush.
+    pub fn deadline(&self) -> Option<u32> {
+        self.deadline
+    }
+
+    pub fn set_deadline(&mut self, turn: u32) {
+        self.deadline = Some(turn);
+    }
+
+    pub fn step(&self, id: StepId) -> Option<&PlanStep> {
+        self.steps.get(id.0)
+    }
+
+    /// Steps in the order they were added.
+    pub fn steps(&self) -> impl Iterator<Item = (StepId, &PlanStep)> {
+        self.steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| (StepId(i), step))
+    }
+
+    /// Steps that have to be done before the given one.
+    pub fn dependencies(&self, id: StepId) -> impl Iterator<Item = StepId> + '_ {
+        self.dependencies
+            .get(id.0)
+            .into_iter()
+            .flatten()
+            .map(|&i| StepId(i))
+    }
+
+    pub fn len(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Groups the steps in waves: every step of a wave only depends on steps of previous waves.
+    pub fn waves(&self) -> Result<Vec<Vec<StepId>>, EvilError> {
+        let mut pending = self.dependencies.iter().map(Vec::len).collect::<Vec<_>>();
+        let mut wave = (0..self.steps.len())
+            .filter(|&i| pending[i] == 0)
+            .collect::<Vec<_>>();
+        let mut waves = vec![];
+        let mut scheduled = 0;
+
+        while !wave.is_empty() {
+            scheduled += wave.len();
+            let mut next = vec![];
+            for &i in &wave {
+                for &j in &self.dependents[i] {
+                    pending[j] -= 1;
+                    if pending[j] == 0 {
+                        next.push(j);
+                    }
+                }
+            }
+            next.sort_unstable();
+            waves.push(wave.into_iter().map(StepId).collect());
+            wave = next;
+        }
+
+        if scheduled < self.steps.len() {
+            let steps = (0..self.steps.len())
+                .filter(|&i| pending[i] > 0)
+                .map(|i| self.steps[i].name.clone())
+                .collect();
+            return Err(EvilError::PlanCycle { steps });
+        }
+        Ok(waves)
+    }
+
+    /// Returns the steps in an order that honors every dependency.
+    pub fn topological_order(&self) -> Result<Vec<StepId>, EvilError> {
+        Ok(self.waves()?.into_iter().flatten().collect())
+    }
+
+    /// Returns the longest chain of dependent steps and its total duration.
+    pub fn critical_path(&self) -> Result<(Vec<StepId>, EvilDuration), EvilError> {
+        let mut finish = vec![EvilDuration::ZERO; self.steps.len()];
+        let mut previous = vec![None; self.steps.len()];
+
+        for StepId(i) in self.topological_order()? {
+            let start = self.dependencies[i]
+                .iter()
+                .copied()
+                .max_by_key(|&dependency| finish[dependency]);
+            previous[i] = start;
+            finish[i] = start.map_or(EvilDuration::ZERO, |dependency| finish[dependency])
+                + self.steps[i].duration;
+        }
+
+        let Some(mut last) = (0..self.steps.len()).max_by_key(|&i| finish[i]) else {
+            return Ok((vec![], EvilDuration::ZERO));
+        };
+        let total = finish[last];
+        let mut path = vec![StepId(last)];
+        while let Some(step) = previous[last] {
+            path.push(StepId(step));
+            last = step;
+        }
+        path.reverse();
+        Ok((path, total))
+    }
+}
+
+/// Dispatches the steps of a plan to a pool of henchmen as soon as they are ready.
+pub struct PlanScheduler<'a, H: Henchman> {
+    henchmen: &'a mut [H],
+}
+
+impl<'a, H: Henchman> PlanScheduler<'a, H> {
+    pub fn new(henchmen: &'a mut [H]) -> PlanScheduler<'a, H> {
+        Self { henchmen }
+    }
+
+    /// Executes every step of the plan, distributing each wave of ready steps among the
+    /// henchmen. Returns the steps in the order they were dispatched.
+    pub fn run(&mut self, graph: &PlanGraph) -> Result<Vec<StepId>, EvilError> {
+        let waves = graph.waves()?;
+        if !graph.is_empty() && self.henchmen.is_empty() {
+            return Err(EvilError::NoHenchmen);
+        }
+
+        let mut dispatched = vec![];
+        for wave in waves {
+            for (i, id) in wave.into_iter().enumerate() {
+                let henchman = &mut self.henchmen[i % self.henchmen.len()];
+                graph.steps[id.0].action.perform(henchman);
+                dispatched.push(id);
+            }
+        }
+        Ok(dispatched)
+    }
+
+    /// Aggregated progress of the whole henchman pool.
+    pub fn progress(&self) -> Progress {
+        let reports = self
+            .henchmen
+            .iter()
+            .map(Henchman::report_progress)
+            .collect::<Vec<_>>();
+        Progress::aggregate(&reports)
+    }
+}
+
+/// What came out of a world domination stage.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StageOutcome {
+    HqBuilt {
+        target: String,
+    },
+    NoTargets,
+    NoSuitableGadget,
+    HenchmenDeployed {
+        hq: String,
+        count: usize,
+        progress: Progress,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use mockall::Sequence;
+    use mockall::predicate::eq;
+    use std::time::Duration;
+
+    #[test]
+    fn topological_order_puts_dependencies_first() -> Result<(), EvilError> {
+        let (graph, [hq, device, fight]) = sample_graph();
+        let order = graph.topological_order()?;
+        let position = |id| order.iter().position(|&step| step == id).unwrap();
+        assert!(position(hq) < position(device));
+        assert!(position(device) < position(fight));
+        Ok(())
+    }
+
+    #[test]
+    fn cyclic_dependencies_are_reported() {
+        let (mut graph, [hq, _, fight]) = sample_graph();
+        graph.add_dependency(hq, fight).unwrap();
+        let result = graph.topological_order();
+        assert_matches!(result, Err(EvilError::PlanCycle { steps }) if steps.len() == 3);
+    }
+
+    #[test]
+    fn dependency_on_unknown_step_fails() {
+        let (mut graph, [hq, ..]) = sample_graph();
+        let result = graph.add_dependency(hq, StepId(42));
+        assert_matches!(result, Err(EvilError::UnknownStep { id: 42 }));
+    }
+
+    #[test]
+    fn critical_path_follows_longest_chain() -> Result<(), EvilError> {
+        let (graph, [hq, device, fight]) = sample_graph();
+        let (path, duration) = graph.critical_path()?;
+        assert_eq!(path, vec![hq, device, fight]);
+        assert_eq!(duration, EvilDuration::from_secs(60));
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_dispatches_steps_in_dependency_order() -> Result<(), EvilError> {
+        let (graph, _) = sample_graph();
+        let mut sequence = Sequence::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::FIRST_TARGET)))
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        mock_henchman
+            .expect_do_hard_things()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        mock_henchman
+            .expect_fight_enemies()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        let mut henchmen = [mock_henchman];
+
+        let dispatched = PlanScheduler::new(&mut henchmen).run(&graph)?;
+        assert_eq!(dispatched.len(), graph.len());
+        Ok(())
+    }
+
+    #[test]
+    fn scheduler_without_henchmen_fails() {
+        let (graph, _) = sample_graph();
+        let mut henchmen: [MockHenchman; 0] = [];
+        let result = PlanScheduler::new(&mut henchmen).run(&graph);
+        assert_matches!(result, Err(EvilError::NoHenchmen));
+    }
+
+    fn sample_graph() -> (PlanGraph, [StepId; 3]) {
+        let mut graph = PlanGraph::new();
+        let hq = graph.add_step(PlanStep::new(
+            "Build HQ",
+            Duration::from_secs(30),
+            StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+        ));
+        let device = graph.add_step(PlanStep::new(
+            "Arm device",
+            Duration::from_secs(20),
+            StepAction::DoHardThings,
+        ));
+        let fight = graph.add_step(PlanStep::new(
+            "Fight heroes",
+            Duration::from_secs(10),
+            StepAction::FightEnemies,
+        ));
+        graph.add_dependency(device, hq).unwrap();
+        graph.add_dependency(fight, device).unwrap();
+        (graph, [hq, device, fight])
+    }
+}
@@ -0,0 +1,243 @@
+//! Estimating how likely a plan is to succeed, by carrying it out many times over in
+//! simulation: Monte Carlo over skill checks, weapon jams and heroes getting in the way.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use rand::Rng;
+
+use super::StepAction;
+use super::quality::Plan;
+use crate::ai::WorldState;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+
+/// Fact of the world saying the HQ is already built, so steps building one only fail if a hero
+/// shows up.
+pub const HQ_BUILT: &str = "hq_built";
+/// Fact of the world saying the weapon was maintained, so it jams less.
+pub const WEAPON_MAINTAINED: &str = "weapon_maintained";
+/// Fact of the world saying heroes expect trouble, so they get in the way more.
+pub const HEROES_ALERTED: &str = "heroes_alerted";
+/// Skill of henchmen carrying out a plan, before its quality is taken into account.
+const BASE_SKILL: u8 = 50;
+/// Chance the weapon jams in a fight.
+const WEAPON_JAM_CHANCE: f64 = 0.1;
+const MAINTAINED_WEAPON_JAM_CHANCE: f64 = 0.02;
+/// Chance each hero of the plan shows up during a step.
+const HERO_INTERFERENCE_CHANCE: f64 = 0.05;
+const ALERTED_HERO_INTERFERENCE_CHANCE: f64 = 0.15;
+
+/// Why a simulated run of a plan failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureCause {
+    /// The henchmen failed the check of a step.
+    SkillCheck,
+    /// The weapon jammed in a fight.
+    WeaponJammed,
+    /// A hero showed up and the henchmen failed the now much harder check.
+    HeroInterference,
+}
+
+/// How simulated runs of a plan went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuccessEstimate {
+    pub samples: u32,
+    /// Runs by the number of steps they completed, from none to every step.
+    pub steps_completed: Vec<u32>,
+    pub skill_failures: u32,
+    pub weapon_jams: u32,
+    pub hero_interferences: u32,
+}
+
+impl SuccessEstimate {
+    fn new(steps: usize, samples: u32) -> SuccessEstimate {
+        Self {
+            samples,
+            steps_completed: vec![0; steps + 1],
+            skill_failures: 0,
+            weapon_jams: 0,
+            hero_interferences: 0,
+        }
+    }
+
+    /// Runs that completed every step.
+    pub fn successes(&self) -> u32 {
+        self.steps_completed.last().copied().unwrap_or(0)
+    }
+
+    /// Chance, between 0 and 1, the plan succeeds. Nothing is known without samples, so 0.
+    pub fn probability(&self) -> f64 {
+        self.ratio(self.successes())
+    }
+
+    /// Chance, between 0 and 1, of completing at least the number of steps.
+    pub fn probability_of_reaching(&self, steps: usize) -> f64 {
+        self.ratio(self.steps_completed.iter().skip(steps).sum())
+    }
+
+    /// Whether the plan succeeds often enough to go ahead with it.
+    pub fn is_go(&self, min_probability: f64) -> bool {
+        self.probability() >= min_probability
+    }
+
+    fn ratio(&self, runs: u32) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        f64::from(runs) / f64::from(self.samples)
+    }
+
+    fn record(&mut self, completed: usize, failure: Option<FailureCause>) {
+        self.steps_completed[completed] += 1;
+        match failure {
+            Some(FailureCause::SkillCheck) => self.skill_failures += 1,
+            Some(FailureCause::WeaponJammed) => self.weapon_jams += 1,
+            Some(FailureCause::HeroInterference) => self.hero_interferences += 1,
+            None => {}
+        }
+    }
+}
+
+impl Plan {
+    /// Carries out the plan `samples` times in simulation, each step being a skill check of the
+    /// henchmen, helped by the quality of the plan. Fights also need the weapon not to jam, and
+    /// any hero of the plan may show up and make a step very hard. A run stops at the first
+    /// step failing. The world tells whether the HQ is built already, the weapon maintained, or
+    /// the heroes alerted.
+    pub fn estimate_success<R: Rng + ?Sized>(
+        &self,
+        world: &WorldState,
+        samples: u32,
+        rng: &mut R,
+    ) -> SuccessEstimate {
+        let skill = self.quality.adjust_skill(BASE_SKILL);
+        let jam_chance = if world.get(WEAPON_MAINTAINED) {
+            MAINTAINED_WEAPON_JAM_CHANCE
+        } else {
+            WEAPON_JAM_CHANCE
+        };
+        let interference_chance = if world.get(HEROES_ALERTED) {
+            ALERTED_HERO_INTERFERENCE_CHANCE
+        } else {
+            HERO_INTERFERENCE_CHANCE
+        };
+        let mut estimate = SuccessEstimate::new(self.steps.len(), samples);
+        for _ in 0..samples {
+            let mut completed = 0;
+            let mut failure = None;
+            for step in &self.steps {
+                let difficulty = match &step.action {
+                    StepAction::BuildSecretHq(_) if world.get(HQ_BUILT) => None,
+                    StepAction::BuildSecretHq(_) => Some(Difficulty::EASY),
+                    StepAction::DoHardThings => Some(Difficulty::MEDIUM),
+                    StepAction::FightEnemies => {
+                        if rng.random_bool(jam_chance) {
+                            failure = Some(FailureCause::WeaponJammed);
+                            break;
+                        }
+                        Some(Difficulty::HARD)
+                    }
+                };
+                let interfered = self
+                    .heroes
+                    .iter()
+                    .any(|_| rng.random_bool(interference_chance));
+                let (difficulty, cause) = if interfered {
+                    (Some(Difficulty::VERY_HARD), FailureCause::HeroInterference)
+                } else {
+                    (difficulty, FailureCause::SkillCheck)
+                };
+                if let Some(difficulty) = difficulty
+                    && !SkillCheck::roll(skill, difficulty, rng).is_success()
+                {
+                    failure = Some(cause);
+                    break;
+                }
+                completed += 1;
+            }
+            estimate.record(completed, failure);
+        }
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+    use crate::plan::quality::PlanQuality;
+    use crate::test_common;
+    use core::time::Duration;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn heist() -> Plan {
+        Plan::new("Rob the bank", PlanQuality::Diabolical)
+            .with_hero(test_common::HERO_NAME)
+            .with_step(PlanStep::new(
+                "Build the HQ",
+                Duration::from_secs(60),
+                StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+            ))
+            .with_step(PlanStep::new(
+                "Fight the guards",
+                Duration::from_secs(60),
+                StepAction::FightEnemies,
+            ))
+    }
+
+    #[test]
+    fn plans_without_steps_always_succeed() {
+        let plan = Plan::new("Do nothing", PlanQuality::Harebrained);
+        let estimate =
+            plan.estimate_success(&WorldState::new(), 100, &mut StdRng::seed_from_u64(1));
+        assert_eq!(estimate.steps_completed, [100]);
+        assert_eq!(estimate.probability(), 1.0);
+        assert!(estimate.is_go(0.99));
+    }
+
+    #[test]
+    fn every_run_is_accounted_for() {
+        let world = WorldState::new().with(HQ_BUILT, true);
+        let estimate = heist().estimate_success(&world, 1_000, &mut StdRng::seed_from_u64(1));
+        assert_eq!(estimate.steps_completed.len(), 3);
+        assert_eq!(estimate.steps_completed.iter().sum::<u32>(), 1_000);
+        assert_eq!(
+            estimate.skill_failures + estimate.weapon_jams + estimate.hero_interferences,
+            1_000 - estimate.successes()
+        );
+        assert_eq!(estimate.probability_of_reaching(0), 1.0);
+        assert!(estimate.probability_of_reaching(1) >= estimate.probability());
+    }
+
+    #[test]
+    fn maintenance_and_quality_help_while_alerted_heroes_hinder() {
+        let probability = |plan: &Plan, world: &WorldState| {
+            plan.estimate_success(world, 10_000, &mut StdRng::seed_from_u64(1))
+                .probability()
+        };
+        let world = WorldState::new();
+        let baseline = probability(&heist(), &world);
+
+        let maintained = probability(&heist(), &world.clone().with(WEAPON_MAINTAINED, true));
+        assert!(maintained > baseline);
+        let alerted = probability(&heist(), &world.clone().with(HEROES_ALERTED, true));
+        assert!(alerted < baseline);
+
+        let mut foolproof = heist();
+        foolproof.quality = PlanQuality::Foolproof;
+        assert!(probability(&foolproof, &world) > baseline);
+        let mut harebrained = heist();
+        harebrained.quality = PlanQuality::Harebrained;
+        assert!(probability(&harebrained, &world) < baseline);
+    }
+
+    #[test]
+    fn no_samples_means_no_chance() {
+        let estimate =
+            heist().estimate_success(&WorldState::new(), 0, &mut StdRng::seed_from_u64(1));
+        assert_eq!(estimate.probability(), 0.0);
+        assert!(!estimate.is_go(0.5));
+    }
+}
@@ -0,0 +1,138 @@
+//! Merging the plans of allied villains into one they carry out together.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::error::Error;
+use core::fmt;
+
+use super::quality::Plan;
+
+/// Something both plans want that only one villain can have.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Conflict {
+    /// Both villains want their lair in the same place.
+    SameLair(String),
+    /// Both villains want to take out the same hero.
+    SameHero(String),
+}
+
+/// Conflicts to resolve by hand before the plans can be merged.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeConflict {
+    pub conflicts: Vec<Conflict>,
+}
+
+impl fmt::Display for MergeConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Plans conflict:")?;
+        for conflict in &self.conflicts {
+            match conflict {
+                Conflict::SameLair(location) => write!(f, " lair='{location}'")?,
+                Conflict::SameHero(hero) => write!(f, " hero='{hero}'")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Error for MergeConflict {}
+
+impl Plan {
+    /// Plan carrying out both plans: steps and targets of this one first, then the ones of the
+    /// other that aren't already there. The merged plan is only as good as the worse one, and
+    /// keeps this one's lair, or the other's if this one has none.
+    ///
+    /// Fails with every conflict if both plans want their lair in the same place or the same
+    /// hero taken out.
+    pub fn merge(&self, other: &Plan) -> Result<Plan, MergeConflict> {
+        let mut conflicts = Vec::new();
+        if let (Some(lair), Some(other_lair)) = (&self.lair, &other.lair)
+            && lair == other_lair
+        {
+            conflicts.push(Conflict::SameLair(lair.clone()));
+        }
+        conflicts.extend(
+            self.heroes
+                .iter()
+                .filter(|hero| other.heroes.contains(hero))
+                .map(|hero| Conflict::SameHero(hero.clone())),
+        );
+        if !conflicts.is_empty() {
+            return Err(MergeConflict { conflicts });
+        }
+        Ok(Plan {
+            scheme: if self.scheme == other.scheme {
+                self.scheme.clone()
+            } else {
+                format!("{} & {}", self.scheme, other.scheme)
+            },
+            quality: self.quality.min(other.quality),
+            lair: self.lair.clone().or_else(|| other.lair.clone()),
+            targets: union(&self.targets, &other.targets),
+            heroes: union(&self.heroes, &other.heroes),
+            steps: union(&self.steps, &other.steps),
+        })
+    }
+}
+
+/// Items of both, in order, without the ones already there.
+fn union<T: Clone + PartialEq>(first: &[T], second: &[T]) -> Vec<T> {
+    let mut items = Vec::with_capacity(first.len() + second.len());
+    for item in first.iter().chain(second) {
+        if !items.contains(item) {
+            items.push(item.clone());
+        }
+    }
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+    use crate::plan::StepAction;
+    use crate::plan::quality::PlanQuality;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use core::time::Duration;
+
+    fn heist() -> PlanStep {
+        PlanStep::new(
+            "Rob the bank",
+            Duration::from_secs(60),
+            StepAction::DoHardThings,
+        )
+    }
+
+    #[test]
+    fn overlapping_plans_merge_without_duplicates() -> Result<(), MergeConflict> {
+        let lex = Plan::new("Take over the world!", PlanQuality::Diabolical)
+            .with_lair(test_common::FIRST_TARGET)
+            .with_target("Metropolis")
+            .with_step(heist());
+        let joker = Plan::new("Take over the world!", PlanQuality::Solid)
+            .with_target("Metropolis")
+            .with_target("Gotham")
+            .with_hero(test_common::HERO_NAME)
+            .with_step(heist());
+
+        let merged = lex.merge(&joker)?;
+        assert_eq!(merged.scheme, "Take over the world!");
+        assert_eq!(merged.quality, PlanQuality::Solid);
+        assert_eq!(merged.lair.as_deref(), Some(test_common::FIRST_TARGET));
+        assert_eq!(merged.targets, ["Metropolis", "Gotham"]);
+        assert_eq!(merged.heroes, [test_common::HERO_NAME]);
+        assert_eq!(merged.steps, [heist()]);
+        Ok(())
+    }
+
+    #[test]
+    fn same_lair_and_hero_are_conflicts() {
+        let plan = Plan::new("Take over the world!", PlanQuality::Solid)
+            .with_lair(test_common::FIRST_TARGET)
+            .with_hero(test_common::HERO_NAME);
+        let result = plan.merge(&plan);
+        assert_matches!(result, Err(MergeConflict { conflicts }) if conflicts.len() == 2);
+    }
+}
@@ -0,0 +1,182 @@
+//! Plans the villain has going at once, and which one to work on next.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::cmp::Reverse;
+
+use super::PlanGraph;
+
+/// Urgency of a plan due this very turn. It halves with every turn left.
+const MAX_URGENCY: u32 = 100;
+
+/// How plans are ranked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PrioritizationPolicy {
+    /// Earliest deadline first; plans without one go last. Ties go to the biggest payoff.
+    #[default]
+    EarliestDeadline,
+    /// Highest score first, adding up urgency and payoff with these weights.
+    Weighted { urgency: u32, payoff: u32 },
+}
+
+/// A plan in the portfolio, with the infamy it's expected to bring.
+#[derive(Debug, Clone)]
+pub struct ActivePlan {
+    pub name: String,
+    pub plan: PlanGraph,
+    pub payoff: u32,
+}
+
+impl ActivePlan {
+    /// How pressing the plan is at the turn, from 0 (no deadline) to 100 (due now).
+    pub fn urgency(&self, turn: u32) -> u32 {
+        match self.plan.deadline() {
+            Some(deadline) => MAX_URGENCY
+                .checked_shr(deadline.saturating_sub(turn))
+                .unwrap_or(0),
+            None => 0,
+        }
+    }
+
+    pub fn is_overdue(&self, turn: u32) -> bool {
+        self.plan.deadline().is_some_and(|deadline| deadline < turn)
+    }
+}
+
+/// Active plans, ranked every turn by a [`PrioritizationPolicy`].
+#[derive(Debug, Clone, Default)]
+pub struct PlanPortfolio {
+    plans: Vec<ActivePlan>,
+    policy: PrioritizationPolicy,
+    turn: u32,
+}
+
+impl PlanPortfolio {
+    pub fn new(policy: PrioritizationPolicy) -> PlanPortfolio {
+        Self {
+            policy,
+            ..Default::default()
+        }
+    }
+
+    pub fn turn(&self) -> u32 {
+        self.turn
+    }
+
+    pub fn plans(&self) -> &[ActivePlan] {
+        &self.plans
+    }
+
+    pub fn add(&mut self, name: &str, plan: PlanGraph, payoff: u32) {
+        self.plans.push(ActivePlan {
+            name: name.into(),
+            plan,
+            payoff,
+        });
+    }
+
+    /// Takes the plan out of the portfolio, e.g. once it's done.
+    pub fn remove(&mut self, name: &str) -> Option<ActivePlan> {
+        let index = self.plans.iter().position(|active| active.name == name)?;
+        Some(self.plans.remove(index))
+    }
+
+    /// Plans still on time, most important first.
+    pub fn ranked(&self) -> Vec<&ActivePlan> {
+        let mut ranked = self
+            .plans
+            .iter()
+            .filter(|active| !active.is_overdue(self.turn))
+            .collect::<Vec<_>>();
+        match self.policy {
+            PrioritizationPolicy::EarliestDeadline => ranked.sort_by_key(|active| {
+                (
+                    active.plan.deadline().unwrap_or(u32::MAX),
+                    Reverse(active.payoff),
+                )
+            }),
+            PrioritizationPolicy::Weighted { urgency, payoff } => {
+                ranked.sort_by_key(|active| {
+                    Reverse(
+                        u64::from(active.urgency(self.turn)) * u64::from(urgency)
+                            + u64::from(active.payoff) * u64::from(payoff),
+                    )
+                });
+            }
+        }
+        ranked
+    }
+
+    /// The plan to work on this turn.
+    pub fn suggest(&self) -> Option<&ActivePlan> {
+        self.ranked().into_iter().next()
+    }
+
+    /// Plans whose deadline has passed.
+    pub fn overdue(&self) -> impl Iterator<Item = &ActivePlan> {
+        self.plans
+            .iter()
+            .filter(|active| active.is_overdue(self.turn))
+    }
+
+    pub fn advance_turn(&mut self) {
+        self.turn += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plan_due(turn: Option<u32>) -> PlanGraph {
+        let mut plan = PlanGraph::new();
+        if let Some(turn) = turn {
+            plan.set_deadline(turn);
+        }
+        plan
+    }
+
+    fn portfolio(policy: PrioritizationPolicy) -> PlanPortfolio {
+        let mut portfolio = PlanPortfolio::new(policy);
+        portfolio.add("Steal the moon", plan_due(None), 500);
+        portfolio.add("Rob the bank", plan_due(Some(3)), 20);
+        portfolio.add("Freeze the city", plan_due(Some(1)), 80);
+        portfolio
+    }
+
+    fn names(portfolio: &PlanPortfolio) -> Vec<&str> {
+        portfolio
+            .ranked()
+            .into_iter()
+            .map(|active| active.name.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn earliest_deadline_goes_first() {
+        let mut portfolio = portfolio(PrioritizationPolicy::EarliestDeadline);
+        assert_eq!(
+            names(&portfolio),
+            ["Freeze the city", "Rob the bank", "Steal the moon"]
+        );
+        portfolio.advance_turn();
+        portfolio.advance_turn();
+        assert_eq!(portfolio.overdue().count(), 1);
+        assert_eq!(
+            portfolio.suggest().map(|active| active.name.as_str()),
+            Some("Rob the bank")
+        );
+    }
+
+    #[test]
+    fn weighted_policy_trades_urgency_for_payoff() {
+        let portfolio = portfolio(PrioritizationPolicy::Weighted {
+            urgency: 10,
+            payoff: 1,
+        });
+        assert_eq!(
+            names(&portfolio),
+            ["Freeze the city", "Steal the moon", "Rob the bank"]
+        );
+    }
+}
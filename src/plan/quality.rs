@@ -0,0 +1,167 @@
+//! Plans villains come up with, how good they are, and how much that helps carrying them out.
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
+use core::time::Duration;
+
+use crate::infamy::Infamy;
+use crate::personality::Personality;
+use crate::plan::PlanStep;
+
+/// Score from which a plan is solid.
+pub const SOLID_SCORE: u8 = 20;
+/// Score from which a plan is diabolical.
+pub const DIABOLICAL_SCORE: u8 = 50;
+/// Score from which a plan is foolproof.
+pub const FOOLPROOF_SCORE: u8 = 80;
+/// Infamy points per point of score, for experience.
+const INFAMY_PER_POINT: u32 = 10;
+/// Most of the score coming from each of experience, paranoia, restraint and planning time.
+const MAX_EXPERIENCE_SCORE: u8 = 40;
+const MAX_TRAIT_SCORE: u8 = 20;
+const MAX_PLANNING_SCORE: u8 = 20;
+/// Planning time giving the most score. Thinking longer doesn't help.
+const THOROUGH_PLANNING_TIME: Duration = Duration::from_secs(1);
+
+/// Quality tier of a plan, worst first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PlanQuality {
+    Harebrained,
+    Solid,
+    Diabolical,
+    Foolproof,
+}
+
+impl PlanQuality {
+    /// Tier of a score from 0 to 100.
+    pub fn from_score(score: u8) -> PlanQuality {
+        match score {
+            FOOLPROOF_SCORE.. => PlanQuality::Foolproof,
+            DIABOLICAL_SCORE.. => PlanQuality::Diabolical,
+            SOLID_SCORE.. => PlanQuality::Solid,
+            _ => PlanQuality::Harebrained,
+        }
+    }
+
+    /// Rates a plan by the villain's experience, its paranoia and restraint (theatrical villains
+    /// plan for show), and how long it thought about it.
+    pub fn rate(infamy: Infamy, personality: &Personality, planning_time: Duration) -> PlanQuality {
+        let experience = (infamy.points() / INFAMY_PER_POINT).min(u32::from(MAX_EXPERIENCE_SCORE));
+        let trait_score = |score: u8| u32::from(score.min(100)) * u32::from(MAX_TRAIT_SCORE) / 100;
+        let paranoia = trait_score(personality.paranoia);
+        let restraint = trait_score(100 - personality.theatricality.min(100));
+        let planning = planning_time.min(THOROUGH_PLANNING_TIME).as_millis()
+            * u128::from(MAX_PLANNING_SCORE)
+            / THOROUGH_PLANNING_TIME.as_millis();
+        let score = experience + paranoia + restraint + planning as u32;
+        Self::from_score(score as u8)
+    }
+
+    /// Skill added to checks carrying out the plan, or taken away for harebrained ones.
+    pub fn skill_bonus(&self) -> i8 {
+        match self {
+            PlanQuality::Harebrained => -10,
+            PlanQuality::Solid => 0,
+            PlanQuality::Diabolical => 10,
+            PlanQuality::Foolproof => 20,
+        }
+    }
+
+    /// Skill with the bonus, from 0 to 100.
+    pub fn adjust_skill(&self, skill: u8) -> u8 {
+        skill
+            .min(100)
+            .saturating_add_signed(self.skill_bonus())
+            .min(100)
+    }
+}
+
+impl fmt::Display for PlanQuality {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PlanQuality::Harebrained => f.write_str("Harebrained"),
+            PlanQuality::Solid => f.write_str("Solid"),
+            PlanQuality::Diabolical => f.write_str("Diabolical"),
+            PlanQuality::Foolproof => f.write_str("Foolproof"),
+        }
+    }
+}
+
+/// Plan a villain came up with, and how good it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Plan {
+    pub scheme: String,
+    pub quality: PlanQuality,
+    /// Where the villain's lair goes, if the plan needs one.
+    pub lair: Option<String>,
+    /// Places to strike.
+    pub targets: Vec<String>,
+    /// Heroes to take out.
+    pub heroes: Vec<String>,
+    pub steps: Vec<PlanStep>,
+}
+
+impl Plan {
+    /// Plan with no lair, targets, heroes nor steps.
+    pub fn new(scheme: &str, quality: PlanQuality) -> Plan {
+        Self {
+            scheme: scheme.into(),
+            quality,
+            lair: None,
+            targets: Vec::new(),
+            heroes: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+
+    pub fn with_lair(mut self, location: &str) -> Plan {
+        self.lair = Some(location.into());
+        self
+    }
+
+    pub fn with_target(mut self, target: &str) -> Plan {
+        self.targets.push(target.into());
+        self
+    }
+
+    pub fn with_hero(mut self, hero: &str) -> Plan {
+        self.heroes.push(hero.into());
+        self
+    }
+
+    pub fn with_step(mut self, step: PlanStep) -> Plan {
+        self.steps.push(step);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn experience_and_time_make_better_plans() {
+        let personality = Personality::default();
+        assert_eq!(
+            PlanQuality::rate(Infamy::default(), &personality, Duration::ZERO),
+            PlanQuality::Solid
+        );
+        assert_eq!(
+            PlanQuality::rate(Infamy::new(400), &personality, Duration::from_secs(5)),
+            PlanQuality::Foolproof
+        );
+        let theatrical = Personality::new(50, 0, 100);
+        assert_eq!(
+            PlanQuality::rate(Infamy::default(), &theatrical, Duration::ZERO),
+            PlanQuality::Harebrained
+        );
+    }
+
+    #[test]
+    fn quality_bonus_keeps_skill_in_range() {
+        assert_eq!(PlanQuality::Harebrained.adjust_skill(5), 0);
+        assert_eq!(PlanQuality::Foolproof.adjust_skill(90), 100);
+        assert_eq!(PlanQuality::Solid.adjust_skill(40), 40);
+    }
+}
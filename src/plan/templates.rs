@@ -0,0 +1,257 @@
+//! Library of ready-made schemes that only need a target, a timeline and resources to become
+//! a plan.
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::MissionRequirements;
+use super::PlanGraph;
+use super::PlanStep;
+use super::StepAction;
+use crate::error::EvilError;
+use crate::gadget::Capability;
+use crate::units::EvilDuration;
+
+/// What a henchman does in a templated step. The HQ is built in the scheme's target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemplateAction {
+    BuildSecretHq,
+    DoHardThings,
+    FightEnemies,
+}
+
+/// Step of a template, taking a share of the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TemplateStep {
+    pub name: &'static str,
+    /// Percentage of the timeline the step takes.
+    pub share: u8,
+    pub action: TemplateAction,
+    /// Positions of the steps it depends on.
+    pub after: &'static [usize],
+}
+
+/// Parameterized plan. Shares along any chain of steps add up to at most 100, so the plan
+/// fits in the timeline.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlanTemplate {
+    pub name: &'static str,
+    pub steps: &'static [TemplateStep],
+    pub gadgets: &'static [(Capability, u8)],
+    pub ammo: u32,
+    pub minimum_timeline: EvilDuration,
+    pub minimum_henchmen: usize,
+    pub minimum_budget: u64,
+}
+
+/// What the user fills in to turn a template into a plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemeParameters {
+    pub target: String,
+    pub timeline: EvilDuration,
+    pub henchmen: usize,
+    pub budget: u64,
+}
+
+pub const FREEZE_THE_CITY: PlanTemplate = PlanTemplate {
+    name: "Freeze the city",
+    steps: &[
+        TemplateStep {
+            name: "Build the ice lair",
+            share: 30,
+            action: TemplateAction::BuildSecretHq,
+            after: &[],
+        },
+        TemplateStep {
+            name: "Assemble the freeze ray",
+            share: 40,
+            action: TemplateAction::DoHardThings,
+            after: &[0],
+        },
+        TemplateStep {
+            name: "Hold off the heroes",
+            share: 30,
+            action: TemplateAction::FightEnemies,
+            after: &[1],
+        },
+    ],
+    gadgets: &[(Capability::Combat, 60)],
+    ammo: 1,
+    minimum_timeline: EvilDuration::from_hours(48),
+    minimum_henchmen: 3,
+    minimum_budget: 50_000,
+};
+
+pub const STEAL_THE_MOON: PlanTemplate = PlanTemplate {
+    name: "Steal the moon",
+    steps: &[
+        TemplateStep {
+            name: "Build the launch base",
+            share: 20,
+            action: TemplateAction::BuildSecretHq,
+            after: &[],
+        },
+        TemplateStep {
+            name: "Build the shrink ray",
+            share: 40,
+            action: TemplateAction::DoHardThings,
+            after: &[0],
+        },
+        TemplateStep {
+            name: "Build the rocket",
+            share: 40,
+            action: TemplateAction::DoHardThings,
+            after: &[0],
+        },
+        TemplateStep {
+            name: "Shrink and grab the moon",
+            share: 40,
+            action: TemplateAction::FightEnemies,
+            after: &[1, 2],
+        },
+    ],
+    gadgets: &[(Capability::Combat, 80), (Capability::Escape, 50)],
+    ammo: 1,
+    minimum_timeline: EvilDuration::from_hours(720),
+    minimum_henchmen: 10,
+    minimum_budget: 1_000_000,
+};
+
+/// Every template in the library.
+pub const LIBRARY: [PlanTemplate; 2] = [FREEZE_THE_CITY, STEAL_THE_MOON];
+
+/// Template with the given name, ignoring case.
+pub fn find(name: &str) -> Option<&'static PlanTemplate> {
+    LIBRARY
+        .iter()
+        .find(|template| template.name.eq_ignore_ascii_case(name))
+}
+
+impl PlanTemplate {
+    /// Plan for the parameters, with the step durations split from the timeline and the
+    /// resources as its requirements. Fails if any parameter falls short of the template.
+    pub fn instantiate(&self, parameters: &SchemeParameters) -> Result<PlanGraph, EvilError> {
+        self.validate(parameters)?;
+        let mut graph = PlanGraph::new();
+        let timeline = parameters.timeline.as_secs();
+        let ids = self
+            .steps
+            .iter()
+            .map(|step| {
+                let action = match step.action {
+                    TemplateAction::BuildSecretHq => {
+                        StepAction::BuildSecretHq(parameters.target.clone())
+                    }
+                    TemplateAction::DoHardThings => StepAction::DoHardThings,
+                    TemplateAction::FightEnemies => StepAction::FightEnemies,
+                };
+                let duration = EvilDuration::from_secs(timeline * u64::from(step.share) / 100);
+                graph.add_step(PlanStep::new(step.name, duration, action))
+            })
+            .collect::<Vec<_>>();
+        for (step, template) in ids.iter().zip(self.steps) {
+            for &dependency in template.after {
+                let depends_on = ids
+                    .get(dependency)
+                    .copied()
+                    .ok_or(EvilError::UnknownStep { id: dependency })?;
+                graph.add_dependency(*step, depends_on)?;
+            }
+        }
+        graph.set_requirements(MissionRequirements {
+            gadgets: self.gadgets.to_vec(),
+            ammo: self.ammo,
+            henchmen: parameters.henchmen,
+            budget: parameters.budget,
+        });
+        Ok(graph)
+    }
+
+    fn validate(&self, parameters: &SchemeParameters) -> Result<(), EvilError> {
+        let invalid = |parameter: &str, reason: String| EvilError::InvalidParameter {
+            parameter: parameter.into(),
+            reason,
+        };
+        if parameters.target.trim().is_empty() {
+            return Err(invalid("target", String::from("Empty")));
+        }
+        if parameters.timeline < self.minimum_timeline {
+            return Err(invalid(
+                "timeline",
+                format!(
+                    "{} is shorter than {}",
+                    parameters.timeline, self.minimum_timeline
+                ),
+            ));
+        }
+        if parameters.henchmen < self.minimum_henchmen {
+            return Err(invalid(
+                "henchmen",
+                format!(
+                    "{} are fewer than {}",
+                    parameters.henchmen, self.minimum_henchmen
+                ),
+            ));
+        }
+        if parameters.budget < self.minimum_budget {
+            return Err(invalid(
+                "budget",
+                format!("{} is less than {}", parameters.budget, self.minimum_budget),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    fn parameters(timeline_hours: u64) -> SchemeParameters {
+        SchemeParameters {
+            target: test_common::FIRST_TARGET.into(),
+            timeline: EvilDuration::from_hours(timeline_hours),
+            henchmen: 12,
+            budget: 2_000_000,
+        }
+    }
+
+    #[test]
+    fn every_template_fits_its_timeline() -> Result<(), EvilError> {
+        for template in LIBRARY {
+            let graph = template.instantiate(&parameters(1_000))?;
+            let (_, total) = graph.critical_path()?;
+            assert!(
+                total <= EvilDuration::from_hours(1_000),
+                "{}",
+                template.name
+            );
+            assert_eq!(graph.requirements().henchmen, 12);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn templated_hq_is_built_in_target() -> Result<(), EvilError> {
+        let Some(template) = find("freeze THE city") else {
+            panic!("Template not found");
+        };
+        let graph = template.instantiate(&parameters(48))?;
+        assert!(
+            graph.steps().any(|(_, step)| step.action
+                == StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn short_timeline_is_rejected() {
+        assert_matches!(
+            STEAL_THE_MOON.instantiate(&parameters(48)),
+            Err(EvilError::InvalidParameter { parameter, .. }) if parameter == "timeline"
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! When every step of an executed plan started and ended, and who did it, for Gantt charts.
+
+use std::time::Duration;
+
+use serde::Serialize;
+
+use super::PlanGraph;
+use super::PlanScheduler;
+use super::StepId;
+use crate::Henchman;
+use crate::clock::Clock;
+use crate::supervillain::EvilError;
+
+/// A step as it was executed: its lane is the henchman that did it, and its start and end are
+/// measured from the start of the plan.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StepSpan {
+    pub step: StepId,
+    pub name: String,
+    pub lane: usize,
+    pub start: Duration,
+    pub end: Duration,
+}
+
+impl StepSpan {
+    pub fn duration(&self) -> Duration {
+        self.end.saturating_sub(self.start)
+    }
+}
+
+/// Task of a Gantt chart, with times in microseconds since the start of the plan.
+#[derive(Debug, Serialize)]
+struct GanttTask<'t> {
+    id: usize,
+    name: &'t str,
+    lane: usize,
+    start_us: u128,
+    end_us: u128,
+}
+
+/// How an executed plan unfolded, step by step.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlanTimeline {
+    spans: Vec<StepSpan>,
+}
+
+impl PlanTimeline {
+    /// Steps in the order they were executed.
+    pub fn spans(&self) -> &[StepSpan] {
+        &self.spans
+    }
+
+    pub fn span(&self, step: StepId) -> Option<&StepSpan> {
+        self.spans.iter().find(|span| span.step == step)
+    }
+
+    /// Number of lanes, one per henchman that did any step.
+    pub fn lanes(&self) -> usize {
+        self.spans
+            .iter()
+            .map(|span| span.lane + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Time from the start of the plan to the end of its last step.
+    pub fn total(&self) -> Duration {
+        self.spans
+            .iter()
+            .map(|span| span.end)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// The steps as a JSON array of tasks with `id`, `name`, `lane`, `start_us` and `end_us`.
+    pub fn to_json(&self) -> Result<String, EvilError> {
+        let tasks = self
+            .spans
+            .iter()
+            .map(|span| GanttTask {
+                id: span.step.index(),
+                name: &span.name,
+                lane: span.lane,
+                start_us: span.start.as_micros(),
+                end_us: span.end.as_micros(),
+            })
+            .collect::<Vec<_>>();
+        serde_json::to_string(&tasks).map_err(|error| EvilError::ParseError {
+            purpose: "timeline".into(),
+            reason: error.to_string(),
+        })
+    }
+
+    /// The steps as a [Mermaid](https://mermaid.js.org) Gantt chart, with a section per lane
+    /// and times in milliseconds.
+    pub fn to_mermaid(&self, title: &str) -> String {
+        let mut lines = vec![
+            String::from("gantt"),
+            format!("    title {title}"),
+            String::from("    dateFormat x"),
+            String::from("    axisFormat %S.%L"),
+        ];
+        for lane in 0..self.lanes() {
+            lines.push(format!("    section Henchman {}", lane + 1));
+            for span in self.spans.iter().filter(|span| span.lane == lane) {
+                lines.push(format!(
+                    "    {} :step{}, {}, {}",
+                    span.name.replace(':', " "),
+                    span.step.index(),
+                    span.start.as_millis(),
+                    span.end.as_millis()
+                ));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+impl<H: Henchman> PlanScheduler<'_, H> {
+    /// Like [`PlanScheduler::run`], timing every step with the clock.
+    pub fn run_with_timeline<C: Clock + ?Sized>(
+        &mut self,
+        graph: &PlanGraph,
+        clock: &C,
+    ) -> Result<PlanTimeline, EvilError> {
+        let waves = graph.waves()?;
+        if !graph.is_empty() && self.henchmen.is_empty() {
+            return Err(EvilError::NoHenchmen);
+        }
+
+        let started = clock.now();
+        let mut timeline = PlanTimeline::default();
+        for wave in waves {
+            for (i, id) in wave.into_iter().enumerate() {
+                let lane = i % self.henchmen.len();
+                let step = &graph.steps[id.0];
+                let start = clock.now().duration_since(started);
+                step.action.perform(&mut self.henchmen[lane]);
+                timeline.spans.push(StepSpan {
+                    step: id,
+                    name: step.name.clone(),
+                    lane,
+                    start,
+                    end: clock.now().duration_since(started),
+                });
+            }
+        }
+        Ok(timeline)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::henchman::MockHenchman;
+    use crate::plan::PlanStep;
+    use crate::plan::StepAction;
+    use std::time::Instant;
+
+    fn henchman() -> MockHenchman {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_fight_enemies().return_const(());
+        henchman
+    }
+
+    #[test]
+    fn timeline_tracks_steps_per_lane() -> Result<(), EvilError> {
+        let mut plan = PlanGraph::new();
+        let ambush = plan.add_step(PlanStep::new(
+            "Ambush",
+            Duration::ZERO,
+            StepAction::FightEnemies,
+        ));
+        let diversion = plan.add_step(PlanStep::new(
+            "Diversion",
+            Duration::ZERO,
+            StepAction::FightEnemies,
+        ));
+        let escape = plan.add_step(PlanStep::new(
+            "Escape",
+            Duration::ZERO,
+            StepAction::FightEnemies,
+        ));
+        plan.add_dependency(escape, ambush)?;
+        let base = Instant::now();
+        let mut ticks = 0..;
+        let mut clock = MockClock::new();
+        clock.expect_now().returning(move || {
+            base + Duration::from_micros(1_500 * ticks.next().unwrap_or_default())
+        });
+        let mut henchmen = [henchman(), henchman()];
+
+        let timeline = PlanScheduler::new(&mut henchmen).run_with_timeline(&plan, &clock)?;
+        assert_eq!(timeline.lanes(), 2);
+        let Some(span) = timeline.span(diversion) else {
+            panic!("Diversion should be on the timeline");
+        };
+        assert_eq!(span.lane, 1);
+        assert_eq!(span.duration(), Duration::from_micros(1_500));
+        assert_eq!(timeline.total(), Duration::from_micros(9_000));
+        assert!(
+            timeline
+                .to_json()?
+                .contains(r#"{"id":1,"name":"Diversion","lane":1,"start_us":4500,"end_us":6000}"#)
+        );
+        assert!(
+            timeline
+                .to_mermaid("Heist")
+                .ends_with("section Henchman 2\n    Diversion :step1, 4, 6")
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+//! Module with ready-made villain archetypes for demos and games.
+#![allow(dead_code)]
+
+use rand::Rng;
+
+use crate::SuperVillain;
+use crate::gadget::Capability;
+use crate::gadget::Gadget;
+use crate::gadget::GadgetInventory;
+use crate::infamy::Infamy;
+use crate::monologue::MonologueTemplate;
+use crate::supervillain::EvilError;
+use crate::treasury::Treasury;
+
+/// Kind of villain a preset is based on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Archetype {
+    MadScientist,
+    CrimeLord,
+    AlienOverlord,
+}
+
+impl Archetype {
+    /// Id of the cipher villains of this archetype prefer.
+    pub fn preferred_cipher(&self) -> &'static str {
+        match self {
+            Archetype::MadScientist => "quantum-scramble",
+            Archetype::CrimeLord => "one-time-pad",
+            Archetype::AlienOverlord => "xenolinguistic",
+        }
+    }
+
+    /// Monologue in the style of the archetype.
+    ///
+    /// # Panics
+    ///
+    /// Never in practice, as preset templates are valid. Deprecated with the `strict` feature;
+    /// use [`Archetype::try_monologue_template`] instead.
+    #[cfg_attr(
+        feature = "strict",
+        deprecated = "Use `try_monologue_template` instead"
+    )]
+    pub fn monologue_template(&self) -> MonologueTemplate {
+        self.try_monologue_template()
+            .expect("Preset templates only use known placeholders")
+    }
+
+    /// Monologue in the style of the archetype.
+    pub fn try_monologue_template(&self) -> Result<MonologueTemplate, EvilError> {
+        let text = match self {
+            Archetype::MadScientist => {
+                "They called me mad, {hero}! But my {device} proves that {villain} was right all along. Now I shall {plan}, for SCIENCE!"
+            }
+            Archetype::CrimeLord => {
+                "Nothing personal, {hero}. It's just business. My {device} is an investment, and {villain} always collects: time to {plan}."
+            }
+            Archetype::AlienOverlord => {
+                "Kneel, {hero}, primitive of this small blue planet! The {device} of {villain} will {plan}, as it did a thousand worlds before."
+            }
+        };
+        MonologueTemplate::new(text)
+    }
+
+    pub fn default_gadgets(&self) -> GadgetInventory<'static> {
+        let mut inventory = GadgetInventory::new();
+        let gadgets: &[StockGadget] = match self {
+            Archetype::MadScientist => &[
+                StockGadget::new("Freeze ray", Capability::Combat, 70),
+                StockGadget::new("Spectral scanner", Capability::TargetDetection, 80),
+            ],
+            Archetype::CrimeLord => &[
+                StockGadget::new("Bribed informant", Capability::TargetDetection, 60),
+                StockGadget::new("Armored limo", Capability::Escape, 70),
+            ],
+            Archetype::AlienOverlord => &[
+                StockGadget::new("Orbital laser", Capability::Combat, 95),
+                StockGadget::new("Mothership sensors", Capability::Surveillance, 90),
+                StockGadget::new("Teleporter", Capability::Escape, 85),
+            ],
+        };
+        for gadget in gadgets {
+            inventory.add(gadget.clone());
+        }
+        inventory
+    }
+}
+
+/// Theme of the names and aliases of randomly generated villains.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Theme {
+    Science,
+    Crime,
+    Cosmic,
+}
+
+/// First names and last names sharing their initial, so generated names are alliterative.
+type NameGroup = (&'static [&'static str], &'static [&'static str]);
+
+impl Theme {
+    pub fn archetype(&self) -> Archetype {
+        match self {
+            Theme::Science => Archetype::MadScientist,
+            Theme::Crime => Archetype::CrimeLord,
+            Theme::Cosmic => Archetype::AlienOverlord,
+        }
+    }
+
+    fn name_groups(&self) -> &'static [NameGroup] {
+        match self {
+            Theme::Science => &[
+                (&["Viktor", "Vera"], &["Vex", "Voltaire"]),
+                (&["Otto", "Olga"], &["Oppenheim", "Ozmark"]),
+                (&["Nikola", "Nadia"], &["Nox", "Neumann"]),
+            ],
+            Theme::Crime => &[
+                (&["Carlo", "Carmela"], &["Corleone", "Castellano"]),
+                (&["Max", "Mona"], &["Malone", "Moretti"]),
+                (&["Sal", "Sofia"], &["Santoro", "Sterling"]),
+            ],
+            Theme::Cosmic => &[
+                (&["Zorg", "Zyra"], &["Zenith", "Zarkon"]),
+                (&["Krell", "Kyra"], &["Kronos", "Kaal"]),
+                (&["Vorn", "Vexa"], &["Voidborn", "Valkor"]),
+            ],
+        }
+    }
+
+    fn titles(&self) -> &'static [&'static str] {
+        match self {
+            Theme::Science => &["Doctor", "Professor", "Baron"],
+            Theme::Crime => &["Boss", "Madame", "Don"],
+            Theme::Cosmic => &["Emperor", "Overlord", "Empress"],
+        }
+    }
+
+    fn concepts(&self) -> &'static [&'static str] {
+        match self {
+            Theme::Science => &["Entropy", "Fission", "Catalyst", "Paradox"],
+            Theme::Crime => &["Ledger", "Velvet", "Racket", "Stiletto"],
+            Theme::Cosmic => &["Nebula", "Void", "Quasar", "Eclipse"],
+        }
+    }
+
+    /// Range of the starting funds and infamy, in line with the archetype's preset.
+    fn means(&self) -> (core::ops::Range<u64>, core::ops::Range<u32>) {
+        match self {
+            Theme::Science => (1_000..10_000, 10..40),
+            Theme::Crime => (50_000..200_000, 40..80),
+            Theme::Cosmic => (20_000..100_000, 60..100),
+        }
+    }
+}
+
+fn pick<R: Rng + ?Sized, T: Copy>(rng: &mut R, pool: &[T]) -> T {
+    pool[rng.random_range(0..pool.len())]
+}
+
+/// Simple gadget good at a single thing, used by the presets.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StockGadget {
+    pub name: &'static str,
+    pub capability: Capability,
+    pub rating: u8,
+}
+
+impl StockGadget {
+    pub const fn new(name: &'static str, capability: Capability, rating: u8) -> StockGadget {
+        Self {
+            name,
+            capability,
+            rating,
+        }
+    }
+}
+
+impl Gadget for StockGadget {
+    fn do_stuff(&self) {}
+
+    fn rating(&self, capability: Capability) -> u8 {
+        if capability == self.capability {
+            self.rating
+        } else {
+            0
+        }
+    }
+}
+
+impl SuperVillain<'static> {
+    pub fn mad_scientist() -> SuperVillain<'static> {
+        Self::from_preset(Archetype::MadScientist, "Viktor", "Krank", 5_000, 20)
+    }
+
+    pub fn crime_lord() -> SuperVillain<'static> {
+        Self::from_preset(Archetype::CrimeLord, "Don", "Malvagio", 100_000, 60)
+    }
+
+    pub fn alien_overlord() -> SuperVillain<'static> {
+        Self::from_preset(Archetype::AlienOverlord, "Zorg", "Xenar", 50_000, 80)
+    }
+
+    /// Villain with an alliterative name and an alias in the theme, like `"Doctor Entropy"`,
+    /// and the archetype, gadgets and means that go with it.
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, theme: Theme) -> SuperVillain<'static> {
+        let (first_names, last_names) = pick(rng, theme.name_groups());
+        let first_name = pick(rng, first_names);
+        let last_name = pick(rng, last_names);
+        let (funds, infamy) = theme.means();
+        let funds = rng.random_range(funds);
+        let infamy = rng.random_range(infamy);
+        let alias = format!(
+            "{} {}",
+            pick(rng, theme.titles()),
+            pick(rng, theme.concepts())
+        );
+        SuperVillain {
+            alias: Some(alias),
+            ..Self::from_preset(theme.archetype(), first_name, last_name, funds, infamy)
+        }
+    }
+
+    fn from_preset(
+        archetype: Archetype,
+        first_name: &str,
+        last_name: &str,
+        funds: u64,
+        infamy: u32,
+    ) -> SuperVillain<'static> {
+        SuperVillain {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            treasury: Treasury::new(funds),
+            infamy: Infamy::new(infamy),
+            gadgets: archetype.default_gadgets(),
+            archetype: Some(archetype),
+            ..Default::default()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn presets_have_their_archetype_and_gadgets() {
+        for (villain, archetype) in [
+            (SuperVillain::mad_scientist(), Archetype::MadScientist),
+            (SuperVillain::crime_lord(), Archetype::CrimeLord),
+            (SuperVillain::alien_overlord(), Archetype::AlienOverlord),
+        ] {
+            assert_eq!(villain.archetype, Some(archetype));
+            assert!(!villain.gadgets.is_empty());
+            assert!(villain.treasury.balance() > 0);
+        }
+    }
+
+    #[test]
+    fn mad_scientist_can_detect_targets() {
+        let villain = SuperVillain::mad_scientist();
+        assert!(
+            villain
+                .gadgets
+                .best_for(Capability::TargetDetection)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn preset_monologue_uses_archetype_style() {
+        let villain = SuperVillain::mad_scientist();
+        let speech = villain.deliver_monologue(test_common::HERO_NAME);
+        assert!(speech.ends_with("for SCIENCE!"));
+    }
+
+    #[test]
+    fn random_villains_are_alliterative_and_themed() {
+        let mut rng = StdRng::seed_from_u64(11);
+        for theme in [Theme::Science, Theme::Crime, Theme::Cosmic] {
+            let villain = SuperVillain::random(&mut rng, theme);
+            assert_eq!(
+                villain.first_name.chars().next(),
+                villain.last_name.chars().next()
+            );
+            let Some(alias) = villain.alias else {
+                panic!("Random villain without alias");
+            };
+            assert!(theme.titles().iter().any(|title| alias.starts_with(title)));
+            assert_eq!(villain.archetype, Some(theme.archetype()));
+            assert!(!villain.gadgets.is_empty());
+        }
+    }
+
+    #[test]
+    fn preset_prefers_archetype_cipher() {
+        let villain = SuperVillain::crime_lord();
+        assert_eq!(villain.preferred_cipher(), Some("one-time-pad"));
+    }
+}
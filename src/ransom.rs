@@ -0,0 +1,214 @@
+//! Module for hostages, ransom demands and the negotiations around them.
+#![allow(dead_code)]
+
+#[cfg(test)]
+use mockall::automock;
+
+use crate::infamy::Infamy;
+use crate::treasury::Treasury;
+
+/// Infamy lost every time a negotiation fails.
+pub const FAILED_NEGOTIATION_PENALTY: u32 = 10;
+
+/// Somebody held until the ransom is paid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hostage {
+    pub name: String,
+    pub value: u64,
+}
+
+/// Answer of the other party to a ransom demand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Response {
+    Accept,
+    CounterOffer(u64),
+    Refuse,
+}
+
+/// The party paying (or not) the ransom.
+#[cfg_attr(test, automock)]
+pub trait Negotiator {
+    fn respond(&mut self, hostage: &Hostage, demand: u64, round: u32) -> Response;
+}
+
+/// One exchange of the negotiation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Round {
+    pub number: u32,
+    pub demand: u64,
+    pub response: Response,
+}
+
+/// How a negotiation ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NegotiationOutcome {
+    Paid { amount: u64 },
+    Refused,
+    DeadlinePassed,
+}
+
+/// Ransom negotiation for a hostage.
+///
+/// The villain starts asking for `demand` and, on counter-offers, lowers it halfway towards the
+/// offer, but never below `minimum`. After `deadline` rounds without agreement, the negotiation
+/// fails.
+#[derive(Debug, Clone)]
+pub struct Negotiation {
+    hostage: Hostage,
+    demand: u64,
+    minimum: u64,
+    deadline: u32,
+    rounds: Vec<Round>,
+}
+
+impl Negotiation {
+    pub fn new(hostage: Hostage, demand: u64, minimum: u64, deadline: u32) -> Negotiation {
+        Self {
+            hostage,
+            demand,
+            minimum: minimum.min(demand),
+            deadline,
+            rounds: vec![],
+        }
+    }
+
+    pub fn hostage(&self) -> &Hostage {
+        &self.hostage
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    /// Negotiates until there is an agreement, a refusal or the deadline passes. Payments go to
+    /// the treasury and failures cost infamy.
+    pub fn run<N: Negotiator>(
+        &mut self,
+        negotiator: &mut N,
+        treasury: &mut Treasury,
+        infamy: &mut Infamy,
+    ) -> NegotiationOutcome {
+        let outcome = self.negotiate(negotiator);
+        match outcome {
+            NegotiationOutcome::Paid { amount } => treasury.deposit(amount),
+            NegotiationOutcome::Refused | NegotiationOutcome::DeadlinePassed => {
+                infamy.lose(FAILED_NEGOTIATION_PENALTY)
+            }
+        }
+        outcome
+    }
+
+    fn negotiate<N: Negotiator>(&mut self, negotiator: &mut N) -> NegotiationOutcome {
+        for number in 1..=self.deadline {
+            let response = negotiator.respond(&self.hostage, self.demand, number);
+            self.rounds.push(Round {
+                number,
+                demand: self.demand,
+                response,
+            });
+            match response {
+                Response::Accept => {
+                    return NegotiationOutcome::Paid {
+                        amount: self.demand,
+                    };
+                }
+                Response::CounterOffer(offer) if offer >= self.minimum => {
+                    return NegotiationOutcome::Paid { amount: offer };
+                }
+                Response::CounterOffer(offer) => {
+                    self.demand =
+                        (self.demand - (self.demand.saturating_sub(offer) / 2)).max(self.minimum);
+                }
+                Response::Refuse => return NegotiationOutcome::Refused,
+            }
+        }
+        NegotiationOutcome::DeadlinePassed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockall::Sequence;
+
+    #[test]
+    fn accepted_demand_is_paid_into_treasury() {
+        let mut negotiator = MockNegotiator::new();
+        negotiator.expect_respond().return_const(Response::Accept);
+        let (mut treasury, mut infamy) = (Treasury::default(), Infamy::new(50));
+
+        let outcome = negotiation().run(&mut negotiator, &mut treasury, &mut infamy);
+
+        assert_eq!(outcome, NegotiationOutcome::Paid { amount: 1000 });
+        assert_eq!(treasury.balance(), 1000);
+        assert_eq!(infamy.points(), 50);
+    }
+
+    #[test]
+    fn low_counter_offer_lowers_demand_until_acceptable() {
+        let mut sequence = Sequence::new();
+        let mut negotiator = MockNegotiator::new();
+        negotiator
+            .expect_respond()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(Response::CounterOffer(200));
+        negotiator
+            .expect_respond()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(Response::CounterOffer(600));
+        let mut treasury = Treasury::default();
+        let mut negotiation = negotiation();
+
+        let outcome = negotiation.run(&mut negotiator, &mut treasury, &mut Infamy::default());
+
+        assert_eq!(outcome, NegotiationOutcome::Paid { amount: 600 });
+        assert_eq!(negotiation.rounds()[1].demand, 600);
+        assert_eq!(treasury.balance(), 600);
+    }
+
+    #[test]
+    fn refusal_costs_infamy() {
+        let mut negotiator = MockNegotiator::new();
+        negotiator.expect_respond().return_const(Response::Refuse);
+        let (mut treasury, mut infamy) = (Treasury::default(), Infamy::new(50));
+
+        let outcome = negotiation().run(&mut negotiator, &mut treasury, &mut infamy);
+
+        assert_eq!(outcome, NegotiationOutcome::Refused);
+        assert_eq!(treasury.balance(), 0);
+        assert_eq!(infamy.points(), 50 - FAILED_NEGOTIATION_PENALTY);
+    }
+
+    #[test]
+    fn deadline_passes_after_last_round() {
+        let mut negotiator = MockNegotiator::new();
+        negotiator
+            .expect_respond()
+            .times(3)
+            .return_const(Response::CounterOffer(1));
+        let mut negotiation = negotiation();
+
+        let outcome = negotiation.run(
+            &mut negotiator,
+            &mut Treasury::default(),
+            &mut Infamy::default(),
+        );
+
+        assert_eq!(outcome, NegotiationOutcome::DeadlinePassed);
+        assert_eq!(negotiation.rounds().len(), 3);
+    }
+
+    fn negotiation() -> Negotiation {
+        Negotiation::new(
+            Hostage {
+                name: String::from("Lois Lane"),
+                value: 5000,
+            },
+            1000,
+            500,
+            3,
+        )
+    }
+}
@@ -0,0 +1,75 @@
+//! Module for secrets that must never show up in logs, like keys and plans.
+#![allow(dead_code)]
+
+use alloc::string::String;
+use core::fmt;
+
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
+
+/// What a redacted secret prints as.
+pub const REDACTED: &str = "█████";
+
+/// Secret that prints as [`REDACTED`] with both `Debug` and `Display`, so it can't be logged by
+/// accident. Reading it takes an explicit [`Redacted::expose_secret`]. It's serialized as is,
+/// for storage and the wire.
+#[derive(Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(transparent))]
+pub struct Redacted<T>(T);
+
+impl<T> Redacted<T> {
+    pub fn new(secret: T) -> Redacted<T> {
+        Self(secret)
+    }
+
+    pub fn expose_secret(&self) -> &T {
+        &self.0
+    }
+
+    pub fn expose_secret_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+
+    pub fn into_secret(self) -> T {
+        self.0
+    }
+}
+
+impl<T> From<T> for Redacted<T> {
+    fn from(secret: T) -> Self {
+        Self(secret)
+    }
+}
+
+impl From<&str> for Redacted<String> {
+    fn from(secret: &str) -> Self {
+        Self(secret.into())
+    }
+}
+
+impl<T> fmt::Debug for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl<T> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::format;
+
+    #[test]
+    fn secret_only_shows_when_exposed() {
+        let key = Redacted::<String>::from("Kryptonite");
+        assert_eq!(format!("{key:?} {key}"), "█████ █████");
+        assert_eq!(key.expose_secret(), "Kryptonite");
+    }
+}
@@ -0,0 +1,149 @@
+//! Module to look villains, sidekicks and henchmen up by their identifiers.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+
+use crate::henchman::Henchman;
+use crate::id::HenchmanId;
+use crate::id::SidekickId;
+use crate::id::VillainId;
+use crate::sidekick::Sidekick;
+use crate::supervillain::SuperVillain;
+
+/// Owns registered entities and finds them by id. Each registry is independent; there is no
+/// global state.
+#[derive(Default)]
+pub struct Registry<'a> {
+    villains: HashMap<VillainId, SuperVillain<'a>>,
+    sidekicks: HashMap<SidekickId, Sidekick<'a>>,
+    henchmen: HashMap<HenchmanId, Box<dyn Henchman + 'a>>,
+}
+
+impl<'a> Registry<'a> {
+    pub fn new() -> Registry<'a> {
+        Self::default()
+    }
+
+    /// Registers the villain under its own id, replacing any villain with the same id.
+    pub fn add_villain(&mut self, villain: SuperVillain<'a>) -> VillainId {
+        let id = villain.id;
+        self.villains.insert(id, villain);
+        id
+    }
+
+    pub fn villain(&self, id: VillainId) -> Option<&SuperVillain<'a>> {
+        self.villains.get(&id)
+    }
+
+    pub fn villain_mut(&mut self, id: VillainId) -> Option<&mut SuperVillain<'a>> {
+        self.villains.get_mut(&id)
+    }
+
+    pub fn remove_villain(&mut self, id: VillainId) -> Option<SuperVillain<'a>> {
+        self.villains.remove(&id)
+    }
+
+    /// Registers the sidekick under its own id, replacing any sidekick with the same id.
+    pub fn add_sidekick(&mut self, sidekick: Sidekick<'a>) -> SidekickId {
+        let id = sidekick.id();
+        self.sidekicks.insert(id, sidekick);
+        id
+    }
+
+    pub fn sidekick(&self, id: SidekickId) -> Option<&Sidekick<'a>> {
+        self.sidekicks.get(&id)
+    }
+
+    pub fn sidekick_mut(&mut self, id: SidekickId) -> Option<&mut Sidekick<'a>> {
+        self.sidekicks.get_mut(&id)
+    }
+
+    pub fn remove_sidekick(&mut self, id: SidekickId) -> Option<Sidekick<'a>> {
+        self.sidekicks.remove(&id)
+    }
+
+    /// Registers the henchman under a new id.
+    pub fn add_henchman<H: Henchman + 'a>(&mut self, henchman: H) -> HenchmanId {
+        let id = HenchmanId::next();
+        self.henchmen.insert(id, Box::new(henchman));
+        id
+    }
+
+    pub fn henchman(&self, id: HenchmanId) -> Option<&(dyn Henchman + 'a)> {
+        self.henchmen.get(&id).map(|henchman| henchman.as_ref())
+    }
+
+    pub fn henchman_mut(&mut self, id: HenchmanId) -> Option<&mut (dyn Henchman + 'a)> {
+        self.henchmen.get_mut(&id).map(|henchman| henchman.as_mut())
+    }
+
+    pub fn remove_henchman(&mut self, id: HenchmanId) -> Option<Box<dyn Henchman + 'a>> {
+        self.henchmen.remove(&id)
+    }
+
+    /// Number of registered villains, sidekicks and henchmen.
+    pub fn len(&self) -> usize {
+        self.villains.len() + self.sidekicks.len() + self.henchmen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use crate::test_common;
+    use assertables::assert_none;
+
+    fn villain(first_name: &str, last_name: &str) -> SuperVillain<'static> {
+        SuperVillain {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn villains_with_same_name_have_different_ids() {
+        let mut registry = Registry::new();
+        let first = registry.add_villain(villain(
+            test_common::PRIMARY_FIRST_NAME,
+            test_common::PRIMARY_LAST_NAME,
+        ));
+        let second = registry.add_villain(villain(
+            test_common::PRIMARY_FIRST_NAME,
+            test_common::PRIMARY_LAST_NAME,
+        ));
+        assert_ne!(first, second);
+        assert_eq!(registry.len(), 2);
+    }
+
+    #[test]
+    fn villains_are_found_by_id() {
+        let mut registry = Registry::new();
+        let id = registry.add_villain(villain(
+            test_common::SECONDARY_FIRST_NAME,
+            test_common::SECONDARY_LAST_NAME,
+        ));
+        let found = registry.villain(id).map(SuperVillain::full_name);
+        assert_eq!(found.as_deref(), Some(test_common::SECONDARY_FULL_NAME));
+        assert!(registry.remove_villain(id).is_some());
+        assert_none!(registry.villain(id));
+    }
+
+    #[test]
+    fn henchmen_get_an_id_when_registered() {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_fight_enemies().once().return_const(());
+        let mut registry = Registry::new();
+        let id = registry.add_henchman(henchman);
+        let Some(found) = registry.henchman(id) else {
+            panic!("Henchman should be registered");
+        };
+        found.fight_enemies();
+        assert!(registry.henchman(HenchmanId::next()).is_none());
+    }
+}
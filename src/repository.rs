@@ -0,0 +1,278 @@
+//! Module for storing and looking up rosters of villains.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use crate::SuperVillain;
+use crate::names::NameOrder;
+use crate::redacted::Redacted;
+use crate::supervillain::EvilError;
+use crate::supervillain::VillainStatus;
+
+const FIELD_SEPARATOR: char = '\t';
+
+/// Storage of villains, keyed by their full name.
+///
+/// Only the villain's own data is stored; sidekicks are runtime companions and aren't persisted.
+pub trait VillainRepository {
+    fn get(&self, full_name: &str) -> Result<Option<SuperVillain<'static>>, EvilError>;
+    fn put(&mut self, villain: &SuperVillain) -> Result<(), EvilError>;
+    fn list(&self) -> Result<Vec<String>, EvilError>;
+    fn delete(&mut self, full_name: &str) -> Result<bool, EvilError>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct VillainRecord {
+    first_name: String,
+    last_name: String,
+    shared_key: Redacted<String>,
+    status: VillainStatus,
+}
+
+impl VillainRecord {
+    fn from_villain(villain: &SuperVillain) -> VillainRecord {
+        Self {
+            first_name: villain.first_name.clone(),
+            last_name: villain.last_name.clone(),
+            shared_key: villain.shared_key.clone(),
+            status: villain.status.clone(),
+        }
+    }
+
+    fn to_villain(&self) -> SuperVillain<'static> {
+        SuperVillain {
+            first_name: self.first_name.clone(),
+            last_name: self.last_name.clone(),
+            shared_key: self.shared_key.clone(),
+            status: self.status.clone(),
+            ..Default::default()
+        }
+    }
+
+    /// Same as [`SuperVillain::full_name`], so villains are found under the name they go by.
+    fn full_name(&self) -> String {
+        NameOrder::GivenFirst.join(&self.first_name, &self.last_name)
+    }
+
+    fn to_line(&self) -> Result<String, EvilError> {
+        let status = self.status.to_string();
+        let fields = [
+            self.first_name.as_str(),
+            self.last_name.as_str(),
+            self.shared_key.expose_secret().as_str(),
+            status.as_str(),
+        ];
+        if fields
+            .iter()
+            .any(|field| field.contains([FIELD_SEPARATOR, '\n', '\r']))
+        {
+            return Err(EvilError::ParseError {
+                purpose: "villain_record".into(),
+                reason: "Field contains a separator".into(),
+            });
+        }
+        Ok(fields.join(&FIELD_SEPARATOR.to_string()))
+    }
+
+    fn from_line(line: &str) -> Result<VillainRecord, EvilError> {
+        let fields = line.split(FIELD_SEPARATOR).collect::<Vec<_>>();
+        let [first_name, last_name, shared_key, status] = fields[..] else {
+            return Err(EvilError::ParseError {
+                purpose: "villain_record".into(),
+                reason: "Wrong number of fields".into(),
+            });
+        };
+        let status = status.parse::<VillainStatus>()?;
+        Ok(Self {
+            first_name: first_name.into(),
+            last_name: last_name.into(),
+            shared_key: shared_key.into(),
+            status,
+        })
+    }
+}
+
+/// Repository that keeps the villains in memory.
+#[derive(Debug, Default)]
+pub struct InMemoryVillainRepository {
+    villains: BTreeMap<String, VillainRecord>,
+}
+
+impl InMemoryVillainRepository {
+    pub fn new() -> InMemoryVillainRepository {
+        Self::default()
+    }
+}
+
+impl VillainRepository for InMemoryVillainRepository {
+    fn get(&self, full_name: &str) -> Result<Option<SuperVillain<'static>>, EvilError> {
+        Ok(self.villains.get(full_name).map(VillainRecord::to_villain))
+    }
+
+    fn put(&mut self, villain: &SuperVillain) -> Result<(), EvilError> {
+        let record = VillainRecord::from_villain(villain);
+        self.villains.insert(record.full_name(), record);
+        Ok(())
+    }
+
+    fn list(&self) -> Result<Vec<String>, EvilError> {
+        Ok(self.villains.keys().cloned().collect())
+    }
+
+    fn delete(&mut self, full_name: &str) -> Result<bool, EvilError> {
+        Ok(self.villains.remove(full_name).is_some())
+    }
+}
+
+/// Repository that keeps the villains in a file, one villain per line.
+#[derive(Debug)]
+pub struct FileVillainRepository {
+    path: PathBuf,
+}
+
+impl FileVillainRepository {
+    pub fn new<P: Into<PathBuf>>(path: P) -> FileVillainRepository {
+        Self { path: path.into() }
+    }
+
+    fn load(&self) -> Result<BTreeMap<String, VillainRecord>, EvilError> {
+        let content = match fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(error) if error.kind() == ErrorKind::NotFound => String::new(),
+            Err(error) => {
+                return Err(EvilError::StorageError {
+                    reason: error.to_string(),
+                });
+            }
+        };
+        content
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| VillainRecord::from_line(line).map(|record| (record.full_name(), record)))
+            .collect()
+    }
+
+    fn save(&self, villains: &BTreeMap<String, VillainRecord>) -> Result<(), EvilError> {
+        let mut content = String::new();
+        for record in villains.values() {
+            content.push_str(&record.to_line()?);
+            content.push('\n');
+        }
+        fs::write(&self.path, content).map_err(|error| EvilError::StorageError {
+            reason: error.to_string(),
+        })
+    }
+}
+
+impl VillainRepository for FileVillainRepository {
+    fn get(&self, full_name: &str) -> Result<Option<SuperVillain<'static>>, EvilError> {
+        Ok(self.load()?.get(full_name).map(VillainRecord::to_villain))
+    }
+
+    fn put(&mut self, villain: &SuperVillain) -> Result<(), EvilError> {
+        let mut villains = self.load()?;
+        let record = VillainRecord::from_villain(villain);
+        villains.insert(record.full_name(), record);
+        self.save(&villains)
+    }
+
+    fn list(&self) -> Result<Vec<String>, EvilError> {
+        Ok(self.load()?.into_keys().collect())
+    }
+
+    fn delete(&mut self, full_name: &str) -> Result<bool, EvilError> {
+        let mut villains = self.load()?;
+        let deleted = villains.remove(full_name).is_some();
+        if deleted {
+            self.save(&villains)?;
+        }
+        Ok(deleted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_none;
+
+    #[test]
+    fn in_memory_repository_stores_and_deletes_villains() -> Result<(), EvilError> {
+        let mut repository = InMemoryVillainRepository::new();
+        exercise_repository(&mut repository)
+    }
+
+    #[test]
+    fn file_repository_stores_and_deletes_villains() -> Result<(), EvilError> {
+        let path = std::env::temp_dir().join(format!("evil-roster-{}.tsv", std::process::id()));
+        let mut repository = FileVillainRepository::new(&path);
+        let result = exercise_repository(&mut repository);
+        let _ = fs::remove_file(&path);
+        result
+    }
+
+    #[test]
+    fn file_repository_without_file_is_empty() -> Result<(), EvilError> {
+        let repository = FileVillainRepository::new("tmp/does-not-exist.tsv");
+        assert!(repository.list()?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn record_line_round_trips_status() -> Result<(), EvilError> {
+        let record = VillainRecord {
+            first_name: test_common::PRIMARY_FIRST_NAME.into(),
+            last_name: test_common::PRIMARY_LAST_NAME.into(),
+            shared_key: Redacted::default(),
+            status: VillainStatus::Imprisoned {
+                facility: test_common::PRISON_FACILITY.into(),
+            },
+        };
+        assert_eq!(VillainRecord::from_line(&record.to_line()?)?, record);
+        Ok(())
+    }
+
+    fn exercise_repository<R: VillainRepository>(repository: &mut R) -> Result<(), EvilError> {
+        let lex = SuperVillain {
+            first_name: test_common::PRIMARY_FIRST_NAME.into(),
+            last_name: test_common::PRIMARY_LAST_NAME.into(),
+            status: VillainStatus::Captured,
+            ..Default::default()
+        };
+        let darth = SuperVillain::try_from(test_common::SECONDARY_FULL_NAME)?;
+        let sinestro = SuperVillain::try_from("Sinestro")?;
+        let riddler = SuperVillain::try_from("The Riddler")?;
+        for villain in [&lex, &darth, &sinestro, &riddler] {
+            repository.put(villain)?;
+        }
+
+        assert_eq!(
+            repository.list()?,
+            vec![
+                String::from(test_common::SECONDARY_FULL_NAME),
+                String::from(test_common::PRIMARY_FULL_NAME),
+                sinestro.full_name(),
+                riddler.full_name(),
+            ]
+        );
+        for villain in [&sinestro, &riddler] {
+            let Some(stored) = repository.get(&villain.full_name())? else {
+                panic!("Stored villain not found");
+            };
+            assert_eq!(stored.full_name(), villain.full_name());
+            assert!(repository.delete(&villain.full_name())?);
+        }
+        let Some(stored) = repository.get(test_common::PRIMARY_FULL_NAME)? else {
+            panic!("Stored villain not found");
+        };
+        assert_eq!(stored.status, VillainStatus::Captured);
+
+        assert!(repository.delete(test_common::PRIMARY_FULL_NAME)?);
+        assert!(!repository.delete(test_common::PRIMARY_FULL_NAME)?);
+        assert_none!(repository.get(test_common::PRIMARY_FULL_NAME)?);
+        Ok(())
+    }
+}
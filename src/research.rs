@@ -0,0 +1,298 @@
+//! Module for the evil lab, where research points earned from heists unlock better ciphers,
+//! gadget blueprints and weapons.
+#![allow(dead_code)]
+
+use alloc::collections::BTreeMap;
+use alloc::collections::BTreeSet;
+use alloc::collections::VecDeque;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use crate::error::EvilError;
+
+/// Coins of loot worth one research point.
+const COINS_PER_POINT: u64 = 100;
+
+/// What finishing a tech gives the villain.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Unlock {
+    /// A cipher, by id, the villain can use from now on.
+    CipherUpgrade(String),
+    /// A gadget blueprint, by name.
+    GadgetBlueprint(String),
+    /// Extra weapon damage, in percent.
+    WeaponBoost(u8),
+}
+
+/// Something the lab can research once its prerequisites are done.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tech {
+    pub name: String,
+    /// Research points it takes.
+    pub cost: u32,
+    pub prerequisites: Vec<String>,
+    pub unlock: Unlock,
+}
+
+impl Tech {
+    pub fn new(name: &str, cost: u32, unlock: Unlock) -> Tech {
+        Self {
+            name: name.into(),
+            cost,
+            prerequisites: Vec::new(),
+            unlock,
+        }
+    }
+
+    pub fn requires(mut self, prerequisite: &str) -> Tech {
+        self.prerequisites.push(prerequisite.into());
+        self
+    }
+}
+
+/// Every tech there is, by name.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TechTree {
+    techs: BTreeMap<String, Tech>,
+}
+
+impl TechTree {
+    pub fn new() -> TechTree {
+        Self::default()
+    }
+
+    /// Tree with the classic upgrades: ciphers, a couple of gadgets and ever stronger weapons.
+    pub fn standard() -> TechTree {
+        let mut tree = Self::new();
+        tree.add(Tech::new(
+            "Rotating alphabets",
+            20,
+            Unlock::CipherUpgrade("caesar".into()),
+        ));
+        tree.add(
+            Tech::new(
+                "One-time pads",
+                60,
+                Unlock::CipherUpgrade("one-time-pad".into()),
+            )
+            .requires("Rotating alphabets"),
+        );
+        tree.add(Tech::new(
+            "Miniaturization",
+            30,
+            Unlock::GadgetBlueprint("Shrink ray".into()),
+        ));
+        tree.add(
+            Tech::new(
+                "Cryogenics",
+                50,
+                Unlock::GadgetBlueprint("Freeze ray".into()),
+            )
+            .requires("Miniaturization"),
+        );
+        tree.add(Tech::new("Focused lasers", 40, Unlock::WeaponBoost(10)));
+        tree.add(
+            Tech::new("Orbital lenses", 100, Unlock::WeaponBoost(25))
+                .requires("Focused lasers")
+                .requires("Cryogenics"),
+        );
+        tree
+    }
+
+    /// Adds the tech, replacing any with the same name.
+    pub fn add(&mut self, tech: Tech) {
+        self.techs.insert(tech.name.clone(), tech);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Tech> {
+        self.techs.get(name)
+    }
+
+    /// Techs, sorted by name.
+    pub fn techs(&self) -> impl Iterator<Item = &Tech> {
+        self.techs.values()
+    }
+
+    /// Techs not researched yet whose prerequisites all are.
+    pub fn available<'t>(
+        &'t self,
+        researched: &'t BTreeSet<String>,
+    ) -> impl Iterator<Item = &'t Tech> {
+        self.techs().filter(|tech| {
+            !researched.contains(&tech.name)
+                && tech
+                    .prerequisites
+                    .iter()
+                    .all(|prerequisite| researched.contains(prerequisite))
+        })
+    }
+}
+
+/// Research points earned from a heist that brought in loot worth `coins`.
+pub fn points_for_loot(coins: u64) -> u32 {
+    u32::try_from(coins / COINS_PER_POINT).unwrap_or(u32::MAX)
+}
+
+/// Techs waiting to be researched, in order, and the points to research them with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ResearchQueue {
+    tree: TechTree,
+    queue: VecDeque<String>,
+    /// Points already put into the tech at the front of the queue.
+    progress: u32,
+    points: u32,
+    researched: BTreeSet<String>,
+    unlocked: Vec<Unlock>,
+}
+
+impl ResearchQueue {
+    pub fn new(tree: TechTree) -> ResearchQueue {
+        Self {
+            tree,
+            ..Default::default()
+        }
+    }
+
+    pub fn tree(&self) -> &TechTree {
+        &self.tree
+    }
+
+    /// Points earned but not spent yet.
+    pub fn points(&self) -> u32 {
+        self.points
+    }
+
+    pub fn queued(&self) -> impl Iterator<Item = &str> {
+        self.queue.iter().map(String::as_str)
+    }
+
+    pub fn is_researched(&self, name: &str) -> bool {
+        self.researched.contains(name)
+    }
+
+    /// Everything the finished techs gave, in the order they were finished.
+    pub fn unlocked(&self) -> &[Unlock] {
+        &self.unlocked
+    }
+
+    /// Extra weapon damage from every finished tech, in percent.
+    pub fn weapon_boost(&self) -> u32 {
+        self.unlocked
+            .iter()
+            .map(|unlock| match unlock {
+                Unlock::WeaponBoost(percentage) => u32::from(*percentage),
+                _ => 0,
+            })
+            .sum()
+    }
+
+    pub fn earn(&mut self, points: u32) {
+        self.points = self.points.saturating_add(points);
+    }
+
+    /// Earns the research points a heist with that loot is worth.
+    pub fn earn_from_heist(&mut self, loot: u64) {
+        self.earn(points_for_loot(loot));
+    }
+
+    /// Queues the tech. Its prerequisites must be researched or queued before it.
+    pub fn enqueue(&mut self, name: &str) -> Result<(), EvilError> {
+        let tech = self
+            .tree
+            .get(name)
+            .ok_or_else(|| EvilError::UnknownTech { name: name.into() })?;
+        if let Some(prerequisite) = tech.prerequisites.iter().find(|prerequisite| {
+            !self.researched.contains(*prerequisite) && !self.queue.contains(prerequisite)
+        }) {
+            return Err(EvilError::MissingPrerequisite {
+                tech: name.into(),
+                prerequisite: prerequisite.clone(),
+            });
+        }
+        if !self.researched.contains(name) && !self.queue.iter().any(|queued| queued == name) {
+            self.queue.push_back(name.into());
+        }
+        Ok(())
+    }
+
+    /// Runs the lab for a simulation turn: spends the points on the queue in order and returns
+    /// what the techs finished this turn unlocked.
+    pub fn process_turn(&mut self) -> Vec<Unlock> {
+        let mut finished = Vec::new();
+        while let Some(name) = self.queue.front() {
+            let Some(tech) = self.tree.get(name) else {
+                self.queue.pop_front();
+                continue;
+            };
+            let spent = tech.cost.saturating_sub(self.progress).min(self.points);
+            self.points -= spent;
+            self.progress += spent;
+            if self.progress < tech.cost {
+                break;
+            }
+            let unlock = tech.unlock.clone();
+            if let Some(name) = self.queue.pop_front() {
+                self.researched.insert(name);
+            }
+            self.progress = 0;
+            self.unlocked.push(unlock.clone());
+            finished.push(unlock);
+        }
+        finished
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use assertables::assert_matches;
+    use assertables::assert_ok;
+
+    #[test]
+    fn prerequisites_must_come_first() {
+        let mut lab = ResearchQueue::new(TechTree::standard());
+        assert_matches!(
+            lab.enqueue("One-time pads"),
+            Err(EvilError::MissingPrerequisite { .. })
+        );
+        assert_ok!(lab.enqueue("Rotating alphabets"));
+        assert_ok!(lab.enqueue("One-time pads"));
+        assert_matches!(
+            lab.enqueue("Death star"),
+            Err(EvilError::UnknownTech { .. })
+        );
+    }
+
+    #[test]
+    fn turns_spend_points_in_queue_order() -> Result<(), EvilError> {
+        let mut lab = ResearchQueue::new(TechTree::standard());
+        lab.enqueue("Focused lasers")?;
+        lab.enqueue("Miniaturization")?;
+        lab.earn_from_heist(5_000);
+
+        assert_eq!(lab.process_turn(), [Unlock::WeaponBoost(10)]);
+        assert_eq!(lab.points(), 0);
+        assert!(!lab.is_researched("Miniaturization"));
+
+        lab.earn(30);
+        assert_eq!(
+            lab.process_turn(),
+            [Unlock::GadgetBlueprint("Shrink ray".into())]
+        );
+        assert_eq!(lab.points(), 10);
+        assert_eq!(lab.weapon_boost(), 10);
+        Ok(())
+    }
+
+    #[test]
+    fn available_techs_follow_what_was_researched() {
+        let tree = TechTree::standard();
+        let mut researched = BTreeSet::new();
+        assert_eq!(tree.available(&researched).count(), 3);
+        researched.insert(String::from("Rotating alphabets"));
+        assert!(
+            tree.available(&researched)
+                .any(|tech| tech.name == "One-time pads")
+        );
+    }
+}
@@ -0,0 +1,288 @@
+//! Module to keep working with henchmen that fail now and then.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::thread;
+use std::time::Duration;
+
+use crate::gadget::Gadget;
+use crate::henchman::Borrower;
+use crate::henchman::Henchman;
+use crate::henchman::Progress;
+use crate::id::HenchmanId;
+use crate::id::LoanId;
+use crate::plan::StepAction;
+use crate::supervillain::EvilError;
+
+const DEFAULT_MAX_RETRIES: u32 = 3;
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(10);
+const DEFAULT_MULTIPLIER: u32 = 2;
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const CIRCUIT_OPEN_BLOCKER: &str = "Henchman unreliable";
+
+/// How many times to try again and how long to wait in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    /// What the wait is multiplied by after every retry.
+    pub multiplier: u32,
+}
+
+impl RetryPolicy {
+    /// Wait before the given retry, counting from 0.
+    pub fn backoff(&self, retry: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(self.multiplier.saturating_pow(retry))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+            multiplier: DEFAULT_MULTIPLIER,
+        }
+    }
+}
+
+/// Henchman that tries again when the work doesn't get done, and stops giving work to the
+/// wrapped henchman after too many failed tasks in a row.
+///
+/// An attempt fails when the henchman reports blockers right after it.
+#[derive(Debug)]
+pub struct Retry<H> {
+    inner: RefCell<H>,
+    policy: RetryPolicy,
+    failure_threshold: u32,
+    failures: Cell<u32>,
+}
+
+impl<H: Henchman> Retry<H> {
+    pub fn new(henchman: H) -> Retry<H> {
+        Self::with_policy(henchman, RetryPolicy::default())
+    }
+
+    pub fn with_policy(henchman: H, policy: RetryPolicy) -> Retry<H> {
+        Self {
+            inner: RefCell::new(henchman),
+            policy,
+            failure_threshold: DEFAULT_FAILURE_THRESHOLD,
+            failures: Cell::new(0),
+        }
+    }
+
+    /// Opens the circuit after this many failed tasks in a row.
+    pub fn with_failure_threshold(mut self, threshold: u32) -> Retry<H> {
+        self.failure_threshold = threshold.max(1);
+        self
+    }
+
+    pub fn into_inner(self) -> H {
+        self.inner.into_inner()
+    }
+
+    /// Whether the henchman failed too often to be given any more work.
+    pub fn is_circuit_open(&self) -> bool {
+        self.failures.get() >= self.failure_threshold
+    }
+
+    /// Gives the henchman another chance.
+    pub fn reset(&self) {
+        self.failures.set(0);
+    }
+
+    /// Has the henchman do the work, retrying with a blocking wait in between.
+    pub fn perform(&self, action: &StepAction) -> Result<(), EvilError> {
+        self.ensure_circuit_closed()?;
+        for retry in 0..=self.policy.max_retries {
+            if retry > 0 {
+                thread::sleep(self.policy.backoff(retry - 1));
+            }
+            if self.attempt(action) {
+                return Ok(());
+            }
+        }
+        self.fail()
+    }
+
+    /// Has the henchman do the work, retrying with an async wait in between.
+    #[cfg(feature = "async")]
+    pub async fn perform_async(&self, action: &StepAction) -> Result<(), EvilError> {
+        self.ensure_circuit_closed()?;
+        for retry in 0..=self.policy.max_retries {
+            if retry > 0 {
+                tokio::time::sleep(self.policy.backoff(retry - 1)).await;
+            }
+            if self.attempt(action) {
+                return Ok(());
+            }
+        }
+        self.fail()
+    }
+
+    fn attempt(&self, action: &StepAction) -> bool {
+        let mut henchman = self.inner.borrow_mut();
+        action.perform(&mut *henchman);
+        if henchman.report_progress().blockers.is_empty() {
+            self.failures.set(0);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn fail(&self) -> Result<(), EvilError> {
+        self.failures.set(self.failures.get().saturating_add(1));
+        Err(EvilError::HenchmanUnreliable {
+            failures: self.failures.get(),
+        })
+    }
+
+    fn ensure_circuit_closed(&self) -> Result<(), EvilError> {
+        if self.is_circuit_open() {
+            return Err(EvilError::HenchmanUnreliable {
+                failures: self.failures.get(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Failures are only visible through [`Retry::perform`]; through this trait the work is
+/// simply not done, and the progress report says so once the circuit is open.
+impl<H: Henchman> Henchman for Retry<H> {
+    fn build_secret_hq(&mut self, location: String) {
+        let _ = self.perform(&StepAction::BuildSecretHq(location));
+    }
+
+    fn demolish_secret_hq(&mut self, location: String) -> Result<(), EvilError> {
+        self.inner.get_mut().demolish_secret_hq(location)
+    }
+
+    fn do_hard_things(&self) {
+        let _ = self.perform(&StepAction::DoHardThings);
+    }
+
+    fn fight_enemies(&self) {
+        let _ = self.perform(&StepAction::FightEnemies);
+    }
+
+    fn report_progress(&self) -> Progress {
+        let mut progress = self.inner.borrow().report_progress();
+        if self.is_circuit_open() {
+            progress.blockers.push(CIRCUIT_OPEN_BLOCKER.into());
+        }
+        progress
+    }
+
+    fn is_on_strike(&self) -> bool {
+        self.inner.borrow().is_on_strike()
+    }
+}
+
+impl<'a, H: Borrower<'a>> Borrower<'a> for Retry<H> {
+    fn id(&self) -> HenchmanId {
+        self.inner.borrow().id()
+    }
+
+    fn borrow_gadget(
+        &mut self,
+        loan: LoanId,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), Box<dyn Gadget + 'a>> {
+        self.inner.get_mut().borrow_gadget(loan, gadget)
+    }
+
+    fn return_gadget(&mut self, loan: LoanId) -> Option<Box<dyn Gadget + 'a>> {
+        self.inner.get_mut().return_gadget(loan)
+    }
+
+    fn lose_loyalty(&mut self, points: u8) {
+        self.inner.get_mut().lose_loyalty(points);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::henchman::MockHenchman;
+    use assertables::assert_matches;
+    use assertables::assert_ok;
+
+    const NO_WAIT: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        initial_backoff: Duration::ZERO,
+        multiplier: 2,
+    };
+
+    fn flaky_henchman(failures: usize) -> MockHenchman {
+        let mut henchman = MockHenchman::new();
+        henchman.expect_do_hard_things().return_const(());
+        let mut reports = 0;
+        henchman.expect_report_progress().returning(move || {
+            reports += 1;
+            Progress {
+                blockers: if reports <= failures {
+                    vec![String::from("Tripped over a cable")]
+                } else {
+                    vec![]
+                },
+                ..Default::default()
+            }
+        });
+        henchman
+    }
+
+    #[test]
+    fn backoff_grows_exponentially() {
+        let policy = RetryPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_millis(10));
+        assert_eq!(policy.backoff(3), Duration::from_millis(80));
+    }
+
+    #[test]
+    fn retries_until_the_work_gets_done() {
+        let retry = Retry::with_policy(flaky_henchman(2), NO_WAIT);
+        assert_ok!(retry.perform(&StepAction::DoHardThings));
+        assert!(!retry.is_circuit_open());
+    }
+
+    #[test]
+    fn gives_up_after_max_retries() {
+        let retry = Retry::with_policy(flaky_henchman(3), NO_WAIT);
+        let result = retry.perform(&StepAction::DoHardThings);
+        assert_matches!(result, Err(EvilError::HenchmanUnreliable { failures: 1 }));
+    }
+
+    #[test]
+    fn circuit_opens_after_repeated_failures() {
+        let retry =
+            Retry::with_policy(flaky_henchman(usize::MAX), NO_WAIT).with_failure_threshold(2);
+        for _ in 0..2 {
+            assert!(retry.perform(&StepAction::DoHardThings).is_err());
+        }
+        assert!(retry.is_circuit_open());
+        assert_matches!(
+            retry.perform(&StepAction::DoHardThings),
+            Err(EvilError::HenchmanUnreliable { failures: 2 })
+        );
+        assert!(
+            retry
+                .report_progress()
+                .blockers
+                .contains(&String::from(CIRCUIT_OPEN_BLOCKER))
+        );
+        retry.reset();
+        assert!(!retry.is_circuit_open());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn async_retries_until_the_work_gets_done() {
+        let retry = Retry::with_policy(flaky_henchman(1), NO_WAIT);
+        assert_ok!(retry.perform_async(&StepAction::DoHardThings).await);
+    }
+}
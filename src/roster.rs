@@ -0,0 +1,380 @@
+//! Module to import rosters of villains in bulk from CSV or JSON files.
+#![allow(dead_code)]
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use serde_json::Value;
+
+use crate::SuperVillain;
+use crate::infamy::Infamy;
+use crate::supervillain::EvilError;
+use crate::supervillain::VillainStatus;
+use crate::treasury::Treasury;
+
+/// Fields a roster row can have. Only the names are required.
+pub const ROSTER_FIELDS: [&str; 6] = [
+    "first_name",
+    "last_name",
+    "shared_key",
+    "status",
+    "treasury",
+    "infamy",
+];
+
+/// File formats a roster can be in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RosterFormat {
+    /// Comma-separated values with a header row naming the fields.
+    Csv,
+    /// Array of objects with the fields as keys.
+    Json,
+}
+
+impl RosterFormat {
+    /// Guesses the format from the file extension.
+    pub fn from_path(path: &Path) -> Option<RosterFormat> {
+        let extension = path.extension()?.to_str()?;
+        if extension.eq_ignore_ascii_case("csv") {
+            Some(RosterFormat::Csv)
+        } else if extension.eq_ignore_ascii_case("json") {
+            Some(RosterFormat::Json)
+        } else {
+            None
+        }
+    }
+}
+
+/// Problem with a single row of a roster.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowError {
+    /// Line number in a CSV file, or position (from 1) in a JSON array.
+    pub line: usize,
+    /// Field that couldn't be used, if the problem is in a single field.
+    pub field: Option<String>,
+    pub reason: String,
+}
+
+/// Villains imported from a roster along with the rows that couldn't be imported.
+#[derive(Debug, Default)]
+pub struct RosterImportReport {
+    pub villains: Vec<SuperVillain<'static>>,
+    pub errors: Vec<RowError>,
+}
+
+impl RosterImportReport {
+    /// Whether every row was imported.
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+type Row = BTreeMap<String, String>;
+
+/// Imports the roster in the file, guessing its format from the extension.
+///
+/// Only problems with the whole file are errors; bad rows are reported and skipped.
+pub fn import(path: &Path) -> Result<RosterImportReport, EvilError> {
+    let format = RosterFormat::from_path(path).ok_or_else(|| roster_error("Unknown format"))?;
+    let contents = fs::read_to_string(path).map_err(|error| EvilError::StorageError {
+        reason: error.to_string(),
+    })?;
+    import_str(&contents, format)
+}
+
+/// Imports a roster that's already in memory.
+pub fn import_str(contents: &str, format: RosterFormat) -> Result<RosterImportReport, EvilError> {
+    let rows = match format {
+        RosterFormat::Csv => csv_rows(contents)?,
+        RosterFormat::Json => json_rows(contents)?,
+    };
+    let mut report = RosterImportReport::default();
+    for (line, row) in rows {
+        match row.and_then(|row| villain_from_row(&row)) {
+            Ok(villain) => report.villains.push(villain),
+            Err((field, reason)) => report.errors.push(RowError {
+                line,
+                field,
+                reason,
+            }),
+        }
+    }
+    Ok(report)
+}
+
+type RowResult<T> = Result<T, (Option<String>, String)>;
+
+fn villain_from_row(row: &Row) -> RowResult<SuperVillain<'static>> {
+    if let Some(field) = row
+        .keys()
+        .find(|field| !ROSTER_FIELDS.contains(&field.as_str()))
+    {
+        return Err((Some(field.clone()), String::from("Unknown field")));
+    }
+    let required = |field: &str| match row.get(field).map(|value| value.trim()) {
+        Some(value) if !value.is_empty() && !value.contains(char::is_whitespace) => {
+            Ok(String::from(value))
+        }
+        Some(value) if !value.is_empty() => {
+            Err((Some(field.into()), String::from("Not a single word")))
+        }
+        _ => Err((Some(field.into()), String::from("Missing"))),
+    };
+    let optional = |field: &str| {
+        row.get(field)
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    };
+    let number = |field: &str| {
+        optional(field)
+            .map(|value| {
+                value
+                    .parse::<u64>()
+                    .map_err(|_| (Some(String::from(field)), String::from("Not a number")))
+            })
+            .transpose()
+    };
+
+    let first_name = required("first_name")?;
+    let last_name = required("last_name")?;
+    let status = optional("status")
+        .map(|status| {
+            status
+                .parse::<VillainStatus>()
+                .map_err(|_| (Some(String::from("status")), String::from("Unknown status")))
+        })
+        .transpose()?
+        .unwrap_or_default();
+    let treasury = number("treasury")?.unwrap_or_default();
+    let infamy = number("infamy")?
+        .map(|points| {
+            u32::try_from(points)
+                .map_err(|_| (Some(String::from("infamy")), String::from("Too large")))
+        })
+        .transpose()?
+        .unwrap_or_default();
+    Ok(SuperVillain {
+        first_name,
+        last_name,
+        shared_key: optional("shared_key").unwrap_or_default().into(),
+        status,
+        treasury: Treasury::new(treasury),
+        infamy: Infamy::new(infamy),
+        ..Default::default()
+    })
+}
+
+fn csv_rows(contents: &str) -> Result<Vec<(usize, RowResult<Row>)>, EvilError> {
+    let mut lines = contents
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty());
+    let Some((_, header)) = lines.next() else {
+        return Err(roster_error("Missing header"));
+    };
+    let header = split_csv_line(header).map_err(|reason| roster_error(&reason))?;
+    let header = header
+        .iter()
+        .map(|field| field.trim().to_string())
+        .collect::<Vec<_>>();
+    Ok(lines
+        .map(|(line, text)| {
+            let row = split_csv_line(text)
+                .map_err(|reason| (None, reason))
+                .and_then(|fields| {
+                    if fields.len() != header.len() {
+                        return Err((
+                            None,
+                            format!("Expected {} fields, found {}", header.len(), fields.len()),
+                        ));
+                    }
+                    Ok(header.iter().cloned().zip(fields).collect())
+                });
+            (line, row)
+        })
+        .collect())
+}
+
+/// Splits a line on commas, honouring double quotes, where `""` stands for a quote.
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(String::from("Unclosed quote"));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+fn json_rows(contents: &str) -> Result<Vec<(usize, RowResult<Row>)>, EvilError> {
+    let value = serde_json::from_str::<Value>(contents)
+        .map_err(|error| roster_error(&error.to_string()))?;
+    let Some(entries) = value.as_array() else {
+        return Err(roster_error("Expected an array of villains"));
+    };
+    Ok(entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| (index + 1, json_row(entry)))
+        .collect())
+}
+
+fn json_row(entry: &Value) -> RowResult<Row> {
+    let Some(object) = entry.as_object() else {
+        return Err((None, String::from("Expected an object")));
+    };
+    object
+        .iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(field, value)| match value {
+            Value::String(text) => Ok((field.clone(), text.clone())),
+            Value::Number(number) => Ok((field.clone(), number.to_string())),
+            _ => Err((
+                Some(field.clone()),
+                String::from("Expected text or a number"),
+            )),
+        })
+        .collect()
+}
+
+fn roster_error(reason: &str) -> EvilError {
+    EvilError::ParseError {
+        purpose: "roster".into(),
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    const CSV_ROSTER: &str = "first_name,last_name,status,treasury
+Lex,Luthor,AtLarge,1000
+
+Darth,Vader,\"Imprisoned:Arkham Asylum\",
+Joker,,Escaped,10
+Hans,Gruber,Retired,0
+Dr,Evil,AtLarge,lots
+";
+
+    #[test]
+    fn csv_import_keeps_good_rows_and_reports_bad_ones() -> Result<(), EvilError> {
+        let report = import_str(CSV_ROSTER, RosterFormat::Csv)?;
+        let names = report
+            .villains
+            .iter()
+            .map(SuperVillain::full_name)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            [
+                test_common::PRIMARY_FULL_NAME,
+                test_common::SECONDARY_FULL_NAME
+            ]
+        );
+        assert_eq!(report.villains[0].treasury.balance(), 1000);
+        assert_eq!(
+            report.villains[1].status,
+            VillainStatus::Imprisoned {
+                facility: test_common::PRISON_FACILITY.into()
+            }
+        );
+
+        let problems = report
+            .errors
+            .iter()
+            .map(|error| (error.line, error.field.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            problems,
+            [
+                (5, Some("last_name")),
+                (6, Some("status")),
+                (7, Some("treasury"))
+            ]
+        );
+        assert!(!report.is_clean());
+        Ok(())
+    }
+
+    #[test]
+    fn csv_row_with_wrong_field_count_is_reported() -> Result<(), EvilError> {
+        let report = import_str(
+            "first_name,last_name\nLex,Luthor,extra\n",
+            RosterFormat::Csv,
+        )?;
+        assert_eq!(report.errors.len(), 1);
+        assert_eq!(report.errors[0].line, 2);
+        assert_eq!(report.errors[0].field, None);
+        Ok(())
+    }
+
+    #[test]
+    fn csv_without_header_fails() {
+        let result = import_str("\n\n", RosterFormat::Csv);
+        assert_matches!(result, Err(EvilError::ParseError { purpose, .. }) if purpose == "roster");
+    }
+
+    #[test]
+    fn json_import_keeps_good_entries_and_reports_bad_ones() -> Result<(), EvilError> {
+        let roster = r#"[
+            {"first_name": "Lex", "last_name": "Luthor", "infamy": 75},
+            {"first_name": "Darth", "last_name": ["Vader"]},
+            {"first_name": "Darth", "last_name": "Vader", "lightsaber": "red"}
+        ]"#;
+        let report = import_str(roster, RosterFormat::Json)?;
+        assert_eq!(report.villains.len(), 1);
+        assert_eq!(report.villains[0].infamy.points(), 75);
+        let problems = report
+            .errors
+            .iter()
+            .map(|error| (error.line, error.field.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(problems, [(2, Some("last_name")), (3, Some("lightsaber"))]);
+        Ok(())
+    }
+
+    #[test]
+    fn json_that_isnt_an_array_fails() {
+        let result = import_str(r#"{"first_name": "Lex"}"#, RosterFormat::Json);
+        assert_matches!(result, Err(EvilError::ParseError { reason, .. }) if reason == "Expected an array of villains");
+    }
+
+    #[test]
+    fn import_reads_file_in_format_of_extension() -> Result<(), EvilError> {
+        let path = std::env::temp_dir().join(format!("evil-roster-{}.csv", std::process::id()));
+        fs::write(&path, CSV_ROSTER).map_err(|error| EvilError::StorageError {
+            reason: error.to_string(),
+        })?;
+        let report = import(&path);
+        let _ = fs::remove_file(&path);
+        assert_eq!(report?.villains.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn format_is_guessed_from_extension() {
+        assert_eq!(
+            RosterFormat::from_path(Path::new("villains.JSON")),
+            Some(RosterFormat::Json)
+        );
+        assert_eq!(RosterFormat::from_path(Path::new("villains.txt")), None);
+    }
+}
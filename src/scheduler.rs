@@ -0,0 +1,258 @@
+//! Module to run plan steps at a later time, e.g. to arm the device at midnight.
+#![allow(dead_code)]
+
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::event::EventBus;
+use crate::event::EvilEvent;
+use crate::plan::StepId;
+
+/// Identifier of a scheduled step, used to cancel or reschedule it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ScheduleId(u64);
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    id: ScheduleId,
+    step: StepId,
+    at: Instant,
+}
+
+/// Keeps plan steps until they are due, then fires them and publishes
+/// [`EvilEvent::StepFired`] for each.
+///
+/// Time comes from a [`Clock`], so steps can be fired by hand with [`Scheduler::fire_due`] or
+/// waited for with tokio timers in [`Scheduler::run`].
+pub struct Scheduler<'a> {
+    clock: Box<dyn Clock + Send + 'a>,
+    entries: Vec<Entry>,
+    next_id: u64,
+    pub event_bus: EventBus<'a>,
+}
+
+impl<'a> Scheduler<'a> {
+    pub fn new() -> Scheduler<'a> {
+        Self::with_clock(SystemClock)
+    }
+
+    pub fn with_clock<C: Clock + Send + 'a>(clock: C) -> Scheduler<'a> {
+        Self {
+            clock: Box::new(clock),
+            entries: Vec::new(),
+            next_id: 1,
+            event_bus: EventBus::new(),
+        }
+    }
+
+    pub fn schedule_at(&mut self, step: StepId, at: Instant) -> ScheduleId {
+        let id = ScheduleId(self.next_id);
+        self.next_id += 1;
+        self.entries.push(Entry { id, step, at });
+        id
+    }
+
+    pub fn schedule_in(&mut self, step: StepId, delay: Duration) -> ScheduleId {
+        let at = self.clock.now() + delay;
+        self.schedule_at(step, at)
+    }
+
+    /// Removes the step from the schedule, returning whether it was still pending.
+    pub fn cancel(&mut self, id: ScheduleId) -> bool {
+        let pending = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != pending
+    }
+
+    /// Moves the step to another time, returning whether it was still pending.
+    pub fn reschedule(&mut self, id: ScheduleId, at: Instant) -> bool {
+        match self.entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => {
+                entry.at = at;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn pending(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// When the next step is due, if any.
+    pub fn next_due(&self) -> Option<Instant> {
+        self.entries.iter().map(|entry| entry.at).min()
+    }
+
+    /// Fires every step that is due, earliest first, and returns them.
+    pub fn fire_due(&mut self) -> Vec<StepId> {
+        let now = self.clock.now();
+        let (mut due, pending) = self
+            .entries
+            .iter()
+            .partition::<Vec<_>, _>(|entry| entry.at <= now);
+        self.entries = pending;
+        due.sort_by_key(|entry| (entry.at, entry.id));
+        due.iter()
+            .map(|entry| {
+                self.event_bus.publish(&EvilEvent::StepFired {
+                    schedule: entry.id,
+                    step: entry.step,
+                });
+                entry.step
+            })
+            .collect()
+    }
+
+    /// Sleeps until each step is due and fires it, until nothing is left.
+    #[cfg(feature = "async")]
+    pub async fn run(&mut self) -> Vec<StepId> {
+        let mut fired = Vec::new();
+        while let Some(at) = self.next_due() {
+            tokio::time::sleep(at.saturating_duration_since(self.clock.now())).await;
+            fired.extend(self.fire_due());
+        }
+        fired
+    }
+
+    /// Same as `run`, but blocks the thread while waiting, so it needs no async runtime.
+    pub fn run_blocking(&mut self) -> Vec<StepId> {
+        let mut fired = Vec::new();
+        while let Some(at) = self.next_due() {
+            std::thread::sleep(at.saturating_duration_since(self.clock.now()));
+            fired.extend(self.fire_due());
+        }
+        fired
+    }
+}
+
+impl Default for Scheduler<'_> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::MockEventListener;
+    use crate::plan::PlanGraph;
+    use crate::plan::PlanStep;
+    use crate::plan::StepAction;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+
+    #[derive(Clone)]
+    struct ManualClock(Arc<Mutex<Instant>>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            let mut now = self.0.lock().expect("Clock lock poisoned");
+            *now += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().expect("Clock lock poisoned")
+        }
+    }
+
+    fn steps() -> (StepId, StepId) {
+        let mut graph = PlanGraph::new();
+        let arm = graph.add_step(PlanStep::new(
+            "Arm the device",
+            Duration::from_secs(60),
+            StepAction::DoHardThings,
+        ));
+        let fight = graph.add_step(PlanStep::new(
+            "Fight the hero",
+            Duration::from_secs(60),
+            StepAction::FightEnemies,
+        ));
+        (arm, fight)
+    }
+
+    fn scheduler<'a>() -> (Scheduler<'a>, ManualClock) {
+        let clock = ManualClock(Arc::new(Mutex::new(Instant::now())));
+        (Scheduler::with_clock(clock.clone()), clock)
+    }
+
+    #[test]
+    fn steps_fire_when_due_in_time_order() {
+        let (mut scheduler, clock) = scheduler();
+        let (arm, fight) = steps();
+        scheduler.schedule_in(fight, Duration::from_secs(20));
+        scheduler.schedule_in(arm, Duration::from_secs(10));
+
+        assert!(scheduler.fire_due().is_empty());
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(scheduler.fire_due(), vec![arm, fight]);
+        assert_eq!(scheduler.pending(), 0);
+    }
+
+    #[test]
+    fn cancelled_steps_never_fire() {
+        let (mut scheduler, clock) = scheduler();
+        let (arm, _) = steps();
+        let id = scheduler.schedule_in(arm, Duration::from_secs(10));
+        assert!(scheduler.cancel(id));
+        assert!(!scheduler.cancel(id));
+        clock.advance(Duration::from_secs(30));
+        assert!(scheduler.fire_due().is_empty());
+    }
+
+    #[test]
+    fn rescheduled_steps_fire_at_new_time() {
+        let (mut scheduler, clock) = scheduler();
+        let (arm, _) = steps();
+        let id = scheduler.schedule_in(arm, Duration::from_secs(10));
+        assert!(scheduler.reschedule(id, clock.now() + Duration::from_secs(60)));
+        clock.advance(Duration::from_secs(30));
+        assert!(scheduler.fire_due().is_empty());
+        clock.advance(Duration::from_secs(30));
+        assert_eq!(scheduler.fire_due(), vec![arm]);
+    }
+
+    #[test]
+    fn firing_publishes_event() {
+        let (mut scheduler, _) = scheduler();
+        let (arm, _) = steps();
+        let id = scheduler.schedule_in(arm, Duration::ZERO);
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .withf(move |event| {
+                *event
+                    == EvilEvent::StepFired {
+                        schedule: id,
+                        step: arm,
+                    }
+            })
+            .once()
+            .return_const(());
+        scheduler.event_bus.subscribe(listener);
+        scheduler.fire_due();
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn run_waits_for_every_step() {
+        let mut scheduler = Scheduler::new();
+        let (arm, fight) = steps();
+        scheduler.schedule_in(arm, Duration::from_millis(20));
+        scheduler.schedule_in(fight, Duration::from_millis(10));
+        assert_eq!(scheduler.run().await, vec![fight, arm]);
+    }
+
+    #[test]
+    fn run_blocking_waits_for_every_step() {
+        let mut scheduler = Scheduler::new();
+        let (arm, fight) = steps();
+        scheduler.schedule_in(arm, Duration::from_millis(20));
+        scheduler.schedule_in(fight, Duration::from_millis(10));
+        assert_eq!(scheduler.run_blocking(), vec![fight, arm]);
+    }
+}
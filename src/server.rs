@@ -0,0 +1,326 @@
+//! Module exposing villain operations over HTTP with JSON bodies, to back multiplayer servers.
+//!
+//! | Method | Path                      | Operation                     |
+//! |--------|---------------------------|-------------------------------|
+//! | POST   | `/villains`               | Create a villain              |
+//! | GET    | `/villains/{id}`          | Get a villain                 |
+//! | POST   | `/villains/{id}/attack`   | Attack with the house weapon  |
+//! | GET    | `/villains/{id}/plan`     | Come up with a plan           |
+//! | GET    | `/villains/{id}/henchmen` | List the villain's henchmen   |
+//! | POST   | `/villains/{id}/henchmen` | Hire a henchman for a villain |
+//!
+//! Serve it with `axum::serve(listener, VillainServer::new().router())`.
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::Json;
+use axum::Router;
+use axum::extract::Path;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::IntoResponse;
+use axum::response::Response;
+use axum::routing::get;
+use axum::routing::post;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::sync::RwLock;
+
+use crate::device::DoomsdayDevice;
+use crate::environment::Environment;
+use crate::handle::VillainHandle;
+use crate::id::HenchmanId;
+use crate::supervillain::EvilError;
+use crate::supervillain::SuperVillain;
+
+/// Weapon every villain on the server attacks with.
+const HOUSE_WEAPON: &str = "House doomsday device";
+
+/// Body to create a villain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateVillain {
+    pub first_name: String,
+    pub last_name: String,
+}
+
+/// A villain, as seen by clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VillainDto {
+    pub id: String,
+    pub full_name: String,
+    pub status: String,
+    pub infamy: u32,
+    pub treasury: u64,
+}
+
+impl VillainDto {
+    fn of(villain: &SuperVillain) -> VillainDto {
+        Self {
+            id: villain.id.to_string(),
+            full_name: villain.full_name(),
+            status: villain.status.to_string(),
+            infamy: villain.total_infamy().points(),
+            treasury: villain.treasury.balance(),
+        }
+    }
+}
+
+/// Body to attack.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackRequest {
+    pub intense: bool,
+}
+
+/// How an attack went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttackDto {
+    pub shots_fired: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanDto {
+    pub plan: String,
+    pub quality: String,
+}
+
+/// Body to hire a henchman.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HireHenchman {
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HenchmanDto {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ErrorDto {
+    pub error: String,
+}
+
+/// Status picked by the root cause, so errors given context still map to the right one.
+impl IntoResponse for EvilError {
+    fn into_response(self) -> Response {
+        let status = match self.root_cause() {
+            EvilError::UnknownVillain { .. } => StatusCode::NOT_FOUND,
+            EvilError::ParseError { .. } | EvilError::InvalidParameter { .. } => {
+                StatusCode::BAD_REQUEST
+            }
+            EvilError::Incarcerated
+            | EvilError::WeaponOverheated { .. }
+            | EvilError::WeaponJammed => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        let body = ErrorDto {
+            error: self.to_string(),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+#[derive(Default)]
+struct Lobby {
+    villains: HashMap<String, VillainHandle<'static>>,
+    henchmen: HashMap<String, Vec<HenchmanDto>>,
+}
+
+/// Villains played on the server, with their henchmen. Clones share them.
+#[derive(Clone, Default)]
+pub struct VillainServer {
+    lobby: Arc<RwLock<Lobby>>,
+}
+
+impl VillainServer {
+    pub fn new() -> VillainServer {
+        Self::default()
+    }
+
+    /// Routes of every operation, ready to be served.
+    pub fn router(self) -> Router {
+        Router::new()
+            .route("/villains", post(create_villain))
+            .route("/villains/{id}", get(get_villain))
+            .route("/villains/{id}/attack", post(attack))
+            .route("/villains/{id}/plan", get(get_plan))
+            .route(
+                "/villains/{id}/henchmen",
+                get(list_henchmen).post(hire_henchman),
+            )
+            .with_state(self)
+    }
+
+    /// Handle to the villain, so the lobby isn't held while the villain acts.
+    async fn villain(&self, id: &str) -> Result<VillainHandle<'static>, EvilError> {
+        self.lobby
+            .read()
+            .await
+            .villains
+            .get(id)
+            .cloned()
+            .ok_or_else(|| EvilError::UnknownVillain { id: id.into() })
+    }
+}
+
+async fn create_villain(
+    State(server): State<VillainServer>,
+    Json(request): Json<CreateVillain>,
+) -> Result<(StatusCode, Json<VillainDto>), EvilError> {
+    let mut villain = SuperVillain::default();
+    villain.try_set_full_name(&format!("{} {}", request.first_name, request.last_name))?;
+    let dto = VillainDto::of(&villain);
+    let mut lobby = server.lobby.write().await;
+    lobby.villains.insert(dto.id.clone(), villain.into());
+    lobby.henchmen.insert(dto.id.clone(), Vec::new());
+    Ok((StatusCode::CREATED, Json(dto)))
+}
+
+async fn get_villain(
+    State(server): State<VillainServer>,
+    Path(id): Path<String>,
+) -> Result<Json<VillainDto>, EvilError> {
+    let villain = server.villain(&id).await?;
+    Ok(Json(villain.read(VillainDto::of).await))
+}
+
+async fn attack(
+    State(server): State<VillainServer>,
+    Path(id): Path<String>,
+    Json(request): Json<AttackRequest>,
+) -> Result<Json<AttackDto>, EvilError> {
+    let weapon = DoomsdayDevice::builder(HOUSE_WEAPON).build()?;
+    let report = server
+        .villain(&id)
+        .await?
+        .read(|villain| {
+            villain.attack_with_report(&weapon, request.intense, &Environment::default())
+        })
+        .await?;
+    Ok(Json(AttackDto {
+        shots_fired: report.shots_fired,
+    }))
+}
+
+/// Thinking neither blocks the worker thread nor keeps other requests from the villain.
+async fn get_plan(
+    State(server): State<VillainServer>,
+    Path(id): Path<String>,
+) -> Result<Json<PlanDto>, EvilError> {
+    let plan = server.villain(&id).await?.come_up_with_plan().await?;
+    Ok(Json(PlanDto {
+        plan: plan.scheme,
+        quality: plan.quality.to_string(),
+    }))
+}
+
+async fn list_henchmen(
+    State(server): State<VillainServer>,
+    Path(id): Path<String>,
+) -> Result<Json<Vec<HenchmanDto>>, EvilError> {
+    server
+        .lobby
+        .read()
+        .await
+        .henchmen
+        .get(&id)
+        .cloned()
+        .map(Json)
+        .ok_or(EvilError::UnknownVillain { id })
+}
+
+async fn hire_henchman(
+    State(server): State<VillainServer>,
+    Path(id): Path<String>,
+    Json(request): Json<HireHenchman>,
+) -> Result<(StatusCode, Json<HenchmanDto>), EvilError> {
+    if request.name.trim().is_empty() {
+        return Err(EvilError::InvalidParameter {
+            parameter: "name".into(),
+            reason: "Must not be empty".into(),
+        });
+    }
+    let mut lobby = server.lobby.write().await;
+    let Some(henchmen) = lobby.henchmen.get_mut(&id) else {
+        return Err(EvilError::UnknownVillain { id });
+    };
+    let henchman = HenchmanDto {
+        id: HenchmanId::next().to_string(),
+        name: request.name,
+    };
+    henchmen.push(henchman.clone());
+    Ok((StatusCode::CREATED, Json(henchman)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    async fn created(server: &VillainServer) -> Result<VillainDto, EvilError> {
+        let (status, Json(villain)) = create_villain(
+            State(server.clone()),
+            Json(CreateVillain {
+                first_name: test_common::PRIMARY_FIRST_NAME.into(),
+                last_name: test_common::PRIMARY_LAST_NAME.into(),
+            }),
+        )
+        .await?;
+        assert_eq!(status, StatusCode::CREATED);
+        Ok(villain)
+    }
+
+    #[tokio::test]
+    async fn villains_created_can_be_fetched_and_attack() -> Result<(), EvilError> {
+        let server = VillainServer::new();
+        let villain = created(&server).await?;
+        assert_eq!(villain.full_name, test_common::PRIMARY_FULL_NAME);
+
+        let Json(fetched) = get_villain(State(server.clone()), Path(villain.id.clone())).await?;
+        assert_eq!(fetched, villain);
+        let Json(report) = attack(
+            State(server.clone()),
+            Path(villain.id.clone()),
+            Json(AttackRequest { intense: false }),
+        )
+        .await?;
+        assert_eq!(report.shots_fired, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn errors_with_context_keep_their_status() {
+        let error = EvilError::UnknownVillain { id: "0".into() }.context("fetching the villain");
+        assert_eq!(error.into_response().status(), StatusCode::NOT_FOUND);
+        let error = EvilError::Incarcerated
+            .context("attacking")
+            .context("handling a request");
+        assert_eq!(error.into_response().status(), StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn henchmen_are_listed_per_villain() -> Result<(), EvilError> {
+        let server = VillainServer::new();
+        let villain = created(&server).await?;
+        let (status, Json(hired)) = hire_henchman(
+            State(server.clone()),
+            Path(villain.id.clone()),
+            Json(HireHenchman {
+                name: String::from("Kevin"),
+            }),
+        )
+        .await?;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(hired.name, "Kevin");
+
+        let Json(henchmen) = list_henchmen(State(server.clone()), Path(villain.id)).await?;
+        assert_eq!(henchmen.len(), 1);
+        assert_eq!(henchmen[0].name, "Kevin");
+        let missing = list_henchmen(State(server), Path(String::from("0"))).await;
+        assert_matches!(missing, Err(EvilError::UnknownVillain { .. }));
+        Ok(())
+    }
+}
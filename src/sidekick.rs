@@ -1,40 +1,1009 @@
 //! Module for Sidekicks and all the related functionality
 #![allow(dead_code)]
 
-#[cfg(test)]
+use std::cell::RefCell;
+use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::sync::Arc;
+use std::time::Duration;
+
+#[cfg(any(test, feature = "test-utils"))]
 use mockall::mock;
 
 use crate::Gadget;
+use crate::cipher::handshake;
+use crate::cipher::handshake::CipherSuite;
+use crate::cipher::handshake::Session;
+use crate::envelope::MessageEnvelope;
+use crate::henchman::Borrower;
+use crate::henchman::Goon;
+use crate::id::LoanId;
+pub use crate::id::SidekickId;
+use crate::lending::LendingLedger;
+use crate::lending::OVERDUE_LOYALTY_PENALTY;
+use crate::message::EvilMessage;
+use crate::supervillain::EvilError;
 
-/// Type that represents a sidekick.
-pub struct Sidekick<'a> {
+pub mod keystore;
+
+use keystore::KeyStore;
+
+/// Abilities of a sidekick, from 0 to 100.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SidekickStats {
+    pub loyalty: u8,
+    pub competence: u8,
+    pub dramatic_flair: u8,
+}
+
+impl Default for SidekickStats {
+    fn default() -> Self {
+        Self {
+            loyalty: 50,
+            competence: 50,
+            dramatic_flair: 50,
+        }
+    }
+}
+
+/// Minimum interview scores, from 0 to 100, a candidate needs to be hired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HiringThresholds {
+    pub loyalty: u8,
+    pub competence: u8,
+    pub dramatic_flair: u8,
+    pub overall: u8,
+}
+
+impl Default for HiringThresholds {
+    fn default() -> Self {
+        Self {
+            loyalty: 60,
+            competence: 40,
+            dramatic_flair: 20,
+            overall: 50,
+        }
+    }
+}
+
+/// How a candidate did in the interview, from 0 to 100. Loyalty weighs the most in the
+/// overall score, then competence, then dramatic flair.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterviewScore {
+    pub loyalty: u8,
+    pub competence: u8,
+    pub dramatic_flair: u8,
+    pub overall: u8,
+}
+
+impl InterviewScore {
+    /// Why the candidate falls short of the thresholds, if it does.
+    pub fn shortfall(&self, thresholds: &HiringThresholds) -> Option<String> {
+        [
+            ("Loyalty", self.loyalty, thresholds.loyalty),
+            ("Competence", self.competence, thresholds.competence),
+            (
+                "Dramatic flair",
+                self.dramatic_flair,
+                thresholds.dramatic_flair,
+            ),
+            ("Overall score", self.overall, thresholds.overall),
+        ]
+        .into_iter()
+        .find(|(_, score, minimum)| score < minimum)
+        .map(|(trait_name, score, minimum)| format!("{trait_name} {score} below {minimum}"))
+    }
+}
+
+/// Someone applying to be a sidekick, with the gadget it would bring along.
+pub struct SidekickCandidate<'a> {
+    pub name: String,
+    pub stats: SidekickStats,
     gadget: Box<dyn Gadget + 'a>,
 }
 
-impl<'a> Sidekick<'a> {
-    pub fn new<G: Gadget + 'a>(gadget: G) -> Sidekick<'a> {
+impl<'a> SidekickCandidate<'a> {
+    pub fn new<G: Gadget + 'a>(name: &str, stats: SidekickStats, gadget: G) -> Self {
         Self {
+            name: name.into(),
+            stats,
             gadget: Box::new(gadget),
         }
     }
 
+    pub fn interview(&self) -> InterviewScore {
+        let SidekickStats {
+            loyalty,
+            competence,
+            dramatic_flair,
+        } = self.stats;
+        let overall =
+            (u16::from(loyalty) * 5 + u16::from(competence) * 3 + u16::from(dramatic_flair) * 2)
+                / 10;
+        InterviewScore {
+            loyalty,
+            competence,
+            dramatic_flair,
+            overall: overall as u8,
+        }
+    }
+}
+
+impl fmt::Debug for SidekickCandidate<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SidekickCandidate")
+            .field("name", &self.name)
+            .field("stats", &self.stats)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A target the sidekick scouted.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TargetUpdate {
+    pub target: String,
+    /// Whether it's still weak enough to build an HQ in.
+    pub weak: bool,
+}
+
+/// Something a hero was overheard saying, and where, if the sidekick could tell.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct HeroChatter {
+    pub hero: String,
+    pub location: Option<String>,
+    pub note: String,
+}
+
+/// What the sidekick found out in the field since the last debrief.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SidekickReport {
+    pub target_updates: Vec<TargetUpdate>,
+    /// How keen the sidekick still is, from 0 to 100.
+    pub morale: u8,
+    pub chatter: Vec<HeroChatter>,
+}
+
+/// Gadget slot of a sidekick's loadout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slot {
+    Primary,
+    Backup,
+}
+
+#[derive(Clone, Default)]
+struct Loadout<'a> {
+    primary: Option<Box<dyn Gadget + 'a>>,
+    backup: Option<Box<dyn Gadget + 'a>>,
+}
+
+impl<'a> Loadout<'a> {
+    fn slot(&self, slot: Slot) -> &Option<Box<dyn Gadget + 'a>> {
+        match slot {
+            Slot::Primary => &self.primary,
+            Slot::Backup => &self.backup,
+        }
+    }
+
+    fn slot_mut(&mut self, slot: Slot) -> &mut Option<Box<dyn Gadget + 'a>> {
+        match slot {
+            Slot::Primary => &mut self.primary,
+            Slot::Backup => &mut self.backup,
+        }
+    }
+}
+
+/// Type that represents a sidekick.
+///
+/// Two sidekicks are equal only if they are the same sidekick (or clones of it). Messages told
+/// to the sidekick are kept, to decipher with the keys it has.
+#[derive(Clone)]
+pub struct Sidekick<'a> {
+    id: SidekickId,
+    name: String,
+    stats: SidekickStats,
+    loadout: Loadout<'a>,
+    ciphers: Vec<CipherSuite>,
+    findings: SidekickReport,
+    ledger: LendingLedger,
+    /// Last message told, shared with the sender rather than copied.
+    latest: RefCell<Option<Arc<[u8]>>>,
+    keys: KeyStore,
+    /// Session agreed on in the last handshake, if the sidekick had the key for its cipher.
+    session: RefCell<Option<Session>>,
+}
+
+impl<'a> Sidekick<'a> {
+    pub fn new<G: Gadget + 'a>(gadget: G) -> Sidekick<'a> {
+        Self::with_name("Unnamed", gadget)
+    }
+
+    pub fn with_name<G: Gadget + 'a>(name: &str, gadget: G) -> Sidekick<'a> {
+        let mut sidekick = Self {
+            id: SidekickId::next(),
+            name: name.into(),
+            stats: SidekickStats::default(),
+            loadout: Loadout::default(),
+            ciphers: Vec::new(),
+            findings: SidekickReport::default(),
+            ledger: LendingLedger::new(),
+            latest: RefCell::default(),
+            keys: KeyStore::new(),
+            session: RefCell::default(),
+        };
+        sidekick.equip(Slot::Primary, gadget);
+        sidekick
+    }
+
+    pub fn id(&self) -> SidekickId {
+        self.id
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn stats(&self) -> &SidekickStats {
+        &self.stats
+    }
+
+    pub fn stats_mut(&mut self) -> &mut SidekickStats {
+        &mut self.stats
+    }
+
+    /// Puts the gadget in the slot, returning the gadget that was there before.
+    pub fn equip<G: Gadget + 'a>(&mut self, slot: Slot, gadget: G) -> Option<Box<dyn Gadget + 'a>> {
+        self.equip_boxed(slot, Box::new(gadget))
+    }
+
+    pub fn equip_boxed(
+        &mut self,
+        slot: Slot,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Option<Box<dyn Gadget + 'a>> {
+        self.loadout.slot_mut(slot).replace(gadget)
+    }
+
+    pub fn unequip(&mut self, slot: Slot) -> Option<Box<dyn Gadget + 'a>> {
+        self.loadout.slot_mut(slot).take()
+    }
+
+    pub fn gadget(&self, slot: Slot) -> Option<&(dyn Gadget + 'a)> {
+        self.loadout.slot(slot).as_deref()
+    }
+
+    /// The gadget in use: the primary one, or the backup when the primary slot is empty.
+    pub fn active_gadget(&self) -> Option<&(dyn Gadget + 'a)> {
+        self.gadget(Slot::Primary).or(self.gadget(Slot::Backup))
+    }
+
+    /// Exchanges the primary and backup gadgets.
+    pub fn swap_slots(&mut self) {
+        std::mem::swap(&mut self.loadout.primary, &mut self.loadout.backup);
+    }
+
     pub fn agree(&self) -> bool {
         true
     }
 
-    pub fn get_weak_targets<G: Gadget>(&self, _gadget: &G) -> Vec<String> {
+    pub fn get_weak_targets<G: Gadget + ?Sized>(&self, _gadget: &G) -> Vec<String> {
         vec![]
     }
 
-    pub fn tell(&self, _ciphered_msg: &str) {}
+    /// Tells the sidekick something: plain text, a plan, a target or raw bytes. Only the last
+    /// thing told is kept.
+    pub fn tell<M: EvilMessage + ?Sized>(&self, message: &M) {
+        self.latest.replace(Some(message.shared_bytes()));
+    }
+
+    pub fn keys(&self) -> &KeyStore {
+        &self.keys
+    }
+
+    pub fn keys_mut(&mut self) -> &mut KeyStore {
+        &mut self.keys
+    }
+
+    /// Deciphers the last message told, an enveloped plan as sent by `tell_plans`, with the key
+    /// for its cipher. `None` if nothing was told yet.
+    pub fn decrypt_latest(&self) -> Result<Option<String>, EvilError> {
+        let latest = self.latest.borrow();
+        let Some(latest) = latest.as_deref() else {
+            return Ok(None);
+        };
+        let envelope = MessageEnvelope::from_wire(latest)?;
+        envelope.validate()?;
+        let session = self.session.borrow();
+        match session
+            .as_ref()
+            .filter(|session| session.suite.id() == envelope.cipher_id)
+        {
+            Some(session) => self.keys.open_in(&envelope, session),
+            None => self.keys.open(&envelope),
+        }
+        .map(Some)
+    }
+
+    /// Notes whether the target is still weak, for the next report.
+    pub fn scout(&mut self, target: &str, weak: bool) {
+        self.findings.target_updates.push(TargetUpdate {
+            target: target.into(),
+            weak,
+        });
+    }
+
+    /// Notes what a hero was overheard saying, for the next report.
+    pub fn intercept(&mut self, chatter: HeroChatter) {
+        self.findings.chatter.push(chatter);
+    }
+
+    /// Everything found out since the last debrief. Morale follows loyalty.
+    pub fn report(&self) -> SidekickReport {
+        SidekickReport {
+            morale: self.stats.loyalty,
+            ..self.findings.clone()
+        }
+    }
+
+    /// Forgets what was already reported.
+    pub fn clear_report(&mut self) {
+        self.findings = SidekickReport::default();
+    }
+
+    /// Adds a cipher the sidekick knows how to decipher.
+    pub fn support_cipher(&mut self, suite: CipherSuite) {
+        if !self.ciphers.contains(&suite) {
+            self.ciphers.push(suite);
+        }
+    }
+
+    pub fn supported_ciphers(&self) -> &[CipherSuite] {
+        &self.ciphers
+    }
+
+    /// Gadgets lent out and when they're due back.
+    pub fn ledger(&self) -> &LendingLedger {
+        &self.ledger
+    }
+
+    pub fn ledger_mut(&mut self) -> &mut LendingLedger {
+        &mut self.ledger
+    }
+
+    /// Lends the backup gadget, or the primary one if there's no backup, to the henchman for the
+    /// duration. Fails if there's nothing to lend or the henchman can't carry it, in which case
+    /// the gadget stays in its slot.
+    pub fn lend_gadget(
+        &mut self,
+        to: &mut dyn Borrower<'a>,
+        duration: Duration,
+    ) -> Result<LoanId, EvilError> {
+        let slot = if self.gadget(Slot::Backup).is_some() {
+            Slot::Backup
+        } else {
+            Slot::Primary
+        };
+        let gadget = self.unequip(slot).ok_or(EvilError::NothingToLend)?;
+        let loan = self.ledger.open(slot, to.id(), duration);
+        if let Err(gadget) = to.borrow_gadget(loan.id, gadget) {
+            self.ledger.close(loan.id);
+            self.equip_boxed(slot, gadget);
+            return Err(EvilError::LoanRefused);
+        }
+        Ok(loan.id)
+    }
+
+    /// Takes back the gadget lent under the loan, into the slot it was lent from, returning the
+    /// gadget that was there. Fails if the gadget wasn't lent to the henchman or it doesn't have
+    /// it, keeping the loan open.
+    pub fn take_back(
+        &mut self,
+        loan: LoanId,
+        from: &mut dyn Borrower<'a>,
+    ) -> Result<Option<Box<dyn Gadget + 'a>>, EvilError> {
+        let slot = self
+            .ledger
+            .loan(loan)
+            .filter(|lent| lent.borrower == from.id())
+            .ok_or(EvilError::LoanRefused)?
+            .slot;
+        let gadget = from.return_gadget(loan).ok_or(EvilError::LoanRefused)?;
+        self.ledger.close(loan);
+        Ok(self.equip_boxed(slot, gadget))
+    }
+
+    /// Takes back every overdue gadget lent to the henchman, who loses loyalty for each of them.
+    /// Returns the gadgets that were in the slots they go back to.
+    pub fn recall_overdue(&mut self, from: &mut dyn Borrower<'a>) -> Vec<Box<dyn Gadget + 'a>> {
+        let borrower = from.id();
+        let mut replaced = Vec::new();
+        for loan in self.ledger.overdue() {
+            if loan.borrower != borrower {
+                continue;
+            }
+            if let Ok(gadget) = self.take_back(loan.id, from) {
+                from.lose_loyalty(OVERDUE_LOYALTY_PENALTY);
+                replaced.extend(gadget);
+            }
+        }
+        replaced
+    }
+
+    /// Turns the sidekick into a plain henchman, who keeps its stats and gadgets, primary first.
+    pub fn demote_to_henchman(self) -> Goon<'a> {
+        let Loadout { primary, backup } = self.loadout;
+        Goon {
+            name: self.name,
+            stats: self.stats,
+            gadgets: primary.into_iter().chain(backup).collect(),
+            ..Goon::new("")
+        }
+    }
+
+    /// Answers a handshake offer with the villain's most preferred cipher the sidekick knows.
+    pub fn pick_cipher(&self, offered: &[CipherSuite]) -> Option<CipherSuite> {
+        handshake::pick(offered, &self.ciphers)
+    }
+
+    /// Answers a handshake offer like [`Sidekick::pick_cipher`], and derives the session key
+    /// from its own key for the picked cipher and the nonce sent with the offer. Plans told in
+    /// the session are then deciphered with it.
+    pub fn accept_handshake(&self, offered: &[CipherSuite], nonce: u64) -> Option<CipherSuite> {
+        let suite = self.pick_cipher(offered)?;
+        self.session.replace(self.keys.session(&suite, nonce));
+        Some(suite)
+    }
+
+    /// Decodes an enveloped message as sent by `tell_plans`, checking it's a valid envelope
+    /// sealed with the key, so it wasn't tampered with on the way.
+    pub fn open_envelope(&self, message: &str, key: &str) -> Result<MessageEnvelope, EvilError> {
+        let envelope = MessageEnvelope::from_json(message)?;
+        envelope.validate()?;
+        envelope.verify(key)?;
+        Ok(envelope)
+    }
 }
 
-#[cfg(test)]
+impl<'a> From<SidekickCandidate<'a>> for Sidekick<'a> {
+    fn from(candidate: SidekickCandidate<'a>) -> Self {
+        Self {
+            id: SidekickId::next(),
+            name: candidate.name,
+            stats: candidate.stats,
+            loadout: Loadout {
+                primary: Some(candidate.gadget),
+                backup: None,
+            },
+            ciphers: Vec::new(),
+            findings: SidekickReport::default(),
+            ledger: LendingLedger::new(),
+            latest: RefCell::default(),
+            keys: KeyStore::new(),
+            session: RefCell::default(),
+        }
+    }
+}
+
+impl<'a> From<Sidekick<'a>> for Goon<'a> {
+    fn from(sidekick: Sidekick<'a>) -> Self {
+        sidekick.demote_to_henchman()
+    }
+}
+
+/// Makes a sidekick out of a henchman, who keeps its stats. Its first two gadgets become the
+/// primary and backup ones; gadgets that don't fit in the loadout are handed back. Villains take
+/// it with [`appoint_sidekick`](crate::supervillain::SuperVillain::appoint_sidekick), which
+/// clears the calendar of the sidekick it replaces.
+pub fn promote_henchman_to_sidekick<'a>(
+    henchman: Goon<'a>,
+) -> (Sidekick<'a>, Vec<Box<dyn Gadget + 'a>>) {
+    let mut gadgets = henchman.gadgets.into_iter();
+    let sidekick = Sidekick {
+        id: SidekickId::next(),
+        name: henchman.name,
+        stats: henchman.stats,
+        loadout: Loadout {
+            primary: gadgets.next(),
+            backup: gadgets.next(),
+        },
+        ciphers: Vec::new(),
+        findings: SidekickReport::default(),
+        ledger: LendingLedger::new(),
+        latest: RefCell::default(),
+        keys: KeyStore::new(),
+        session: RefCell::default(),
+    };
+    (sidekick, gadgets.collect())
+}
+
+impl fmt::Debug for Sidekick<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Sidekick")
+            .field("id", &self.id)
+            .field("name", &self.name)
+            .field("stats", &self.stats)
+            .field("ciphers", &self.ciphers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for Sidekick<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Sidekick<'_> {}
+
+impl Hash for Sidekick<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
 mock! {
     #[derive(Debug)]
     pub Sidekick<'a> {
+        pub fn id(&self) -> SidekickId;
+        pub fn equip_boxed(
+            &mut self,
+            slot: Slot,
+            gadget: Box<dyn Gadget + 'a>,
+        ) -> Option<Box<dyn Gadget>>;
+        pub fn unequip(&mut self, slot: Slot) -> Option<Box<dyn Gadget>>;
+        pub fn swap_slots(&mut self);
         pub fn agree(&self) -> bool;
         pub fn get_weak_targets(&self, _gadget: &'a dyn Gadget) -> Vec<String>;
-        pub fn tell(&self, _ciphered_msg: &str);
+        pub fn tell(&self, message: &Arc<[u8]>);
+        pub fn report(&self) -> SidekickReport;
+        pub fn clear_report(&mut self);
+        pub fn pick_cipher(&self, offered: &[CipherSuite]) -> Option<CipherSuite>;
+        pub fn accept_handshake(&self, offered: &[CipherSuite], nonce: u64) -> Option<CipherSuite>;
+    }
+}
+
+#[cfg(test)]
+impl<'a> From<SidekickCandidate<'a>> for MockSidekick<'a> {
+    fn from(_candidate: SidekickCandidate<'a>) -> Self {
+        MockSidekick::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Instant;
+
+    use crate::Cipher;
+    use crate::cipher::morse::MorseCipher;
+    use crate::clock::Clock;
+    use crate::gadget::Capability;
+    use crate::id::HenchmanId;
+    use crate::test_common;
+    use assertables::assert_matches;
+    use assertables::assert_none;
+
+    #[derive(Clone)]
+    struct GadgetDummy;
+
+    impl Gadget for GadgetDummy {
+        fn do_stuff(&self) {}
+    }
+
+    #[derive(Clone)]
+    struct RatedGadget(u8);
+
+    impl Gadget for RatedGadget {
+        fn do_stuff(&self) {}
+
+        fn rating(&self, _capability: Capability) -> u8 {
+            self.0
+        }
+    }
+
+    struct Unarmed;
+
+    impl Borrower<'_> for Unarmed {
+        fn id(&self) -> HenchmanId {
+            HenchmanId::new(0)
+        }
+    }
+
+    #[derive(Clone)]
+    struct ManualClock(Arc<Mutex<Instant>>);
+
+    impl ManualClock {
+        fn advance(&self, by: Duration) {
+            let mut now = self.0.lock().expect("Clock lock poisoned");
+            *now += by;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> Instant {
+            *self.0.lock().expect("Clock lock poisoned")
+        }
+    }
+
+    #[test]
+    fn clone_keeps_identity() {
+        let sidekick = Sidekick::with_name("Igor", GadgetDummy);
+        let clone = sidekick.clone();
+        assert_eq!(clone.id(), sidekick.id());
+        assert_eq!(clone, sidekick);
+    }
+
+    #[test]
+    fn different_sidekicks_are_not_equal() {
+        let igor = Sidekick::with_name("Igor", GadgetDummy);
+        let other_igor = Sidekick::with_name("Igor", GadgetDummy);
+        assert_ne!(igor, other_igor);
+    }
+
+    #[test]
+    // Sidekicks hash by id only, which the inbox they tell through doesn't touch.
+    #[allow(clippy::mutable_key_type)]
+    fn sidekicks_can_be_hash_map_keys() {
+        let igor = Sidekick::with_name("Igor", GadgetDummy);
+        let mut salaries = HashMap::new();
+        salaries.insert(igor.clone(), 100);
+        assert_eq!(salaries.get(&igor), Some(&100));
+    }
+
+    #[test]
+    fn new_sidekick_has_gadget_in_primary_slot() {
+        let igor = Sidekick::new(RatedGadget(10));
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(10));
+        assert!(igor.gadget(Slot::Backup).is_none());
+    }
+
+    #[test]
+    fn equip_returns_replaced_gadget() {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        let Some(replaced) = igor.equip(Slot::Primary, RatedGadget(20)) else {
+            panic!("Unexpected: nothing replaced");
+        };
+        assert_eq!(replaced.rating(Capability::Combat), 10);
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(20));
+    }
+
+    #[test]
+    fn backup_is_active_when_primary_is_unequipped() {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        igor.equip(Slot::Backup, RatedGadget(20));
+        igor.unequip(Slot::Primary);
+        let active = igor
+            .active_gadget()
+            .map(|gadget| gadget.rating(Capability::Combat));
+        assert_eq!(active, Some(20));
+    }
+
+    #[test]
+    fn swap_slots_exchanges_gadgets() {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        igor.equip(Slot::Backup, RatedGadget(20));
+        igor.swap_slots();
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(20));
+        assert_eq!(rating_in(&igor, Slot::Backup), Some(10));
+    }
+
+    #[test]
+    fn open_envelope_decodes_valid_envelope() -> Result<(), EvilError> {
+        let igor = Sidekick::new(GadgetDummy);
+        let envelope = MessageEnvelope::new("Lex Luthor", "plus-wrap", String::from("+plan+"))
+            .seal(test_common::SHARED_KEY);
+        assert_eq!(
+            igor.open_envelope(&envelope.try_to_json()?, test_common::SHARED_KEY)?,
+            envelope
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn open_envelope_rejects_garbage() {
+        let igor = Sidekick::new(GadgetDummy);
+        assert!(
+            igor.open_envelope("+plan+", test_common::SHARED_KEY)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn open_envelope_rejects_tampered_payload() -> Result<(), EvilError> {
+        let igor = Sidekick::new(GadgetDummy);
+        let mut envelope = MessageEnvelope::new("Lex Luthor", "plus-wrap", String::from("+plan+"))
+            .seal(test_common::SHARED_KEY);
+        envelope.payload = "+surrender+".into();
+        let result = igor.open_envelope(&envelope.try_to_json()?, test_common::SHARED_KEY);
+        assert_matches!(result, Err(EvilError::IntegrityFailure));
+        Ok(())
+    }
+
+    #[test]
+    fn pick_cipher_prefers_offer_order_among_supported() {
+        let mut igor = Sidekick::new(GadgetDummy);
+        igor.support_cipher(CipherSuite::new("plus-wrap"));
+        igor.support_cipher(CipherSuite::new("caesar"));
+        igor.support_cipher(CipherSuite::new("caesar"));
+        assert_eq!(igor.supported_ciphers().len(), 2);
+
+        let offered = [CipherSuite::new("one-time-pad"), CipherSuite::new("caesar")];
+        assert_eq!(igor.pick_cipher(&offered), Some(CipherSuite::new("caesar")));
+        assert_none!(igor.pick_cipher(&offered[..1]));
+    }
+
+    #[test]
+    fn report_holds_findings_until_cleared() {
+        let mut igor = Sidekick::new(GadgetDummy);
+        igor.stats_mut().loyalty = 70;
+        igor.scout("Tampa", true);
+        igor.intercept(HeroChatter {
+            hero: String::from("Superman"),
+            location: None,
+            note: String::from("Lois is in town"),
+        });
+
+        let report = igor.report();
+        assert_eq!(report.morale, 70);
+        assert_eq!(report.target_updates.len(), 1);
+        assert_eq!(report.chatter.len(), 1);
+        igor.clear_report();
+        assert!(igor.report().chatter.is_empty());
+    }
+
+    #[test]
+    fn demoted_sidekick_keeps_stats_and_gadgets() {
+        let mut igor = Sidekick::with_name("Igor", RatedGadget(3));
+        igor.equip(Slot::Backup, RatedGadget(7));
+        igor.stats_mut().loyalty = 90;
+
+        let goon = igor.demote_to_henchman();
+        assert_eq!(goon.name, "Igor");
+        assert_eq!(goon.stats.loyalty, 90);
+        let ratings = goon
+            .gadgets
+            .iter()
+            .map(|gadget| gadget.rating(Capability::Combat))
+            .collect::<Vec<_>>();
+        assert_eq!(ratings, [3, 7]);
+    }
+
+    #[test]
+    fn promoted_henchman_fills_loadout_and_hands_back_the_rest() {
+        let mut goon = Goon::new("Igor");
+        goon.stats.competence = 80;
+        goon.gadgets = vec![
+            Box::new(RatedGadget(1)),
+            Box::new(RatedGadget(2)),
+            Box::new(RatedGadget(3)),
+        ];
+
+        let (igor, leftovers) = promote_henchman_to_sidekick(goon);
+        assert_eq!(igor.name(), "Igor");
+        assert_eq!(igor.stats().competence, 80);
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(1));
+        assert_eq!(rating_in(&igor, Slot::Backup), Some(2));
+        assert_eq!(leftovers.len(), 1);
+    }
+
+    #[test]
+    fn interview_weighs_loyalty_the_most() {
+        let candidate = SidekickCandidate::new(
+            "Igor",
+            SidekickStats {
+                loyalty: 90,
+                competence: 30,
+                dramatic_flair: 80,
+            },
+            GadgetDummy,
+        );
+        let score = candidate.interview();
+        assert_eq!(score.overall, 70);
+        assert_eq!(
+            score.shortfall(&HiringThresholds::default()),
+            Some(String::from("Competence 30 below 40"))
+        );
+
+        let igor = Sidekick::from(candidate);
+        assert_eq!(igor.name(), "Igor");
+        assert_eq!(igor.stats().loyalty, 90);
+    }
+
+    #[test]
+    fn latest_plans_are_deciphered_with_the_stored_key() -> Result<(), EvilError> {
+        let mut igor = Sidekick::new(GadgetDummy);
+        assert_none!(igor.decrypt_latest()?);
+
+        let envelope = MessageEnvelope::new(
+            test_common::PRIMARY_FULL_NAME,
+            &MorseCipher.id(),
+            MorseCipher.transform(test_common::MAIN_SECRET_MESSAGE, test_common::SHARED_KEY),
+        )
+        .seal(test_common::SHARED_KEY);
+        igor.tell(&envelope.try_to_wire()?);
+        assert_matches!(
+            igor.decrypt_latest(),
+            Err(EvilError::NoMatchingKey { cipher }) if cipher == "morse"
+        );
+
+        igor.keys_mut().insert(MorseCipher, test_common::SHARED_KEY);
+        assert_eq!(
+            igor.decrypt_latest()?,
+            Some(test_common::MAIN_SECRET_MESSAGE.into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn session_plans_are_deciphered_with_the_derived_key() -> Result<(), EvilError> {
+        let mut igor = Sidekick::new(GadgetDummy);
+        igor.support_cipher(CipherSuite::of(&MorseCipher));
+        igor.keys_mut().insert(MorseCipher, test_common::SHARED_KEY);
+        let offered = [CipherSuite::new("caesar"), CipherSuite::of(&MorseCipher)];
+
+        let session = Session::establish(test_common::SHARED_KEY, CipherSuite::of(&MorseCipher), 7);
+        let key = session.key.expose_secret();
+        let envelope = MessageEnvelope::new(
+            test_common::PRIMARY_FULL_NAME,
+            &MorseCipher.id(),
+            MorseCipher.transform(test_common::MAIN_SECRET_MESSAGE, key),
+        )
+        .seal(key);
+        igor.tell(&envelope.try_to_wire()?);
+        assert_matches!(igor.decrypt_latest(), Err(EvilError::IntegrityFailure));
+
+        assert_eq!(igor.accept_handshake(&offered, 7), Some(session.suite));
+        assert_eq!(
+            igor.decrypt_latest()?,
+            Some(test_common::MAIN_SECRET_MESSAGE.into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_latest_message_is_kept_and_it_is_shared() -> Result<(), EvilError> {
+        let igor = Sidekick::new(GadgetDummy);
+        let wire = MessageEnvelope::new(
+            test_common::PRIMARY_FULL_NAME,
+            test_common::CIPHER_ID,
+            test_common::MAIN_CIPHERED_MESSAGE.into(),
+        )
+        .try_to_wire()?;
+        igor.tell(&wire);
+        assert_eq!(Arc::strong_count(&wire), 2);
+        igor.tell("Never mind");
+        assert_eq!(Arc::strong_count(&wire), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn debug_shows_name_and_stats() {
+        let igor = Sidekick::with_name("Igor", GadgetDummy);
+        let debug = format!("{igor:?}");
+        assert!(debug.contains("Igor"));
+        assert!(debug.contains("loyalty"));
+    }
+
+    #[test]
+    fn lent_gadgets_are_taken_back_into_their_slot() -> Result<(), EvilError> {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        igor.equip(Slot::Backup, RatedGadget(20));
+        let mut goon = Goon::new("Bruno");
+
+        let loan = igor.lend_gadget(&mut goon, Duration::from_secs(60))?;
+        assert_eq!(rating_in(&igor, Slot::Backup), None);
+        assert_eq!(goon.borrowed.len(), 1);
+        assert_eq!(igor.ledger().loans().len(), 1);
+
+        assert!(igor.take_back(loan, &mut goon)?.is_none());
+        assert_eq!(rating_in(&igor, Slot::Backup), Some(20));
+        assert!(goon.borrowed.is_empty());
+        assert!(igor.ledger().loans().is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn gadgets_borrowing_data_can_be_lent() -> Result<(), EvilError> {
+        #[derive(Clone)]
+        struct Pointer<'r>(&'r u8);
+        impl Gadget for Pointer<'_> {
+            fn do_stuff(&self) {}
+        }
+
+        let rating = 10;
+        let mut igor = Sidekick::new(Pointer(&rating));
+        let mut goon = Goon::new("Bruno");
+        let loan = igor.lend_gadget(&mut goon, Duration::from_secs(60))?;
+        assert_eq!(goon.borrowed.len(), 1);
+        assert!(igor.take_back(loan, &mut goon)?.is_none());
+        assert!(igor.gadget(Slot::Primary).is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn gadgets_go_back_to_the_sidekick_that_lent_them() -> Result<(), EvilError> {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        let mut fritz = Sidekick::new(RatedGadget(20));
+        let mut goon = Goon::new("Bruno");
+
+        let igor_loan = igor.lend_gadget(&mut goon, Duration::from_secs(60))?;
+        let fritz_loan = fritz.lend_gadget(&mut goon, Duration::from_secs(60))?;
+        assert_ne!(igor_loan, fritz_loan);
+
+        igor.take_back(igor_loan, &mut goon)?;
+        fritz.take_back(fritz_loan, &mut goon)?;
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(10));
+        assert_eq!(rating_in(&fritz, Slot::Primary), Some(20));
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_borrower_gives_gadgets_back() -> Result<(), EvilError> {
+        let clock = ManualClock(Arc::new(Mutex::new(Instant::now())));
+        let mut igor = Sidekick::new(RatedGadget(10));
+        *igor.ledger_mut() = LendingLedger::with_clock(Arc::new(clock.clone()));
+        let mut bruno = Goon::new("Bruno");
+        let mut kevin = Goon::new("Kevin");
+        kevin.stats.loyalty = 50;
+        let loan = igor.lend_gadget(&mut bruno, Duration::from_secs(60))?;
+
+        assert_matches!(
+            igor.take_back(loan, &mut kevin),
+            Err(EvilError::LoanRefused)
+        );
+        clock.advance(Duration::from_secs(61));
+        assert!(igor.recall_overdue(&mut kevin).is_empty());
+        assert_eq!(kevin.stats.loyalty, 50);
+        assert_eq!(bruno.borrowed.len(), 1);
+        assert_eq!(igor.ledger().loans().len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn refused_loans_keep_the_gadget() {
+        let mut igor = Sidekick::new(RatedGadget(10));
+        assert_matches!(
+            igor.lend_gadget(&mut Unarmed, Duration::from_secs(60)),
+            Err(EvilError::LoanRefused)
+        );
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(10));
+        assert!(igor.ledger().loans().is_empty());
+
+        igor.unequip(Slot::Primary);
+        assert_matches!(
+            igor.lend_gadget(&mut Goon::new("Bruno"), Duration::from_secs(60)),
+            Err(EvilError::NothingToLend)
+        );
+    }
+
+    #[test]
+    fn overdue_gadgets_are_recalled_at_a_loyalty_cost() -> Result<(), EvilError> {
+        let clock = ManualClock(Arc::new(Mutex::new(Instant::now())));
+        let mut igor = Sidekick::new(RatedGadget(10));
+        *igor.ledger_mut() = LendingLedger::with_clock(Arc::new(clock.clone()));
+        let mut goon = Goon::new("Bruno");
+        goon.stats.loyalty = 50;
+        igor.lend_gadget(&mut goon, Duration::from_secs(60))?;
+
+        clock.advance(Duration::from_secs(60));
+        assert!(igor.recall_overdue(&mut goon).is_empty());
+        assert_eq!(goon.borrowed.len(), 1);
+
+        clock.advance(Duration::from_secs(1));
+        assert!(igor.recall_overdue(&mut goon).is_empty());
+        assert_eq!(rating_in(&igor, Slot::Primary), Some(10));
+        assert_eq!(goon.stats.loyalty, 50 - OVERDUE_LOYALTY_PENALTY);
+        assert!(igor.ledger().loans().is_empty());
+        Ok(())
+    }
+
+    fn rating_in(sidekick: &Sidekick, slot: Slot) -> Option<u8> {
+        sidekick
+            .gadget(slot)
+            .map(|gadget| gadget.rating(Capability::Combat))
     }
 }
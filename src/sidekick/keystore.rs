@@ -0,0 +1,117 @@
+//! Keys a sidekick opens the plans it's told with.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::cipher::Decipher;
+use crate::cipher::handshake::CipherSuite;
+use crate::cipher::handshake::Session;
+use crate::envelope::MessageEnvelope;
+use crate::redacted::Redacted;
+use crate::supervillain::EvilError;
+
+/// Cipher the sidekick can decipher, with its key.
+type Entry = (Arc<dyn Decipher + Send + Sync>, Redacted<String>);
+
+/// Ciphers the sidekick can decipher, each with its key, by [`Cipher::id`](crate::Cipher::id).
+#[derive(Clone, Default)]
+pub struct KeyStore {
+    keys: HashMap<String, Entry>,
+}
+
+impl KeyStore {
+    pub fn new() -> KeyStore {
+        Self::default()
+    }
+
+    /// Adds the key for the cipher, replacing any other key for it.
+    pub fn insert<D: Decipher + Send + Sync + 'static>(&mut self, decipher: D, key: &str) {
+        self.keys
+            .insert(decipher.id(), (Arc::new(decipher), key.into()));
+    }
+
+    /// Forgets the key for the cipher, returning whether there was one.
+    pub fn remove(&mut self, cipher_id: &str) -> bool {
+        self.keys.remove(cipher_id).is_some()
+    }
+
+    pub fn contains(&self, cipher_id: &str) -> bool {
+        self.keys.contains_key(cipher_id)
+    }
+
+    /// Session for the cipher, keyed from the stored key and the nonce of a handshake, if there's
+    /// a key for it.
+    pub fn session(&self, suite: &CipherSuite, nonce: u64) -> Option<Session> {
+        let (_, key) = self.keys.get(suite.id())?;
+        Some(Session::establish(
+            key.expose_secret(),
+            suite.clone(),
+            nonce,
+        ))
+    }
+
+    /// Checks the envelope was sealed with the key for its cipher and deciphers the payload.
+    pub fn open(&self, envelope: &MessageEnvelope) -> Result<String, EvilError> {
+        let (decipher, key) = self.entry(&envelope.cipher_id)?;
+        envelope.verify(key.expose_secret())?;
+        decipher.decipher(envelope.payload.expose_secret(), key.expose_secret())
+    }
+
+    /// Like [`KeyStore::open`], but with the key of the session instead of the stored one.
+    pub fn open_in(
+        &self,
+        envelope: &MessageEnvelope,
+        session: &Session,
+    ) -> Result<String, EvilError> {
+        let (decipher, _) = self.entry(&envelope.cipher_id)?;
+        let key = session.key.expose_secret();
+        envelope.verify(key)?;
+        decipher.decipher(envelope.payload.expose_secret(), key)
+    }
+
+    fn entry(&self, cipher_id: &str) -> Result<&Entry, EvilError> {
+        self.keys
+            .get(cipher_id)
+            .ok_or_else(|| EvilError::NoMatchingKey {
+                cipher: cipher_id.into(),
+            })
+    }
+}
+
+impl fmt::Debug for KeyStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyStore")
+            .field("ciphers", &self.keys.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Cipher;
+    use crate::cipher::morse::MorseCipher;
+    use crate::cipher::steganography::Steganography;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    #[test]
+    fn envelopes_open_with_the_key_for_their_cipher() -> Result<(), EvilError> {
+        let cipher = Steganography::new(MorseCipher, "Lovely weather today.");
+        let envelope = MessageEnvelope::new(
+            test_common::PRIMARY_FULL_NAME,
+            &cipher.id(),
+            cipher.transform(test_common::MAIN_SECRET_MESSAGE, test_common::SHARED_KEY),
+        )
+        .seal(test_common::SHARED_KEY);
+
+        let mut keys = KeyStore::new();
+        assert_matches!(keys.open(&envelope), Err(EvilError::NoMatchingKey { cipher }) if cipher == "steganography+morse");
+        keys.insert(cipher.clone(), "Not the key");
+        assert_matches!(keys.open(&envelope), Err(EvilError::IntegrityFailure));
+        keys.insert(cipher, test_common::SHARED_KEY);
+        assert_eq!(keys.open(&envelope)?, test_common::MAIN_SECRET_MESSAGE);
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+//! Module to record how a villain does turn after turn, and export it for analysis.
+#![allow(dead_code)]
+
+use std::io::Write;
+
+use rand::Rng;
+
+use crate::disguise::Scrutiny;
+use crate::supervillain::EvilError;
+use crate::supervillain::SuperVillain;
+
+/// Columns of the exported metrics, in order.
+pub const METRIC_COLUMNS: [&str; 4] = ["turn", "attacks", "infamy_delta", "treasury"];
+
+/// What happened in a turn of the simulation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct TurnMetrics {
+    pub turn: u32,
+    pub attacks: u32,
+    /// Infamy gained, or lost if negative, since the previous turn.
+    pub infamy_delta: i64,
+    /// Treasury balance at the end of the turn.
+    pub treasury: u64,
+}
+
+/// Metrics of every turn simulated so far.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SimulationLog {
+    turns: Vec<TurnMetrics>,
+    last_infamy: Option<u32>,
+}
+
+impl SimulationLog {
+    pub fn new() -> SimulationLog {
+        Self::default()
+    }
+
+    pub fn turns(&self) -> &[TurnMetrics] {
+        &self.turns
+    }
+
+    /// Records the end of a turn, taking the infamy, trophy bonuses included, and treasury from
+    /// the villain. The infamy delta of the first turn recorded is 0.
+    pub fn record_turn(&mut self, villain: &SuperVillain, attacks: u32) -> &TurnMetrics {
+        let infamy = villain.total_infamy().points();
+        let infamy_delta = self
+            .last_infamy
+            .map_or(0, |last| i64::from(infamy) - i64::from(last));
+        self.last_infamy = Some(infamy);
+        self.turns.push(TurnMetrics {
+            turn: self.turns.len() as u32 + 1,
+            attacks,
+            infamy_delta,
+            treasury: villain.treasury.balance(),
+        });
+        &self.turns[self.turns.len() - 1]
+    }
+
+    /// The metrics as comma-separated values, with a header row naming the columns.
+    pub fn to_csv(&self) -> String {
+        let mut csv = METRIC_COLUMNS.join(",");
+        for metrics in &self.turns {
+            csv.push_str(&format!(
+                "\n{},{},{},{}",
+                metrics.turn, metrics.attacks, metrics.infamy_delta, metrics.treasury
+            ));
+        }
+        csv.push('\n');
+        csv
+    }
+
+    pub fn write_csv<W: Write>(&self, out: &mut W) -> Result<(), EvilError> {
+        out.write_all(self.to_csv().as_bytes())
+            .map_err(|error| storage_error(&error.to_string()))
+    }
+
+    /// Writes the metrics as a Parquet file, one column per metric.
+    #[cfg(feature = "parquet")]
+    pub fn write_parquet<W: Write + Send>(&self, out: W) -> Result<(), EvilError> {
+        use std::sync::Arc;
+
+        use arrow::array::ArrayRef;
+        use arrow::array::Int64Array;
+        use arrow::array::UInt32Array;
+        use arrow::array::UInt64Array;
+        use arrow::datatypes::DataType;
+        use arrow::datatypes::Field;
+        use arrow::datatypes::Schema;
+        use arrow::record_batch::RecordBatch;
+        use parquet::arrow::ArrowWriter;
+
+        let [turn, attacks, infamy_delta, treasury] = METRIC_COLUMNS;
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(turn, DataType::UInt32, false),
+            Field::new(attacks, DataType::UInt32, false),
+            Field::new(infamy_delta, DataType::Int64, false),
+            Field::new(treasury, DataType::UInt64, false),
+        ]));
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(UInt32Array::from_iter_values(
+                self.turns.iter().map(|metrics| metrics.turn),
+            )),
+            Arc::new(UInt32Array::from_iter_values(
+                self.turns.iter().map(|metrics| metrics.attacks),
+            )),
+            Arc::new(Int64Array::from_iter_values(
+                self.turns.iter().map(|metrics| metrics.infamy_delta),
+            )),
+            Arc::new(UInt64Array::from_iter_values(
+                self.turns.iter().map(|metrics| metrics.treasury),
+            )),
+        ];
+        let batch = RecordBatch::try_new(schema.clone(), columns)
+            .map_err(|error| storage_error(&error.to_string()))?;
+        let mut writer = ArrowWriter::try_new(out, schema, None)
+            .map_err(|error| storage_error(&error.to_string()))?;
+        writer
+            .write(&batch)
+            .map_err(|error| storage_error(&error.to_string()))?;
+        writer
+            .close()
+            .map(|_| ())
+            .map_err(|error| storage_error(&error.to_string()))
+    }
+}
+
+/// Investigation step of a turn: a hero investigates the villain in the region it operates in,
+/// as in [`SuperVillain::face_investigation`]. Villains that operate nowhere, or go undisguised,
+/// aren't investigated.
+pub fn investigate<R: Rng + ?Sized>(
+    villain: &mut SuperVillain,
+    investigation_level: u8,
+    rng: &mut R,
+) -> Option<Scrutiny> {
+    let region = villain.region.clone()?;
+    villain.face_investigation(investigation_level, &region, rng)
+}
+
+fn storage_error(reason: &str) -> EvilError {
+    EvilError::StorageError {
+        reason: reason.into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disguise::Disguise;
+    use crate::heat::HeatLevel;
+    use crate::supervillain::VillainStatus;
+    use crate::test_common;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn turns_export_as_csv() -> Result<(), EvilError> {
+        let mut villain = SuperVillain::default();
+        let mut log = SimulationLog::new();
+        villain.infamy.gain(10);
+        log.record_turn(&villain, 2);
+        villain.infamy.lose(4);
+        villain.treasury.deposit(300);
+        assert_eq!(log.record_turn(&villain, 0).infamy_delta, -4);
+
+        let mut out = Vec::new();
+        log.write_csv(&mut out)?;
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            "turn,attacks,infamy_delta,treasury\n1,2,0,0\n2,0,-4,300\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn blown_cover_in_investigation_step_spikes_heat_and_captures() {
+        let mut villain = SuperVillain::default();
+        let mut rng = StdRng::seed_from_u64(1);
+        villain.disguise = Some(Disguise::new("Mr. Smith", 0));
+        assert_eq!(investigate(&mut villain, 80, &mut rng), None);
+
+        villain.region = Some(test_common::FIRST_TARGET.into());
+        villain.heat_map().raise(test_common::FIRST_TARGET, 60);
+        assert_eq!(
+            investigate(&mut villain, 80, &mut rng),
+            Some(Scrutiny::Blown)
+        );
+        assert!(villain.disguise.is_none());
+        assert_eq!(
+            villain.heat_map().level(test_common::FIRST_TARGET),
+            HeatLevel::Manhunt
+        );
+        assert_eq!(villain.status, VillainStatus::Captured);
+    }
+}
@@ -0,0 +1,218 @@
+//! Module for skill checks: rolling dice against a difficulty class, d20-style.
+//!
+//! Skills and the ratings they're checked against go from 0 to 100. A check beating the
+//! difficulty by 10 or more is a critical success, and one missing it by 10 or more a critical
+//! failure. Rolling the highest number on the dice improves the result by one degree; rolling
+//! the lowest worsens it by one.
+#![allow(dead_code)]
+
+use rand::Rng;
+
+/// How hard a check is: the total the roll plus the skill modifier has to reach.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Difficulty(u8);
+
+impl Difficulty {
+    pub const TRIVIAL: Difficulty = Difficulty(5);
+    pub const EASY: Difficulty = Difficulty(10);
+    pub const MEDIUM: Difficulty = Difficulty(15);
+    pub const HARD: Difficulty = Difficulty(20);
+    pub const VERY_HARD: Difficulty = Difficulty(25);
+    pub const NEARLY_IMPOSSIBLE: Difficulty = Difficulty(30);
+
+    pub fn new(class: u8) -> Difficulty {
+        Self(class)
+    }
+
+    /// Difficulty of beating an opposing rating, from 0 to 100: 10 plus a quarter of it, so
+    /// that equal skill and rating succeed a bit more than half the time on a d20.
+    pub fn against(rating: u8) -> Difficulty {
+        Self(10 + rating.min(100) / 4)
+    }
+
+    pub fn class(&self) -> u8 {
+        self.0
+    }
+}
+
+/// Dice rolled for a check.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Curve {
+    /// One twenty-sided die: every result as likely.
+    #[default]
+    D20,
+    /// Three six-sided dice: results bunch up around the middle.
+    ThreeD6,
+}
+
+impl Curve {
+    /// Lowest and highest results of the dice.
+    pub fn range(&self) -> (u8, u8) {
+        match self {
+            Curve::D20 => (1, 20),
+            Curve::ThreeD6 => (3, 18),
+        }
+    }
+
+    pub fn roll<R: Rng + ?Sized>(&self, rng: &mut R) -> u8 {
+        match self {
+            Curve::D20 => rng.random_range(1..=20),
+            Curve::ThreeD6 => (0..3).map(|_| rng.random_range(1..=6u8)).sum(),
+        }
+    }
+
+    /// Ways of rolling every result, lowest result first.
+    fn weights(&self) -> impl Iterator<Item = (u8, u32)> {
+        let (lowest, highest) = self.range();
+        let curve = *self;
+        (lowest..=highest).map(move |result| {
+            let ways = match curve {
+                Curve::D20 => 1,
+                Curve::ThreeD6 => (1..=6u8)
+                    .flat_map(|first| (1..=6u8).map(move |second| first + second))
+                    .filter(|two| (1..=6).contains(&result.saturating_sub(*two)))
+                    .count() as u32,
+            };
+            (result, ways)
+        })
+    }
+}
+
+/// How a check went.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum CheckResult {
+    CriticalFailure,
+    Failure,
+    Success,
+    CriticalSuccess,
+}
+
+impl CheckResult {
+    pub fn is_success(&self) -> bool {
+        matches!(self, CheckResult::Success | CheckResult::CriticalSuccess)
+    }
+
+    fn improved(self) -> CheckResult {
+        match self {
+            CheckResult::CriticalFailure => CheckResult::Failure,
+            CheckResult::Failure => CheckResult::Success,
+            _ => CheckResult::CriticalSuccess,
+        }
+    }
+
+    fn worsened(self) -> CheckResult {
+        match self {
+            CheckResult::CriticalSuccess => CheckResult::Success,
+            CheckResult::Success => CheckResult::Failure,
+            _ => CheckResult::CriticalFailure,
+        }
+    }
+}
+
+/// Rolls skill checks on a [`Curve`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SkillCheck {
+    pub curve: Curve,
+}
+
+impl SkillCheck {
+    pub fn new(curve: Curve) -> SkillCheck {
+        Self { curve }
+    }
+
+    /// Checks the skill on a d20.
+    pub fn roll<R: Rng + ?Sized>(skill: u8, difficulty: Difficulty, rng: &mut R) -> CheckResult {
+        Self::default().check(skill, difficulty, rng)
+    }
+
+    /// What the skill adds to the roll: a quarter of it.
+    pub fn modifier(skill: u8) -> u8 {
+        skill.min(100) / 4
+    }
+
+    pub fn check<R: Rng + ?Sized>(
+        &self,
+        skill: u8,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> CheckResult {
+        self.resolve(self.curve.roll(rng), skill, difficulty)
+    }
+
+    /// How a check goes with the given roll of the dice.
+    pub fn resolve(&self, roll: u8, skill: u8, difficulty: Difficulty) -> CheckResult {
+        let total = u32::from(roll) + u32::from(Self::modifier(skill));
+        let class = u32::from(difficulty.class());
+        let result = if total >= class + 10 {
+            CheckResult::CriticalSuccess
+        } else if total >= class {
+            CheckResult::Success
+        } else if total + 10 <= class {
+            CheckResult::CriticalFailure
+        } else {
+            CheckResult::Failure
+        };
+        match self.curve.range() {
+            (_, highest) if roll >= highest => result.improved(),
+            (lowest, _) if roll <= lowest => result.worsened(),
+            _ => result,
+        }
+    }
+
+    /// Chance, between 0 and 1, the check succeeds, critically or not.
+    pub fn success_chance(&self, skill: u8, difficulty: Difficulty) -> f64 {
+        let (successes, total) =
+            self.curve
+                .weights()
+                .fold((0, 0), |(successes, total), (roll, ways)| {
+                    let success = self.resolve(roll, skill, difficulty).is_success();
+                    (successes + if success { ways } else { 0 }, total + ways)
+                });
+        f64::from(successes) / f64::from(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn margin_and_natural_rolls_set_the_degree() {
+        let check = SkillCheck::default();
+        let difficulty = Difficulty::MEDIUM;
+        assert_eq!(check.resolve(10, 40, difficulty), CheckResult::Success);
+        assert_eq!(
+            check.resolve(15, 40, difficulty),
+            CheckResult::CriticalSuccess
+        );
+        assert_eq!(
+            check.resolve(2, 0, difficulty),
+            CheckResult::CriticalFailure
+        );
+        assert_eq!(check.resolve(1, 100, difficulty), CheckResult::Success);
+        assert_eq!(
+            check.resolve(20, 0, difficulty),
+            CheckResult::CriticalSuccess
+        );
+        assert_eq!(
+            check.resolve(20, 0, Difficulty::against(100)),
+            CheckResult::Failure
+        );
+    }
+
+    #[test]
+    fn bell_curve_favors_average_rolls() {
+        let flat = SkillCheck::default();
+        let bell = SkillCheck::new(Curve::ThreeD6);
+        let difficulty = Difficulty::new(10);
+        assert_eq!(flat.success_chance(0, difficulty), 0.55);
+        assert_eq!(bell.success_chance(0, difficulty), 0.625);
+        assert_eq!(bell.success_chance(100, Difficulty::TRIVIAL), 1.0);
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let roll = bell.curve.roll(&mut rng);
+        assert!((3..=18).contains(&roll));
+    }
+}
@@ -0,0 +1,207 @@
+//! Module with golden-test support: the observable state of villains and plans in a canonical
+//! text form.
+//!
+//! Available with the `testing` feature. Snapshots list one `field = value` per line, always in
+//! the same order, so they can be compared with a stored copy, e.g. with `insta`. Identifiers
+//! change from run to run and are left out; shared keys are redacted unless told otherwise.
+
+use std::fmt::Write;
+
+use crate::SuperVillain;
+use crate::plan::PlanGraph;
+
+/// What redacted values are replaced with.
+pub const REDACTED: &str = "[redacted]";
+
+type Redactor<'r> = Box<dyn Fn(&str) -> String + 'r>;
+
+/// Builder of the canonical text form of villains, rosters of villains and plans.
+pub struct Snapshot<'r> {
+    fields: Vec<(String, String)>,
+    redactors: Vec<(String, Redactor<'r>)>,
+}
+
+impl Default for Snapshot<'_> {
+    fn default() -> Self {
+        Self {
+            fields: Vec::new(),
+            redactors: Vec::new(),
+        }
+        .redact("shared_key", |_| REDACTED.into())
+    }
+}
+
+impl<'r> Snapshot<'r> {
+    /// Empty snapshot that redacts shared keys.
+    pub fn new() -> Snapshot<'r> {
+        Self::default()
+    }
+
+    /// Rewrites the value of every field named `field`, whatever it's nested in. Later
+    /// redactions of the same field replace earlier ones.
+    pub fn redact<F: Fn(&str) -> String + 'r>(mut self, field: &str, redactor: F) -> Snapshot<'r> {
+        self.redactors.retain(|(name, _)| name != field);
+        self.redactors.push((field.into(), Box::new(redactor)));
+        self
+    }
+
+    /// Shows the value of the field as is, e.g. to check the shared key.
+    pub fn reveal(mut self, field: &str) -> Snapshot<'r> {
+        self.redactors.retain(|(name, _)| name != field);
+        self
+    }
+
+    pub fn villain(mut self, villain: &SuperVillain) -> Snapshot<'r> {
+        self.push_villain("villain", villain);
+        self
+    }
+
+    pub fn roster(mut self, villains: &[SuperVillain]) -> Snapshot<'r> {
+        self.push("roster.len", villains.len());
+        for (i, villain) in villains.iter().enumerate() {
+            self.push_villain(&format!("roster.{i}"), villain);
+        }
+        self
+    }
+
+    /// Adds the steps of the plan, in the order they were added, with what they depend on.
+    pub fn plan(mut self, plan: &PlanGraph) -> Snapshot<'r> {
+        self.push("plan.len", plan.len());
+        for (id, step) in plan.steps() {
+            let mut dependencies = plan
+                .dependencies(id)
+                .map(|dependency| dependency.index())
+                .collect::<Vec<_>>();
+            dependencies.sort_unstable();
+            let prefix = format!("plan.{}", id.index());
+            self.push(&format!("{prefix}.name"), &step.name);
+            self.push(&format!("{prefix}.duration"), step.duration);
+            self.push(&format!("{prefix}.action"), format!("{:?}", step.action));
+            self.push(&format!("{prefix}.after"), format!("{dependencies:?}"));
+        }
+        self
+    }
+
+    /// The snapshot, one `field = value` per line.
+    pub fn render(&self) -> String {
+        let mut text = String::new();
+        for (field, value) in &self.fields {
+            let name = field.rsplit('.').next().unwrap_or(field);
+            let value = match self.redactors.iter().find(|(redacted, _)| redacted == name) {
+                Some((_, redactor)) => redactor(value),
+                None => value.clone(),
+            };
+            let _ = writeln!(text, "{field} = {value}");
+        }
+        text
+    }
+
+    fn push_villain(&mut self, prefix: &str, villain: &SuperVillain) {
+        self.push(&format!("{prefix}.first_name"), &villain.first_name);
+        self.push(&format!("{prefix}.last_name"), &villain.last_name);
+        self.push(
+            &format!("{prefix}.shared_key"),
+            villain.shared_key.expose_secret(),
+        );
+        self.push(&format!("{prefix}.status"), &villain.status);
+        self.push(&format!("{prefix}.mood"), format!("{:?}", villain.mood));
+        self.push(&format!("{prefix}.infamy"), villain.infamy.points());
+        self.push(&format!("{prefix}.treasury"), villain.treasury.balance());
+        self.push(
+            &format!("{prefix}.archetype"),
+            villain.archetype.map_or_else(
+                || String::from("none"),
+                |archetype| format!("{archetype:?}"),
+            ),
+        );
+        self.push(&format!("{prefix}.sidekick"), villain.sidekick.is_some());
+        self.push(&format!("{prefix}.gadgets"), villain.gadgets.len());
+        self.push(
+            &format!("{prefix}.dossiers"),
+            format!("{:?}", villain.dossiers.heroes().collect::<Vec<_>>()),
+        );
+    }
+
+    fn push(&mut self, field: &str, value: impl ToString) {
+        self.fields.push((field.into(), value.to_string()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plan::PlanStep;
+    use crate::plan::StepAction;
+    use crate::test_common;
+    use std::time::Duration;
+
+    fn villain() -> SuperVillain<'static> {
+        SuperVillain {
+            first_name: test_common::PRIMARY_FIRST_NAME.into(),
+            last_name: test_common::PRIMARY_LAST_NAME.into(),
+            shared_key: test_common::SHARED_KEY.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn villain_snapshot_is_canonical_and_redacts_keys() {
+        let text = Snapshot::new().villain(&villain()).render();
+        assert_eq!(
+            text,
+            "villain.first_name = Lex\n\
+             villain.last_name = Luthor\n\
+             villain.shared_key = [redacted]\n\
+             villain.status = AtLarge\n\
+             villain.mood = Calm\n\
+             villain.infamy = 0\n\
+             villain.treasury = 0\n\
+             villain.archetype = none\n\
+             villain.sidekick = false\n\
+             villain.gadgets = 0\n\
+             villain.dossiers = []\n"
+        );
+        assert_eq!(text, Snapshot::new().villain(&villain()).render());
+    }
+
+    #[test]
+    fn redaction_hooks_rewrite_fields() {
+        let text = Snapshot::new()
+            .reveal("shared_key")
+            .redact("last_name", |name| name.chars().take(1).collect())
+            .roster(&[villain()])
+            .render();
+        assert!(text.contains("roster.0.shared_key = Kryptonite\n"));
+        assert!(text.contains("roster.0.last_name = L\n"));
+    }
+
+    #[test]
+    fn plan_snapshot_lists_steps_and_dependencies() {
+        let mut plan = PlanGraph::new();
+        let hq = plan.add_step(PlanStep::new(
+            "Build HQ",
+            Duration::from_secs(3600),
+            StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+        ));
+        let fight = plan.add_step(PlanStep::new(
+            "Fight",
+            Duration::from_secs(90),
+            StepAction::FightEnemies,
+        ));
+        assert!(plan.add_dependency(fight, hq).is_ok());
+
+        let text = Snapshot::new().plan(&plan).render();
+        assert_eq!(
+            text,
+            "plan.len = 2\n\
+             plan.0.name = Build HQ\n\
+             plan.0.duration = 1h 0m 0s\n\
+             plan.0.action = BuildSecretHq(\"Tampa\")\n\
+             plan.0.after = []\n\
+             plan.1.name = Fight\n\
+             plan.1.duration = 1m 30s\n\
+             plan.1.action = FightEnemies\n\
+             plan.1.after = [0]\n"
+        );
+    }
+}
@@ -1,8 +1,15 @@
 //! Module for Super Villains and their related stuff
 
 use rand::Rng;
+use std::cell::RefCell;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
 use std::time::Duration;
-use thiserror::Error;
+use std::time::Instant;
+use std::time::SystemTime;
 
 #[allow(unused_imports)]
 use std::io::Read;
@@ -21,34 +28,352 @@ use crate::sidekick::Sidekick;
 use mockall_double::double;
 
 use crate::Henchman;
-use crate::{Cipher, Gadget};
+use crate::broadcast::Delivery;
+use crate::broadcast::Recipient;
+use crate::calendar::Absence;
+use crate::calendar::AvailabilityCalendar;
+#[cfg(feature = "chaos")]
+use crate::chaos::Fault;
+#[cfg(feature = "chaos")]
+use crate::chaos::FaultInjector;
+use crate::cipher::handshake::CipherSuite;
+use crate::cipher::handshake::Session;
+use crate::cipher::secret_sharing;
+use crate::cipher::secret_sharing::KeyShare;
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::damage;
+use crate::damage::DamageKind;
+use crate::damage::Resistances;
+use crate::disguise::Disguise;
+use crate::disguise::Scrutiny;
+use crate::dossier::Dossiers;
+use crate::dossier::Intel;
+use crate::envelope::MessageEnvelope;
+use crate::environment::Environment;
+use crate::event::EventBus;
+use crate::event::EvilEvent;
+use crate::gadget::Capability;
+use crate::gadget::GadgetInventory;
+use crate::heat::HeatLevel;
+use crate::heat::HeatMap;
+use crate::heat::Operation;
+use crate::id::VillainId;
+use crate::infamy::Infamy;
+use crate::lair::Lair;
+use crate::legacy::Ancestor;
+use crate::monologue::MonologueContext;
+use crate::monologue::MonologueTemplate;
+use crate::mood::Mood;
+use crate::mood::MoodTrigger;
+use crate::names;
+use crate::names::NameOrder;
+use crate::names::NameParser;
+use crate::personality::Personality;
+use crate::plan::PlanGraph;
+use crate::plan::ReadinessIssue;
+use crate::plan::quality::Plan;
+use crate::plan::quality::PlanQuality;
+use crate::presets::Archetype;
+use crate::ransom::Negotiation;
+use crate::ransom::NegotiationOutcome;
+use crate::ransom::Negotiator;
+use crate::redacted::Redacted;
+use crate::replay::Decision;
+use crate::replay::ReplayRecord;
+use crate::replay::ReplayRecorder;
+use crate::sidekick::HiringThresholds;
+use crate::sidekick::SidekickCandidate;
+use crate::sidekick::Slot;
+use crate::skillcheck::CheckResult;
+use crate::skillcheck::Difficulty;
+use crate::skillcheck::SkillCheck;
+use crate::targeting::MostDefensible;
+use crate::targeting::ScanKey;
+use crate::targeting::TargetCache;
+use crate::targeting::TargetSelectionStrategy;
+use crate::targeting::WeakestFirst;
+use crate::transaction;
+use crate::treasury::Treasury;
+use crate::units::Distance;
+use crate::units::Power;
+use crate::{AsyncCipher, Cipher, Gadget};
+
+pub use crate::error::ErrorContext;
+pub use crate::error::EvilError;
+pub use crate::plan::StageOutcome;
 
 const LISTING_PATH: &str = "tmp/listings.csv";
+const MONOLOGUE_PLAN: &str = "take over the world";
+const MONOLOGUE_DEVICE: &str = "doomsday device";
+/// Whom theatrical villains monologue to before attacking.
+const MONOLOGUE_AUDIENCE: &str = "the world";
+/// How long it takes to come up with a plan.
+pub(crate) const PLANNING_TIME: Duration = Duration::from_millis(100);
+/// Heat raised in the region where the villain's cover is blown.
+const BLOWN_COVER_HEAT: u32 = 40;
+/// Sidekicks with morale below this should be replaced.
+const LOW_MORALE: u8 = 30;
+/// Reliability of what heroes are overheard saying.
+const CHATTER_RELIABILITY: u8 = 60;
+/// Infamy gained by pulling off a heist flawlessly.
+const FLAWLESS_HEIST_INFAMY: u32 = 10;
+
+/// Change to the plans after debriefing the sidekick.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PlanRevision {
+    /// The target is still weak: go ahead with it.
+    StrikeTarget(String),
+    /// The target got stronger: look elsewhere.
+    AbandonTarget(String),
+    /// The sidekick's morale is too low to count on it.
+    ReplaceSidekick,
+}
 
 /// Type that represents supervillains
 #[derive(Default)]
 pub struct SuperVillain<'a> {
+    pub id: VillainId,
     pub first_name: String,
     pub last_name: String,
+    /// Name the villain goes by in public, like `"Doctor Entropy"`.
+    pub alias: Option<String>,
+    /// How [`SuperVillain::try_set_full_name`] splits full names. Accepts mononyms and stage
+    /// names unless set to [`NameParser::strict`].
+    pub name_parser: NameParser,
     pub sidekick: Option<Sidekick<'a>>,
-    pub shared_key: String,
+    /// When the sidekick is busy or on holiday. Cleared whenever the sidekick is hired,
+    /// appointed or fired.
+    pub sidekick_calendar: AvailabilityCalendar,
+    pub shared_key: Redacted<String>,
+    pub status: VillainStatus,
+    pub cooldown: Option<Cooldown<'a>>,
+    /// Clock timing attacks and planning, and telling whether the sidekick is available. The
+    /// system clock when not set.
+    pub clock: Option<Box<dyn Clock + Send + 'a>>,
+    pub event_bus: EventBus<'a>,
+    pub treasury: Treasury,
+    pub infamy: Infamy,
+    pub full_name_cache: FullNameCache,
+    pub gadgets: GadgetInventory<'a>,
+    pub archetype: Option<Archetype>,
+    pub mood: Mood,
+    pub personality: Personality,
+    pub dossiers: Dossiers,
+    /// How the HQ target is picked among the weak ones. When not set, [`WeakestFirst`], or
+    /// [`MostDefensible`] for paranoid villains.
+    pub target_strategy: Option<Box<dyn TargetSelectionStrategy + Send + 'a>>,
+    /// Recent weak-target scans, reused by stage 1 instead of scanning again. Not cached when
+    /// not set.
+    pub target_cache: Option<TargetCache<'a>>,
+    pub lair: Option<Lair>,
+    /// Villains this one inherited from, founder first.
+    pub lineage: Vec<Ancestor>,
+    pub disguise: Option<Disguise>,
+    /// Region the villain operates in. Attacks and heists raise the heat there, and heists
+    /// there suffer its penalty. Heat isn't raised when not set.
+    pub region: Option<String>,
+    /// Attention the villain draws from law enforcement, region by region.
+    pub heat: Mutex<HeatMap>,
+    /// Faults to inject into the villain's operations, for resilience tests.
+    #[cfg(feature = "chaos")]
+    pub fault_injector: Option<FaultInjector>,
+}
+
+/// Cache for [`SuperVillain::full_name_cached`]. It's checked against the current names on
+/// every use, so changing the name fields directly doesn't leave it stale.
+#[derive(Debug, Default)]
+pub struct FullNameCache(RefCell<Option<Arc<str>>>);
+
+impl FullNameCache {
+    pub fn invalidate(&self) {
+        self.0.replace(None);
+    }
+}
+
+impl fmt::Debug for SuperVillain<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SuperVillain")
+            .field("id", &self.id)
+            .field("first_name", &self.first_name)
+            .field("last_name", &self.last_name)
+            .field("alias", &self.alias)
+            .field("sidekick", &self.sidekick)
+            .field("shared_key", &self.shared_key)
+            .field("status", &self.status)
+            .field("treasury", &self.treasury)
+            .field("infamy", &self.infamy)
+            .field("archetype", &self.archetype)
+            .field("mood", &self.mood)
+            .field("personality", &self.personality)
+            .field("dossiers", &self.dossiers)
+            .field("lair", &self.lair)
+            .field("lineage", &self.lineage)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether the villain is free to do evil or has been caught.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum VillainStatus {
+    #[default]
+    AtLarge,
+    Captured,
+    Imprisoned {
+        facility: String,
+    },
+    Escaped,
+}
+
+impl VillainStatus {
+    pub fn is_locked_up(&self) -> bool {
+        matches!(
+            self,
+            VillainStatus::Captured | VillainStatus::Imprisoned { .. }
+        )
+    }
+}
+
+impl fmt::Display for VillainStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VillainStatus::AtLarge => f.write_str("AtLarge"),
+            VillainStatus::Captured => f.write_str("Captured"),
+            VillainStatus::Imprisoned { facility } => write!(f, "Imprisoned:{facility}"),
+            VillainStatus::Escaped => f.write_str("Escaped"),
+        }
+    }
+}
+
+impl FromStr for VillainStatus {
+    type Err = EvilError;
+
+    fn from_str(status: &str) -> Result<Self, Self::Err> {
+        match status.split_once(':') {
+            Some(("Imprisoned", facility)) => Ok(VillainStatus::Imprisoned {
+                facility: facility.into(),
+            }),
+            _ => match status {
+                "AtLarge" => Ok(VillainStatus::AtLarge),
+                "Captured" => Ok(VillainStatus::Captured),
+                "Escaped" => Ok(VillainStatus::Escaped),
+                _ => Err(EvilError::ParseError {
+                    purpose: "villain_status".into(),
+                    reason: "Unknown status".into(),
+                }),
+            },
+        }
+    }
+}
+
+/// Minimum time between attacks, so weapons have time to cool down. It can be shared across
+/// threads, which see the same last attack.
+pub struct Cooldown<'a> {
+    window: Duration,
+    clock: Box<dyn Clock + Send + Sync + 'a>,
+    last_attack: Mutex<Option<Instant>>,
+}
+
+impl<'a> Cooldown<'a> {
+    pub fn new(window: Duration) -> Cooldown<'a> {
+        Self::with_clock(window, SystemClock)
+    }
+
+    pub fn with_clock<C: Clock + Send + Sync + 'a>(window: Duration, clock: C) -> Cooldown<'a> {
+        Self {
+            window,
+            clock: Box::new(clock),
+            last_attack: Mutex::new(None),
+        }
+    }
+
+    /// Time left until the weapon can fire again.
+    pub fn remaining(&self) -> Duration {
+        self.last_attack().map_or(Duration::ZERO, |last_attack| {
+            let elapsed = self.clock.now().saturating_duration_since(last_attack);
+            self.window.saturating_sub(elapsed)
+        })
+    }
+
+    fn last_attack(&self) -> Option<Instant> {
+        *self
+            .last_attack
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Checks and records the attack under the same lock, so two threads can't both fire.
+    fn engage(&self) -> Result<(), EvilError> {
+        let mut last_attack = self
+            .last_attack
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = self.clock.now();
+        if let Some(last_attack) = *last_attack {
+            let elapsed = now.saturating_duration_since(last_attack);
+            if elapsed < self.window {
+                return Err(EvilError::WeaponOverheated {
+                    remaining: self.window - elapsed,
+                });
+            }
+        }
+        *last_attack = Some(now);
+        Ok(())
+    }
+}
+
+/// State of the weapon right after an attack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WeaponStatus {
+    Ready,
+    CoolingDown { remaining: Duration },
+}
+
+/// How an attack went, so simulations can account for ammo and damage.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AttackReport {
+    pub shots_fired: u32,
+    pub weapon_status: WeaponStatus,
+    pub duration: Duration,
 }
 
-#[derive(Error, Debug)]
-pub enum EvilError {
-    #[error("Parse error: purpose='{}', reason='{}'", .purpose, .reason)]
-    ParseError { purpose: String, reason: String },
+impl AttackReport {
+    /// Damage the shots fired with the weapon deal to a target with those resistances.
+    pub fn damage_against(&self, weapon: &impl MegaWeapon, resistances: &Resistances) -> u32 {
+        let per_shot = damage::calculate(
+            damage::per_shot(weapon.power()),
+            weapon.damage_kind(),
+            resistances,
+        );
+        per_shot.saturating_mul(self.shots_fired)
+    }
 }
 
 #[cfg_attr(test, automock)]
 pub trait MegaWeapon {
     fn shoot(&self);
+
+    /// Power of every shot.
+    fn power(&self) -> Power;
+
+    /// How far away the weapon can hit.
+    fn range(&self) -> Distance;
+
+    /// Shots left, or `None` if the weapon never runs out.
+    fn ammo(&self) -> Option<u32> {
+        None
+    }
+
+    fn damage_kind(&self) -> DamageKind {
+        DamageKind::Kinetic
+    }
 }
 
-impl SuperVillain<'_> {
+impl<'a> SuperVillain<'a> {
     /// Returns the Super Villain's full name as a single string.
     ///
-    /// A Full Name is produced by concatenating the first and last names with a space.
+    /// A Full Name is produced by concatenating the first and last names with a space. Mononyms
+    /// and stage names, with an empty last name, are just the first name.
     ///
     /// # Examples
     /// ```
@@ -61,277 +386,2597 @@ impl SuperVillain<'_> {
     /// assert_eq!(lex.full_name(), "Lex Luthor");
     /// ```
     pub fn full_name(&self) -> String {
-        format!("{} {}", self.first_name, self.last_name)
+        NameOrder::GivenFirst.join(&self.first_name, &self.last_name)
+    }
+
+    /// Villains this one inherited from, its predecessor first and the founder last.
+    pub fn ancestors(&self) -> impl Iterator<Item = &Ancestor> {
+        self.lineage.iter().rev()
+    }
+
+    /// Full name in the order used in the locale, e.g. `"Luthor Lex"` for `"ja-JP"`.
+    pub fn full_name_in(&self, locale: &str) -> String {
+        NameOrder::for_locale(locale).join(&self.first_name, &self.last_name)
+    }
+
+    /// Full name with only ASCII characters.
+    pub fn ascii_full_name(&self) -> String {
+        names::transliterate(&self.full_name())
+    }
+
+    /// Writes the full name without allocating a new string.
+    pub fn write_full_name(&self, out: &mut impl fmt::Write) -> fmt::Result {
+        out.write_str(&self.first_name)?;
+        if !self.last_name.is_empty() {
+            out.write_char(' ')?;
+        }
+        out.write_str(&self.last_name)
+    }
+
+    /// Returns the full name, only allocating it the first time or after a name change.
+    pub fn full_name_cached(&self) -> Arc<str> {
+        let Ok(mut cache) = self.full_name_cache.0.try_borrow_mut() else {
+            return Arc::from(self.full_name());
+        };
+        match cache.as_ref() {
+            Some(full_name) if self.is_full_name(full_name) => full_name.clone(),
+            _ => {
+                let full_name = Arc::<str>::from(self.full_name());
+                *cache = Some(full_name.clone());
+                full_name
+            }
+        }
+    }
+
+    fn is_full_name(&self, candidate: &str) -> bool {
+        if self.last_name.is_empty() {
+            return candidate == self.first_name;
+        }
+        candidate.len() == self.first_name.len() + 1 + self.last_name.len()
+            && candidate.starts_with(&self.first_name)
+            && candidate[self.first_name.len()..].starts_with(' ')
+            && candidate.ends_with(&self.last_name)
     }
 
+    /// Sets the first and last names from a full name.
+    ///
+    /// # Panics
+    ///
+    /// If the name is empty or only whitespace, or, with a [`NameParser::strict`] parser, isn't
+    /// made of a first and a last name. Deprecated with the `strict` feature; use
+    /// [`SuperVillain::try_set_full_name`] instead.
+    #[cfg_attr(feature = "strict", deprecated = "Use `try_set_full_name` instead")]
     pub fn set_full_name(&mut self, name: &str) {
-        let components = name.split_whitespace().collect::<Vec<_>>();
-        if components.len() != 2 {
-            panic!("Name must have first and last name, separated by a space");
+        if self.try_set_full_name(name).is_err() {
+            if self.name_parser.require_two_parts {
+                panic!("Name must have first and last name, separated by a space");
+            }
+            panic!("Name must not be empty or only whitespace");
+        }
+    }
+
+    /// Sets the first and last names from a full name, parsed with the villain's
+    /// [`NameParser`]. Fails without changing the names if it can't be parsed.
+    pub fn try_set_full_name(&mut self, name: &str) -> Result<(), EvilError> {
+        let (first_name, last_name) = self.name_parser.parse(name)?;
+        self.first_name = first_name;
+        self.last_name = last_name;
+        self.full_name_cache.invalidate();
+        Ok(())
+    }
+
+    /// Delivers a dramatic monologue to the hero using one of the built-in templates.
+    ///
+    /// Villains with an archetype always use the monologue style of their archetype.
+    pub fn deliver_monologue(&self, hero: &str) -> String {
+        if let Some(template) = self
+            .archetype
+            .and_then(|archetype| archetype.try_monologue_template().ok())
+        {
+            return self.deliver_monologue_with(hero, &template);
+        }
+        let templates = MonologueTemplate::built_in();
+        let template = &templates[rand::rng().random_range(0..templates.len())];
+        self.deliver_monologue_with(hero, template)
+    }
+
+    /// Id of the cipher the villain prefers, if it has an archetype.
+    pub fn preferred_cipher(&self) -> Option<&'static str> {
+        self.archetype.map(|archetype| archetype.preferred_cipher())
+    }
+
+    /// Delivers a dramatic monologue to the hero and publishes it through the event bus.
+    pub fn deliver_monologue_with(&self, hero: &str, template: &MonologueTemplate) -> String {
+        let villain = self.full_name();
+        let speech = template.render(&MonologueContext {
+            villain: &villain,
+            hero,
+            plan: MONOLOGUE_PLAN,
+            device: MONOLOGUE_DEVICE,
+        });
+        self.event_bus.publish(&EvilEvent::MonologueDelivered {
+            villain,
+            hero: hero.into(),
+            speech: speech.clone(),
+        });
+        speech
+    }
+
+    pub fn attack(&self, weapon: &impl MegaWeapon, intense: bool) -> Result<(), EvilError> {
+        self.attack_in(weapon, intense, &Environment::default())
+    }
+
+    /// Attacks under the given conditions, which change how many extra shots an intense attack
+    /// fires.
+    pub fn attack_in(
+        &self,
+        weapon: &impl MegaWeapon,
+        intense: bool,
+        environment: &Environment,
+    ) -> Result<(), EvilError> {
+        self.attack_with_report(weapon, intense, environment)
+            .map(|_| ())
+    }
+
+    /// Attacks like [`SuperVillain::attack_in`], telling how many shots were fired.
+    pub fn attack_with_report(
+        &self,
+        weapon: &impl MegaWeapon,
+        intense: bool,
+        environment: &Environment,
+    ) -> Result<AttackReport, EvilError> {
+        self.attack_with_rng(weapon, intense, environment, &mut rand::rng())
+            .map(|(_, report)| report)
+    }
+
+    /// Attacks a target with the given resistances, returning the report and the damage dealt.
+    pub fn attack_target(
+        &self,
+        weapon: &impl MegaWeapon,
+        intense: bool,
+        environment: &Environment,
+        resistances: &Resistances,
+    ) -> Result<(AttackReport, u32), EvilError> {
+        let report = self.attack_with_report(weapon, intense, environment)?;
+        Ok((report, report.damage_against(weapon, resistances)))
+    }
+
+    /// Attacks like [`SuperVillain::attack_in`], taking the random decisions from the recorder
+    /// so that the session can be replayed later.
+    pub fn attack_recorded(
+        &self,
+        weapon: &impl MegaWeapon,
+        intense: bool,
+        environment: &Environment,
+        recorder: &mut ReplayRecorder,
+    ) -> Result<AttackReport, EvilError> {
+        let (decision, report) =
+            self.attack_with_rng(weapon, intense, environment, recorder.rng())?;
+        recorder.record(decision);
+        Ok(report)
+    }
+
+    /// Replays the attacks of a recorded session, shooting exactly as many times as back then.
+    pub fn replay(&self, weapon: &impl MegaWeapon, record: &ReplayRecord) -> Result<(), EvilError> {
+        self.ensure_at_large()?;
+        for decision in &record.decisions {
+            let Decision::Attack { extra_shots, .. } = *decision;
+            for _ in 0..=extra_shots {
+                weapon.shoot();
+            }
         }
-        self.first_name = components[0].into();
-        self.last_name = components[1].into();
+        Ok(())
     }
 
-    pub fn attack(&self, weapon: &impl MegaWeapon, intense: bool) {
+    /// Furious and ambitious villains attack intensely even if not asked to, and theatrical ones
+    /// monologue first.
+    fn attack_with_rng(
+        &self,
+        weapon: &impl MegaWeapon,
+        intense: bool,
+        environment: &Environment,
+        rng: &mut impl Rng,
+    ) -> Result<(Decision, AttackReport), EvilError> {
+        self.ensure_at_large()?;
+        if let Some(ref cooldown) = self.cooldown {
+            cooldown.engage()?;
+        }
+        #[cfg(feature = "chaos")]
+        if self.fault_strikes(Fault::WeaponJam) {
+            return Err(EvilError::WeaponJammed);
+        }
+        if self.personality.is_theatrical() {
+            self.deliver_monologue(MONOLOGUE_AUDIENCE);
+        }
+        let started = self.now();
+        let intense =
+            intense || self.mood.always_attacks_intensely() || self.personality.is_ambitious();
         weapon.shoot();
-        if intense {
-            let mut rng = rand::rng();
-            let times = rng.random_range(1..3);
-            for _ in 0..times {
+        let shots = environment.intense_shots();
+        let mut extra_shots = 0;
+        if intense && !shots.is_empty() {
+            extra_shots = rng.random_range(shots);
+            for _ in 0..extra_shots {
                 weapon.shoot();
             }
         }
+        let shots_fired = 1 + extra_shots;
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_shots(&self.full_name(), shots_fired);
+        let weapon_status = match self.cooldown {
+            Some(ref cooldown) if !cooldown.window.is_zero() => WeaponStatus::CoolingDown {
+                remaining: cooldown.window,
+            },
+            _ => WeaponStatus::Ready,
+        };
+        let report = AttackReport {
+            shots_fired,
+            weapon_status,
+            duration: self.now().saturating_duration_since(started),
+        };
+        self.draw_heat(Operation::Attack);
+        Ok((
+            Decision::Attack {
+                intense,
+                extra_shots,
+            },
+            report,
+        ))
+    }
+
+    /// Comes up with a plan, rated by the villain's infamy, personality and the time it spent
+    /// thinking.
+    #[cfg(feature = "async")]
+    pub async fn come_up_with_plan(&self) -> Result<Plan, EvilError> {
+        self.ensure_at_large()?;
+        let started = self.now();
+        tokio::time::sleep(PLANNING_TIME).await;
+        Ok(self.plan_hatched(started))
+    }
+
+    /// Same as `come_up_with_plan`, but blocks the thread while thinking, so it needs no
+    /// async runtime.
+    pub fn come_up_with_plan_blocking(&self) -> Result<Plan, EvilError> {
+        self.ensure_at_large()?;
+        let started = self.now();
+        std::thread::sleep(PLANNING_TIME);
+        Ok(self.plan_hatched(started))
+    }
+
+    /// Current time on the villain's clock.
+    pub(crate) fn now(&self) -> Instant {
+        self.clock
+            .as_ref()
+            .map_or_else(Instant::now, |clock| clock.now())
+    }
+
+    /// Current calendar time on the villain's clock.
+    pub(crate) fn system_time(&self) -> SystemTime {
+        self.clock
+            .as_ref()
+            .map_or_else(SystemTime::now, |clock| clock.system_time())
+    }
+
+    pub(crate) fn plan_hatched(&self, started: Instant) -> Plan {
+        let planning_time = self.now().saturating_duration_since(started);
+        #[cfg(feature = "metrics")]
+        crate::metrics::record_plan_hatched(&self.full_name(), planning_time);
+        let quality = PlanQuality::rate(self.total_infamy(), &self.personality, planning_time);
+        Plan::new("Take over the world!", quality)
+    }
+
+    /// Breaks the villain out of captivity with the help of the sidekick and a henchman.
+    ///
+    /// The sidekick has to agree to take part, then the henchman digs the tunnel and fights
+    /// the guards.
+    pub fn escape_plan<H: Henchman>(&mut self, henchman: &H) -> Result<(), EvilError> {
+        if !self.status.is_locked_up() {
+            return Err(EvilError::EscapeFailed {
+                reason: "Not locked up".into(),
+            });
+        }
+        let Some(ref sidekick) = self.sidekick else {
+            return Err(EvilError::EscapeFailed {
+                reason: "No sidekick".into(),
+            });
+        };
+        if !sidekick.agree() {
+            return Err(EvilError::EscapeFailed {
+                reason: "Sidekick refused".into(),
+            });
+        }
+        henchman.do_hard_things();
+        henchman.fight_enemies();
+        self.status = VillainStatus::Escaped;
+        Ok(())
+    }
+
+    /// Negotiates a ransom, collecting the payment in the treasury or losing infamy on failure.
+    pub fn negotiate_ransom<N: Negotiator>(
+        &mut self,
+        negotiation: &mut Negotiation,
+        negotiator: &mut N,
+    ) -> NegotiationOutcome {
+        let outcome = negotiation.run(negotiator, &mut self.treasury, &mut self.infamy);
+        self.react_to(match outcome {
+            NegotiationOutcome::Paid { .. } => MoodTrigger::HeistSucceeded,
+            _ => MoodTrigger::HeistFailed,
+        });
+        outcome
+    }
+
+    /// Infamy earned plus the bonus of the complete trophy sets on display in the lair. Plans
+    /// are rated by it.
+    pub fn total_infamy(&self) -> Infamy {
+        let mut infamy = self.infamy;
+        if let Some(ref lair) = self.lair {
+            infamy.gain(lair.trophy_room.infamy_bonus());
+        }
+        infamy
+    }
+
+    /// Extra accuracy against the hero earned by researching it, for the battle resolver.
+    pub fn accuracy_against(&self, hero: &str) -> u8 {
+        self.dossiers.accuracy_bonus(hero)
+    }
+
+    /// Changes the mood according to what just happened.
+    pub fn react_to(&mut self, trigger: MoodTrigger) {
+        self.mood = self.mood.after(trigger);
+    }
+
+    /// Whether the villain joins an alliance that gives it this share of the spoils, in percent.
+    pub fn accepts_alliance_share(&self, share: u8) -> bool {
+        share >= self.mood.minimum_alliance_share()
+    }
+
+    /// Checks everything the villain itself brings against the plan's requirements: freedom,
+    /// gadgets, a weapon that isn't cooling down and funds. Empty if the villain is ready.
+    pub fn validate_readiness(&self, plan: &PlanGraph) -> Vec<ReadinessIssue> {
+        let requirements = plan.requirements();
+        let mut issues = Vec::new();
+        if self.status.is_locked_up() {
+            issues.push(ReadinessIssue::Incarcerated);
+        }
+        for &(capability, required) in &requirements.gadgets {
+            let best = self
+                .gadgets
+                .best_for(capability)
+                .map_or(0, |gadget| gadget.rating(capability));
+            if best < required {
+                issues.push(ReadinessIssue::MissingGadget {
+                    capability,
+                    required,
+                    best,
+                });
+            }
+        }
+        if let Some(ref cooldown) = self.cooldown {
+            let remaining = cooldown.remaining();
+            if !remaining.is_zero() {
+                issues.push(ReadinessIssue::WeaponCoolingDown { remaining });
+            }
+        }
+        if self.treasury.balance() < requirements.budget {
+            issues.push(ReadinessIssue::InsufficientFunds {
+                required: requirements.budget,
+                balance: self.treasury.balance(),
+            });
+        }
+        issues
+    }
+
+    #[cfg(feature = "chaos")]
+    fn fault_strikes(&self, fault: Fault) -> bool {
+        self.fault_injector
+            .as_ref()
+            .is_some_and(|injector| injector.strikes(fault))
+    }
+
+    pub(crate) fn ensure_at_large(&self) -> Result<(), EvilError> {
+        if self.status.is_locked_up() {
+            Err(EvilError::Incarcerated)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// The sidekick, which is gone once fired until a new one is hired.
+    fn ensure_sidekick(&self) -> Result<&Sidekick<'a>, EvilError> {
+        self.sidekick.as_ref().ok_or(EvilError::NoSidekick)
+    }
+
+    /// The sidekick, unless its calendar says it's away right now.
+    fn ensure_available_sidekick(&self) -> Result<&Sidekick<'a>, EvilError> {
+        let sidekick = self.ensure_sidekick()?;
+        let now = self.system_time();
+        if let Some(window) = self.sidekick_calendar.unavailability_at(now) {
+            let reason = match window.absence {
+                Absence::Busy => "Busy",
+                Absence::Holiday => "On holiday",
+            };
+            return Err(EvilError::SidekickUnavailable {
+                reason: reason.into(),
+                remaining: self
+                    .sidekick_calendar
+                    .next_available_at(now)
+                    .duration_since(now)
+                    .unwrap_or_default(),
+            });
+        }
+        Ok(sidekick)
     }
 
-    pub async fn come_up_with_plan(&self) -> String {
-        tokio::time::sleep(Duration::from_millis(100)).await;
-        String::from("Take over the world!")
+    /// When the sidekick will next be available, from now on. Plans needing it can be
+    /// scheduled for then.
+    pub fn next_available_at(&self) -> Result<SystemTime, EvilError> {
+        self.ensure_sidekick()?;
+        Ok(self.sidekick_calendar.next_available_at(self.system_time()))
     }
 
     pub fn conspire(&mut self) {
-        if let Some(ref sidekick) = self.sidekick {
-            if !sidekick.agree() {
-                self.sidekick = None;
+        if let Some(ref sidekick) = self.sidekick
+            && !sidekick.agree()
+        {
+            let sidekick = sidekick.id();
+            self.sidekick = None;
+            self.sidekick_calendar = AvailabilityCalendar::new();
+            #[cfg(feature = "metrics")]
+            crate::metrics::record_sidekick_fired(&self.full_name());
+            self.event_bus.publish(&EvilEvent::SidekickFired {
+                villain: self.full_name(),
+                sidekick,
+            });
+        }
+    }
+
+    /// Interviews the candidate and hires it as sidekick, replacing the current one, unless it
+    /// scores below the default [`HiringThresholds`].
+    pub fn hire_sidekick(&mut self, candidate: SidekickCandidate<'a>) -> Result<(), EvilError> {
+        self.hire_sidekick_with(candidate, &HiringThresholds::default())
+    }
+
+    pub fn hire_sidekick_with(
+        &mut self,
+        candidate: SidekickCandidate<'a>,
+        thresholds: &HiringThresholds,
+    ) -> Result<(), EvilError> {
+        if let Some(reason) = candidate.interview().shortfall(thresholds) {
+            return Err(EvilError::CandidateRejected {
+                candidate: candidate.name,
+                reason,
+            });
+        }
+        self.appoint_sidekick(candidate.into());
+        Ok(())
+    }
+
+    /// Makes the sidekick the villain's without an interview, e.g. one promoted with
+    /// [`promote_henchman_to_sidekick`](crate::sidekick::promote_henchman_to_sidekick), and
+    /// clears the calendar of the one it replaces.
+    pub fn appoint_sidekick(&mut self, sidekick: Sidekick<'a>) {
+        self.sidekick = Some(sidekick);
+        self.sidekick_calendar = AvailabilityCalendar::new();
+    }
+
+    /// Re-arms the sidekick, even mid-plan, and publishes the swap through the event bus.
+    pub fn rearm_sidekick(
+        &mut self,
+        slot: Slot,
+        gadget: Box<dyn Gadget + 'a>,
+    ) -> Result<(), EvilError> {
+        let Some(ref mut sidekick) = self.sidekick else {
+            return Err(EvilError::NoSidekick);
+        };
+        sidekick.equip_boxed(slot, gadget);
+        self.event_bus.publish(&EvilEvent::GadgetSwapped {
+            sidekick: sidekick.id(),
+            slot,
+        });
+        Ok(())
+    }
+
+    /// Takes the gadget out of the sidekick's slot, and publishes the swap through the event bus.
+    pub fn disarm_sidekick(
+        &mut self,
+        slot: Slot,
+    ) -> Result<Option<Box<dyn Gadget + 'a>>, EvilError> {
+        let Some(ref mut sidekick) = self.sidekick else {
+            return Err(EvilError::NoSidekick);
+        };
+        let gadget = sidekick.unequip(slot);
+        self.event_bus.publish(&EvilEvent::GadgetSwapped {
+            sidekick: sidekick.id(),
+            slot,
+        });
+        Ok(gadget)
+    }
+
+    /// Exchanges the sidekick's primary and backup gadgets, and publishes a swap for each slot
+    /// through the event bus.
+    pub fn swap_sidekick_slots(&mut self) -> Result<(), EvilError> {
+        let Some(ref mut sidekick) = self.sidekick else {
+            return Err(EvilError::NoSidekick);
+        };
+        sidekick.swap_slots();
+        for slot in [Slot::Primary, Slot::Backup] {
+            self.event_bus.publish(&EvilEvent::GadgetSwapped {
+                sidekick: sidekick.id(),
+                slot,
+            });
+        }
+        Ok(())
+    }
+
+    /// Heat map of the villain, even if a panic poisoned it.
+    pub fn heat_map(&self) -> MutexGuard<'_, HeatMap> {
+        self.heat
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Raises the heat in the villain's region, if it operates in one.
+    fn draw_heat(&self, operation: Operation) {
+        if let Some(ref region) = self.region {
+            self.heat_map().record(region, operation);
+        }
+    }
+
+    /// A hero investigates the villain in the region, testing its disguise, if it has one. A
+    /// blown cover is gone for good and turns up the heat there; if that sets off a manhunt,
+    /// the villain is captured.
+    pub fn face_investigation<R: Rng + ?Sized>(
+        &mut self,
+        investigation_level: u8,
+        region: &str,
+        rng: &mut R,
+    ) -> Option<Scrutiny> {
+        let scrutiny = self
+            .disguise
+            .as_mut()?
+            .resist_scrutiny(investigation_level, rng);
+        if scrutiny == Scrutiny::Blown {
+            self.disguise = None;
+            let heat = self
+                .heat
+                .get_mut()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            heat.raise(region, BLOWN_COVER_HEAT);
+            if heat.level(region) == HeatLevel::Manhunt && !self.status.is_locked_up() {
+                self.status = VillainStatus::Captured;
+                self.react_to(MoodTrigger::Captured);
+            }
+        }
+        Some(scrutiny)
+    }
+
+    /// Pulls a heist with a crew of the given skill, taking a skill check. The take goes to the
+    /// treasury when it succeeds, and a critical success also brings infamy. A critical failure
+    /// gets the villain captured. The heat in the villain's region takes its penalty off the
+    /// crew's skill, and the heist raises it further.
+    pub fn pull_heist<R: Rng + ?Sized>(
+        &mut self,
+        take: u64,
+        skill: u8,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Result<CheckResult, EvilError> {
+        self.ensure_at_large()?;
+        let penalty = self
+            .region
+            .as_ref()
+            .map_or(0, |region| self.heat_map().penalty(region));
+        let skill = skill - (u16::from(skill) * u16::from(penalty) / 100) as u8;
+        let result = SkillCheck::roll(skill, difficulty, rng);
+        self.draw_heat(Operation::Heist);
+        if result.is_success() {
+            self.treasury.deposit(take);
+            if result == CheckResult::CriticalSuccess {
+                self.infamy.gain(FLAWLESS_HEIST_INFAMY);
+            }
+            self.react_to(MoodTrigger::HeistSucceeded);
+        } else {
+            self.react_to(MoodTrigger::HeistFailed);
+            if result == CheckResult::CriticalFailure {
+                self.status = VillainStatus::Captured;
+                self.react_to(MoodTrigger::Captured);
+            }
+        }
+        Ok(result)
+    }
+
+    /// Pulls a heist following the plan: the crew's skill goes up or down with the quality of
+    /// the plan.
+    pub fn pull_planned_heist<R: Rng + ?Sized>(
+        &mut self,
+        plan: &Plan,
+        take: u64,
+        skill: u8,
+        difficulty: Difficulty,
+        rng: &mut R,
+    ) -> Result<CheckResult, EvilError> {
+        self.pull_heist(take, plan.quality.adjust_skill(skill), difficulty, rng)
+    }
+
+    /// Hears the sidekick out: chatter goes into the hero dossiers, and scouted targets and
+    /// morale become plan revisions. The sidekick forgets what it reported.
+    pub fn debrief_sidekick(&mut self) -> Result<Vec<PlanRevision>, EvilError> {
+        let Some(ref mut sidekick) = self.sidekick else {
+            return Err(EvilError::NoSidekick);
+        };
+        let report = sidekick.report();
+        sidekick.clear_report();
+
+        for chatter in report.chatter {
+            if let Some(location) = &chatter.location {
+                self.dossiers.record_sighting(&chatter.hero, location);
             }
+            self.dossiers.add_intel(
+                &chatter.hero,
+                Intel {
+                    note: chatter.note,
+                    reliability: CHATTER_RELIABILITY,
+                },
+            );
+        }
+        let mut revisions = report
+            .target_updates
+            .into_iter()
+            .map(|update| {
+                if update.weak {
+                    PlanRevision::StrikeTarget(update.target)
+                } else {
+                    PlanRevision::AbandonTarget(update.target)
+                }
+            })
+            .collect::<Vec<_>>();
+        if report.morale < LOW_MORALE {
+            revisions.push(PlanRevision::ReplaceSidekick);
         }
+        Ok(revisions)
     }
 
     pub fn start_world_domination_stage1<H: Henchman, G: Gadget>(
         &self,
         henchman: &mut H,
         gadget: &G,
-    ) {
-        if let Some(ref sidekick) = self.sidekick {
-            let targets = sidekick.get_weak_targets(gadget);
-            if !targets.is_empty() {
-                henchman.build_secret_hq(targets[0].clone());
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        self.build_hq_in_weakest_target(henchman, gadget, &Environment::default())
+    }
+
+    /// Like [`Self::start_world_domination_stage1`], but ranks the targets according to the
+    /// conditions before choosing where to build the HQ.
+    pub fn start_world_domination_stage1_in<H: Henchman, G: Gadget>(
+        &self,
+        henchman: &mut H,
+        gadget: &G,
+        environment: &Environment,
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        self.build_hq_in_weakest_target(henchman, gadget, environment)
+    }
+
+    /// Like [`Self::start_world_domination_stage1`], but picks the target with the given
+    /// strategy instead of the villain's.
+    pub fn start_world_domination_stage1_with_strategy<H: Henchman, G: Gadget>(
+        &self,
+        henchman: &mut H,
+        gadget: &G,
+        strategy: &dyn TargetSelectionStrategy,
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        self.build_hq(henchman, gadget, &Environment::default(), strategy)
+    }
+
+    /// Like [`Self::start_world_domination_stage1`], but picks the inventory gadget best suited
+    /// for detecting targets. If no gadget can detect targets, nothing is done.
+    pub fn start_world_domination_stage1_with_inventory<H: Henchman>(
+        &self,
+        henchman: &mut H,
+        inventory: &GadgetInventory,
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        match inventory.best_for(Capability::TargetDetection) {
+            Some(gadget) => {
+                self.build_hq_in_weakest_target(henchman, gadget, &Environment::default())
             }
+            None => Ok(StageOutcome::NoSuitableGadget),
         }
     }
 
-    pub fn start_world_domination_stage2<H: Henchman>(&self, henchman: H) {
-        henchman.fight_enemies();
-        henchman.do_hard_things();
+    fn build_hq_in_weakest_target<H: Henchman>(
+        &self,
+        henchman: &mut H,
+        gadget: &dyn Gadget,
+        environment: &Environment,
+    ) -> Result<StageOutcome, EvilError> {
+        let strategy = match self.target_strategy.as_deref() {
+            Some(strategy) => strategy,
+            None if self.personality.is_paranoid() => &MostDefensible,
+            None => &WeakestFirst,
+        };
+        self.build_hq(henchman, gadget, environment, strategy)
+    }
+
+    fn build_hq<H: Henchman>(
+        &self,
+        henchman: &mut H,
+        gadget: &dyn Gadget,
+        environment: &Environment,
+        strategy: &dyn TargetSelectionStrategy,
+    ) -> Result<StageOutcome, EvilError> {
+        let sidekick = self.ensure_available_sidekick()?;
+        let scan = || sidekick.get_weak_targets(gadget);
+        let weak_targets = match &self.target_cache {
+            Some(cache) => cache.get_or_scan(ScanKey::new(gadget, environment.terrain), scan),
+            None => scan(),
+        };
+        let targets = environment.rank_targets(weak_targets);
+        let Some(target) = strategy.select(&targets) else {
+            return Ok(StageOutcome::NoTargets);
+        };
+        henchman.build_secret_hq(target.clone());
+        self.event_bus.publish(&EvilEvent::HqBuilt {
+            villain: self.full_name(),
+            target: target.clone(),
+        });
+        Ok(StageOutcome::HqBuilt { target })
+    }
+
+    /// Sends the henchman out of the HQ built in stage 1 to fight and do the hard work, unless
+    /// it is on strike. Kept to the crate, so stage 2 is only reached through
+    /// [`Domination`](crate::domination::Domination) or a pipeline, once the HQ is built.
+    pub(crate) fn start_world_domination_stage2<H: Henchman>(
+        &self,
+        hq: &str,
+        henchman: H,
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        if henchman.is_on_strike() {
+            return Err(EvilError::HenchmenOnStrike);
+        }
+        #[cfg(feature = "chaos")]
+        let ignores_orders = self.fault_strikes(Fault::IgnoredOrders);
+        #[cfg(not(feature = "chaos"))]
+        let ignores_orders = false;
+        if !ignores_orders {
+            henchman.fight_enemies();
+            henchman.do_hard_things();
+        }
+        Ok(StageOutcome::HenchmenDeployed {
+            hq: hq.into(),
+            count: 1,
+            progress: henchman.report_progress(),
+        })
+    }
+
+    /// Runs stages 1 and 2 as a single transaction: pays for the HQ, builds it and deploys the
+    /// henchman. If any of it fails, the HQ is demolished and the treasury refunded; an HQ the
+    /// henchman can't demolish makes it fail with [`EvilError::RollbackFailed`].
+    pub fn start_world_domination_atomically<H: Henchman, G: Gadget>(
+        &mut self,
+        henchman: &mut H,
+        gadget: &G,
+        hq_cost: u64,
+    ) -> Result<StageOutcome, EvilError> {
+        self.ensure_at_large()?;
+        transaction::atomically(&mut (self, henchman), |transaction, context| {
+            transaction.step(
+                "pay-for-hq",
+                context,
+                |(villain, _)| villain.treasury.withdraw(hq_cost),
+                move |(villain, _), _| {
+                    villain.treasury.deposit(hq_cost);
+                    Ok(())
+                },
+            )?;
+            let hq = transaction.step(
+                "build-hq",
+                context,
+                |(villain, henchman)| match villain.build_hq_in_weakest_target(
+                    henchman,
+                    gadget,
+                    &Environment::default(),
+                )? {
+                    StageOutcome::HqBuilt { target } => Ok(target),
+                    outcome => Err(EvilError::StageFailed {
+                        stage: "build-hq".into(),
+                        outcome,
+                    }),
+                },
+                |(_, henchman), target| henchman.demolish_secret_hq(target),
+            )?;
+            transaction.step(
+                "deploy-henchmen",
+                context,
+                |(villain, henchman)| villain.start_world_domination_stage2(&hq, &mut **henchman),
+                |_, _| Ok(()),
+            )
+        })
+    }
+
+    /// Splits the shared key so that any `threshold` of the `count` shares can rebuild it, e.g. to
+    /// hand one share to each sidekick of a syndicate.
+    pub fn distribute_key_shares(
+        &self,
+        threshold: u8,
+        count: u8,
+    ) -> Result<Vec<KeyShare>, EvilError> {
+        secret_sharing::split(
+            self.shared_key.expose_secret().as_bytes(),
+            threshold,
+            count,
+            &mut rand::rng(),
+        )
+    }
+
+    /// Ciphers the secret and tells it to the sidekick inside a JSON [`MessageEnvelope`], sealed
+    /// with the shared key. The ciphered secret is copied once, into the encoded envelope, which
+    /// the sidekick keeps without copying.
+    pub fn tell_plans<C: Cipher + ?Sized>(
+        &self,
+        secret: &str,
+        cipher: &C,
+    ) -> Result<(), EvilError> {
+        self.ensure_at_large()?;
+        let sidekick = self.ensure_available_sidekick()?;
+        let ciphered_message = cipher.transform(secret, self.shared_key.expose_secret());
+        #[cfg(feature = "chaos")]
+        let ciphered_message = match self.fault_injector {
+            Some(ref injector) => injector.corrupt(ciphered_message),
+            None => ciphered_message,
+        };
+        let envelope = MessageEnvelope::new(&self.full_name(), &cipher.id(), ciphered_message)
+            .seal(self.shared_key.expose_secret());
+        sidekick.tell(&envelope.try_to_wire()?);
+        Ok(())
+    }
+
+    /// Offers the ciphers, most preferred first, with a fresh nonce, and lets the sidekick pick
+    /// one it knows. Both parties derive the session key from the shared key and the nonce, so
+    /// the key never travels.
+    pub fn handshake(&self, offered: &[CipherSuite]) -> Result<Session, EvilError> {
+        let nonce = rand::random();
+        let suite = self
+            .ensure_sidekick()?
+            .accept_handshake(offered, nonce)
+            .ok_or(EvilError::NoCommonCipher)?;
+        Ok(Session::establish(
+            self.shared_key.expose_secret(),
+            suite,
+            nonce,
+        ))
+    }
+
+    /// Whether a session key used for that long should be replaced by handshaking again. The
+    /// more paranoid the villain, the sooner.
+    pub fn should_rotate_key(&self, key_age: Duration) -> bool {
+        key_age >= self.personality.key_rotation_interval()
+    }
+
+    /// Like [`SuperVillain::tell_plans`], but with the cipher and key agreed on in a handshake.
+    pub fn tell_plans_in_session<C: Cipher>(
+        &self,
+        secret: &str,
+        cipher: &C,
+        session: &Session,
+    ) -> Result<(), EvilError> {
+        self.ensure_at_large()?;
+        if !session.allows(cipher) {
+            return Err(EvilError::NoCommonCipher);
+        }
+        let sidekick = self.ensure_available_sidekick()?;
+        let ciphered_message = cipher.transform(secret, session.key.expose_secret());
+        let envelope = MessageEnvelope::new(&self.full_name(), &cipher.id(), ciphered_message)
+            .seal(session.key.expose_secret());
+        sidekick.tell(&envelope.try_to_wire()?);
+        Ok(())
+    }
+
+    /// Tells the plans to every recipient, ciphered with its own key, and reports how each
+    /// delivery went. With the `parallel` feature, recipients are told in parallel. Nothing is
+    /// told while the villain is locked up.
+    pub fn tell_plans_broadcast<R, K, C>(
+        &self,
+        secret: &str,
+        recipients: &[(R, K)],
+        cipher: &C,
+    ) -> Vec<Delivery>
+    where
+        R: Recipient + Sync,
+        K: AsRef<str> + Sync,
+        C: Cipher + Sync + ?Sized,
+    {
+        let sender = self.full_name();
+        let cipher_id = cipher.id();
+        let locked_up = self.status.is_locked_up();
+        let deliver = |(recipient, key): &(R, K)| {
+            if locked_up {
+                return Delivery {
+                    recipient: recipient.name(),
+                    result: Err(EvilError::Incarcerated),
+                };
+            }
+            let ciphered_message = cipher.transform(secret, key.as_ref());
+            let envelope =
+                MessageEnvelope::new(&sender, &cipher_id, ciphered_message).seal(key.as_ref());
+            Delivery {
+                recipient: recipient.name(),
+                result: envelope
+                    .try_to_wire()
+                    .and_then(|message| recipient.tell(&message)),
+            }
+        };
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            recipients.par_iter().map(deliver).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        recipients.iter().map(deliver).collect()
+    }
+
+    pub async fn tell_plans_async<C: AsyncCipher>(
+        &self,
+        secret: &str,
+        cipher: &C,
+    ) -> Result<(), EvilError> {
+        self.ensure_at_large()?;
+        let sidekick = self.ensure_available_sidekick()?;
+        let ciphered_message = cipher
+            .transform(secret, self.shared_key.expose_secret())
+            .await;
+        let envelope = MessageEnvelope::new(&self.full_name(), &cipher.id(), ciphered_message)
+            .seal(self.shared_key.expose_secret());
+        sidekick.tell(&envelope.try_to_wire()?);
+        Ok(())
+    }
+
+    pub fn are_there_vulnerable_locations(&self) -> Option<bool> {
+        let mut listing = String::new();
+
+        let Ok(mut file_listing) = File::open(LISTING_PATH) else {
+            return None;
+        };
+
+        let Ok(_) = file_listing.read_to_string(&mut listing) else {
+            return None;
+        };
+
+        for line in listing.lines() {
+            if line.ends_with("weak") {
+                return Some(true);
+            }
+        }
+
+        Some(false)
+    }
+}
+
+impl TryFrom<&str> for SuperVillain<'_> {
+    type Error = EvilError;
+
+    /// Parses the name leniently, accepting mononyms and stage names.
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        let (first_name, last_name) = NameParser::default().parse(name)?;
+        Ok(Self {
+            first_name,
+            last_name,
+            ..Default::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::broadcast::MockRecipient;
+    use crate::cipher::MockCipher;
+    use crate::cipher::SyncCipherAdapter;
+    use crate::clock::MockClock;
+    use crate::environment::Terrain;
+    use crate::environment::Weather;
+    use crate::event::MockEventListener;
+    use crate::gadget::MockGadget;
+    use crate::henchman::MockHenchman;
+    use crate::henchman::Progress;
+    use crate::plan::MissionRequirements;
+    use crate::ransom::Hostage;
+    use crate::ransom::MockNegotiator;
+    use crate::ransom::Response;
+    use crate::sidekick::HeroChatter;
+    use crate::sidekick::SidekickId;
+    use crate::sidekick::SidekickReport;
+    use crate::sidekick::SidekickStats;
+    use crate::sidekick::TargetUpdate;
+    use crate::targeting::HighestValue;
+    use crate::test_common;
+    use crate::trophies::Trophy;
+    use crate::trophies::TrophySet;
+    use crate::trophies::TrophySource;
+    use assertables::assert_ok;
+    use assertables::{assert_matches, assert_some};
+    use assertables::{assert_none, assert_some_eq_x};
+    use mockall::Sequence;
+    use mockall::predicate::eq;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+    use std::cell::RefCell;
+    use std::panic;
+    use test_context::AsyncTestContext;
+    use test_context::test_context;
+
+    #[test_context(Context)]
+    #[test]
+    fn full_name_returns_first_name_space_last_name(context: &mut Context) {
+        let full_name = context.supervillain.full_name();
+        assert_eq!(
+            full_name,
+            test_common::PRIMARY_FULL_NAME,
+            "Unexpected full name"
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn full_name_in_family_first_locale_starts_with_last_name(context: &mut Context) {
+        assert_eq!(context.supervillain.full_name_in("zh-CN"), "Luthor Lex");
+        assert_eq!(
+            context.supervillain.full_name_in("es-ES"),
+            test_common::PRIMARY_FULL_NAME
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn ascii_full_name_transliterates_names(context: &mut Context) {
+        context.supervillain.last_name = "Lüthor".into();
+        assert_eq!(
+            context.supervillain.ascii_full_name(),
+            test_common::PRIMARY_FULL_NAME
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn write_full_name_writes_first_name_space_last_name(context: &mut Context) {
+        let mut full_name = String::new();
+        assert_ok!(context.supervillain.write_full_name(&mut full_name));
+        assert_eq!(full_name, test_common::PRIMARY_FULL_NAME);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn full_name_cached_reuses_cached_name(context: &mut Context) {
+        let first = context.supervillain.full_name_cached();
+        let second = context.supervillain.full_name_cached();
+        assert_eq!(&*first, test_common::PRIMARY_FULL_NAME);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn full_name_cached_follows_name_changes(context: &mut Context) {
+        context.supervillain.full_name_cached();
+        assert_ok!(
+            context
+                .supervillain
+                .try_set_full_name(test_common::SECONDARY_FULL_NAME)
+        );
+        assert_eq!(
+            &*context.supervillain.full_name_cached(),
+            test_common::SECONDARY_FULL_NAME
+        );
+        context.supervillain.first_name = test_common::PRIMARY_FIRST_NAME.into();
+        assert_eq!(&*context.supervillain.full_name_cached(), "Lex Vader");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn set_full_name_sets_first_and_last_names(context: &mut Context) {
+        #[cfg_attr(feature = "strict", allow(deprecated))]
+        context
+            .supervillain
+            .set_full_name(test_common::SECONDARY_FULL_NAME);
+        assert2::check!(context.supervillain.first_name == test_common::SECONDARY_FIRST_NAME);
+        assert2::assert!(context.supervillain.last_name == test_common::SECONDARY_LAST_NAME);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    #[should_panic(expected = "Name must not be empty or only whitespace")]
+    fn set_full_name_panics_with_empty_name(context: &mut Context) {
+        #[cfg_attr(feature = "strict", allow(deprecated))]
+        context.supervillain.set_full_name("  ");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn try_set_full_name_fails_without_changing_names(context: &mut Context) {
+        context.supervillain.name_parser = NameParser::strict();
+        let result = context.supervillain.try_set_full_name("Lex");
+        assert_matches!(result, Err(EvilError::ParseError { purpose, .. }) if purpose == "full_name");
+        assert_eq!(
+            context.supervillain.full_name(),
+            test_common::PRIMARY_FULL_NAME
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn full_name_cached_works_while_cache_is_borrowed(context: &mut Context) {
+        let _borrowed = context.supervillain.full_name_cache.0.borrow();
+        assert_eq!(
+            &*context.supervillain.full_name_cached(),
+            test_common::PRIMARY_FULL_NAME
+        );
+    }
+
+    #[test]
+    fn try_from_str_slice_produces_supervillain_full_with_first_and_last_name()
+    -> Result<(), EvilError> {
+        let supervillain = SuperVillain::try_from(test_common::SECONDARY_FULL_NAME)?;
+        assert_eq!(supervillain.first_name, test_common::SECONDARY_FIRST_NAME);
+        assert_eq!(supervillain.last_name, test_common::SECONDARY_LAST_NAME);
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_str_slice_accepts_mononyms_and_stage_names() -> Result<(), EvilError> {
+        for name in ["Sinestro", "The Riddler"] {
+            let supervillain = SuperVillain::try_from(name)?;
+            assert_eq!(supervillain.first_name, name);
+            assert_eq!(supervillain.full_name(), name);
+            assert_eq!(&*supervillain.full_name_cached(), name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn try_from_str_slice_produces_error_without_substrings() {
+        let result = SuperVillain::try_from("");
+        let Err(error) = result else {
+            panic!("Unexpected value returned by try_from");
+        };
+        assert_matches!(error, EvilError::ParseError { purpose, reason } if purpose == "full_name" && reason == "Too few arguments");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn monologue_is_published_through_event_bus(context: &mut Context) {
+        let Ok(template) = MonologueTemplate::new("{hero}, meet {villain}.") else {
+            panic!("Unexpected invalid template");
+        };
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .with(eq(EvilEvent::MonologueDelivered {
+                villain: test_common::PRIMARY_FULL_NAME.into(),
+                hero: test_common::HERO_NAME.into(),
+                speech: String::from("Superman, meet Lex Luthor."),
+            }))
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+
+        let speech = context
+            .supervillain
+            .deliver_monologue_with(test_common::HERO_NAME, &template);
+        assert_eq!(speech, "Superman, meet Lex Luthor.");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn built_in_monologue_addresses_the_hero(context: &mut Context) {
+        let speech = context
+            .supervillain
+            .deliver_monologue(test_common::HERO_NAME);
+        assert!(speech.contains(test_common::HERO_NAME));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn non_intense_attack_shoots_weapon_once(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().once().return_const(());
+        assert_ok!(context.supervillain.attack(&weapon, false));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_on_target_applies_resistance_to_weapon_damage(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().once().return_const(());
+        weapon
+            .expect_power()
+            .return_const(Power::from_megawatts(40));
+        weapon.expect_damage_kind().return_const(DamageKind::Cryo);
+        let resistances = Resistances::none().with(DamageKind::Cryo, 50);
+
+        let result = context.supervillain.attack_target(
+            &weapon,
+            false,
+            &Environment::default(),
+            &resistances,
+        );
+        assert_matches!(result, Ok((AttackReport { shots_fired: 1, .. }, 20)));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn intensive_attack_shoots_weapon_twice_or_more(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(2..=3).return_const(());
+        assert_ok!(context.supervillain.attack(&weapon, true));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn theatrical_ambitious_villain_monologues_then_attacks_intensely(context: &mut Context) {
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .withf(|event| {
+                matches!(event, EvilEvent::MonologueDelivered { hero, .. } if hero == MONOLOGUE_AUDIENCE)
+            })
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+        context.supervillain.personality = Personality::new(100, 0, 100);
+
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(2..=3).return_const(());
+        assert_ok!(context.supervillain.attack(&weapon, false));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn intense_attack_in_harsh_conditions_shoots_once(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().once().return_const(());
+        let environment = Environment::new(Weather::Storm, Terrain::Mountains);
+        assert_ok!(context.supervillain.attack_in(&weapon, true, &environment));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_report_counts_every_shot(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(2..=3).return_const(());
+        let Ok(report) =
+            context
+                .supervillain
+                .attack_with_report(&weapon, true, &Environment::default())
+        else {
+            panic!("Attack should succeed");
+        };
+        assert!((2..=3).contains(&report.shots_fired));
+        assert_eq!(report.weapon_status, WeaponStatus::Ready);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_report_is_timed_by_the_villains_clock(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().return_const(());
+        let start = Instant::now();
+        let mut instants = [start, start + Duration::from_secs(3)].into_iter();
+        let mut clock = MockClock::new();
+        clock
+            .expect_now()
+            .returning(move || instants.next().unwrap());
+        context.supervillain.clock = Some(Box::new(clock));
+
+        let report =
+            context
+                .supervillain
+                .attack_with_report(&weapon, false, &Environment::default());
+        assert_eq!(
+            report.map(|report| report.duration).ok(),
+            Some(Duration::from_secs(3))
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_report_tells_weapon_is_cooling_down(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().once().return_const(());
+        context.supervillain.cooldown = Some(cooldown_with_attacks_at(&[0]));
+        let Ok(report) =
+            context
+                .supervillain
+                .attack_with_report(&weapon, false, &Environment::default())
+        else {
+            panic!("Attack should succeed");
+        };
+        assert_eq!(report.shots_fired, 1);
+        assert_eq!(
+            report.weapon_status,
+            WeaponStatus::CoolingDown {
+                remaining: Duration::from_secs(5)
+            }
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_within_cooldown_window_overheats_weapon(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().once().return_const(());
+        context.supervillain.cooldown = Some(cooldown_with_attacks_at(&[0, 1]));
+
+        assert_ok!(context.supervillain.attack(&weapon, false));
+        let result = context.supervillain.attack(&weapon, false);
+        assert_matches!(result, Err(EvilError::WeaponOverheated { remaining }) if remaining == Duration::from_secs(4));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_after_cooldown_window_shoots_again(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(2).return_const(());
+        context.supervillain.cooldown = Some(cooldown_with_attacks_at(&[0, 5]));
+
+        assert_ok!(context.supervillain.attack(&weapon, false));
+        assert_ok!(context.supervillain.attack(&weapon, false));
+    }
+
+    #[test]
+    fn cooldown_shared_across_threads_lets_one_attack_through() {
+        let mut clock = MockClock::new();
+        clock.expect_now().return_const(Instant::now());
+        let cooldown = Cooldown::with_clock(Duration::from_secs(5), clock);
+
+        let engaged = std::thread::scope(|scope| {
+            let attacks = (0..4)
+                .map(|_| scope.spawn(|| cooldown.engage().is_ok()))
+                .collect::<Vec<_>>();
+            attacks
+                .into_iter()
+                .filter_map(|attack| attack.join().ok())
+                .filter(|&engaged| engaged)
+                .count()
+        });
+        assert_eq!(engaged, 1);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn attack_while_imprisoned_fails_without_shooting(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().never();
+        context.supervillain.status = VillainStatus::Imprisoned {
+            facility: test_common::PRISON_FACILITY.into(),
+        };
+        let result = context.supervillain.attack(&weapon, false);
+        assert_matches!(result, Err(EvilError::Incarcerated));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn recorded_attacks_with_same_seed_take_same_decisions(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().return_const(());
+        let environment = Environment::default();
+        let mut first = ReplayRecorder::new();
+        let mut second = ReplayRecorder::with_seed(first.seed());
+        for recorder in [&mut first, &mut second] {
+            for intense in [true, false, true, true] {
+                assert_ok!(context.supervillain.attack_recorded(
+                    &weapon,
+                    intense,
+                    &environment,
+                    recorder
+                ));
+            }
+        }
+        assert_eq!(first.finish(), second.finish());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn replay_shoots_as_many_times_as_recorded(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(4).return_const(());
+        let record = ReplayRecord {
+            seed: 42,
+            decisions: vec![
+                Decision::Attack {
+                    intense: true,
+                    extra_shots: 2,
+                },
+                Decision::Attack {
+                    intense: false,
+                    extra_shots: 0,
+                },
+            ],
+        };
+        assert_ok!(context.supervillain.replay(&weapon, &record));
+    }
+
+    #[cfg(feature = "async")]
+    #[test_context(Context)]
+    #[tokio::test]
+    async fn plan_is_sadly_expected(context: &mut Context<'_>) -> Result<(), EvilError> {
+        let plan = context.supervillain.come_up_with_plan().await?;
+        assert_eq!(plan.scheme, "Take over the world!");
+        assert_eq!(plan.quality, PlanQuality::Solid);
+        Ok(())
+    }
+
+    #[cfg(feature = "async")]
+    #[test_context(Context)]
+    #[tokio::test]
+    async fn plan_while_captured_fails(context: &mut Context<'_>) {
+        context.supervillain.status = VillainStatus::Captured;
+        assert_matches!(
+            context.supervillain.come_up_with_plan().await,
+            Err(EvilError::Incarcerated)
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn blocking_plan_needs_no_runtime(context: &mut Context) -> Result<(), EvilError> {
+        let plan = context.supervillain.come_up_with_plan_blocking()?;
+        assert_eq!(plan.scheme, "Take over the world!");
+        assert_eq!(plan.quality, PlanQuality::Solid);
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn better_plans_make_heists_more_likely(context: &mut Context) -> Result<(), EvilError> {
+        let mut successes = |quality| -> Result<usize, EvilError> {
+            let plan = Plan::new("Rob the bank", quality);
+            let mut rng = StdRng::seed_from_u64(1);
+            let mut successes = 0;
+            for _ in 0..200 {
+                context.supervillain.status = VillainStatus::AtLarge;
+                let result = context.supervillain.pull_planned_heist(
+                    &plan,
+                    1_000,
+                    50,
+                    Difficulty::MEDIUM,
+                    &mut rng,
+                )?;
+                successes += usize::from(result.is_success());
+            }
+            Ok(successes)
+        };
+        let harebrained = successes(PlanQuality::Harebrained)?;
+        let foolproof = successes(PlanQuality::Foolproof)?;
+        assert!(foolproof > harebrained, "{foolproof} <= {harebrained}");
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn escape_with_willing_sidekick_sets_villain_escaped(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_agree().once().return_const(true);
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.status = VillainStatus::Imprisoned {
+            facility: test_common::PRISON_FACILITY.into(),
+        };
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_do_hard_things()
+            .once()
+            .return_const(());
+        mock_henchman.expect_fight_enemies().once().return_const(());
+
+        assert_ok!(context.supervillain.escape_plan(&mock_henchman));
+        assert_eq!(context.supervillain.status, VillainStatus::Escaped);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn escape_without_sidekick_fails(context: &mut Context) {
+        context.supervillain.status = VillainStatus::Captured;
+        let mock_henchman = MockHenchman::new();
+        let result = context.supervillain.escape_plan(&mock_henchman);
+        assert_matches!(result, Err(EvilError::EscapeFailed { reason }) if reason == "No sidekick");
+        assert_eq!(context.supervillain.status, VillainStatus::Captured);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn heist_take_depends_on_skill_check(context: &mut Context) {
+        let mut rng = StdRng::seed_from_u64(1);
+        let balance = context.supervillain.treasury.balance();
+        let Ok(failed) =
+            context
+                .supervillain
+                .pull_heist(1_000, 0, Difficulty::NEARLY_IMPOSSIBLE, &mut rng)
+        else {
+            panic!("Villain should be free to try");
+        };
+        assert!(!failed.is_success());
+        assert_eq!(context.supervillain.treasury.balance(), balance);
+
+        context.supervillain.status = VillainStatus::AtLarge;
+        let Ok(succeeded) =
+            context
+                .supervillain
+                .pull_heist(1_000, 100, Difficulty::TRIVIAL, &mut rng)
+        else {
+            panic!("Villain should be free to try");
+        };
+        assert!(succeeded.is_success());
+        assert_eq!(context.supervillain.treasury.balance(), balance + 1_000);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn escape_while_at_large_fails(context: &mut Context) {
+        let mock_henchman = MockHenchman::new();
+        let result = context.supervillain.escape_plan(&mock_henchman);
+        assert_matches!(result, Err(EvilError::EscapeFailed { reason }) if reason == "Not locked up");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn hire_sidekick_accepts_loyal_candidate(context: &mut Context) {
+        let candidate = SidekickCandidate::new(
+            "Igor",
+            SidekickStats {
+                loyalty: 80,
+                competence: 60,
+                dramatic_flair: 50,
+            },
+            MockGadget::new(),
+        );
+        assert_ok!(context.supervillain.hire_sidekick(candidate));
+        assert_some!(&context.supervillain.sidekick, "Unexpected: no sidekick");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn hire_sidekick_rejects_candidate_below_thresholds(context: &mut Context) {
+        let candidate = SidekickCandidate::new(
+            "Igor",
+            SidekickStats {
+                loyalty: 10,
+                ..Default::default()
+            },
+            MockGadget::new(),
+        );
+        let result = context.supervillain.hire_sidekick(candidate);
+        assert_matches!(
+            result,
+            Err(EvilError::CandidateRejected { candidate, reason })
+                if candidate == "Igor" && reason == "Loyalty 10 below 60"
+        );
+        assert_none!(&context.supervillain.sidekick, "Unexpected: sidekick hired");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn validate_readiness_reports_missing_gadgets_and_funds(context: &mut Context) {
+        let mut combat_gadget = MockGadget::new();
+        combat_gadget.expect_rating().returning(|capability| {
+            if capability == Capability::Combat {
+                80
+            } else {
+                0
+            }
+        });
+        context.supervillain.gadgets.add(combat_gadget);
+        context.supervillain.treasury = Treasury::new(100);
+        let mut plan = PlanGraph::new();
+        plan.set_requirements(MissionRequirements {
+            gadgets: vec![(Capability::Combat, 50), (Capability::Escape, 30)],
+            budget: 500,
+            ..Default::default()
+        });
+
+        let issues = context.supervillain.validate_readiness(&plan);
+        assert_eq!(
+            issues,
+            vec![
+                ReadinessIssue::MissingGadget {
+                    capability: Capability::Escape,
+                    required: 30,
+                    best: 0
+                },
+                ReadinessIssue::InsufficientFunds {
+                    required: 500,
+                    balance: 100
+                },
+            ]
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn keep_sidekick_if_agrees_with_conspiracy(context: &mut Context<'_>) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_agree().once().return_const(true);
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.conspire();
+        assert_some!(&context.supervillain.sidekick, "Unexpected: Sidekick fired");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn fire_sidekick_if_doesnt_agree_with_conspiracy(context: &mut Context<'_>) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_agree().once().return_const(false);
+        mock_sidekick.expect_id().return_const(SidekickId::new(1));
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.conspire();
+        assert_none!(
+            &context.supervillain.sidekick,
+            "Unexpected: Sidekick didn't fire"
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn firing_sidekick_publishes_event(context: &mut Context<'_>) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_agree().once().return_const(false);
+        mock_sidekick.expect_id().return_const(SidekickId::new(1));
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .with(eq(EvilEvent::SidekickFired {
+                villain: test_common::PRIMARY_FULL_NAME.into(),
+                sidekick: SidekickId::new(1),
+            }))
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+        context.supervillain.conspire();
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn conspiracy_without_sidekick_doesnt_fail(context: &mut Context<'_>) {
+        context.supervillain.conspire();
+        assert_none!(&context.supervillain.sidekick, "Unexpected: no sidekick");
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn rearm_sidekick_equips_gadget_and_publishes_swap(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_equip_boxed()
+            .withf(|slot, _| *slot == Slot::Backup)
+            .once()
+            .returning(|_, _| None);
+        mock_sidekick.expect_id().return_const(SidekickId::new(7));
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .with(eq(EvilEvent::GadgetSwapped {
+                sidekick: SidekickId::new(7),
+                slot: Slot::Backup,
+            }))
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+
+        assert_ok!(
+            context
+                .supervillain
+                .rearm_sidekick(Slot::Backup, Box::new(MockGadget::new()))
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn rearm_without_sidekick_fails(context: &mut Context) {
+        let result = context
+            .supervillain
+            .rearm_sidekick(Slot::Primary, Box::new(MockGadget::new()));
+        assert_matches!(result, Err(EvilError::NoSidekick));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn disarm_sidekick_unequips_gadget_and_publishes_swap(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_unequip()
+            .with(eq(Slot::Primary))
+            .once()
+            .returning(|_| Some(Box::new(MockGadget::new())));
+        mock_sidekick.expect_id().return_const(SidekickId::new(7));
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .with(eq(EvilEvent::GadgetSwapped {
+                sidekick: SidekickId::new(7),
+                slot: Slot::Primary,
+            }))
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+
+        let disarmed = context.supervillain.disarm_sidekick(Slot::Primary)?;
+        assert!(disarmed.is_some());
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn swap_sidekick_slots_publishes_swap_of_both_slots(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_swap_slots().once().return_const(());
+        mock_sidekick.expect_id().return_const(SidekickId::new(7));
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut listener = MockEventListener::new();
+        for slot in [Slot::Primary, Slot::Backup] {
+            listener
+                .expect_on_event()
+                .with(eq(EvilEvent::GadgetSwapped {
+                    sidekick: SidekickId::new(7),
+                    slot,
+                }))
+                .once()
+                .return_const(());
+        }
+        context.supervillain.event_bus.subscribe(listener);
+
+        assert_ok!(context.supervillain.swap_sidekick_slots());
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_reuses_cached_scan(context: &mut Context) {
+        let mut gadget = MockGadget::new();
+        gadget.expect_rating().return_const(40);
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::FIRST_TARGET)))
+            .times(2)
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.target_cache = Some(TargetCache::new(8, Duration::from_secs(60)));
+
+        for _ in 0..2 {
+            assert_ok!(
+                context
+                    .supervillain
+                    .start_world_domination_stage1(&mut mock_henchman, &gadget)
+            );
+        }
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_builds_hq_in_first_weak_target(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::FIRST_TARGET)))
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut listener = MockEventListener::new();
+        listener
+            .expect_on_event()
+            .with(eq(EvilEvent::HqBuilt {
+                villain: test_common::PRIMARY_FULL_NAME.into(),
+                target: test_common::FIRST_TARGET.into(),
+            }))
+            .once()
+            .return_const(());
+        context.supervillain.event_bus.subscribe(listener);
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+        assert_eq!(
+            outcome?,
+            StageOutcome::HqBuilt {
+                target: test_common::FIRST_TARGET.into()
+            }
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_in_environment_avoids_unfavorable_target(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::TARGETS[1])))
+            .once()
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let mut environment = Environment::default();
+        environment.set_target_terrain(test_common::FIRST_TARGET, Terrain::Mountains);
+
+        let outcome = context.supervillain.start_world_domination_stage1_in(
+            &mut mock_henchman,
+            &gadget_dummy,
+            &environment,
+        );
+        assert_eq!(
+            outcome?,
+            StageOutcome::HqBuilt {
+                target: test_common::TARGETS[1].into()
+            }
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn paranoid_villain_builds_hq_in_most_defensible_target(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::TARGETS[2])))
+            .once()
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.personality = Personality::new(50, 90, 50);
+
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+        assert_eq!(
+            outcome?,
+            StageOutcome::HqBuilt {
+                target: test_common::TARGETS[2].into()
+            }
+        );
+        assert!(
+            context
+                .supervillain
+                .should_rotate_key(Duration::from_secs(6 * 3600))
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_uses_villain_target_strategy(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::TARGETS[2])))
+            .once()
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.target_strategy = Some(Box::new(
+            HighestValue::new().with_value(test_common::TARGETS[2], 1_000),
+        ));
+
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+        assert_eq!(
+            outcome?,
+            StageOutcome::HqBuilt {
+                target: test_common::TARGETS[2].into()
+            }
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_without_targets_builds_nothing(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().never();
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| vec![]);
+        context.supervillain.sidekick = Some(mock_sidekick);
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+        assert_eq!(outcome?, StageOutcome::NoTargets);
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_without_sidekick_fails(context: &mut Context) {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().never();
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+        assert_matches!(outcome, Err(EvilError::NoSidekick));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_with_inventory_uses_detection_gadget(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let mut detector = MockGadget::new();
+        detector
+            .expect_rating()
+            .with(eq(Capability::TargetDetection))
+            .return_const(90);
+        let mut inventory = GadgetInventory::new();
+        inventory.add(detector);
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .with(eq(String::from(test_common::FIRST_TARGET)))
+            .once()
+            .return_const(());
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1_with_inventory(&mut mock_henchman, &inventory);
+        assert_eq!(
+            outcome?,
+            StageOutcome::HqBuilt {
+                target: test_common::FIRST_TARGET.into()
+            }
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_with_inventory_skips_without_detection_gadget(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let mut fighter = MockGadget::new();
+        fighter.expect_rating().return_const(0);
+        let mut inventory = GadgetInventory::new();
+        inventory.add(fighter);
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().never();
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_get_weak_targets().never();
+        context.supervillain.sidekick = Some(mock_sidekick);
+
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage1_with_inventory(&mut mock_henchman, &inventory);
+        assert_eq!(outcome?, StageOutcome::NoSuitableGadget);
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage1_while_captured_fails(context: &mut Context) {
+        let gadget = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().never();
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_get_weak_targets().never();
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.status = VillainStatus::Captured;
+
+        let result = context
+            .supervillain
+            .start_world_domination_stage1(&mut mock_henchman, &gadget);
+        assert_matches!(result, Err(EvilError::Incarcerated));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage2_tells_henchman_to_do_hard_things_and_fight_with_enemies(
+        context: &mut Context,
+    ) {
+        let mut mock_henchman = MockHenchman::new();
+        let mut sequence = Sequence::new();
+
+        mock_henchman.expect_is_on_strike().return_const(false);
+        mock_henchman
+            .expect_fight_enemies()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+
+        mock_henchman
+            .expect_do_hard_things()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+
+        mock_henchman
+            .expect_report_progress()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(Progress::default());
+
+        assert_ok!(
+            context
+                .supervillain
+                .start_world_domination_stage2(test_common::FIRST_TARGET, mock_henchman)
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage2_surfaces_henchman_progress(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let progress = Progress {
+            percent_complete: 40,
+            casualties: 2,
+            blockers: vec![String::from("Hero in the way")],
+        };
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_is_on_strike().return_const(false);
+        mock_henchman.expect_fight_enemies().return_const(());
+        mock_henchman.expect_do_hard_things().return_const(());
+        mock_henchman
+            .expect_report_progress()
+            .once()
+            .return_const(progress.clone());
+
+        let outcome = context
+            .supervillain
+            .start_world_domination_stage2(test_common::FIRST_TARGET, mock_henchman);
+        assert_eq!(
+            outcome?,
+            StageOutcome::HenchmenDeployed {
+                hq: test_common::FIRST_TARGET.into(),
+                count: 1,
+                progress
+            }
+        );
+        Ok(())
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn world_domination_stage2_fails_when_henchmen_are_on_strike(context: &mut Context) {
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_is_on_strike().return_const(true);
+        mock_henchman.expect_fight_enemies().never();
+        mock_henchman.expect_do_hard_things().never();
+
+        let result = context
+            .supervillain
+            .start_world_domination_stage2(test_common::FIRST_TARGET, mock_henchman);
+        assert_matches!(result, Err(EvilError::HenchmenOnStrike));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn atomic_world_domination_rolls_back_when_henchmen_strike(context: &mut Context) {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        let mut sequence = Sequence::new();
+        mock_henchman
+            .expect_build_secret_hq()
+            .once()
+            .in_sequence(&mut sequence)
+            .return_const(());
+        mock_henchman.expect_is_on_strike().return_const(true);
+        mock_henchman
+            .expect_demolish_secret_hq()
+            .with(eq(String::from(test_common::FIRST_TARGET)))
+            .once()
+            .in_sequence(&mut sequence)
+            .returning(|_| Ok(()));
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.treasury = Treasury::new(1_000);
+
+        let result = context.supervillain.start_world_domination_atomically(
+            &mut mock_henchman,
+            &gadget_dummy,
+            600,
+        );
+        assert_matches!(result, Err(EvilError::HenchmenOnStrike));
+        assert_eq!(context.supervillain.treasury.balance(), 1_000);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn atomic_world_domination_reports_hqs_left_standing(context: &mut Context) {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().return_const(());
+        mock_henchman.expect_is_on_strike().return_const(true);
+        mock_henchman
+            .expect_demolish_secret_hq()
+            .once()
+            .returning(|location| Err(EvilError::HqStillStanding { location }));
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_get_weak_targets()
+            .once()
+            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.treasury = Treasury::new(1_000);
+
+        let result = context.supervillain.start_world_domination_atomically(
+            &mut mock_henchman,
+            &gadget_dummy,
+            600,
+        );
+        assert_matches!(
+            result,
+            Err(EvilError::RollbackFailed { steps, source })
+                if steps == ["build-hq"] && matches!(*source, EvilError::HenchmenOnStrike)
+        );
+        assert_eq!(context.supervillain.treasury.balance(), 1_000);
     }
 
-    pub fn tell_plans<C: Cipher>(&self, secret: &str, cipher: &C) {
-        if let Some(ref sidekick) = self.sidekick {
-            let ciphered_message = cipher.transform(secret, &self.shared_key);
-            sidekick.tell(&ciphered_message);
-        }
-    }
+    #[test_context(Context)]
+    #[test]
+    fn atomic_world_domination_without_funds_builds_nothing(context: &mut Context) {
+        let gadget_dummy = MockGadget::new();
+        let mut mock_henchman = MockHenchman::new();
+        mock_henchman.expect_build_secret_hq().never();
 
-    pub fn are_there_vulnerable_locations(&self) -> Option<bool> {
-        let mut listing = String::new();
+        let result = context.supervillain.start_world_domination_atomically(
+            &mut mock_henchman,
+            &gadget_dummy,
+            600,
+        );
+        assert_matches!(result, Err(EvilError::InsufficientFunds { .. }));
+    }
 
-        let Ok(mut file_listing) = File::open(LISTING_PATH) else {
-            return None;
-        };
+    #[test_context(Context)]
+    #[test]
+    fn distributed_key_shares_rebuild_shared_key(context: &mut Context) -> Result<(), EvilError> {
+        context.supervillain.shared_key = test_common::SHARED_KEY.into();
+        let shares = context.supervillain.distribute_key_shares(2, 3)?;
+        assert_eq!(shares.len(), 3);
+        assert_eq!(
+            secret_sharing::recover_key(&shares[1..])?,
+            test_common::SHARED_KEY
+        );
+        Ok(())
+    }
 
-        let Ok(_) = file_listing.read_to_string(&mut listing) else {
-            return None;
-        };
+    #[test_context(Context)]
+    #[test]
+    fn tell_plans_sends_ciphered_message(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_tell()
+            .withf(is_main_ciphered_envelope)
+            .once()
+            .return_const(());
+        context.supervillain.sidekick = Some(mock_sidekick);
 
-        for line in listing.lines() {
-            if line.ends_with("weak") {
-                return Some(true);
-            }
-        }
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher
+            .expect_transform()
+            .returning(|secret, _| String::from("+") + secret + "+");
+        mock_cipher
+            .expect_id()
+            .return_const(String::from(test_common::CIPHER_ID));
 
-        Some(false)
+        assert_ok!(
+            context
+                .supervillain
+                .tell_plans(test_common::MAIN_SECRET_MESSAGE, &mock_cipher)
+        );
     }
-}
 
-impl TryFrom<&str> for SuperVillain<'_> {
-    type Error = EvilError;
+    #[test_context(Context)]
+    #[test]
+    fn tell_plans_while_captured_fails(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_tell().never();
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.status = VillainStatus::Captured;
 
-    fn try_from(name: &str) -> Result<Self, Self::Error> {
-        let components = name.split_whitespace().collect::<Vec<_>>();
-        if components.len() < 2 {
-            Err(EvilError::ParseError {
-                purpose: "full_name".into(),
-                reason: "Too few arguments".into(),
-            })
-        } else {
-            Ok(Self {
-                first_name: components[0].into(),
-                last_name: components[1].into(),
-                ..Default::default()
-            })
-        }
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher.expect_transform().never();
+        let result = context
+            .supervillain
+            .tell_plans(test_common::MAIN_SECRET_MESSAGE, &mock_cipher);
+        assert_matches!(result, Err(EvilError::Incarcerated));
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::cipher::MockCipher;
-    use crate::gadget::MockGadget;
-    use crate::henchman::MockHenchman;
-    use crate::test_common;
-    use assertables::{assert_matches, assert_some};
-    use assertables::{assert_none, assert_some_eq_x};
-    use mockall::Sequence;
-    use mockall::predicate::eq;
-    use std::cell::RefCell;
-    use std::panic;
-    use test_context::AsyncTestContext;
-    use test_context::test_context;
 
     #[test_context(Context)]
     #[test]
-    fn full_name_returns_first_name_space_last_name(context: &mut Context) {
-        let full_name = context.supervillain.full_name();
+    fn blown_cover_in_hot_region_gets_villain_captured(context: &mut Context) {
+        context
+            .supervillain
+            .heat_map()
+            .raise(test_common::FIRST_TARGET, 60);
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_none!(context.supervillain.face_investigation(
+            80,
+            test_common::FIRST_TARGET,
+            &mut rng
+        ));
+
+        context.supervillain.disguise = Some(Disguise::new("Mr. Smith", 0));
+        assert_some_eq_x!(
+            context
+                .supervillain
+                .face_investigation(80, test_common::FIRST_TARGET, &mut rng),
+            Scrutiny::Blown
+        );
+        assert!(context.supervillain.disguise.is_none());
         assert_eq!(
-            full_name,
-            test_common::PRIMARY_FULL_NAME,
-            "Unexpected full name"
+            context
+                .supervillain
+                .heat_map()
+                .level(test_common::FIRST_TARGET),
+            HeatLevel::Manhunt
         );
+        assert_eq!(context.supervillain.status, VillainStatus::Captured);
     }
 
     #[test_context(Context)]
     #[test]
-    fn set_full_name_sets_first_and_last_names(context: &mut Context) {
+    fn attacks_and_heists_raise_the_heat_in_the_region(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let mut mock_weapon = MockMegaWeapon::new();
+        mock_weapon.expect_shoot().return_const(());
+        context.supervillain.attack(&mock_weapon, false)?;
+        assert!(
+            context
+                .supervillain
+                .heat_map()
+                .region(test_common::FIRST_TARGET)
+                .is_none()
+        );
+
+        context.supervillain.region = Some(test_common::FIRST_TARGET.into());
+        context.supervillain.attack(&mock_weapon, false)?;
+        let heat = |villain: &SuperVillain| {
+            villain
+                .heat_map()
+                .region(test_common::FIRST_TARGET)
+                .map_or(0, |region| region.heat)
+        };
+        assert_eq!(heat(&context.supervillain), Operation::Attack.heat());
+
+        let mut rng = StdRng::seed_from_u64(1);
         context
             .supervillain
-            .set_full_name(test_common::SECONDARY_FULL_NAME);
-        assert2::check!(context.supervillain.first_name == test_common::SECONDARY_FIRST_NAME);
-        assert2::assert!(context.supervillain.last_name == test_common::SECONDARY_LAST_NAME);
+            .pull_heist(100, 50, Difficulty::MEDIUM, &mut rng)?;
+        assert_eq!(
+            heat(&context.supervillain),
+            Operation::Attack.heat() + Operation::Heist.heat()
+        );
+        assert_eq!(
+            context
+                .supervillain
+                .heat_map()
+                .level(test_common::FIRST_TARGET),
+            HeatLevel::Noticed
+        );
+        Ok(())
     }
 
     #[test_context(Context)]
     #[test]
-    #[should_panic(expected = "Name must have first and last name, separated by a space")]
-    fn set_full_name_panics_with_empty_name(context: &mut Context) {
-        context.supervillain.set_full_name("");
-    }
-
-    #[test]
-    fn try_from_str_slice_produces_supervillain_full_with_first_and_last_name()
-    -> Result<(), EvilError> {
-        let supervillain = SuperVillain::try_from(test_common::SECONDARY_FULL_NAME)?;
-        assert_eq!(supervillain.first_name, test_common::SECONDARY_FIRST_NAME);
-        assert_eq!(supervillain.last_name, test_common::SECONDARY_LAST_NAME);
+    fn manhunts_make_heists_less_likely(context: &mut Context) -> Result<(), EvilError> {
+        context.supervillain.region = Some(test_common::FIRST_TARGET.into());
+        let mut successes = |heat: u32| -> Result<usize, EvilError> {
+            let mut rng = StdRng::seed_from_u64(1);
+            let mut successes = 0;
+            for _ in 0..200 {
+                *context.supervillain.heat_map() = HeatMap::new();
+                context
+                    .supervillain
+                    .heat_map()
+                    .raise(test_common::FIRST_TARGET, heat);
+                context.supervillain.status = VillainStatus::AtLarge;
+                let result =
+                    context
+                        .supervillain
+                        .pull_heist(100, 50, Difficulty::MEDIUM, &mut rng)?;
+                successes += usize::from(result.is_success());
+            }
+            Ok(successes)
+        };
+        let cold = successes(0)?;
+        let manhunt = successes(90)?;
+        assert!(manhunt < cold, "manhunt {manhunt} vs cold {cold}");
         Ok(())
     }
 
+    #[test_context(Context)]
     #[test]
-    fn try_from_str_slice_produces_error_with_less_than_two_substrings() {
-        let result = SuperVillain::try_from("");
-        let Err(error) = result else {
-            panic!("Unexpected value returned by try_from");
+    fn debrief_files_chatter_and_revises_plans(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_report()
+            .once()
+            .returning(|| SidekickReport {
+                target_updates: vec![
+                    TargetUpdate {
+                        target: test_common::FIRST_TARGET.into(),
+                        weak: true,
+                    },
+                    TargetUpdate {
+                        target: test_common::TARGETS[1].into(),
+                        weak: false,
+                    },
+                ],
+                morale: 10,
+                chatter: vec![HeroChatter {
+                    hero: test_common::HERO_NAME.into(),
+                    location: Some(test_common::FIRST_TARGET.into()),
+                    note: String::from("Allergic to green rocks"),
+                }],
+            });
+        mock_sidekick.expect_clear_report().once().return_const(());
+        context.supervillain.sidekick = Some(mock_sidekick);
+
+        let Ok(revisions) = context.supervillain.debrief_sidekick() else {
+            panic!("Unexpected error debriefing the sidekick");
         };
-        assert_matches!(error, EvilError::ParseError { purpose, reason } if purpose == "full_name" && reason == "Too few arguments");
+        assert_eq!(
+            revisions,
+            [
+                PlanRevision::StrikeTarget(test_common::FIRST_TARGET.into()),
+                PlanRevision::AbandonTarget(test_common::TARGETS[1].into()),
+                PlanRevision::ReplaceSidekick,
+            ]
+        );
+        let Some(dossier) = context.supervillain.dossiers.get(test_common::HERO_NAME) else {
+            panic!("Chatter should open a dossier");
+        };
+        assert_eq!(dossier.sightings.len(), 1);
+        assert_eq!(dossier.intel.len(), 1);
     }
 
     #[test_context(Context)]
     #[test]
-    fn non_intense_attack_shoots_weapon_once(context: &mut Context) {
-        let mut weapon = MockMegaWeapon::new();
-        weapon.expect_shoot().once().return_const(());
-        context.supervillain.attack(&weapon, false);
+    fn debrief_without_sidekick_fails(context: &mut Context) {
+        context.supervillain.sidekick = None;
+        assert_matches!(
+            context.supervillain.debrief_sidekick(),
+            Err(EvilError::NoSidekick)
+        );
     }
 
     #[test_context(Context)]
     #[test]
-    fn intensive_attack_shoots_weapon_twice_or_more(context: &mut Context) {
-        let mut weapon = MockMegaWeapon::new();
-        weapon.expect_shoot().times(2..=3).return_const(());
-        context.supervillain.attack(&weapon, true);
-    }
+    fn broadcast_ciphers_with_each_key_and_reports_failures(context: &mut Context) {
+        let recipient = |name: &'static str, key: &'static str, delivered: bool| {
+            let mut mock_recipient = MockRecipient::new();
+            mock_recipient
+                .expect_name()
+                .return_const(String::from(name));
+            mock_recipient
+                .expect_tell()
+                .withf(move |message| {
+                    String::from_utf8_lossy(message).contains(&format!("+{key}+"))
+                })
+                .once()
+                .returning(move |_| {
+                    if delivered {
+                        Ok(())
+                    } else {
+                        Err(EvilError::NotificationError {
+                            reason: String::from("Line tapped"),
+                        })
+                    }
+                });
+            (mock_recipient, key)
+        };
+        let recipients = [
+            recipient("Joker", "Ha", true),
+            recipient("Penguin", "Umbrella", false),
+        ];
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher
+            .expect_transform()
+            .returning(|_, key| String::from("+") + key + "+");
+        mock_cipher
+            .expect_id()
+            .return_const(String::from(test_common::CIPHER_ID));
 
-    #[test_context(Context)]
-    #[tokio::test]
-    async fn plan_is_sadly_expected(context: &mut Context<'_>) {
-        assert_eq!(
-            context.supervillain.come_up_with_plan().await,
-            "Take over the world!"
+        let deliveries = context.supervillain.tell_plans_broadcast(
+            test_common::MAIN_SECRET_MESSAGE,
+            &recipients,
+            &mock_cipher,
         );
+        let delivered = deliveries
+            .iter()
+            .map(|delivery| (delivery.recipient.as_str(), delivery.is_delivered()))
+            .collect::<Vec<_>>();
+        assert_eq!(delivered, [("Joker", true), ("Penguin", false)]);
     }
 
     #[test_context(Context)]
     #[test]
-    fn keep_sidekick_if_agrees_with_conspiracy(context: &mut Context<'_>) {
+    fn tell_plans_after_firing_sidekick_fails(context: &mut Context) {
         let mut mock_sidekick = Sidekick::new();
-        mock_sidekick.expect_agree().once().return_const(true);
+        mock_sidekick.expect_agree().once().return_const(false);
+        mock_sidekick.expect_id().return_const(SidekickId::new(1));
+        mock_sidekick.expect_tell().never();
         context.supervillain.sidekick = Some(mock_sidekick);
         context.supervillain.conspire();
-        assert_some!(&context.supervillain.sidekick, "Unexpected: Sidekick fired");
+
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher.expect_transform().never();
+        let result = context
+            .supervillain
+            .tell_plans(test_common::MAIN_SECRET_MESSAGE, &mock_cipher);
+        assert_matches!(result, Err(EvilError::NoSidekick));
     }
 
     #[test_context(Context)]
     #[test]
-    fn fire_sidekick_if_doesnt_agree_with_conspiracy(context: &mut Context<'_>) {
+    fn tell_plans_to_sidekick_on_holiday_fails(context: &mut Context) -> Result<(), EvilError> {
+        let hour = Duration::from_secs(3600);
+        let now = SystemTime::UNIX_EPOCH + 1000 * hour;
+        let mut clock = MockClock::new();
+        clock.expect_system_time().return_const(now);
+        context.supervillain.clock = Some(Box::new(clock));
         let mut mock_sidekick = Sidekick::new();
-        mock_sidekick.expect_agree().once().return_const(false);
+        mock_sidekick.expect_tell().never();
         context.supervillain.sidekick = Some(mock_sidekick);
-        context.supervillain.conspire();
-        assert_none!(
-            &context.supervillain.sidekick,
-            "Unexpected: Sidekick didn't fire"
-        );
+        context
+            .supervillain
+            .sidekick_calendar
+            .holiday(now - hour, now + hour)?;
+
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher.expect_transform().never();
+        let result = context
+            .supervillain
+            .tell_plans(test_common::MAIN_SECRET_MESSAGE, &mock_cipher);
+        assert_matches!(result, Err(EvilError::SidekickUnavailable { reason, .. }) if reason == "On holiday");
+        assert_eq!(context.supervillain.next_available_at()?, now + hour);
+        Ok(())
     }
 
     #[test_context(Context)]
     #[test]
-    fn conspiracy_without_sidekick_doesnt_fail(context: &mut Context<'_>) {
+    fn sidekicks_appointed_or_fired_start_with_a_clear_calendar(
+        context: &mut Context,
+    ) -> Result<(), EvilError> {
+        let now = SystemTime::now();
+        let hour = Duration::from_secs(3600);
+        context
+            .supervillain
+            .sidekick_calendar
+            .busy(now, now + hour)?;
+        context.supervillain.appoint_sidekick(Sidekick::new());
+        assert!(context.supervillain.sidekick_calendar.windows().is_empty());
+
+        context
+            .supervillain
+            .sidekick_calendar
+            .busy(now, now + hour)?;
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick.expect_agree().return_const(false);
+        mock_sidekick.expect_id().return_const(SidekickId::new(1));
+        context.supervillain.appoint_sidekick(mock_sidekick);
+        context
+            .supervillain
+            .sidekick_calendar
+            .busy(now, now + hour)?;
         context.supervillain.conspire();
-        assert_none!(&context.supervillain.sidekick, "Unexpected: no sidekick");
+        assert!(context.supervillain.sidekick.is_none());
+        assert!(context.supervillain.sidekick_calendar.windows().is_empty());
+        Ok(())
     }
 
     #[test_context(Context)]
     #[test]
-    fn world_domination_stage1_builds_hq_in_first_weak_target(context: &mut Context) {
-        let gadget_dummy = MockGadget::new();
-        let mut mock_henchman = MockHenchman::new();
-        mock_henchman
-            .expect_build_secret_hq()
-            .with(eq(String::from(test_common::FIRST_TARGET)))
-            .return_const(());
+    fn handshake_without_common_cipher_fails(context: &mut Context) {
         let mut mock_sidekick = Sidekick::new();
-        mock_sidekick
-            .expect_get_weak_targets()
-            .once()
-            .returning(|_| test_common::TARGETS.map(String::from).to_vec());
+        mock_sidekick.expect_accept_handshake().return_const(None);
         context.supervillain.sidekick = Some(mock_sidekick);
-        context
+
+        let result = context
             .supervillain
-            .start_world_domination_stage1(&mut mock_henchman, &gadget_dummy);
+            .handshake(&[CipherSuite::new(test_common::CIPHER_ID)]);
+        assert_matches!(result, Err(EvilError::NoCommonCipher));
     }
 
     #[test_context(Context)]
     #[test]
-    fn world_domination_stage2_tells_henchman_to_do_hard_things_and_fight_with_enemies(
-        context: &mut Context,
-    ) {
-        let mut mock_henchman = MockHenchman::new();
-        let mut sequence = Sequence::new();
-
-        mock_henchman
-            .expect_fight_enemies()
+    fn tell_plans_in_session_ciphers_with_session_key(context: &mut Context) {
+        let mut mock_sidekick = Sidekick::new();
+        mock_sidekick
+            .expect_accept_handshake()
+            .returning(|offered, _| offered.last().cloned());
+        mock_sidekick
+            .expect_tell()
+            .withf(is_main_ciphered_envelope)
             .once()
-            .in_sequence(&mut sequence)
             .return_const(());
+        context.supervillain.sidekick = Some(mock_sidekick);
+        context.supervillain.shared_key = test_common::SHARED_KEY.into();
 
-        mock_henchman
-            .expect_do_hard_things()
-            .once()
-            .in_sequence(&mut sequence)
-            .return_const(());
+        let Ok(session) = context.supervillain.handshake(&[
+            CipherSuite::new("one-time-pad"),
+            CipherSuite::new(test_common::CIPHER_ID),
+        ]) else {
+            panic!("Handshake should succeed");
+        };
+        assert_eq!(session.suite, CipherSuite::new(test_common::CIPHER_ID));
+        assert_ne!(session.key.expose_secret(), test_common::SHARED_KEY);
 
-        context
-            .supervillain
-            .start_world_domination_stage2(mock_henchman);
+        let session_key = session.key.expose_secret().clone();
+        let mut mock_cipher = MockCipher::new();
+        mock_cipher
+            .expect_transform()
+            .withf(move |_, key| key == session_key)
+            .returning(|secret, _| String::from("+") + secret + "+");
+        mock_cipher
+            .expect_id()
+            .return_const(String::from(test_common::CIPHER_ID));
+
+        assert_ok!(context.supervillain.tell_plans_in_session(
+            test_common::MAIN_SECRET_MESSAGE,
+            &mock_cipher,
+            &session
+        ));
     }
 
     #[test_context(Context)]
-    #[test]
-    fn tell_plans_sends_ciphered_message(context: &mut Context) {
+    #[tokio::test]
+    async fn tell_plans_async_sends_ciphered_message(context: &mut Context<'_>) {
         let mut mock_sidekick = Sidekick::new();
         mock_sidekick
             .expect_tell()
-            .with(eq(String::from(test_common::MAIN_CIPHERED_MESSAGE)))
+            .withf(is_main_ciphered_envelope)
             .once()
             .return_const(());
         context.supervillain.sidekick = Some(mock_sidekick);
@@ -340,10 +2985,89 @@ mod tests {
         mock_cipher
             .expect_transform()
             .returning(|secret, _| String::from("+") + secret + "+");
+        mock_cipher
+            .expect_id()
+            .return_const(String::from(test_common::CIPHER_ID));
+        let cipher = SyncCipherAdapter::new(mock_cipher);
+
+        assert_ok!(
+            context
+                .supervillain
+                .tell_plans_async(test_common::MAIN_SECRET_MESSAGE, &cipher)
+                .await
+        );
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn paid_ransom_goes_to_villain_treasury(context: &mut Context) {
+        let mut negotiator = MockNegotiator::new();
+        negotiator.expect_respond().return_const(Response::Accept);
+        let hostage = Hostage {
+            name: String::from("Lois Lane"),
+            value: 5000,
+        };
+        let mut negotiation = Negotiation::new(hostage, 1000, 500, 3);
+
+        let outcome = context
+            .supervillain
+            .negotiate_ransom(&mut negotiation, &mut negotiator);
+
+        assert_eq!(outcome, NegotiationOutcome::Paid { amount: 1000 });
+        assert_eq!(context.supervillain.treasury.balance(), 1000);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn refused_ransom_makes_villain_furious(context: &mut Context) {
+        let mut negotiator = MockNegotiator::new();
+        negotiator.expect_respond().return_const(Response::Refuse);
+        let hostage = Hostage {
+            name: String::from("Lois Lane"),
+            value: 5000,
+        };
+        let mut negotiation = Negotiation::new(hostage, 1000, 500, 3);
 
         context
             .supervillain
-            .tell_plans(test_common::MAIN_SECRET_MESSAGE, &mock_cipher);
+            .negotiate_ransom(&mut negotiation, &mut negotiator);
+
+        assert_eq!(context.supervillain.mood, Mood::Furious);
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn furious_villain_always_attacks_intensely(context: &mut Context) {
+        let mut weapon = MockMegaWeapon::new();
+        weapon.expect_shoot().times(2..=3).return_const(());
+        context.supervillain.react_to(MoodTrigger::HeistFailed);
+        assert_ok!(context.supervillain.attack(&weapon, false));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn desperate_villain_accepts_worse_alliance_terms(context: &mut Context) {
+        assert!(!context.supervillain.accepts_alliance_share(30));
+        context.supervillain.react_to(MoodTrigger::Broke);
+        assert!(context.supervillain.accepts_alliance_share(30));
+    }
+
+    #[test_context(Context)]
+    #[test]
+    fn complete_trophy_sets_add_to_infamy(context: &mut Context) {
+        context.supervillain.infamy = Infamy::new(40);
+        assert_eq!(context.supervillain.total_infamy(), Infamy::new(40));
+        let mut lair = Lair::new(test_common::FIRST_TARGET);
+        lair.trophy_room
+            .add_set(TrophySet::new("Superman's outfit", &["Cape", "Boots"], 100));
+        for piece in ["Cape", "Boots"] {
+            let source = TrophySource::DefeatedHero(String::from("Superman"));
+            lair.trophy_room.display(Trophy::new(piece, source, 50, 2));
+        }
+        context.supervillain.lair = Some(lair);
+
+        assert_eq!(context.supervillain.total_infamy(), Infamy::new(140));
+        assert_eq!(context.supervillain.infamy, Infamy::new(40));
     }
 
     #[test_context(Context)]
@@ -382,6 +3106,28 @@ mod tests {
         assert_some_eq_x!(context.supervillain.are_there_vulnerable_locations(), false);
     }
 
+    fn is_main_ciphered_envelope(message: &Arc<[u8]>) -> bool {
+        MessageEnvelope::from_wire(message).is_ok_and(|envelope| {
+            envelope.sender == test_common::PRIMARY_FULL_NAME
+                && envelope.cipher_id == test_common::CIPHER_ID
+                && envelope.payload.expose_secret() == test_common::MAIN_CIPHERED_MESSAGE
+        })
+    }
+
+    fn cooldown_with_attacks_at<'a>(seconds: &[u64]) -> Cooldown<'a> {
+        let start = Instant::now();
+        let mut instants = seconds
+            .iter()
+            .map(|&second| start + Duration::from_secs(second))
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut clock = MockClock::new();
+        clock
+            .expect_now()
+            .returning(move || instants.next().unwrap());
+        Cooldown::with_clock(Duration::from_secs(5), clock)
+    }
+
     thread_local! {
         static FILE_OPEN_OK: RefCell<Option<doubles::File>> = const { RefCell::new(None) };
     }
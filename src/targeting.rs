@@ -0,0 +1,354 @@
+//! Module for the strategies villains follow to choose where to strike.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use std::time::Instant;
+
+use rand::Rng;
+use rand::seq::IndexedRandom;
+
+use crate::clock::Clock;
+use crate::clock::SystemClock;
+use crate::environment::Terrain;
+use crate::gadget::Capability;
+use crate::gadget::Gadget;
+use crate::units::Distance;
+
+/// Picks one target out of the weak targets found, which come ranked weakest first.
+pub trait TargetSelectionStrategy {
+    fn select(&self, targets: &[String]) -> Option<String>;
+}
+
+/// The weakest target, which is what villains do unless told otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WeakestFirst;
+
+impl TargetSelectionStrategy for WeakestFirst {
+    fn select(&self, targets: &[String]) -> Option<String> {
+        targets.first().cloned()
+    }
+}
+
+/// The strongest of the weak targets, where an HQ is easiest to defend, which is what paranoid
+/// villains do.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MostDefensible;
+
+impl TargetSelectionStrategy for MostDefensible {
+    fn select(&self, targets: &[String]) -> Option<String> {
+        targets.last().cloned()
+    }
+}
+
+/// The closest target. Targets at an unknown distance go last; ties go to the weakest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClosestFirst {
+    distances: HashMap<String, Distance>,
+}
+
+impl ClosestFirst {
+    pub fn new() -> ClosestFirst {
+        Self::default()
+    }
+
+    pub fn with_distance(mut self, target: &str, distance: Distance) -> ClosestFirst {
+        self.distances.insert(target.into(), distance);
+        self
+    }
+}
+
+impl TargetSelectionStrategy for ClosestFirst {
+    fn select(&self, targets: &[String]) -> Option<String> {
+        targets
+            .iter()
+            .min_by_key(|target| match self.distances.get(*target) {
+                Some(distance) => (false, *distance),
+                None => (true, Distance::ZERO),
+            })
+            .cloned()
+    }
+}
+
+/// The most valuable target. Targets of unknown value are worth nothing; ties go to the
+/// weakest.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HighestValue {
+    values: HashMap<String, u64>,
+}
+
+impl HighestValue {
+    pub fn new() -> HighestValue {
+        Self::default()
+    }
+
+    pub fn with_value(mut self, target: &str, value: u64) -> HighestValue {
+        self.values.insert(target.into(), value);
+        self
+    }
+}
+
+impl TargetSelectionStrategy for HighestValue {
+    fn select(&self, targets: &[String]) -> Option<String> {
+        targets
+            .iter()
+            .min_by_key(|target| Reverse(self.values.get(*target).copied().unwrap_or(0)))
+            .cloned()
+    }
+}
+
+/// Any target, chosen with the given RNG, so the choice can be replayed with a seeded one.
+#[derive(Debug)]
+pub struct Random<R> {
+    rng: RefCell<R>,
+}
+
+impl<R: Rng> Random<R> {
+    pub fn new(rng: R) -> Random<R> {
+        Self {
+            rng: RefCell::new(rng),
+        }
+    }
+}
+
+impl<R: Rng> TargetSelectionStrategy for Random<R> {
+    fn select(&self, targets: &[String]) -> Option<String> {
+        targets.choose(&mut *self.rng.borrow_mut()).cloned()
+    }
+}
+
+/// What a scan for weak targets depends on: how good the gadget is at everything, so gadgets
+/// rated the same scan the same, and the region scanned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ScanKey {
+    ratings: [u8; Capability::ALL.len()],
+    pub region: Terrain,
+}
+
+impl ScanKey {
+    pub fn new(gadget: &(impl Gadget + ?Sized), region: Terrain) -> ScanKey {
+        Self {
+            ratings: Capability::ALL.map(|capability| gadget.rating(capability)),
+            region,
+        }
+    }
+}
+
+struct CachedScan {
+    targets: Vec<String>,
+    scanned_at: Instant,
+    last_used: u64,
+}
+
+/// Weak targets found by recent scans, so the same region isn't scanned again with the same
+/// kind of gadget. Scans expire after a while, and the least recently used go first when the
+/// cache is full.
+pub struct TargetCache<'a> {
+    capacity: usize,
+    ttl: Duration,
+    clock: Box<dyn Clock + Send + 'a>,
+    scans: RefCell<HashMap<ScanKey, CachedScan>>,
+    uses: Cell<u64>,
+}
+
+impl<'a> TargetCache<'a> {
+    pub fn new(capacity: usize, ttl: Duration) -> TargetCache<'a> {
+        Self::with_clock(capacity, ttl, SystemClock)
+    }
+
+    pub fn with_clock<C: Clock + Send + 'a>(
+        capacity: usize,
+        ttl: Duration,
+        clock: C,
+    ) -> TargetCache<'a> {
+        Self {
+            capacity,
+            ttl,
+            clock: Box::new(clock),
+            scans: RefCell::new(HashMap::new()),
+            uses: Cell::new(0),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scans.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scans.borrow().is_empty()
+    }
+
+    /// Targets of a fresh scan for the key, or else of running `scan`, which are then kept.
+    pub fn get_or_scan(&self, key: ScanKey, scan: impl FnOnce() -> Vec<String>) -> Vec<String> {
+        let now = self.clock.now();
+        let used = self.uses.get() + 1;
+        self.uses.set(used);
+        if let Some(cached) = self.scans.borrow_mut().get_mut(&key)
+            && now.saturating_duration_since(cached.scanned_at) < self.ttl
+        {
+            cached.last_used = used;
+            return cached.targets.clone();
+        }
+        let targets = scan();
+        if self.capacity == 0 {
+            return targets;
+        }
+        let mut scans = self.scans.borrow_mut();
+        scans.retain(|_, cached| now.saturating_duration_since(cached.scanned_at) < self.ttl);
+        if scans.len() >= self.capacity
+            && !scans.contains_key(&key)
+            && let Some(oldest) = scans
+                .iter()
+                .min_by_key(|(_, cached)| cached.last_used)
+                .map(|(key, _)| *key)
+        {
+            scans.remove(&oldest);
+        }
+        scans.insert(
+            key,
+            CachedScan {
+                targets: targets.clone(),
+                scanned_at: now,
+                last_used: used,
+            },
+        );
+        targets
+    }
+
+    /// Forgets the scan for the key, e.g. after a target there got stronger.
+    pub fn invalidate(&self, key: &ScanKey) {
+        self.scans.borrow_mut().remove(key);
+    }
+
+    /// Forgets every scan of the region.
+    pub fn invalidate_region(&self, region: Terrain) {
+        self.scans
+            .borrow_mut()
+            .retain(|key, _| key.region != region);
+    }
+
+    pub fn clear(&self) {
+        self.scans.borrow_mut().clear();
+    }
+}
+
+impl fmt::Debug for TargetCache<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TargetCache")
+            .field("capacity", &self.capacity)
+            .field("ttl", &self.ttl)
+            .field("len", &self.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::MockClock;
+    use crate::test_common;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    fn targets() -> Vec<String> {
+        test_common::TARGETS.map(String::from).to_vec()
+    }
+
+    #[test]
+    fn closest_first_leaves_unknown_distances_last() {
+        let strategy = ClosestFirst::new()
+            .with_distance(test_common::TARGETS[1], Distance::from_km(900))
+            .with_distance(test_common::TARGETS[2], Distance::from_km(300));
+        assert_eq!(
+            strategy.select(&targets()).as_deref(),
+            Some(test_common::TARGETS[2])
+        );
+        assert_eq!(
+            ClosestFirst::new().select(&targets()).as_deref(),
+            Some(test_common::FIRST_TARGET)
+        );
+    }
+
+    #[test]
+    fn highest_value_prefers_weakest_on_ties() {
+        let strategy = HighestValue::new()
+            .with_value(test_common::TARGETS[1], 500)
+            .with_value(test_common::TARGETS[2], 500);
+        assert_eq!(
+            strategy.select(&targets()).as_deref(),
+            Some(test_common::TARGETS[1])
+        );
+    }
+
+    #[derive(Clone)]
+    struct Scanner(u8);
+
+    impl Gadget for Scanner {
+        fn do_stuff(&self) {}
+
+        fn rating(&self, _capability: Capability) -> u8 {
+            self.0
+        }
+    }
+
+    #[test]
+    fn cache_rescans_only_after_expiry() {
+        let start = Instant::now();
+        let mut clock = MockClock::new();
+        let mut times = [0, 30, 90].into_iter();
+        clock
+            .expect_now()
+            .returning(move || start + Duration::from_secs(times.next().unwrap_or(90)));
+        let cache = TargetCache::with_clock(4, Duration::from_secs(60), clock);
+        let key = ScanKey::new(&Scanner(50), Terrain::Urban);
+        let scans = Cell::new(0);
+        let scan = || {
+            scans.set(scans.get() + 1);
+            targets()
+        };
+
+        assert_eq!(cache.get_or_scan(key, scan), targets());
+        assert_eq!(cache.get_or_scan(key, scan), targets());
+        assert_eq!(scans.get(), 1);
+        cache.get_or_scan(key, scan);
+        assert_eq!(scans.get(), 2);
+    }
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let cache = TargetCache::new(2, Duration::from_secs(60));
+        let keys = [10, 20, 30].map(|rating| ScanKey::new(&Scanner(rating), Terrain::Urban));
+        cache.get_or_scan(keys[0], targets);
+        cache.get_or_scan(keys[1], targets);
+        cache.get_or_scan(keys[0], Vec::new);
+        cache.get_or_scan(keys[2], targets);
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get_or_scan(keys[0], Vec::new), targets());
+        assert!(cache.get_or_scan(keys[1], Vec::new).is_empty());
+    }
+
+    #[test]
+    fn invalidating_a_region_forgets_its_scans() {
+        let cache = TargetCache::new(4, Duration::from_secs(60));
+        cache.get_or_scan(ScanKey::new(&Scanner(10), Terrain::Urban), targets);
+        cache.get_or_scan(ScanKey::new(&Scanner(10), Terrain::Desert), targets);
+        cache.invalidate_region(Terrain::Urban);
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn random_with_same_seed_picks_same_target() {
+        let first = Random::new(StdRng::seed_from_u64(3)).select(&targets());
+        let second = Random::new(StdRng::seed_from_u64(3)).select(&targets());
+        assert!(first.is_some());
+        assert_eq!(first, second);
+        assert!(Random::new(StdRng::seed_from_u64(3)).select(&[]).is_none());
+    }
+}
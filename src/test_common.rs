@@ -5,6 +5,10 @@ pub const SECONDARY_FIRST_NAME: &str = "Darth";
 pub const SECONDARY_LAST_NAME: &str = "Vader";
 pub const SECONDARY_FULL_NAME: &str = "Darth Vader";
 pub const FIRST_TARGET: &str = "Tampa";
-pub const TARGETS: [&'static str; 3] = [FIRST_TARGET, "Pamplona", "Vilnius"];
+pub const TARGETS: [&str; 3] = [FIRST_TARGET, "Pamplona", "Vilnius"];
 pub const MAIN_SECRET_MESSAGE: &str = "Nobody should know this";
 pub const MAIN_CIPHERED_MESSAGE: &str = "+Nobody should know this+";
+pub const PRISON_FACILITY: &str = "Arkham Asylum";
+pub const HERO_NAME: &str = "Superman";
+pub const CIPHER_ID: &str = "plus-wrap";
+pub const SHARED_KEY: &str = "Kryptonite";
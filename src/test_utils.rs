@@ -0,0 +1,93 @@
+//! Module with test doubles for code that drives villains.
+//!
+//! Available with the `test-utils` feature, so downstream crates can use the same doubles as
+//! this crate's own tests instead of rolling their own.
+#![allow(dead_code)]
+
+use std::cell::Cell;
+use std::time::Duration;
+use std::time::Instant;
+
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::clock::Clock;
+
+pub use crate::cipher::MockCipher;
+pub use crate::clock::MockClock;
+pub use crate::gadget::MockGadget;
+pub use crate::henchman::MockHenchman;
+pub use crate::sidekick::MockSidekick;
+
+/// Clock that moves forward by a fixed step every time it is read, and can be sent to any
+/// point in the future with [`AutoAdvancingClock::travel`], so time-dependent code runs
+/// deterministically and without sleeping.
+#[derive(Debug)]
+pub struct AutoAdvancingClock {
+    now: Cell<Instant>,
+    step: Duration,
+}
+
+impl AutoAdvancingClock {
+    /// Clock starting now that advances `step` after every read.
+    pub fn new(step: Duration) -> AutoAdvancingClock {
+        Self::starting_at(Instant::now(), step)
+    }
+
+    pub fn starting_at(start: Instant, step: Duration) -> AutoAdvancingClock {
+        Self {
+            now: Cell::new(start),
+            step,
+        }
+    }
+
+    /// Clock that only moves when told to.
+    pub fn frozen() -> AutoAdvancingClock {
+        Self::new(Duration::ZERO)
+    }
+
+    /// Moves the clock forward without reading it.
+    pub fn travel(&self, duration: Duration) {
+        self.now.set(self.now.get() + duration);
+    }
+
+    /// Time the next read will return.
+    pub fn peek(&self) -> Instant {
+        self.now.get()
+    }
+}
+
+impl Clock for AutoAdvancingClock {
+    fn now(&self) -> Instant {
+        let now = self.now.get();
+        self.now.set(now + self.step);
+        now
+    }
+}
+
+/// RNG that yields the same values for the same seed, for reproducible random behavior.
+pub fn seeded_rng(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn clock_advances_after_every_read() {
+        let clock = AutoAdvancingClock::new(Duration::from_secs(2));
+        let first = clock.now();
+        clock.travel(Duration::from_secs(60));
+        assert_eq!(clock.now() - first, Duration::from_secs(62));
+        assert_eq!(clock.peek() - first, Duration::from_secs(64));
+    }
+
+    #[test]
+    fn same_seed_yields_same_values() {
+        let first: u64 = seeded_rng(7).random();
+        let second: u64 = seeded_rng(7).random();
+        assert_eq!(first, second);
+    }
+}
@@ -0,0 +1,173 @@
+//! Module with property-based testing support for code handling villains.
+//!
+//! Available with the `testing` feature. Besides the [`Arbitrary`] implementations, it offers
+//! strategies for names, so full names can be generated valid or invalid on purpose.
+//! Targets are plain strings in this crate, so they are generated with [`target_name`].
+
+use std::time::Duration;
+
+use proptest::arbitrary::Arbitrary;
+use proptest::arbitrary::any;
+use proptest::collection;
+use proptest::prop_oneof;
+use proptest::strategy::BoxedStrategy;
+use proptest::strategy::Just;
+use proptest::strategy::Strategy;
+
+use crate::SuperVillain;
+use crate::plan::PlanGraph;
+use crate::plan::PlanStep;
+use crate::plan::StepAction;
+use crate::supervillain::VillainStatus;
+
+const MAX_PLAN_STEPS: usize = 8;
+const MAX_PLAN_DEPENDENCIES: usize = 16;
+
+/// A single capitalized name, like `"Lex"`.
+pub fn name() -> impl Strategy<Value = String> {
+    "[A-Z][a-z]{1,11}"
+}
+
+/// A full name that [`SuperVillain::try_from`] accepts: a first and last name, a mononym or a
+/// stage name.
+pub fn valid_full_name() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (name(), name()).prop_map(|(first_name, last_name)| format!("{first_name} {last_name}")),
+        name(),
+        name().prop_map(|name| format!("The {name}")),
+    ]
+}
+
+/// A full name that [`SuperVillain::try_from`] rejects for having no components.
+pub fn invalid_full_name() -> impl Strategy<Value = String> {
+    prop_oneof![Just(String::new()), "[ \t]{1,3}"]
+}
+
+/// Name of a place worth attacking.
+pub fn target_name() -> impl Strategy<Value = String> {
+    "[A-Z][a-z]{2,9}( [A-Z][a-z]{2,9})?"
+}
+
+impl Arbitrary for VillainStatus {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            Just(VillainStatus::AtLarge),
+            Just(VillainStatus::Captured),
+            name().prop_map(|facility| VillainStatus::Imprisoned { facility }),
+            Just(VillainStatus::Escaped),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for SuperVillain<'static> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (name(), name(), "[a-zA-Z0-9]{0,16}", any::<VillainStatus>())
+            .prop_map(|(first_name, last_name, shared_key, status)| SuperVillain {
+                first_name,
+                last_name,
+                shared_key: shared_key.into(),
+                status,
+                ..Default::default()
+            })
+            .boxed()
+    }
+}
+
+impl Arbitrary for StepAction {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        prop_oneof![
+            target_name().prop_map(StepAction::BuildSecretHq),
+            Just(StepAction::DoHardThings),
+            Just(StepAction::FightEnemies),
+        ]
+        .boxed()
+    }
+}
+
+impl Arbitrary for PlanStep {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (name(), 1u64..3600, any::<StepAction>())
+            .prop_map(|(name, seconds, action)| {
+                PlanStep::new(&name, Duration::from_secs(seconds), action)
+            })
+            .boxed()
+    }
+}
+
+/// Generates acyclic plans: steps only ever depend on steps added before them.
+impl Arbitrary for PlanGraph {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (
+            collection::vec(any::<PlanStep>(), 0..MAX_PLAN_STEPS),
+            collection::vec(any::<(usize, usize)>(), 0..MAX_PLAN_DEPENDENCIES),
+        )
+            .prop_map(|(steps, dependencies)| {
+                let mut graph = PlanGraph::new();
+                let ids = steps
+                    .into_iter()
+                    .map(|step| graph.add_step(step))
+                    .collect::<Vec<_>>();
+                if ids.is_empty() {
+                    return graph;
+                }
+                for (step, depends_on) in dependencies {
+                    let (step, depends_on) = (step % ids.len(), depends_on % ids.len());
+                    if step > depends_on {
+                        graph
+                            .add_dependency(ids[step], ids[depends_on])
+                            .expect("Steps were just added");
+                    }
+                }
+                graph
+            })
+            .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prop_assert;
+    use proptest::prop_assert_eq;
+    use proptest::proptest;
+
+    proptest! {
+        #[test]
+        fn valid_full_names_are_parsed(full_name in valid_full_name()) {
+            let villain = SuperVillain::try_from(full_name.as_str());
+            prop_assert!(villain.is_ok());
+            prop_assert_eq!(villain.unwrap().full_name(), full_name);
+        }
+
+        #[test]
+        fn invalid_full_names_are_rejected(full_name in invalid_full_name()) {
+            prop_assert!(SuperVillain::try_from(full_name.as_str()).is_err());
+        }
+
+        #[test]
+        fn arbitrary_plans_are_acyclic(graph in any::<PlanGraph>()) {
+            prop_assert_eq!(graph.topological_order().map(|order| order.len()).ok(), Some(graph.len()));
+        }
+
+        #[test]
+        fn arbitrary_villains_have_valid_full_names(villain in any::<SuperVillain<'static>>()) {
+            prop_assert!(SuperVillain::try_from(villain.full_name().as_str()).is_ok());
+        }
+    }
+}
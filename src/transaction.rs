@@ -0,0 +1,197 @@
+//! Module to run several operations as one: either they all succeed, or the ones done are
+//! undone.
+#![allow(dead_code)]
+
+use crate::supervillain::EvilError;
+
+type Compensation<'t, C> = Box<dyn FnOnce(&mut C) -> Result<(), EvilError> + 't>;
+
+/// What a rollback undid, and what it couldn't.
+#[derive(Debug)]
+pub struct Rollback {
+    /// Names of the steps undone, last done first.
+    pub undone: Vec<String>,
+    /// Steps left done, with why their compensation failed.
+    pub failed: Vec<(String, EvilError)>,
+}
+
+impl Rollback {
+    /// Whether every step was undone.
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+}
+
+/// Operations done so far on a context `C`, each with the compensating action that undoes it.
+///
+/// Dropping a transaction commits it: compensations only run on [`Transaction::rollback`].
+pub struct Transaction<'t, C: ?Sized> {
+    compensations: Vec<(String, Compensation<'t, C>)>,
+}
+
+impl<C: ?Sized> Default for Transaction<'_, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'t, C: ?Sized> Transaction<'t, C> {
+    pub fn new() -> Self {
+        Self {
+            compensations: Vec::new(),
+        }
+    }
+
+    /// Runs the operation and, if it succeeds, registers the compensation that undoes it. The
+    /// compensation gets a copy of what the operation returned, e.g. where an HQ was built.
+    pub fn step<T: Clone + 't>(
+        &mut self,
+        name: &str,
+        context: &mut C,
+        operation: impl FnOnce(&mut C) -> Result<T, EvilError>,
+        compensation: impl FnOnce(&mut C, T) -> Result<(), EvilError> + 't,
+    ) -> Result<T, EvilError> {
+        let value = operation(context)?;
+        let done = value.clone();
+        self.compensations.push((
+            name.into(),
+            Box::new(move |context: &mut C| compensation(context, done)),
+        ));
+        Ok(value)
+    }
+
+    /// Names of the steps done so far, in order.
+    pub fn steps(&self) -> impl Iterator<Item = &str> {
+        self.compensations.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Keeps everything done.
+    pub fn commit(self) {}
+
+    /// Undoes every step, last first. A compensation failing doesn't stop the ones after it.
+    pub fn rollback(self, context: &mut C) -> Rollback {
+        let mut rollback = Rollback {
+            undone: Vec::new(),
+            failed: Vec::new(),
+        };
+        for (name, compensation) in self.compensations.into_iter().rev() {
+            match compensation(context) {
+                Ok(()) => rollback.undone.push(name),
+                Err(error) => rollback.failed.push((name, error)),
+            }
+        }
+        rollback
+    }
+}
+
+/// Runs `body` in a transaction that is committed if it succeeds, or rolled back if it fails.
+/// Steps the rollback couldn't undo turn the error into [`EvilError::RollbackFailed`].
+pub fn atomically<'t, C: ?Sized, T>(
+    context: &mut C,
+    body: impl FnOnce(&mut Transaction<'t, C>, &mut C) -> Result<T, EvilError>,
+) -> Result<T, EvilError> {
+    let mut transaction = Transaction::new();
+    match body(&mut transaction, context) {
+        Ok(value) => {
+            transaction.commit();
+            Ok(value)
+        }
+        Err(error) => {
+            let rollback = transaction.rollback(context);
+            if rollback.is_complete() {
+                return Err(error);
+            }
+            Err(EvilError::RollbackFailed {
+                steps: rollback.failed.into_iter().map(|(name, _)| name).collect(),
+                source: Box::new(error),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::treasury::Treasury;
+    use assertables::assert_matches;
+
+    #[test]
+    fn failed_step_rolls_back_previous_ones_in_reverse() {
+        let mut treasury = Treasury::new(100);
+        let mut transaction = Transaction::new();
+        for amount in [30, 50] {
+            let _ = transaction.step(
+                &format!("withdraw-{amount}"),
+                &mut treasury,
+                |treasury| treasury.withdraw(amount),
+                move |treasury, _| {
+                    treasury.deposit(amount);
+                    Ok(())
+                },
+            );
+        }
+        let result = transaction.step(
+            "withdraw-40",
+            &mut treasury,
+            |treasury| treasury.withdraw(40),
+            |treasury, _| {
+                treasury.deposit(40);
+                Ok(())
+            },
+        );
+        assert_matches!(result, Err(EvilError::InsufficientFunds { .. }));
+        assert_eq!(transaction.steps().count(), 2);
+
+        let rollback = transaction.rollback(&mut treasury);
+        assert!(rollback.is_complete());
+        assert_eq!(rollback.undone, ["withdraw-50", "withdraw-30"]);
+        assert_eq!(treasury.balance(), 100);
+    }
+
+    #[test]
+    fn atomically_commits_on_success() {
+        let mut treasury = Treasury::new(100);
+        let result = atomically(&mut treasury, |transaction, treasury| {
+            transaction.step(
+                "withdraw",
+                treasury,
+                |treasury| treasury.withdraw(60),
+                |treasury, _| {
+                    treasury.deposit(60);
+                    Ok(())
+                },
+            )
+        });
+        assert!(result.is_ok());
+        assert_eq!(treasury.balance(), 40);
+    }
+
+    #[test]
+    fn failed_compensations_are_reported() {
+        let mut treasury = Treasury::new(100);
+        let result: Result<(), EvilError> = atomically(&mut treasury, |transaction, treasury| {
+            transaction.step(
+                "withdraw",
+                treasury,
+                |treasury| treasury.withdraw(60),
+                |treasury, _| {
+                    treasury.deposit(60);
+                    Ok(())
+                },
+            )?;
+            transaction.step(
+                "build-hq",
+                treasury,
+                |_| Ok(String::from("Tampa")),
+                |_, location| Err(EvilError::HqStillStanding { location }),
+            )?;
+            Err(EvilError::HenchmenOnStrike)
+        });
+        assert_matches!(
+            result,
+            Err(EvilError::RollbackFailed { steps, source })
+                if steps == ["build-hq"] && matches!(*source, EvilError::HenchmenOnStrike)
+        );
+        assert_eq!(treasury.balance(), 100);
+    }
+}
@@ -0,0 +1,93 @@
+//! Module for the villain's money.
+#![allow(dead_code)]
+
+use crate::error::EvilError;
+use crate::gadget::GadgetInventory;
+use crate::gadget::appraisal::Appraiser;
+use crate::inventory::Inventory;
+
+/// Evil funds, in whole coins.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Treasury {
+    balance: u64,
+}
+
+impl Treasury {
+    pub fn new(balance: u64) -> Treasury {
+        Self { balance }
+    }
+
+    pub fn balance(&self) -> u64 {
+        self.balance
+    }
+
+    pub fn deposit(&mut self, amount: u64) {
+        self.balance = self.balance.saturating_add(amount);
+    }
+
+    pub fn withdraw(&mut self, amount: u64) -> Result<(), EvilError> {
+        if amount > self.balance {
+            return Err(EvilError::InsufficientFunds {
+                balance: self.balance,
+                requested: amount,
+            });
+        }
+        self.balance -= amount;
+        Ok(())
+    }
+
+    /// What the villain is worth: the coins plus the appraised loot.
+    pub fn valuation(&self, loot: &Inventory) -> u64 {
+        self.balance.saturating_add(loot.appraise())
+    }
+
+    /// Like [`Treasury::valuation`], also counting the gadgets as priced by the appraiser.
+    pub fn net_worth<A: Appraiser + ?Sized>(
+        &self,
+        loot: &Inventory,
+        gadgets: &GadgetInventory,
+        appraiser: &A,
+    ) -> u64 {
+        self.valuation(loot)
+            .saturating_add(gadgets.appraise(appraiser))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::inventory::Item;
+    use crate::inventory::ItemKind;
+    use assertables::assert_matches;
+
+    #[test]
+    fn deposit_and_withdraw_update_balance() -> Result<(), EvilError> {
+        let mut treasury = Treasury::new(100);
+        treasury.deposit(50);
+        treasury.withdraw(120)?;
+        assert_eq!(treasury.balance(), 30);
+        Ok(())
+    }
+
+    #[test]
+    fn withdraw_more_than_balance_fails() {
+        let mut treasury = Treasury::new(10);
+        let result = treasury.withdraw(11);
+        assert_matches!(
+            result,
+            Err(EvilError::InsufficientFunds {
+                balance: 10,
+                requested: 11
+            })
+        );
+        assert_eq!(treasury.balance(), 10);
+    }
+
+    #[test]
+    fn valuation_includes_loot() -> Result<(), EvilError> {
+        let mut loot = Inventory::new();
+        loot.add(Item::new("Mona Lisa", ItemKind::Artifact, 8, 1_000), 1)?;
+        assert_eq!(Treasury::new(500).valuation(&loot), 1_500);
+        Ok(())
+    }
+}
@@ -0,0 +1,233 @@
+//! Module for the trophy room: mementos of defeated heroes and completed heists, kept as long
+//! as the lair has room for them.
+#![allow(dead_code)]
+
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// What a trophy is a memento of.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TrophySource {
+    /// Taken from a hero the villain defeated.
+    DefeatedHero(String),
+    /// Brought back from a heist on a target.
+    Heist(String),
+}
+
+/// How a trophy is shown off.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DisplayCase {
+    #[default]
+    Pedestal,
+    GlassCase,
+    Wall,
+}
+
+/// Memento on display, taking up room in the lair.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Trophy {
+    pub name: String,
+    pub source: TrophySource,
+    pub case: DisplayCase,
+    /// What the plaque under it reads.
+    pub plaque: String,
+    /// How much visitors are impressed by it. Less impressive trophies go first.
+    pub prestige: u32,
+    /// Room it takes up, in square meters.
+    pub size: u32,
+}
+
+impl Trophy {
+    pub fn new(name: &str, source: TrophySource, prestige: u32, size: u32) -> Trophy {
+        Self {
+            name: name.into(),
+            source,
+            case: DisplayCase::default(),
+            plaque: String::new(),
+            prestige,
+            size,
+        }
+    }
+
+    pub fn displayed_in(mut self, case: DisplayCase, plaque: &str) -> Trophy {
+        self.case = case;
+        self.plaque = plaque.into();
+        self
+    }
+}
+
+/// Trophies worth more infamy together than apart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrophySet {
+    pub name: String,
+    /// Names of the trophies making the set.
+    pub pieces: Vec<String>,
+    /// Infamy the complete set brings.
+    pub bonus: u32,
+}
+
+impl TrophySet {
+    pub fn new(name: &str, pieces: &[&str], bonus: u32) -> TrophySet {
+        Self {
+            name: name.into(),
+            pieces: pieces.iter().map(|&piece| piece.into()).collect(),
+            bonus,
+        }
+    }
+}
+
+/// Trophies on display, optionally limited in the room they can take up. When a new trophy
+/// doesn't fit, the least prestigious ones not completing a set are thrown out until it does.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrophyRoom {
+    space: Option<u32>,
+    trophies: Vec<Trophy>,
+    sets: Vec<TrophySet>,
+}
+
+impl TrophyRoom {
+    /// Room that fits any number of trophies.
+    pub fn new() -> TrophyRoom {
+        Self::default()
+    }
+
+    /// Room that fits up to `space` square meters of trophies.
+    pub fn with_space(space: u32) -> TrophyRoom {
+        Self {
+            space: Some(space),
+            ..Self::default()
+        }
+    }
+
+    pub fn space(&self) -> Option<u32> {
+        self.space
+    }
+
+    /// Room taken up by the trophies.
+    pub fn used_space(&self) -> u32 {
+        self.trophies.iter().map(|trophy| trophy.size).sum()
+    }
+
+    pub fn trophies(&self) -> &[Trophy] {
+        &self.trophies
+    }
+
+    pub fn add_set(&mut self, set: TrophySet) {
+        self.sets.push(set);
+    }
+
+    /// Puts the trophy on display and returns the ones thrown out to make room, which may
+    /// include the new one if it's the least worth keeping.
+    pub fn display(&mut self, trophy: Trophy) -> Vec<Trophy> {
+        self.trophies.push(trophy);
+        self.collect_garbage()
+    }
+
+    /// Sets with every piece on display.
+    pub fn complete_sets(&self) -> impl Iterator<Item = &TrophySet> {
+        self.sets.iter().filter(|set| self.is_complete(set))
+    }
+
+    /// Infamy the complete sets bring.
+    pub fn infamy_bonus(&self) -> u32 {
+        self.complete_sets().map(|set| set.bonus).sum()
+    }
+
+    fn is_complete(&self, set: &TrophySet) -> bool {
+        set.pieces
+            .iter()
+            .all(|piece| self.trophies.iter().any(|trophy| &trophy.name == piece))
+    }
+
+    fn completes_set(&self, trophy: &Trophy) -> bool {
+        self.complete_sets()
+            .any(|set| set.pieces.contains(&trophy.name))
+    }
+
+    /// Throws out trophies until they fit: the ones outside complete sets first, then the least
+    /// prestigious, then the newest.
+    fn collect_garbage(&mut self) -> Vec<Trophy> {
+        let mut thrown_out = Vec::new();
+        let Some(space) = self.space else {
+            return thrown_out;
+        };
+        while self.used_space() > space {
+            let Some(index) = (0..self.trophies.len()).min_by_key(|&index| {
+                let trophy = &self.trophies[index];
+                (
+                    self.completes_set(trophy),
+                    trophy.prestige,
+                    usize::MAX - index,
+                )
+            }) else {
+                break;
+            };
+            thrown_out.push(self.trophies.remove(index));
+        }
+        thrown_out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+
+    fn cape() -> Trophy {
+        Trophy::new(
+            "Cape",
+            TrophySource::DefeatedHero(test_common::HERO_NAME.into()),
+            50,
+            2,
+        )
+        .displayed_in(DisplayCase::GlassCase, "Torn off mid-flight")
+    }
+
+    fn boots() -> Trophy {
+        Trophy::new(
+            "Boots",
+            TrophySource::DefeatedHero(test_common::HERO_NAME.into()),
+            10,
+            2,
+        )
+    }
+
+    fn vault_door() -> Trophy {
+        Trophy::new(
+            "Vault door",
+            TrophySource::Heist(test_common::FIRST_TARGET.into()),
+            30,
+            3,
+        )
+    }
+
+    #[test]
+    fn complete_sets_bring_infamy() {
+        let mut room = TrophyRoom::new();
+        room.add_set(TrophySet::new("Superman's outfit", &["Cape", "Boots"], 100));
+        assert!(room.display(cape()).is_empty());
+        assert_eq!(room.infamy_bonus(), 0);
+        assert!(room.display(boots()).is_empty());
+        assert_eq!(room.infamy_bonus(), 100);
+        assert_eq!(room.used_space(), 4);
+    }
+
+    #[test]
+    fn least_prestigious_trophies_go_first() {
+        let mut room = TrophyRoom::with_space(5);
+        room.display(boots());
+        room.display(cape());
+        assert_eq!(room.display(vault_door()), [boots()]);
+        assert_eq!(room.trophies(), [cape(), vault_door()]);
+    }
+
+    #[test]
+    fn complete_sets_are_kept() {
+        let mut room = TrophyRoom::with_space(5);
+        room.add_set(TrophySet::new("Superman's outfit", &["Cape", "Boots"], 100));
+        room.display(boots());
+        room.display(cape());
+        assert_eq!(room.display(vault_door()), [vault_door()]);
+        assert_eq!(room.infamy_bonus(), 100);
+    }
+}
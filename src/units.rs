@@ -0,0 +1,300 @@
+//! Module for typed quantities, so durations, distances and power can't be mixed up.
+#![allow(dead_code)]
+
+use core::fmt;
+use core::iter::Sum;
+use core::ops::Add;
+use core::ops::AddAssign;
+use core::ops::Mul;
+use core::ops::Sub;
+use core::time::Duration;
+
+#[cfg(feature = "std")]
+use serde::Deserialize;
+#[cfg(feature = "std")]
+use serde::Serialize;
+
+/// How long something evil takes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize), serde(transparent))]
+pub struct EvilDuration(Duration);
+
+impl EvilDuration {
+    pub const ZERO: EvilDuration = EvilDuration(Duration::ZERO);
+
+    pub const fn from_secs(seconds: u64) -> EvilDuration {
+        Self(Duration::from_secs(seconds))
+    }
+
+    pub const fn from_mins(minutes: u64) -> EvilDuration {
+        Self::from_secs(minutes * 60)
+    }
+
+    pub const fn from_hours(hours: u64) -> EvilDuration {
+        Self::from_secs(hours * 3600)
+    }
+
+    pub const fn as_duration(&self) -> Duration {
+        self.0
+    }
+
+    pub const fn as_secs(&self) -> u64 {
+        self.0.as_secs()
+    }
+}
+
+impl From<Duration> for EvilDuration {
+    fn from(duration: Duration) -> Self {
+        Self(duration)
+    }
+}
+
+impl From<EvilDuration> for Duration {
+    fn from(duration: EvilDuration) -> Self {
+        duration.0
+    }
+}
+
+impl Add for EvilDuration {
+    type Output = EvilDuration;
+
+    fn add(self, other: EvilDuration) -> EvilDuration {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+impl AddAssign for EvilDuration {
+    fn add_assign(&mut self, other: EvilDuration) {
+        *self = *self + other;
+    }
+}
+
+/// Never goes below zero.
+impl Sub for EvilDuration {
+    type Output = EvilDuration;
+
+    fn sub(self, other: EvilDuration) -> EvilDuration {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul<u32> for EvilDuration {
+    type Output = EvilDuration;
+
+    fn mul(self, times: u32) -> EvilDuration {
+        Self(self.0.saturating_mul(times))
+    }
+}
+
+impl Sum for EvilDuration {
+    fn sum<I: Iterator<Item = EvilDuration>>(iter: I) -> EvilDuration {
+        iter.fold(EvilDuration::ZERO, Add::add)
+    }
+}
+
+/// Shown in hours, minutes and seconds, e.g. `1h 30m 5s`.
+impl fmt::Display for EvilDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.0.as_secs();
+        let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+        match (hours, minutes) {
+            (0, 0) => write!(f, "{seconds}s"),
+            (0, _) => write!(f, "{minutes}m {seconds}s"),
+            _ => write!(f, "{hours}h {minutes}m {seconds}s"),
+        }
+    }
+}
+
+/// Distance, in whole meters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Distance(u64);
+
+impl Distance {
+    pub const ZERO: Distance = Distance(0);
+
+    pub const fn from_meters(meters: u64) -> Distance {
+        Self(meters)
+    }
+
+    pub const fn from_km(kilometers: u64) -> Distance {
+        Self(kilometers * 1000)
+    }
+
+    pub const fn meters(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Add for Distance {
+    type Output = Distance;
+
+    fn add(self, other: Distance) -> Distance {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+/// Never goes below zero.
+impl Sub for Distance {
+    type Output = Distance;
+
+    fn sub(self, other: Distance) -> Distance {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul<u64> for Distance {
+    type Output = Distance;
+
+    fn mul(self, times: u64) -> Distance {
+        Self(self.0.saturating_mul(times))
+    }
+}
+
+impl Sum for Distance {
+    fn sum<I: Iterator<Item = Distance>>(iter: I) -> Distance {
+        iter.fold(Distance::ZERO, Add::add)
+    }
+}
+
+/// Shown in meters below a kilometer, and in kilometers otherwise, e.g. `1.5 km`.
+impl fmt::Display for Distance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0 < 1000 {
+            write!(f, "{} m", self.0)
+        } else {
+            write_scaled(f, self.0, 1000, "km")
+        }
+    }
+}
+
+/// Power, in whole watts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Power(u64);
+
+impl Power {
+    pub const ZERO: Power = Power(0);
+
+    pub const fn from_watts(watts: u64) -> Power {
+        Self(watts)
+    }
+
+    pub const fn from_megawatts(megawatts: u64) -> Power {
+        Self(megawatts * 1_000_000)
+    }
+
+    pub const fn from_gigawatts(gigawatts: u64) -> Power {
+        Self(gigawatts * 1_000_000_000)
+    }
+
+    pub const fn watts(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Add for Power {
+    type Output = Power;
+
+    fn add(self, other: Power) -> Power {
+        Self(self.0.saturating_add(other.0))
+    }
+}
+
+/// Never goes below zero.
+impl Sub for Power {
+    type Output = Power;
+
+    fn sub(self, other: Power) -> Power {
+        Self(self.0.saturating_sub(other.0))
+    }
+}
+
+impl Mul<u64> for Power {
+    type Output = Power;
+
+    fn mul(self, times: u64) -> Power {
+        Self(self.0.saturating_mul(times))
+    }
+}
+
+impl Sum for Power {
+    fn sum<I: Iterator<Item = Power>>(iter: I) -> Power {
+        iter.fold(Power::ZERO, Add::add)
+    }
+}
+
+/// Shown with the largest fitting prefix, e.g. `1.21 GW`.
+impl fmt::Display for Power {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            watts if watts >= 1_000_000_000 => write_scaled(f, watts, 1_000_000_000, "GW"),
+            watts if watts >= 1_000_000 => write_scaled(f, watts, 1_000_000, "MW"),
+            watts if watts >= 1_000 => write_scaled(f, watts, 1_000, "kW"),
+            watts => write!(f, "{watts} W"),
+        }
+    }
+}
+
+/// Writes the value divided by the scale with up to two decimals, dropping trailing zeros.
+fn write_scaled(f: &mut fmt::Formatter<'_>, value: u64, scale: u64, unit: &str) -> fmt::Result {
+    let hundredths = value % scale * 100 / scale;
+    match hundredths {
+        0 => write!(f, "{} {unit}", value / scale),
+        _ if hundredths.is_multiple_of(10) => {
+            write!(f, "{}.{} {unit}", value / scale, hundredths / 10)
+        }
+        _ => write!(f, "{}.{hundredths:02} {unit}", value / scale),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_add_up_and_display_in_hours_minutes_and_seconds() {
+        let total = [
+            EvilDuration::from_hours(1),
+            EvilDuration::from_mins(30),
+            EvilDuration::from_secs(5),
+        ]
+        .into_iter()
+        .sum::<EvilDuration>();
+        assert_eq!(total.as_secs(), 5405);
+        assert_eq!(total.to_string(), "1h 30m 5s");
+        assert_eq!((EvilDuration::from_secs(90)).to_string(), "1m 30s");
+    }
+
+    #[test]
+    fn subtraction_never_goes_below_zero() {
+        assert_eq!(
+            EvilDuration::from_secs(1) - EvilDuration::from_secs(2),
+            EvilDuration::ZERO
+        );
+        assert_eq!(
+            Distance::from_meters(1) - Distance::from_km(1),
+            Distance::ZERO
+        );
+        assert_eq!(Power::from_watts(1) - Power::from_megawatts(1), Power::ZERO);
+    }
+
+    #[test]
+    fn distances_display_in_meters_or_kilometers() {
+        assert_eq!(Distance::from_meters(300).to_string(), "300 m");
+        assert_eq!(Distance::from_meters(1500).to_string(), "1.5 km");
+        assert_eq!((Distance::from_km(2) * 3).to_string(), "6 km");
+    }
+
+    #[test]
+    fn power_displays_with_largest_prefix() {
+        assert_eq!(Power::from_watts(1_210_000_000).to_string(), "1.21 GW");
+        assert_eq!(Power::from_megawatts(5).to_string(), "5 MW");
+        assert_eq!(Power::from_watts(1_050).to_string(), "1.05 kW");
+        assert_eq!(Power::from_watts(12).to_string(), "12 W");
+    }
+
+    #[test]
+    fn durations_convert_to_and_from_std() {
+        let duration = Duration::from_secs(42);
+        assert_eq!(Duration::from(EvilDuration::from(duration)), duration);
+    }
+}
@@ -0,0 +1,385 @@
+//! Module to have many henchmen work on a plan at once, each in its own thread.
+#![allow(dead_code)]
+
+use std::collections::VecDeque;
+use std::iter;
+use std::mem;
+use std::sync::Arc;
+use std::sync::Condvar;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+use std::thread;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::Instant;
+
+use crate::henchman::Henchman;
+use crate::plan::PlanStep;
+use crate::plan::StepAction;
+use crate::supervillain::EvilError;
+
+/// Work for a single henchman.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Task {
+    pub name: String,
+    pub action: StepAction,
+}
+
+impl Task {
+    pub fn new(name: &str, action: StepAction) -> Task {
+        Self {
+            name: name.into(),
+            action,
+        }
+    }
+}
+
+impl From<PlanStep> for Task {
+    fn from(step: PlanStep) -> Self {
+        Self {
+            name: step.name,
+            action: step.action,
+        }
+    }
+}
+
+/// How much work a workforce has done so far.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WorkforceStats {
+    pub completed: usize,
+    /// Tasks done by a henchman other than the one they were assigned to.
+    pub stolen: usize,
+    pub elapsed: Duration,
+}
+
+impl WorkforceStats {
+    /// Tasks completed per second.
+    pub fn throughput(&self) -> f64 {
+        if self.elapsed.is_zero() {
+            return 0.0;
+        }
+        self.completed as f64 / self.elapsed.as_secs_f64()
+    }
+}
+
+/// Whether the henchmen should go home once their queues run dry. Submitters and idle henchmen
+/// also hold it while checking the queues before they wait, so no signal gets lost.
+struct Signals {
+    shutting_down: bool,
+}
+
+struct Shared {
+    /// Queue of each henchman, locked on its own so henchmen work and steal in parallel.
+    queues: Vec<Mutex<VecDeque<Task>>>,
+    /// Henchman the next task goes to, taking turns.
+    next: AtomicUsize,
+    signals: Mutex<Signals>,
+    /// Signaled when there are tasks to do or the workforce is shutting down.
+    work: Condvar,
+    /// Signaled when a task leaves a queue, so blocked submitters can retry.
+    space: Condvar,
+    capacity: usize,
+    completed: AtomicUsize,
+    stolen: AtomicUsize,
+}
+
+impl Shared {
+    fn new(workers: usize, capacity: usize) -> Shared {
+        Self {
+            queues: (0..workers).map(|_| Mutex::default()).collect(),
+            next: AtomicUsize::new(0),
+            signals: Mutex::new(Signals {
+                shutting_down: false,
+            }),
+            work: Condvar::new(),
+            space: Condvar::new(),
+            capacity: capacity.max(1),
+            completed: AtomicUsize::new(0),
+            stolen: AtomicUsize::new(0),
+        }
+    }
+
+    fn signals(&self) -> MutexGuard<'_, Signals> {
+        self.signals
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn queue(&self, index: usize) -> MutexGuard<'_, VecDeque<Task>> {
+        self.queues[index]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Queues the task with the next henchman in turn, or the least busy one if that queue is
+    /// full. Hands the task back if every queue is full.
+    fn enqueue(&self, task: Task) -> Result<(), Task> {
+        let count = self.queues.len();
+        let preferred = self.next.fetch_add(1, Ordering::Relaxed) % count;
+        let mut others = (0..count).filter(|&i| i != preferred).collect::<Vec<_>>();
+        others.sort_by_key(|&i| self.queue(i).len());
+        for index in iter::once(preferred).chain(others) {
+            let mut queue = self.queue(index);
+            if queue.len() < self.capacity {
+                queue.push_back(task);
+                drop(queue);
+                drop(self.signals());
+                self.work.notify_all();
+                return Ok(());
+            }
+        }
+        Err(task)
+    }
+
+    /// Oldest task of the worker's own queue, or else the newest of the busiest other henchman.
+    fn take(&self, worker: usize) -> Option<Task> {
+        if let Some(task) = self.queue(worker).pop_front() {
+            return Some(task);
+        }
+        let mut victims = (0..self.queues.len())
+            .filter(|&i| i != worker)
+            .map(|i| (self.queue(i).len(), i))
+            .filter(|&(len, _)| len > 0)
+            .collect::<Vec<_>>();
+        victims.sort_unstable_by(|a, b| b.cmp(a));
+        let task = victims
+            .into_iter()
+            .find_map(|(_, i)| self.queue(i).pop_back())?;
+        self.stolen.fetch_add(1, Ordering::Relaxed);
+        Some(task)
+    }
+
+    /// Next task for the worker, waiting for one if there's none. `None` once shutting down
+    /// with nothing left to do.
+    fn next_task(&self, worker: usize) -> Option<Task> {
+        loop {
+            if let Some(task) = self.take(worker) {
+                drop(self.signals());
+                self.space.notify_all();
+                return Some(task);
+            }
+            let signals = self.signals();
+            if (0..self.queues.len()).any(|i| !self.queue(i).is_empty()) {
+                continue;
+            }
+            if signals.shutting_down {
+                return None;
+            }
+            drop(
+                self.work
+                    .wait(signals)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            );
+        }
+    }
+
+    fn has_room(&self) -> bool {
+        (0..self.queues.len()).any(|i| self.queue(i).len() < self.capacity)
+    }
+
+    fn shut_down(&self) {
+        self.signals().shutting_down = true;
+        self.work.notify_all();
+    }
+}
+
+/// Pool of henchmen, each working in its own thread on its own queue of tasks and stealing
+/// from the others when it runs out.
+///
+/// Dropping the workforce sends the henchmen home too, once they finished every queued task.
+pub struct Workforce<H> {
+    shared: Arc<Shared>,
+    workers: Vec<JoinHandle<H>>,
+    started: Instant,
+}
+
+impl<H: Henchman + Send + 'static> Workforce<H> {
+    /// Puts the henchmen to work, each with a queue of up to `capacity` pending tasks.
+    pub fn start(henchmen: Vec<H>, capacity: usize) -> Result<Workforce<H>, EvilError> {
+        if henchmen.is_empty() {
+            return Err(EvilError::NoHenchmen);
+        }
+        let shared = Arc::new(Shared::new(henchmen.len(), capacity));
+        let workers = henchmen
+            .into_iter()
+            .enumerate()
+            .map(|(worker, mut henchman)| {
+                let shared = Arc::clone(&shared);
+                thread::spawn(move || {
+                    while let Some(task) = shared.next_task(worker) {
+                        task.action.perform(&mut henchman);
+                        shared.completed.fetch_add(1, Ordering::Relaxed);
+                    }
+                    henchman
+                })
+            })
+            .collect();
+        Ok(Self {
+            shared,
+            workers,
+            started: Instant::now(),
+        })
+    }
+
+    /// Queues the task, waiting for room if every queue is full.
+    pub fn submit(&self, task: Task) {
+        let mut task = task;
+        while let Err(rejected) = self.shared.enqueue(task) {
+            task = rejected;
+            let signals = self.shared.signals();
+            if self.shared.has_room() {
+                continue;
+            }
+            drop(
+                self.shared
+                    .space
+                    .wait(signals)
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            );
+        }
+    }
+
+    /// Queues the task, failing instead of waiting if every queue is full.
+    pub fn try_submit(&self, task: Task) -> Result<(), EvilError> {
+        self.shared.enqueue(task).map_err(|_| EvilError::QueueFull {
+            capacity: self.shared.capacity,
+        })
+    }
+
+    pub fn stats(&self) -> WorkforceStats {
+        WorkforceStats {
+            completed: self.shared.completed.load(Ordering::Relaxed),
+            stolen: self.shared.stolen.load(Ordering::Relaxed),
+            elapsed: self.started.elapsed(),
+        }
+    }
+
+    /// Lets the henchmen finish every queued task, then sends them home. Returns the henchmen
+    /// and the final stats.
+    pub fn shutdown(mut self) -> (Vec<H>, WorkforceStats) {
+        self.shared.shut_down();
+        let henchmen = mem::take(&mut self.workers)
+            .into_iter()
+            .map(|worker| {
+                worker
+                    .join()
+                    .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+            })
+            .collect();
+        (henchmen, self.stats())
+    }
+}
+
+impl<H> Drop for Workforce<H> {
+    fn drop(&mut self) {
+        if self.workers.is_empty() {
+            return;
+        }
+        self.shared.shut_down();
+        for worker in self.workers.drain(..) {
+            // A henchman that panicked has nothing left to give back.
+            let _ = worker.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_common;
+    use assertables::assert_matches;
+
+    #[derive(Debug, Default)]
+    struct Laborer {
+        done: usize,
+        hq: Option<String>,
+        pace: Duration,
+        fights: Arc<AtomicUsize>,
+    }
+
+    impl Henchman for Laborer {
+        fn build_secret_hq(&mut self, location: String) {
+            self.hq = Some(location);
+            self.done += 1;
+        }
+
+        fn do_hard_things(&self) {
+            thread::sleep(self.pace);
+        }
+
+        fn fight_enemies(&self) {
+            self.fights.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn shutdown_finishes_every_queued_task() -> Result<(), EvilError> {
+        let workforce = Workforce::start(vec![Laborer::default(), Laborer::default()], 4)?;
+        for i in 0..20 {
+            workforce.submit(Task::new(
+                &format!("hq-{i}"),
+                StepAction::BuildSecretHq(test_common::FIRST_TARGET.into()),
+            ));
+        }
+        let (henchmen, stats) = workforce.shutdown();
+        assert_eq!(stats.completed, 20);
+        assert_eq!(henchmen.iter().map(|h| h.done).sum::<usize>(), 20);
+        Ok(())
+    }
+
+    #[test]
+    fn idle_henchmen_steal_the_newest_task_of_the_busiest() {
+        let shared = Shared::new(3, 4);
+        let dig = |name| Task::new(name, StepAction::DoHardThings);
+        for name in ["a", "b", "c", "d", "e"] {
+            assert_eq!(shared.enqueue(dig(name)), Ok(()));
+        }
+        // Round robin: a and d for the first henchman, b and e for the second, c for the third.
+        assert_eq!(shared.next_task(2), Some(dig("c")));
+        assert_eq!(shared.stolen.load(Ordering::Relaxed), 0);
+        assert_eq!(shared.next_task(2), Some(dig("e")));
+        assert_eq!(shared.next_task(2), Some(dig("d")));
+        assert_eq!(shared.stolen.load(Ordering::Relaxed), 2);
+        assert_eq!(shared.next_task(0), Some(dig("a")));
+        assert_eq!(shared.next_task(0), Some(dig("b")));
+        assert_eq!(shared.stolen.load(Ordering::Relaxed), 3);
+        shared.shut_down();
+        assert_eq!(shared.next_task(1), None);
+    }
+
+    #[test]
+    fn full_queues_reject_tasks() {
+        let shared = Shared::new(2, 1);
+        let dig = |name| Task::new(name, StepAction::DoHardThings);
+        assert_eq!(shared.enqueue(dig("a")), Ok(()));
+        assert_eq!(shared.enqueue(dig("b")), Ok(()));
+        assert_eq!(shared.enqueue(dig("c")), Err(dig("c")));
+        assert!(!shared.has_room());
+    }
+
+    #[test]
+    fn dropping_the_workforce_finishes_queued_tasks() -> Result<(), EvilError> {
+        let fights = Arc::new(AtomicUsize::new(0));
+        let laborer = || Laborer {
+            fights: Arc::clone(&fights),
+            ..Default::default()
+        };
+        let workforce = Workforce::start(vec![laborer(), laborer()], 4)?;
+        for i in 0..8 {
+            workforce.submit(Task::new(&format!("fight-{i}"), StepAction::FightEnemies));
+        }
+        drop(workforce);
+        assert_eq!(fights.load(Ordering::Relaxed), 8);
+        Ok(())
+    }
+
+    #[test]
+    fn workforce_needs_henchmen() {
+        assert_matches!(
+            Workforce::<Laborer>::start(vec![], 4).err(),
+            Some(EvilError::NoHenchmen)
+        );
+    }
+}